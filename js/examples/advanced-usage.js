@@ -1,110 +1,84 @@
-import { mergeAndParse, parseEnvArguments } from '../src/index.js';
+import { mkdtempSync, rmSync, writeFileSync } from 'fs';
+import { tmpdir } from 'os';
+import { join } from 'path';
+import { makeConfig, tryMakeConfig } from '../src/index.js';
 
 /**
  * Advanced usage example demonstrating the full power of lino-arguments
- * This shows how to merge configuration from multiple sources:
+ * This shows how configuration merges from multiple sources:
  * 1. .lenv files (environment configuration)
- * 2. Environment variable overrides (using links notation)
+ * 2. Environment variables
  * 3. Command-line arguments
  */
 
 console.log('=== Advanced lino-arguments Usage ===\n');
 
-// Example 1: Parse environment variable overrides
-console.log('1. Parsing overrides from environment variables:');
-console.log('Set: export CLI_OVERRIDES="--debug\\n--port 9000"');
-
-// Simulate environment variable
-process.env.CLI_OVERRIDES = `(
-  --debug
-  --trace
-  --output ./logs
-)`;
-
-const overrides = parseEnvArguments('CLI_OVERRIDES');
-console.log('Parsed overrides:', overrides);
+const dir = mkdtempSync(join(tmpdir(), 'lino-arguments-'));
+const lenvPath = join(dir, '.lenv');
+const productionPath = join(dir, 'production.lenv');
+
+writeFileSync(lenvPath, 'APP_PORT: 3000\nAPP_OUTPUT: ./output\n');
+writeFileSync(productionPath, 'APP_ENV: production\nAPP_PORT: 9000\n');
+
+const options = {
+  debug: { type: 'boolean', description: 'Enable debug mode' },
+  trace: { type: 'boolean', description: 'Enable trace logging' },
+  port: { type: 'integer', description: 'Server port', env: 'APP_PORT' },
+  output: {
+    description: 'Output directory',
+    env: 'APP_OUTPUT',
+    default: './out',
+  },
+  env: {
+    description: 'Environment',
+    env: 'APP_ENV',
+    default: 'development',
+  },
+};
+
+// Example 1: Values from the .lenv file
+console.log('1. Reading the .lenv file:');
+const fromLenv = makeConfig({
+  options,
+  lenv: { path: lenvPath },
+  argv: ['node', 'app.js'],
+});
+console.log('Resolved:', fromLenv);
 console.log();
 
-// Example 2: Merge all sources
-console.log('2. Merging configuration from multiple sources:');
-
-async function runApp() {
-  const config = await mergeAndParse({
-    // Configure yargs with all your options
-    yargsConfig: (yargs) =>
-      yargs
-        .option('debug', {
-          type: 'boolean',
-          description: 'Enable debug mode',
-          default: false,
-        })
-        .option('trace', {
-          type: 'boolean',
-          description: 'Enable trace logging',
-          default: false,
-        })
-        .option('port', {
-          type: 'number',
-          description: 'Server port',
-          default: 3000,
-        })
-        .option('output', {
-          type: 'string',
-          description: 'Output directory',
-          default: './output',
-        })
-        .option('env', {
-          type: 'string',
-          description: 'Environment',
-          default: 'development',
-        }),
-
-    // Load from .lenv file (if it exists)
-    lenvPath: '.lenv',
-
-    // Parse overrides from environment variable
-    overridesEnvVar: 'CLI_OVERRIDES',
+// Example 2: --configuration outranks the .lenv file
+console.log('2. Layering production.lenv with --configuration:');
+const fromConfiguration = makeConfig({
+  options,
+  lenv: { path: lenvPath },
+  argv: ['node', 'app.js', '--configuration', productionPath],
+});
+console.log('Resolved:', fromConfiguration);
+console.log();
 
-    // Additional arguments from code
-    additionalArgs: ['--env', 'production'],
+// Example 3: CLI arguments outrank everything
+console.log('3. Overriding with CLI arguments:');
+const fromCli = makeConfig({
+  options,
+  lenv: { path: lenvPath },
+  argv: ['node', 'app.js', '-c', productionPath, '--port', '8080', '--debug'],
+});
+console.log('Resolved:', fromCli);
+console.log();
 
-    // Apply .lenv to process.env
-    applyEnvToProcess: true,
+// Example 4: Handle errors instead of exiting
+console.log('4. Handling a bad value with tryMakeConfig:');
+try {
+  tryMakeConfig({
+    options,
+    lenv: { enabled: false },
+    argv: ['node', 'app.js', '--port', 'eighty'],
   });
-
-  console.log('\nMerged configuration:');
-  console.log('  Debug:', config.debug);
-  console.log('  Trace:', config.trace);
-  console.log('  Port:', config.port);
-  console.log('  Output:', config.output);
-  console.log('  Environment:', config.env);
-
-  return config;
+} catch (error) {
+  console.log(`${error.code}: ${error.message.split('\n')[0]}`);
 }
 
-// Example 3: Simulate a real CLI application
-console.log('\n3. Simulating a real CLI application:');
-
-async function startServer() {
-  try {
-    const config = await runApp();
-
-    console.log('\n--- Server Starting ---');
-    console.log(`Environment: ${config.env}`);
-    console.log(`Port: ${config.port}`);
-    console.log(`Output Directory: ${config.output}`);
-    console.log(`Debug Mode: ${config.debug ? 'ON' : 'OFF'}`);
-    console.log(`Trace Mode: ${config.trace ? 'ON' : 'OFF'}`);
-    console.log('--- Server Configuration Complete ---\n');
-
-    // Here you would actually start your server with this config
-    console.log(
-      'In a real application, you would now start your server with this configuration.'
-    );
-  } catch (error) {
-    console.error('Error starting server:', error.message);
-    process.exit(1);
-  }
+for (const key of ['APP_PORT', 'APP_OUTPUT', 'APP_ENV']) {
+  delete process.env[key];
 }
-
-startServer();
+rmSync(dir, { recursive: true, force: true });
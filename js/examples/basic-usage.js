@@ -1,12 +1,13 @@
 import {
   parseLinoArguments,
-  createYargsConfig,
-  applyLinoEnv,
+  makeConfig,
+  getenv,
+  parseLenv,
 } from '../src/index.js';
 
 /**
  * Basic usage example showing how to parse links notation arguments
- * and use them with yargs
+ * and resolve them into configuration
  */
 
 console.log('=== Basic lino-arguments Usage ===\n');
@@ -15,8 +16,8 @@ console.log('=== Basic lino-arguments Usage ===\n');
 console.log('1. Parsing links notation arguments:');
 const linoString = `(
   --verbose
-  --port 3000
-  --host localhost
+  --port=3000
+  --host=localhost
 )`;
 
 const args = parseLinoArguments(linoString);
@@ -24,47 +25,44 @@ console.log('Input:', linoString);
 console.log('Parsed:', args);
 console.log();
 
-// Example 2: Use with yargs
-console.log('2. Using parsed arguments with yargs:');
-const yargsInstance = createYargsConfig(['node', 'script.js', ...args]).option(
-  'verbose',
-  {
-    alias: 'v',
-    type: 'boolean',
-    description: 'Run with verbose logging',
-    default: false,
-  }
-);
-
-yargsInstance.option('port', {
-  alias: 'p',
-  type: 'number',
-  description: 'Port to run on',
-  default: 8080,
-});
-
-yargsInstance.option('host', {
-  alias: 'h',
-  type: 'string',
-  description: 'Host to bind to',
-  default: '0.0.0.0',
-});
-
-const parsedArgs = yargsInstance.parse();
-console.log('Parsed with yargs:', {
-  verbose: parsedArgs.verbose,
-  port: parsedArgs.port,
-  host: parsedArgs.host,
+// Example 2: Resolve the parsed arguments into configuration
+console.log('2. Resolving parsed arguments with makeConfig:');
+const config = makeConfig({
+  options: {
+    verbose: {
+      alias: 'v',
+      type: 'boolean',
+      description: 'Run with verbose logging',
+    },
+    port: {
+      alias: 'p',
+      type: 'integer',
+      description: 'Port to run on',
+      default: 8080,
+    },
+    host: {
+      type: 'string',
+      description: 'Host to bind to',
+      default: '0.0.0.0',
+    },
+  },
+  lenv: { enabled: false },
+  argv: ['node', 'script.js', ...args],
 });
+console.log('Resolved:', config);
 console.log();
 
-// Example 3: Apply .lenv file (if exists)
-console.log('3. Loading environment from .lenv file:');
-console.log('Note: Create a .lenv file with format:');
-console.log('API_KEY: your_api_key_here');
-console.log('DATABASE_URL: postgresql://localhost/mydb');
+// Example 3: Read environment variables in any case
+console.log('3. Reading environment variables:');
+process.env.API_KEY = 'secret123';
+console.log("getenv('apiKey', ''):", getenv('apiKey', ''));
+console.log("getenv('PORT', 3000):", getenv('PORT', 3000));
 console.log();
 
-// This would load .lenv if it exists
-const envVars = applyLinoEnv('.lenv');
-console.log('Loaded environment variables:', Object.keys(envVars).join(', '));
+// Example 4: Parse the text of a .lenv file
+console.log('4. Parsing .lenv text:');
+const lenvText = `# Local settings
+API_KEY: your_api_key_here
+DATABASE_URL: postgresql://localhost/mydb`;
+
+console.log('Parsed .lenv:', parseLenv(lenvText));
@@ -1,23 +1,24 @@
+import { createRequire } from 'node:module';
 import { Parser } from 'links-notation';
-import { LinoEnv } from 'lino-env';
-import yargs from 'yargs';
-import { hideBin } from 'yargs/helpers';
-import baseGetenv from 'getenv';
 
 /**
  * lino-arguments - A unified configuration library
  *
- * Combines Links Notation Environment (lenv), dotenvx, and yargs into a single
- * easy-to-use configuration system with clear priority ordering.
+ * A thin layer over the lino-arguments Rust crate, loaded through its
+ * Node.js bindings (lino-arguments-native), so that options resolve, cases
+ * convert, and .lenv files parse exactly as they do in Rust.
  *
  * Priority (highest to lowest):
- * 1. CLI arguments (manually entered options)
- * 2. getenv defaults (from process.env, set by previous steps)
+ * 1. CLI arguments
+ * 2. Environment variables
  * 3. --configuration option (lenv file specified via CLI)
- * 4. .lenv file (local environment overrides)
- * 5. dotenvx/.env file (base configuration, DEPRECATED)
+ * 4. .lenv file
+ * 5. .env file
+ * 6. Option defaults
  */
 
+const native = createRequire(import.meta.url)('lino-arguments-native');
+
 // ============================================================================
 // Case Conversion Utilities
 // ============================================================================
@@ -28,17 +29,7 @@ import baseGetenv from 'getenv';
  * @returns {string} UPPER_CASE string
  */
 export function toUpperCase(str) {
-  // If already all uppercase, just replace separators
-  if (str === str.toUpperCase()) {
-    return str.replace(/[-\s]/g, '_');
-  }
-
-  return str
-    .replace(/([A-Z])/g, '_$1') // PascalCase/camelCase
-    .replace(/[-\s]/g, '_') // kebab-case/spaces
-    .toUpperCase()
-    .replace(/^_/, '') // Remove leading underscore
-    .replace(/__+/g, '_'); // Remove double underscores
+  return native.toUpperCase(str);
 }
 
 /**
@@ -47,10 +38,7 @@ export function toUpperCase(str) {
  * @returns {string} camelCase string
  */
 export function toCamelCase(str) {
-  return str
-    .toLowerCase()
-    .replace(/[-_\s]+(.)?/g, (_, c) => (c ? c.toUpperCase() : ''))
-    .replace(/^[A-Z]/, (c) => c.toLowerCase());
+  return native.toCamelCase(str);
 }
 
 /**
@@ -59,17 +47,7 @@ export function toCamelCase(str) {
  * @returns {string} kebab-case string
  */
 export function toKebabCase(str) {
-  // If already all uppercase, handle specially
-  if (str === str.toUpperCase() && str.includes('_')) {
-    return str.replace(/_/g, '-').toLowerCase();
-  }
-
-  return str
-    .replace(/([A-Z])/g, '-$1')
-    .replace(/[_\s]/g, '-')
-    .toLowerCase()
-    .replace(/^-/, '')
-    .replace(/--+/g, '-');
+  return native.toKebabCase(str);
 }
 
 /**
@@ -78,17 +56,7 @@ export function toKebabCase(str) {
  * @returns {string} snake_case string
  */
 export function toSnakeCase(str) {
-  // If already all uppercase, just lowercase
-  if (str === str.toUpperCase() && str.includes('_')) {
-    return str.toLowerCase();
-  }
-
-  return str
-    .replace(/([A-Z])/g, '_$1')
-    .replace(/[-\s]/g, '_')
-    .toLowerCase()
-    .replace(/^_/, '')
-    .replace(/__+/g, '_');
+  return native.toSnakeCase(str);
 }
 
 /**
@@ -97,10 +65,36 @@ export function toSnakeCase(str) {
  * @returns {string} PascalCase string
  */
 export function toPascalCase(str) {
-  return str
-    .toLowerCase()
-    .replace(/[-_\s]+(.)?/g, (_, c) => (c ? c.toUpperCase() : ''))
-    .replace(/^[a-z]/, (c) => c.toUpperCase());
+  return native.toPascalCase(str);
+}
+
+/**
+ * Convert string to any case the Rust crate knows
+ * @param {string} str - Input string
+ * @param {string} targetCase - Case name in any case ('upper-snake', 'train', 'dot')
+ * @returns {string} Converted string
+ * @throws {Error} If the case is unknown
+ */
+export function convertCase(str, targetCase) {
+  return native.convertCase(str, targetCase);
+}
+
+/**
+ * Detect the case a string is written in
+ * @param {string} str - Input string
+ * @returns {string|null} Case name in kebab-case ('camel', 'upper-snake'), or null
+ */
+export function detectCase(str) {
+  return native.detectCase(str);
+}
+
+/**
+ * The key every case variant of a name resolves to
+ * @param {string} key - Key in any case
+ * @returns {string} kebab-case key ('API_KEY' and 'apiKey' give 'api-key')
+ */
+export function canonicalKey(key) {
+  return native.canonicalKey(key);
 }
 
 // ============================================================================
@@ -109,8 +103,9 @@ export function toPascalCase(str) {
 
 /**
  * Get environment variable with default value and case conversion
- * Uses the official getenv npm package internally, with enhanced case-insensitive lookup.
- * Tries multiple case formats to find the variable.
+ * Tries every case variant of the key, and parses the value as the type of
+ * the default value, like getenv(), getenv_int(), getenv_float() and
+ * getenv_bool() in Rust.
  *
  * @param {string} key - Variable name (any case format)
  * @param {string|number|boolean} [defaultValue=''] - Default value if not found
@@ -122,266 +117,164 @@ export function toPascalCase(str) {
  * const port = getenv('PORT', 3000); // Returns number if env var is numeric
  */
 export function getenv(key, defaultValue = '') {
-  // Try different case formats
-  const variants = [
-    key, // Original
-    toUpperCase(key), // UPPER_CASE
-    toCamelCase(key), // camelCase
-    toKebabCase(key), // kebab-case
-    toSnakeCase(key), // snake_case
-    toPascalCase(key), // PascalCase
-  ];
-
-  // Try to find the variable using any case variant
-  for (const variant of variants) {
-    if (process.env[variant] !== undefined) {
-      // Use the official getenv package based on the type of defaultValue
-      try {
-        if (typeof defaultValue === 'number') {
-          // Use getenv.int or getenv.float
-          const isFloat = !Number.isInteger(defaultValue);
-          return isFloat
-            ? baseGetenv.float(variant, defaultValue)
-            : baseGetenv.int(variant, defaultValue);
-        }
-
-        if (typeof defaultValue === 'boolean') {
-          // Use getenv.boolish for flexible boolean parsing
-          return baseGetenv.boolish(variant, defaultValue);
-        }
-
-        // Otherwise use getenv.string
-        return baseGetenv.string(variant, defaultValue);
-      } catch {
-        // If getenv throws, return the default value
-        return defaultValue;
-      }
-    }
+  if (typeof defaultValue === 'number') {
+    return Number.isInteger(defaultValue)
+      ? native.getenvInt(key, defaultValue)
+      : native.getenvFloat(key, defaultValue);
   }
-
-  return defaultValue;
+  if (typeof defaultValue === 'boolean') {
+    return native.getenvBool(key, defaultValue);
+  }
+  return native.getenv(key, String(defaultValue));
 }
 
 // ============================================================================
-// Lino-env Loading Functions
+// Lino-env Parsing
 // ============================================================================
 
 /**
- * Load environment configuration from a .lenv file
+ * Parse the text of a .lenv file
  *
- * @param {string} filePath - Path to the .lenv file (default: '.lenv')
- * @returns {LinoEnv|null} LinoEnv instance with loaded data, or null if failed
+ * @param {string} text - `KEY: value` lines, with `#` comments
+ * @returns {Object} Values by key, the last one winning for repeated keys
  */
-function loadLinoEnv(filePath = '.lenv') {
-  try {
-    const env = new LinoEnv(filePath);
-    env.read();
-    return env;
-  } catch {
-    return null;
-  }
+export function parseLenv(text) {
+  return native.parseLenv(text);
 }
 
-/**
- * Apply .lenv configuration to process.env with case conversion
- * All values are stored as UPPER_CASE in process.env
- *
- * @param {string} filePath - Path to the .lenv file
- * @param {Object} options - Options for applying configuration
- * @param {boolean} options.override - Whether to override existing values (default: false)
- * @param {boolean} options.quiet - Suppress output (default: false)
- * @returns {Object} Object containing all loaded environment variables
- */
-function applyLinoEnv(filePath = '.lenv', options = {}) {
-  const { override = false, quiet = false } = options;
-
-  try {
-    const env = loadLinoEnv(filePath);
-    if (!env) {
-      return {};
-    }
-
-    const envObject = env.toObject();
-    const loaded = {};
+// ============================================================================
+// Main Configuration Function
+// ============================================================================
 
-    for (const [key, value] of Object.entries(envObject)) {
-      // Convert all keys to UPPER_CASE for process.env
-      const upperKey = toUpperCase(key);
+const TYPES = new Set(['string', 'number', 'integer', 'boolean']);
 
-      if (override || !process.env[upperKey]) {
-        process.env[upperKey] = value;
-        loaded[upperKey] = value;
-      }
+/**
+ * The options of the native resolver for the options of makeConfig()
+ */
+function optionSpecs(options) {
+  return Object.entries(options).map(([name, option = {}]) => {
+    const type = option.type ?? 'string';
+    if (!TYPES.has(type)) {
+      throw new TypeError(`Unknown type '${type}' of option ${name}`);
     }
-
-    if (!quiet && Object.keys(loaded).length > 0) {
-      console.log(
-        `📝 Loaded ${Object.keys(loaded).length} variables from ${filePath}`
-      );
+    const hasDefault = option.default !== undefined && option.default !== null;
+    if (type === 'boolean' && hasDefault && option.default !== false) {
+      throw new TypeError(`Boolean option ${name} can only default to false`);
     }
-
-    return loaded;
-  } catch (error) {
-    if (!quiet) {
-      console.error(`⚠️  Failed to load ${filePath}:`, error.message);
-    }
-    return {};
-  }
+    return {
+      name,
+      description: option.description,
+      default:
+        hasDefault && type !== 'boolean' ? String(option.default) : undefined,
+      short: option.alias,
+      type,
+      env: option.env,
+    };
+  });
 }
 
 /**
- * Load dotenvx configuration (DEPRECATED)
+ * Create unified configuration from multiple sources, throwing on failure
+ *
+ * Takes the same configuration as makeConfig(), but throws instead of
+ * printing and exiting. The error's `code` is `DisplayHelp` or
+ * `DisplayVersion` for --help and --version, with the text to print as the
+ * message, `ArgumentError` for bad arguments, and `ConfigError` for values
+ * that fail their type or cannot be read.
  *
- * @param {Object} options - Options to pass to dotenvx
- * @param {boolean} options.quiet - Suppress warnings (default: false)
- * @returns {Object} Result from dotenvx.config()
+ * @param {Object} config - Configuration object, see makeConfig()
+ * @returns {Object} Resolved configuration object with camelCase keys
+ * @throws {Error} If the configuration cannot be resolved
  */
-async function loadDotenvx(options = {}) {
-  const { quiet = false } = options;
-
-  if (!quiet) {
-    console.warn(
-      '\x1b[33m⚠️  DEPRECATED: dotenvx/.env files are deprecated.\x1b[0m\n' +
-        '   Please use Links Notation (.lenv files) for environment configuration instead.\n' +
-        '   See: https://github.com/link-foundation/lino-env'
+export function tryMakeConfig(config = {}) {
+  if (config.yargs !== undefined) {
+    throw new TypeError(
+      'makeConfig() no longer takes a yargs function; declare `options` instead'
     );
   }
+  const {
+    options = {},
+    lenv = {},
+    env = {},
+    argv = process.argv,
+    name,
+    about,
+    version,
+  } = config;
 
-  try {
-    const dotenvx = await import('@dotenvx/dotenvx');
-    return dotenvx.config({ ...options, quiet: true });
-  } catch {
-    if (!quiet) {
-      console.error('⚠️  dotenvx not installed, skipping .env loading');
-    }
-    return { parsed: {} };
-  }
+  return native.makeConfig({
+    argv: argv.slice(2),
+    lenv: lenv.enabled === false ? undefined : lenv.path || '.lenv',
+    env: env.enabled === true ? env.path || '.env' : undefined,
+    options: optionSpecs(options),
+    name,
+    about,
+    version,
+  });
 }
 
-// ============================================================================
-// Main Configuration Function
-// ============================================================================
-
 /**
  * Create unified configuration from multiple sources
  *
- * Priority (highest to lowest):
- * 1. CLI arguments (manually entered)
- * 2. getenv defaults (from process.env)
- * 3. --configuration flag (dynamic .lenv file)
+ * Options are resolved by the lino-arguments Rust crate. Priority (highest
+ * to lowest):
+ * 1. CLI arguments (`--api-key`, or the option's alias)
+ * 2. Environment variables (`API_KEY`, or the option's `env`)
+ * 3. --configuration / -c flag (dynamic .lenv file)
  * 4. .lenv file
- * 5. dotenvx/.env file (DEPRECATED)
+ * 5. .env file
+ * 6. The option's default
+ *
+ * --help and --version print and exit the process with status 0; bad
+ * arguments and invalid values print an error and exit with status 2, as
+ * in Rust. Use tryMakeConfig() to handle them instead.
  *
  * @param {Object} config - Configuration object
- * @param {Function} config.yargs - Yargs configuration function: ({ yargs, getenv }) => yargs
+ * @param {Object} config.options - Options by name (any case), each with:
+ *   `type` ('string', 'number', 'integer' or 'boolean', default 'string'),
+ *   `default`, `description`, `alias` (one letter), and `env` (the
+ *   environment variable to read instead of the name in UPPER_CASE)
  * @param {Object} [config.lenv] - Lino-env configuration
  * @param {boolean} [config.lenv.enabled=true] - Enable .lenv loading
  * @param {string} [config.lenv.path='.lenv'] - Path to .lenv file
- * @param {boolean} [config.lenv.override=true] - Override existing env vars
- * @param {Object} [config.env] - Dotenvx/.env configuration (DEPRECATED)
+ * @param {Object} [config.env] - .env configuration
  * @param {boolean} [config.env.enabled=false] - Enable .env loading
- * @param {Object} [config.getenv] - Getenv configuration
- * @param {boolean} [config.getenv.enabled=true] - Enable getenv helper
+ * @param {string} [config.env.path='.env'] - Path to .env file
+ * @param {string} [config.name] - Application name for the help text
+ * @param {string} [config.about] - Application description for the help text
+ * @param {string} [config.version] - Version for --version
  * @param {string[]} [config.argv] - Custom argv to parse (default: process.argv)
- * @returns {Object} Parsed configuration object with camelCase keys
- *
- * @example
- * // Hero example (defaults)
- * const config = makeConfig({
- *   yargs: ({ yargs, getenv }) => yargs
- *     .option('port', { type: 'number', default: getenv('PORT', 3000) })
- *     .option('verbose', { type: 'boolean', default: false })
- * });
+ * @returns {Object} Resolved configuration object with camelCase keys;
+ *   number options without a value are left out
  *
  * @example
- * // Explicit configuration
  * const config = makeConfig({
- *   lenv: { enabled: true },
- *   env: { enabled: true },
- *   getenv: { enabled: true },
- *   yargs: ({ yargs, getenv }) => yargs
- *     .option('api-key', { type: 'string', default: getenv('API_KEY', '') })
- *     .option('port', { type: 'number', default: getenv('PORT', 3000) })
+ *   options: {
+ *     port: { type: 'number', default: 3000 },
+ *     'api-key': { description: 'API key' },
+ *     verbose: { type: 'boolean', alias: 'v' },
+ *   },
  * });
  */
 export function makeConfig(config = {}) {
-  const {
-    yargs: yargsConfigFn,
-    lenv = {},
-    env = {},
-    getenv: getenvConfig = {},
-    argv = process.argv,
-  } = config;
-
-  // Default options
-  const lenvEnabled = lenv.enabled !== false; // Default: true
-  const lenvPath = lenv.path || '.lenv';
-  const lenvOverride = lenv.override !== false; // Default: true
-
-  const envEnabled = env.enabled === true; // Default: false
-  const envQuiet = env.quiet !== false; // Default: true
-
-  const getenvEnabled = getenvConfig.enabled !== false; // Default: true
-
-  // Step 1: Load dotenvx/.env (DEPRECATED, lowest priority)
-  if (envEnabled) {
-    loadDotenvx({ quiet: envQuiet });
-  }
-
-  // Step 2: Load .lenv file (overrides .env)
-  if (lenvEnabled) {
-    applyLinoEnv(lenvPath, { override: lenvOverride, quiet: false });
-  }
-
-  // Step 3: Parse initial CLI args to check for --configuration
-  const initialYargs = yargs(hideBin(argv))
-    .option('configuration', {
-      type: 'string',
-      describe: 'Path to configuration .lenv file',
-      alias: 'c',
-    })
-    .help(false) // Disable help for initial parse
-    .version(false) // Disable version for initial parse
-    .exitProcess(false); // Don't exit on parse errors
-
-  let initialParsed;
   try {
-    initialParsed = initialYargs.parseSync();
-  } catch {
-    initialParsed = {};
-  }
-
-  // Step 4: Load --configuration file if specified (overrides default .lenv)
-  if (initialParsed.configuration) {
-    applyLinoEnv(initialParsed.configuration, { override: true, quiet: false });
-  }
-
-  // Step 5: Configure yargs with user options + getenv helper
-  const yargsInstance = yargs(hideBin(argv)).option('configuration', {
-    type: 'string',
-    describe: 'Path to configuration .lenv file',
-    alias: 'c',
-  });
-
-  // Pass getenv helper if enabled
-  const getenvHelper = getenvEnabled ? getenv : () => '';
-  const configuredYargs = yargsConfigFn
-    ? yargsConfigFn({ yargs: yargsInstance, getenv: getenvHelper })
-    : yargsInstance;
-
-  // Step 6: Parse final configuration (CLI args have highest priority)
-  const parsed = configuredYargs.parseSync();
-
-  // Step 7: Convert kebab-case keys to camelCase for result object
-  const result = {};
-  for (const [key, value] of Object.entries(parsed)) {
-    if (key !== '_' && key !== '$0') {
-      const camelKey = toCamelCase(key);
-      result[camelKey] = value;
+    return tryMakeConfig(config);
+  } catch (error) {
+    switch (error.code) {
+      case 'DisplayHelp':
+      case 'DisplayVersion':
+        console.log(error.message.trimEnd());
+        return process.exit(0);
+      case 'ArgumentError':
+        console.error(error.message.trimEnd());
+        return process.exit(2);
+      case 'ConfigError':
+        console.error(`error: ${error.message}`);
+        return process.exit(2);
+      default:
+        throw error;
     }
   }
-
-  return result;
 }
 
 // ============================================================================
@@ -434,5 +327,3 @@ export function parseLinoArguments(linoString) {
 
 // Export all components for advanced usage
 export { Parser } from 'links-notation';
-export { LinoEnv } from 'lino-env';
-export { yargs };
@@ -3,15 +3,45 @@ import { writeFileSync, unlinkSync, existsSync } from 'node:fs';
 import { join } from 'node:path';
 import {
   makeConfig,
+  tryMakeConfig,
   getenv,
   toUpperCase,
   toCamelCase,
   toKebabCase,
   toSnakeCase,
   toPascalCase,
+  convertCase,
+  detectCase,
+  canonicalKey,
+  parseLenv,
   parseLinoArguments,
 } from '../src/index.js';
 
+/**
+ * The error `fn` throws, if any
+ */
+function thrown(fn) {
+  try {
+    fn();
+  } catch (error) {
+    return error;
+  }
+  return undefined;
+}
+
+/**
+ * Restore process.env to `snapshot` in place, since the Rust core reads
+ * the environment of the process rather than the process.env object
+ */
+function restoreEnvTo(snapshot) {
+  for (const key of Object.keys(process.env)) {
+    if (!(key in snapshot)) {
+      delete process.env[key];
+    }
+  }
+  Object.assign(process.env, snapshot);
+}
+
 // ============================================================================
 // Case Conversion Tests
 // ============================================================================
@@ -60,12 +90,12 @@ describe('Case Conversion Utilities', () => {
     });
 
     it('should convert PascalCase to camelCase', () => {
-      expect(toCamelCase('ApiKey')).toBe('apikey');
-      expect(toCamelCase('MyVariableName')).toBe('myvariablename');
+      expect(toCamelCase('ApiKey')).toBe('apiKey');
+      expect(toCamelCase('MyVariableName')).toBe('myVariableName');
     });
 
     it('should handle already camelCase', () => {
-      expect(toCamelCase('apiKey')).toBe('apikey');
+      expect(toCamelCase('apiKey')).toBe('apiKey');
     });
   });
 
@@ -112,7 +142,7 @@ describe('Case Conversion Utilities', () => {
 
   describe('toPascalCase', () => {
     it('should convert camelCase to PascalCase', () => {
-      expect(toPascalCase('apiKey')).toBe('Apikey');
+      expect(toPascalCase('apiKey')).toBe('ApiKey');
     });
 
     it('should convert kebab-case to PascalCase', () => {
@@ -126,11 +156,58 @@ describe('Case Conversion Utilities', () => {
     });
 
     it('should handle already PascalCase', () => {
-      expect(toPascalCase('ApiKey')).toBe('Apikey');
+      expect(toPascalCase('ApiKey')).toBe('ApiKey');
+    });
+  });
+
+  describe('convertCase', () => {
+    it('should convert to any case the Rust crate knows', () => {
+      expect(convertCase('api-key', 'upper-snake')).toBe('API_KEY');
+      expect(convertCase('apiKey', 'train')).toBe('Api-Key');
+      expect(convertCase('API_KEY', 'dot')).toBe('api.key');
+    });
+
+    it('should throw on an unknown case', () => {
+      const error = thrown(() => convertCase('api-key', 'shouting'));
+      expect(error.message.includes('shouting')).toBe(true);
+    });
+  });
+
+  describe('detectCase', () => {
+    it('should name the case of a string', () => {
+      expect(detectCase('apiKey')).toBe('camel');
+      expect(detectCase('API_KEY')).toBe('upper-snake');
+      expect(detectCase('api-key')).toBe('kebab');
+    });
+  });
+
+  describe('canonicalKey', () => {
+    it('should give every case variant the same key', () => {
+      expect(canonicalKey('API_KEY')).toBe('api-key');
+      expect(canonicalKey('apiKey')).toBe('api-key');
+      expect(canonicalKey('ApiKey')).toBe('api-key');
     });
   });
 });
 
+// ============================================================================
+// parseLenv Tests
+// ============================================================================
+
+describe('parseLenv', () => {
+  it('should parse key-value lines and skip comments', () => {
+    const values = parseLenv('# server\nPORT: 3000\nHOST: localhost\n');
+    expect(values.PORT).toBe('3000');
+    expect(values.HOST).toBe('localhost');
+    expect(Object.keys(values).length).toBe(2);
+  });
+
+  it('should keep the last value of a repeated key', () => {
+    expect(parseLenv('PORT: 3000\nPORT: 8080\n').PORT).toBe('8080');
+  });
+});
+
+
 // ============================================================================
 // getenv Tests
 // ============================================================================
@@ -149,7 +226,7 @@ describe('getenv', () => {
   }
 
   function restoreEnv() {
-    process.env = { ...originalEnv };
+    restoreEnvTo(originalEnv);
   }
 
   it('should find variable in UPPER_CASE', () => {
@@ -213,6 +290,20 @@ describe('getenv', () => {
       restoreEnv();
     }
   });
+
+  it('should preserve the type of the default value', () => {
+    cleanupTestVars();
+    try {
+      process.env.TEST_VAR = '42';
+      expect(getenv('testVar', 3000)).toBe(42);
+      expect(getenv('testVar', 0.5)).toBe(42);
+      process.env.TEST_VAR = 'yes';
+      expect(getenv('testVar', false)).toBe(true);
+      expect(getenv('testVar', 3000)).toBe(3000);
+    } finally {
+      restoreEnv();
+    }
+  });
 });
 
 // ============================================================================
@@ -222,6 +313,7 @@ describe('getenv', () => {
 describe('makeConfig', () => {
   const testLenvFile = join(process.cwd(), '.test-makeconfig.lenv');
   const testConfigFile = join(process.cwd(), '.test-config.lenv');
+  const testEnvFile = join(process.cwd(), '.test-makeconfig.env');
   const originalEnv = { ...process.env };
 
   function cleanupTestEnv() {
@@ -234,7 +326,7 @@ describe('makeConfig', () => {
   }
 
   function cleanupTestFiles() {
-    [testLenvFile, testConfigFile].forEach((file) => {
+    [testLenvFile, testConfigFile, testEnvFile].forEach((file) => {
       if (existsSync(file)) {
         unlinkSync(file);
       }
@@ -242,7 +334,7 @@ describe('makeConfig', () => {
   }
 
   function restoreEnv() {
-    process.env = { ...originalEnv };
+    restoreEnvTo(originalEnv);
   }
 
   function cleanup() {
@@ -255,15 +347,15 @@ describe('makeConfig', () => {
       cleanupTestEnv();
       try {
         const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs
-              .option('port', { type: 'number', default: getenv('PORT', 3000) })
-              .option('verbose', { type: 'boolean', default: false }),
+          options: {
+            'app-port': { type: 'number', default: 3000 },
+            'app-verbose': { type: 'boolean' },
+          },
           argv: ['node', 'script.js'],
         });
 
-        expect(config.port).toBe(3000);
-        expect(config.verbose).toBe(false);
+        expect(config.appPort).toBe(3000);
+        expect(config.appVerbose).toBe(false);
       } finally {
         cleanup();
       }
@@ -272,16 +364,17 @@ describe('makeConfig', () => {
     it('should use CLI arguments with highest priority', () => {
       cleanupTestEnv();
       try {
+        process.env.APP_PORT = '5000';
         const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs
-              .option('port', { type: 'number', default: getenv('PORT', 3000) })
-              .option('verbose', { type: 'boolean', default: false }),
-          argv: ['node', 'script.js', '--port', '8080', '--verbose'],
+          options: {
+            'app-port': { type: 'number', default: 3000 },
+            'app-verbose': { type: 'boolean' },
+          },
+          argv: ['node', 'script.js', '--app-port', '8080', '--app-verbose'],
         });
 
-        expect(config.port).toBe(8080);
-        expect(config.verbose).toBe(true);
+        expect(config.appPort).toBe(8080);
+        expect(config.appVerbose).toBe(true);
       } finally {
         cleanup();
       }
@@ -291,13 +384,26 @@ describe('makeConfig', () => {
       cleanupTestEnv();
       try {
         const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs.option('api-key', { type: 'string', default: 'key123' }),
+          options: { 'app-api-key': { default: 'key123' } },
           argv: ['node', 'script.js'],
         });
 
-        expect(config.apiKey).toBe('key123');
-        expect(config['api-key']).toBe(undefined);
+        expect(config.appApiKey).toBe('key123');
+        expect(config['app-api-key']).toBe(undefined);
+      } finally {
+        cleanup();
+      }
+    });
+
+    it('should leave out numbers without a value', () => {
+      cleanupTestEnv();
+      try {
+        const config = makeConfig({
+          options: { 'app-timeout': { type: 'number' } },
+          argv: ['node', 'script.js'],
+        });
+
+        expect('appTimeout' in config).toBe(false);
       } finally {
         cleanup();
       }
@@ -311,11 +417,7 @@ describe('makeConfig', () => {
         writeFileSync(testLenvFile, 'APP_PORT: 5000\nAPP_HOST: localhost\n');
 
         const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs.option('port', {
-              type: 'number',
-              default: getenv('APP_PORT', 3000),
-            }),
+          options: { port: { type: 'number', default: 3000, env: 'APP_PORT' } },
           lenv: { path: testLenvFile },
           argv: ['node', 'script.js'],
         });
@@ -334,11 +436,7 @@ describe('makeConfig', () => {
         writeFileSync(testConfigFile, 'APP_PORT: 9000\n');
 
         const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs.option('port', {
-              type: 'number',
-              default: getenv('APP_PORT', 3000),
-            }),
+          options: { port: { type: 'number', default: 3000, env: 'APP_PORT' } },
           lenv: { path: testLenvFile },
           argv: ['node', 'script.js', '--configuration', testConfigFile],
         });
@@ -357,11 +455,7 @@ describe('makeConfig', () => {
         writeFileSync(testLenvFile, 'APP_PORT: 5000\n');
 
         const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs.option('port', {
-              type: 'number',
-              default: getenv('APP_PORT', 3000),
-            }),
+          options: { port: { type: 'number', default: 3000, env: 'APP_PORT' } },
           lenv: { path: testLenvFile },
           argv: ['node', 'script.js', '--port', '7000'],
         });
@@ -373,15 +467,29 @@ describe('makeConfig', () => {
       }
     });
 
+    it('should prioritize environment variables over .lenv', () => {
+      cleanupTestEnv();
+      try {
+        writeFileSync(testLenvFile, 'APP_PORT: 5000\n');
+        process.env.APP_PORT = '6000';
+
+        const config = makeConfig({
+          options: { port: { type: 'number', default: 3000, env: 'APP_PORT' } },
+          lenv: { path: testLenvFile },
+          argv: ['node', 'script.js'],
+        });
+
+        expect(config.port).toBe(6000);
+      } finally {
+        cleanup();
+      }
+    });
+
     it('should handle missing .lenv file gracefully', () => {
       cleanupTestEnv();
       try {
         const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs.option('port', {
-              type: 'number',
-              default: getenv('APP_PORT', 3000),
-            }),
+          options: { port: { type: 'number', default: 3000, env: 'APP_PORT' } },
           lenv: { path: 'non-existent.lenv' },
           argv: ['node', 'script.js'],
         });
@@ -394,51 +502,46 @@ describe('makeConfig', () => {
   });
 
   describe('Case Conversion in Environment Loading', () => {
-    it('should convert all keys to UPPER_CASE in process.env', () => {
+    it('should set .lenv keys in process.env as written', () => {
       cleanupTestEnv();
       try {
         writeFileSync(
           testLenvFile,
-          'apiKey: key123\nmy-port: 3000\nmy_host: localhost\n'
+          'APP_API_KEY: key123\nappHost: localhost\n'
         );
 
-        makeConfig({
-          yargs: ({ yargs }) => yargs,
+        const config = makeConfig({
+          options: { 'app-api-key': {}, 'app-host': { default: 'default' } },
           lenv: { path: testLenvFile },
           argv: ['node', 'script.js'],
         });
 
-        expect(process.env.API_KEY).toBe('key123');
-        expect(process.env.MY_PORT).toBe('3000');
-        expect(process.env.MY_HOST).toBe('localhost');
+        // Options read the key named like their environment variable
+        expect(config.appApiKey).toBe('key123');
+        expect(config.appHost).toBe('default');
+        expect(process.env.appHost).toBe('localhost');
+        expect(getenv('APP_HOST')).toBe('localhost');
       } finally {
         cleanup();
       }
     });
 
-    it('should find env vars in any case format via getenv', () => {
+    it('should read options from their UPPER_CASE environment variables', () => {
       cleanupTestEnv();
       try {
-        process.env.API_KEY = 'secret';
+        process.env.APP_API_KEY = 'secret';
 
         const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs
-              .option('api-key', {
-                type: 'string',
-                default: getenv('apiKey', 'default'),
-              })
-              .option('another-key', {
-                type: 'string',
-                default: getenv('anotherKey', 'default'),
-              }),
+          options: {
+            'app-api-key': { default: 'default' },
+            'app-another-key': { default: 'default' },
+          },
           argv: ['node', 'script.js'],
         });
 
-        // getenv should find API_KEY when asked for apiKey
-        expect(config.apiKey).toBe('secret');
+        expect(config.appApiKey).toBe('secret');
         // Non-existent should use default
-        expect(config.anotherKey).toBe('default');
+        expect(config.appAnotherKey).toBe('default');
       } finally {
         cleanup();
       }
@@ -452,11 +555,7 @@ describe('makeConfig', () => {
         writeFileSync(testLenvFile, 'APP_PORT: 5000\n');
 
         const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs.option('port', {
-              type: 'number',
-              default: getenv('APP_PORT', 3000),
-            }),
+          options: { port: { type: 'number', default: 3000, env: 'APP_PORT' } },
           lenv: { enabled: false, path: testLenvFile },
           argv: ['node', 'script.js'],
         });
@@ -469,60 +568,59 @@ describe('makeConfig', () => {
       }
     });
 
-    it('should support enabling env/dotenvx (deprecated)', () => {
+    it('should support enabling .env files', () => {
       cleanupTestEnv();
       try {
-        // Note: This test will show deprecation warning
+        writeFileSync(testEnvFile, 'APP_PORT=4000\nAPP_HOST=env-host\n');
+        writeFileSync(testLenvFile, 'APP_PORT: 5000\n');
+
         const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs.option('test', { type: 'boolean', default: false }),
-          env: { enabled: true, quiet: true },
+          options: {
+            'app-port': { type: 'number', default: 3000 },
+            'app-host': {},
+          },
+          lenv: { path: testLenvFile },
+          env: { enabled: true, path: testEnvFile },
           argv: ['node', 'script.js'],
         });
 
-        expect(config.test).toBe(false);
+        // .lenv wins over .env
+        expect(config.appPort).toBe(5000);
+        expect(config.appHost).toBe('env-host');
       } finally {
         cleanup();
       }
     });
 
-    it('should support disabling getenv', () => {
+    it('should support configuration alias -c', () => {
       cleanupTestEnv();
       try {
-        process.env.TEST_PORT = '5000';
+        writeFileSync(testConfigFile, 'APP_PORT: 9000\n');
 
         const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs.option('port', {
-              type: 'number',
-              // getenv should return empty string when disabled
-              default: getenv('TEST_PORT', 3000) || 3000,
-            }),
-          getenv: { enabled: false },
-          argv: ['node', 'script.js'],
+          options: { port: { type: 'number', default: 3000, env: 'APP_PORT' } },
+          argv: ['node', 'script.js', '-c', testConfigFile],
         });
 
-        expect(config.port).toBe(3000);
+        expect(config.port).toBe(9000);
       } finally {
         cleanup();
       }
     });
 
-    it('should support configuration alias -c', () => {
+    it('should support option aliases', () => {
       cleanupTestEnv();
       try {
-        writeFileSync(testConfigFile, 'APP_PORT: 9000\n');
-
         const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs.option('port', {
-              type: 'number',
-              default: getenv('APP_PORT', 3000),
-            }),
-          argv: ['node', 'script.js', '-c', testConfigFile],
+          options: {
+            'app-port': { type: 'integer', alias: 'p' },
+            'app-verbose': { type: 'boolean', alias: 'v' },
+          },
+          argv: ['node', 'script.js', '-p', '8080', '-v'],
         });
 
-        expect(config.port).toBe(9000);
+        expect(config.appPort).toBe(8080);
+        expect(config.appVerbose).toBe(true);
       } finally {
         cleanup();
       }
@@ -530,7 +628,7 @@ describe('makeConfig', () => {
   });
 
   describe('Complete Priority Chain', () => {
-    it('should demonstrate full priority: CLI > getenv > --configuration > .lenv', () => {
+    it('should demonstrate full priority: CLI > --configuration > .lenv', () => {
       cleanupTestEnv();
       try {
         // Setup: .lenv with base config
@@ -545,94 +643,57 @@ describe('makeConfig', () => {
           'APP_PORT: 5000\nAPP_HOST: override-host\n'
         );
 
+        const options = {
+          'app-port': { type: 'number', default: 0 },
+          'app-host': {},
+          'app-name': {},
+        };
+
         // Test 1: Only .lenv (no CLI, no --configuration)
         let config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs
-              .option('port', {
-                type: 'number',
-                default: getenv('APP_PORT', 0),
-              })
-              .option('host', {
-                type: 'string',
-                default: getenv('APP_HOST', ''),
-              })
-              .option('name', {
-                type: 'string',
-                default: getenv('APP_NAME', ''),
-              }),
+          options,
           lenv: { path: testLenvFile },
           argv: ['node', 'script.js'],
         });
 
-        expect(config.port).toBe(3000);
-        expect(config.host).toBe('localhost');
-        expect(config.name).toBe('base');
+        expect(config.appPort).toBe(3000);
+        expect(config.appHost).toBe('localhost');
+        expect(config.appName).toBe('base');
 
         // Clean env for next test
-        delete process.env.APP_PORT;
-        delete process.env.APP_HOST;
-        delete process.env.APP_NAME;
+        cleanupTestEnv();
 
         // Test 2: .lenv + --configuration
         config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs
-              .option('port', {
-                type: 'number',
-                default: getenv('APP_PORT', 0),
-              })
-              .option('host', {
-                type: 'string',
-                default: getenv('APP_HOST', ''),
-              })
-              .option('name', {
-                type: 'string',
-                default: getenv('APP_NAME', ''),
-              }),
+          options,
           lenv: { path: testLenvFile },
           argv: ['node', 'script.js', '--configuration', testConfigFile],
         });
 
-        expect(config.port).toBe(5000); // from --configuration
-        expect(config.host).toBe('override-host'); // from --configuration
-        expect(config.name).toBe('base'); // from .lenv (not in --configuration)
+        expect(config.appPort).toBe(5000); // from --configuration
+        expect(config.appHost).toBe('override-host'); // from --configuration
+        expect(config.appName).toBe('base'); // from .lenv (not in --configuration)
 
         // Clean env for next test
-        delete process.env.APP_PORT;
-        delete process.env.APP_HOST;
-        delete process.env.APP_NAME;
+        cleanupTestEnv();
 
         // Test 3: .lenv + --configuration + CLI
         config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs
-              .option('port', {
-                type: 'number',
-                default: getenv('APP_PORT', 0),
-              })
-              .option('host', {
-                type: 'string',
-                default: getenv('APP_HOST', ''),
-              })
-              .option('name', {
-                type: 'string',
-                default: getenv('APP_NAME', ''),
-              }),
+          options,
           lenv: { path: testLenvFile },
           argv: [
             'node',
             'script.js',
             '--configuration',
             testConfigFile,
-            '--port',
+            '--app-port',
             '9000',
           ],
         });
 
-        expect(config.port).toBe(9000); // from CLI (highest priority)
-        expect(config.host).toBe('override-host'); // from --configuration
-        expect(config.name).toBe('base'); // from .lenv
+        expect(config.appPort).toBe(9000); // from CLI (highest priority)
+        expect(config.appHost).toBe('override-host'); // from --configuration
+        expect(config.appName).toBe('base'); // from .lenv
       } finally {
         cleanup();
       }
@@ -640,25 +701,14 @@ describe('makeConfig', () => {
   });
 
   describe('Built-in Flag Conflicts (Issue #14)', () => {
-    it('should allow user-defined --version option by calling .version(false)', () => {
+    it('should allow a user-defined --version option without a version', () => {
       cleanupTestEnv();
       try {
         const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs
-              .version(false) // Disable built-in --version flag
-              .help(false) // Disable built-in --help flag
-              .option('version', {
-                type: 'string',
-                description: 'Version to process',
-                default: '',
-              })
-              .option('repository', {
-                type: 'string',
-                description: 'Repository name',
-                default: '',
-              })
-              .strict(),
+          options: {
+            version: { description: 'Version to process', default: '' },
+            repository: { description: 'Repository name', default: '' },
+          },
           argv: [
             'node',
             'script.js',
@@ -669,7 +719,6 @@ describe('makeConfig', () => {
           ],
         });
 
-        // Should parse --version as user-defined option, not yargs' built-in
         expect(config.version).toBe('0.8.36');
         expect(config.repository).toBe('link-foundation/test-anywhere');
       } finally {
@@ -677,65 +726,27 @@ describe('makeConfig', () => {
       }
     });
 
-    it('should allow user-defined --help option', () => {
+    it('should allow user-defined --help-text option', () => {
       cleanupTestEnv();
       try {
         const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs
-              .option('help-text', {
-                type: 'string',
-                description: 'Help text to display',
-                default: '',
-              })
-              .strict(),
+          options: {
+            'help-text': { description: 'Help text to display', default: '' },
+          },
           argv: ['node', 'script.js', '--help-text', 'Custom help message'],
         });
 
-        // Should parse --help-text as user option
         expect(config.helpText).toBe('Custom help message');
       } finally {
         cleanup();
       }
     });
 
-    it('should work with --version in strict mode', () => {
-      cleanupTestEnv();
-      try {
-        const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs
-              .version(false) // Disable built-in version to allow user-defined
-              .option('version', {
-                type: 'string',
-                default: '',
-              })
-              .option('name', {
-                type: 'string',
-                default: '',
-              })
-              .strict(),
-          argv: ['node', 'script.js', '--version', '1.2.3', '--name', 'test'],
-        });
-
-        expect(config.version).toBe('1.2.3');
-        expect(config.name).toBe('test');
-      } finally {
-        cleanup();
-      }
-    });
-
     it('should handle --version with boolean type', () => {
       cleanupTestEnv();
       try {
         const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs
-              .version(false) // Disable built-in version to allow user-defined
-              .option('version', {
-                type: 'boolean',
-                default: false,
-              }),
+          options: { version: { type: 'boolean' } },
           argv: ['node', 'script.js', '--version'],
         });
 
@@ -745,198 +756,96 @@ describe('makeConfig', () => {
       }
     });
 
-    it('should allow users to explicitly enable help', () => {
-      cleanupTestEnv();
-      try {
-        const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs
-              .option('port', {
-                type: 'number',
-                default: 3000,
-              })
-              .help(), // User explicitly enables help
-          argv: ['node', 'script.js', '--port', '8080'],
-        });
-
-        expect(config.port).toBe(8080);
-      } finally {
-        cleanup();
-      }
-    });
-
-    it('should work with multiple custom options including version', () => {
-      cleanupTestEnv();
-      try {
-        const config = makeConfig({
-          yargs: ({ yargs, getenv }) =>
-            yargs
-              .version(false) // Disable built-in version to allow user-defined
-              .option('version', {
-                type: 'string',
-                default: getenv('VERSION') || '',
-              })
-              .option('repository', {
-                type: 'string',
-                default: getenv('REPOSITORY') || '',
-              })
-              .option('release-id', {
-                type: 'string',
-                default: getenv('RELEASE_ID') || '',
-              })
-              .strict(),
-          argv: [
-            'node',
-            'script.js',
-            '--version',
-            'v0.8.36',
-            '--repository',
-            'test-repo',
-            '--release-id',
-            '12345',
-          ],
-        });
-
-        expect(config.version).toBe('v0.8.36');
-        expect(config.repository).toBe('test-repo');
-        expect(config.releaseId).toBe('12345');
-      } finally {
-        cleanup();
-      }
-    });
-  });
-
-  describe('Explicitly Enabling Built-in Version and Help', () => {
-    it('should allow explicitly enabling built-in --version', () => {
+    it('should parse options alongside the built-in --version', () => {
       cleanupTestEnv();
       try {
         const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs
-              .option('port', {
-                type: 'number',
-                default: 3000,
-              })
-              .option('host', {
-                type: 'string',
-                default: 'localhost',
-              })
-              .version('1.0.0'), // Explicitly enable built-in version
+          options: {
+            port: { type: 'number', default: 3000 },
+            host: { default: 'localhost' },
+          },
+          version: '1.0.0',
           argv: ['node', 'script.js', '--port', '8080'],
         });
 
-        // Config should be parsed correctly
         expect(config.port).toBe(8080);
         expect(config.host).toBe('localhost');
       } finally {
         cleanup();
       }
     });
+  });
 
-    it('should allow explicitly enabling both --version and --help', () => {
-      cleanupTestEnv();
-      try {
-        const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs
-              .option('debug', {
-                type: 'boolean',
-                default: false,
-              })
-              .option('verbose', {
-                type: 'boolean',
-                default: false,
-              })
-              .version('2.5.0') // Enable version
-              .help(), // Enable help
-          argv: ['node', 'script.js', '--debug', '--verbose'],
-        });
-
-        // Config should be parsed correctly
-        expect(config.debug).toBe(true);
-        expect(config.verbose).toBe(true);
-      } finally {
-        cleanup();
-      }
-    });
-
-    it('should allow version with alias', () => {
-      cleanupTestEnv();
-      try {
-        const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs
-              .option('config', {
-                type: 'string',
-                default: './config.json',
-              })
-              .version('3.0.0')
-              .alias('version', 'v'), // Add -v alias
-          argv: ['node', 'script.js', '--config', './custom.json'],
-        });
-
-        // Config should be parsed correctly
-        expect(config.config).toBe('./custom.json');
-      } finally {
-        cleanup();
-      }
-    });
-
-    it('should allow help with alias', () => {
-      cleanupTestEnv();
-      try {
-        const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs
-              .option('port', {
-                type: 'number',
-                default: 3000,
-              })
-              .help()
-              .alias('help', 'h'), // Add -h alias
-          argv: ['node', 'script.js', '--port', '9000'],
-        });
-
-        // Config should be parsed correctly
-        expect(config.port).toBe(9000);
-      } finally {
-        cleanup();
-      }
-    });
-
-    it('should work with both built-in version/help and custom options', () => {
-      cleanupTestEnv();
+  describe('Errors', () => {
+    it('should throw on bad arguments from tryMakeConfig', () => {
+      const options = { 'app-port': { type: 'integer', default: 3000 } };
+      let error = thrown(() =>
+        tryMakeConfig({ options, argv: ['node', 'script.js', '--bogus'] })
+      );
+      expect(error.code).toBe('ArgumentError');
+      expect(error.message.includes("'--bogus'")).toBe(true);
+
+      error = thrown(() =>
+        tryMakeConfig({ options, argv: ['node', 'script.js', '--help'] })
+      );
+      expect(error.code).toBe('DisplayHelp');
+      expect(error.message.includes('--app-port')).toBe(true);
+
+      error = thrown(() =>
+        tryMakeConfig({
+          options,
+          name: 'app',
+          version: '2.5.0',
+          argv: ['node', 'script.js', '--version'],
+        })
+      );
+      expect(error.code).toBe('DisplayVersion');
+      expect(error.message.trim()).toBe('app 2.5.0');
+    });
+
+    it('should throw on values that fail their type', () => {
+      const error = thrown(() =>
+        tryMakeConfig({
+          options: { 'app-port': { type: 'integer' } },
+          argv: ['node', 'script.js', '--app-port', 'many'],
+        })
+      );
+      expect(error.code).toBe('ConfigError');
+      expect(error.message.includes('not a valid integer')).toBe(true);
+    });
+
+    it('should reject yargs functions and unknown types', () => {
+      let error = thrown(() =>
+        tryMakeConfig({ yargs: ({ yargs }) => yargs })
+      );
+      expect(error instanceof TypeError).toBe(true);
+
+      error = thrown(() =>
+        tryMakeConfig({ options: { port: { type: 'float' } }, argv: [] })
+      );
+      expect(error instanceof TypeError).toBe(true);
+    });
+
+    it('should print help and exit from makeConfig', () => {
+      const { exit } = process;
+      const { log } = console;
+      const printed = [];
+      process.exit = (code) => {
+        throw new Error(`exit ${code}`);
+      };
+      console.log = (text) => printed.push(text);
       try {
-        const config = makeConfig({
-          yargs: ({ yargs }) =>
-            yargs
-              .option('output', {
-                type: 'string',
-                description: 'Output directory',
-                default: './output',
-              })
-              .option('format', {
-                type: 'string',
-                description: 'Output format',
-                default: 'json',
-              })
-              .version('1.2.3')
-              .help(),
-          argv: [
-            'node',
-            'script.js',
-            '--output',
-            './results',
-            '--format',
-            'xml',
-          ],
-        });
-
-        // All custom options should be parsed correctly
-        expect(config.output).toBe('./results');
-        expect(config.format).toBe('xml');
+        const error = thrown(() =>
+          makeConfig({
+            options: { 'app-port': { default: '3000' } },
+            argv: ['node', 'script.js', '--help'],
+          })
+        );
+        expect(error.message).toBe('exit 0');
+        expect(printed.join('\n').includes('--app-port')).toBe(true);
       } finally {
-        cleanup();
+        process.exit = exit;
+        console.log = log;
       }
     });
   });
@@ -0,0 +1,110 @@
+//! Benchmarks of the hot paths: case conversion, environment lookups,
+//! `.lenv` parsing, resolving a configuration of 500 keys, and looking keys
+//! up in it.
+//!
+//! Run them with `cargo bench --bench performance`, then compare the
+//! results with the baseline in `benches/baseline.json` by running
+//! `node scripts/compare-benchmarks.mjs` from the repository root.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lino_arguments::{
+    convert_case, getenv, parse_lenv, try_make_config_from, Case, EnvSnapshot, KeySpec, Schema,
+    ValueType,
+};
+
+/// The number of keys of the configuration resolved by `resolution`.
+const KEYS: usize = 500;
+
+fn case_conversion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("case_conversion");
+    let cases = [
+        ("kebab", Case::Kebab),
+        ("upper_snake", Case::UpperSnake),
+        ("camel", Case::Camel),
+        ("pascal", Case::Pascal),
+    ];
+    for (name, case) in cases {
+        // A key already in the target case borrows it; the others allocate
+        for input in ["maxConnectionsPerHost", "MAX_CONNECTIONS_PER_HOST"] {
+            group.bench_with_input(BenchmarkId::new(name, input), input, |b, input| {
+                b.iter(|| convert_case(black_box(input), case))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn environment(c: &mut Criterion) {
+    std::env::set_var("BENCH_API_KEY", "sk-live-1234");
+    let mut group = c.benchmark_group("getenv");
+    group.bench_function("hit", |b| {
+        b.iter(|| getenv(black_box("benchApiKey"), "default"))
+    });
+    group.bench_function("miss", |b| {
+        b.iter(|| getenv(black_box("benchMissingKey"), "default"))
+    });
+    group.bench_function("snapshot_capture", |b| b.iter(EnvSnapshot::capture));
+    let env = EnvSnapshot::capture();
+    group.bench_function("snapshot_hit", |b| {
+        b.iter(|| env.getenv(black_box("benchApiKey"), "default"))
+    });
+    group.finish();
+}
+
+fn lenv_parsing(c: &mut Criterion) {
+    let text: String = (0..KEYS)
+        .map(|i| format!("# key {i}\nBENCH_KEY_{i}: value {i}\n"))
+        .collect();
+    c.bench_function("parse_lenv/500", |b| {
+        b.iter(|| parse_lenv(black_box(&text)))
+    });
+}
+
+fn resolution(c: &mut Criterion) {
+    // Half of the keys are set in the environment, the others default
+    let schema = (0..KEYS).fold(Schema::new(), |schema, i| {
+        schema.key(KeySpec::new(&format!("bench-key-{i}"), ValueType::String).default("none"))
+    });
+    for i in (0..KEYS).step_by(2) {
+        std::env::set_var(format!("BENCH_KEY_{i}"), format!("value {i}"));
+    }
+    let mut group = c.benchmark_group("resolution");
+    group.sample_size(20);
+    group.bench_function("500_keys", |b| {
+        b.iter(|| {
+            try_make_config_from(["app", "--bench-key-1", "cli"], |c| {
+                c.schema(schema.clone())
+            })
+            .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn lookup(c: &mut Criterion) {
+    let schema = (0..KEYS).fold(Schema::new(), |schema, i| {
+        schema.key(KeySpec::new(&format!("bench-key-{i}"), ValueType::String).default("none"))
+    });
+    let config = try_make_config_from(["app"], |c| c.schema(schema)).unwrap();
+    // `Config::get()` records every read, so lookups are measured with
+    // `source()`, which finds the key the same way
+    let mut group = c.benchmark_group("lookup");
+    for key in ["bench-key-250", "BENCH_KEY_250", "benchMissingKey"] {
+        group.bench_with_input(BenchmarkId::new("source", key), key, |b, key| {
+            b.iter(|| config.source(black_box(key)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    case_conversion,
+    environment,
+    lenv_parsing,
+    resolution,
+    lookup
+);
+criterion_main!(benches);
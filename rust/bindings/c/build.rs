@@ -0,0 +1,10 @@
+//! Regenerate `include/lino_arguments.h` from the `extern "C"` API.
+
+fn main() {
+    let dir = std::env::var("CARGO_MANIFEST_DIR").expect("set by cargo");
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    cbindgen::generate(&dir)
+        .expect("the C API is valid for cbindgen")
+        .write_to_file(format!("{dir}/include/lino_arguments.h"));
+}
@@ -0,0 +1,30 @@
+/* Resolve a port and a flag from the command line, the environment, and
+ * `.lenv`. Build it with:
+ *
+ *   cargo build --release
+ *   cc examples/example.c -Iinclude -Ltarget/release -llino_arguments_c -o example
+ */
+#include <stdio.h>
+
+#include "lino_arguments.h"
+
+int main(int argc, char **argv) {
+    LinoOptions *options = lino_options_new();
+    lino_options_lenv(options, ".lenv");
+    lino_options_add(options, "port", "Server port", "3000");
+    lino_options_add_flag(options, "verbose", "Verbose output");
+
+    char *error = NULL;
+    LinoConfig *config = lino_config_new(options, argc, (const char *const *)argv, &error);
+    lino_options_free(options);
+    if (config == NULL) {
+        fprintf(stderr, "%s\n", error);
+        lino_string_free(error);
+        return 1;
+    }
+
+    printf("port: %lld\n", (long long)lino_config_get_int(config, "port", 3000));
+    printf("verbose: %s\n", lino_config_get_bool(config, "verbose") ? "yes" : "no");
+    lino_config_free(config);
+    return 0;
+}
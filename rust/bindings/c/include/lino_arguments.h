@@ -0,0 +1,146 @@
+/* lino-arguments C API. Generated by cbindgen from src/lib.rs; do not edit. */
+
+#ifndef LINO_ARGUMENTS_H
+#define LINO_ARGUMENTS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A resolved configuration.
+typedef struct LinoConfig LinoConfig;
+
+// The options to resolve and the files to read them from, built up
+// before [`lino_config_new()`].
+typedef struct LinoOptions LinoOptions;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// A new, empty set of options. Free it with [`lino_options_free()`].
+struct LinoOptions *lino_options_new(void);
+
+// Add an option, with the value `default_value` when no layer sets one.
+// Returns `false` if an argument is NULL or not UTF-8.
+//
+// # Safety
+//
+// `options` must come from [`lino_options_new()`], and the strings must
+// be NUL-terminated.
+bool lino_options_add(struct LinoOptions *options,
+                      const char *name,
+                      const char *description,
+                      const char *default_value);
+
+// Add a boolean flag, resolved as `"true"` or `"false"`. Returns `false`
+// if an argument is NULL or not UTF-8.
+//
+// # Safety
+//
+// `options` must come from [`lino_options_new()`], and the strings must
+// be NUL-terminated.
+bool lino_options_add_flag(struct LinoOptions *options, const char *name, const char *description);
+
+// Read the `.lenv` file at `path`. Returns `false` if an argument is NULL
+// or not UTF-8.
+//
+// # Safety
+//
+// `options` must come from [`lino_options_new()`], and `path` must be
+// NUL-terminated.
+bool lino_options_lenv(struct LinoOptions *options, const char *path);
+
+// Read the `.env` file at `path`. Returns `false` if an argument is NULL
+// or not UTF-8.
+//
+// # Safety
+//
+// `options` must come from [`lino_options_new()`], and `path` must be
+// NUL-terminated.
+bool lino_options_env(struct LinoOptions *options, const char *path);
+
+// Free options from [`lino_options_new()`]. NULL is ignored.
+//
+// # Safety
+//
+// `options` must come from [`lino_options_new()`] and not be used again.
+void lino_options_free(struct LinoOptions *options);
+
+// Resolve `options` from the command line `argv` (`argc` arguments,
+// starting with the program name, as given to `main`), the environment,
+// and the config files. Free the result with [`lino_config_free()`].
+//
+// Returns NULL on failure, with the message in `*error` if `error` is not
+// NULL; free it with [`lino_string_free()`]. Unknown or invalid arguments
+// fail without exiting the process, and so do `--help` and `--version`,
+// with the help or version text as the message.
+//
+// # Safety
+//
+// `options` must come from [`lino_options_new()`], and `argv` must hold
+// `argc` NUL-terminated strings.
+struct LinoConfig *lino_config_new(const struct LinoOptions *options,
+                                   int argc,
+                                   const char *const *argv,
+                                   char **error);
+
+// The value of `key`, in any case, or NULL if it is not set. Free it with
+// [`lino_string_free()`].
+//
+// # Safety
+//
+// `config` must come from [`lino_config_new()`], and `key` must be
+// NUL-terminated.
+char *lino_config_get_string(const struct LinoConfig *config, const char *key);
+
+// The value of `key` as an integer, or `default_value` if it is not set
+// or not an integer.
+//
+// # Safety
+//
+// `config` must come from [`lino_config_new()`], and `key` must be
+// NUL-terminated.
+int64_t lino_config_get_int(const struct LinoConfig *config,
+                            const char *key,
+                            int64_t default_value);
+
+// The value of `key` as a boolean (`true`, `1`, `yes`, `on`), or `false`
+// if it is not set.
+//
+// # Safety
+//
+// `config` must come from [`lino_config_new()`], and `key` must be
+// NUL-terminated.
+bool lino_config_get_bool(const struct LinoConfig *config, const char *key);
+
+// Free a configuration from [`lino_config_new()`]. NULL is ignored.
+//
+// # Safety
+//
+// `config` must come from [`lino_config_new()`] and not be used again.
+void lino_config_free(struct LinoConfig *config);
+
+// `value` converted to `case`, named in any case (`upper-snake`,
+// `UPPER_SNAKE`), or NULL if the case is unknown. Free it with
+// [`lino_string_free()`].
+//
+// # Safety
+//
+// The strings must be NUL-terminated.
+char *lino_convert_case(const char *value, const char *case_);
+
+// Free a string returned by this library. NULL is ignored.
+//
+// # Safety
+//
+// `string` must have been returned by this library and not be used again.
+void lino_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* LINO_ARGUMENTS_H */
@@ -0,0 +1,429 @@
+//! C bindings of lino-arguments, so that C and C++ services, and any
+//! runtime with a C FFI, resolve configuration with the same code as the
+//! Rust crate.
+//!
+//! Strings passed in are NUL-terminated UTF-8, and strings returned are
+//! owned by the caller, who frees them with [`lino_string_free()`].
+//! `include/lino_arguments.h` is generated from this file by cbindgen.
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::ptr;
+
+use lino_arguments::{Case, Config};
+
+/// The options to resolve and the files to read them from, built up
+/// before [`lino_config_new()`].
+pub struct LinoOptions {
+    lenv: Option<String>,
+    env: Option<String>,
+    options: Vec<OptionSpec>,
+}
+
+struct OptionSpec {
+    name: String,
+    description: String,
+    default: String,
+    flag: bool,
+}
+
+/// A resolved configuration.
+pub struct LinoConfig(Config);
+
+/// A new, empty set of options. Free it with [`lino_options_free()`].
+#[no_mangle]
+pub extern "C" fn lino_options_new() -> *mut LinoOptions {
+    Box::into_raw(Box::new(LinoOptions {
+        lenv: None,
+        env: None,
+        options: Vec::new(),
+    }))
+}
+
+/// Add an option, with the value `default_value` when no layer sets one.
+/// Returns `false` if an argument is NULL or not UTF-8.
+///
+/// # Safety
+///
+/// `options` must come from [`lino_options_new()`], and the strings must
+/// be NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn lino_options_add(
+    options: *mut LinoOptions,
+    name: *const c_char,
+    description: *const c_char,
+    default_value: *const c_char,
+) -> bool {
+    let (Some(options), Some(name), Some(description), Some(default_value)) = (
+        options.as_mut(),
+        str_arg(name),
+        str_arg(description),
+        str_arg(default_value),
+    ) else {
+        return false;
+    };
+    options.options.push(OptionSpec {
+        name: name.to_string(),
+        description: description.to_string(),
+        default: default_value.to_string(),
+        flag: false,
+    });
+    true
+}
+
+/// Add a boolean flag, resolved as `"true"` or `"false"`. Returns `false`
+/// if an argument is NULL or not UTF-8.
+///
+/// # Safety
+///
+/// `options` must come from [`lino_options_new()`], and the strings must
+/// be NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn lino_options_add_flag(
+    options: *mut LinoOptions,
+    name: *const c_char,
+    description: *const c_char,
+) -> bool {
+    let (Some(options), Some(name), Some(description)) =
+        (options.as_mut(), str_arg(name), str_arg(description))
+    else {
+        return false;
+    };
+    options.options.push(OptionSpec {
+        name: name.to_string(),
+        description: description.to_string(),
+        default: String::new(),
+        flag: true,
+    });
+    true
+}
+
+/// Read the `.lenv` file at `path`. Returns `false` if an argument is NULL
+/// or not UTF-8.
+///
+/// # Safety
+///
+/// `options` must come from [`lino_options_new()`], and `path` must be
+/// NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn lino_options_lenv(options: *mut LinoOptions, path: *const c_char) -> bool {
+    let (Some(options), Some(path)) = (options.as_mut(), str_arg(path)) else {
+        return false;
+    };
+    options.lenv = Some(path.to_string());
+    true
+}
+
+/// Read the `.env` file at `path`. Returns `false` if an argument is NULL
+/// or not UTF-8.
+///
+/// # Safety
+///
+/// `options` must come from [`lino_options_new()`], and `path` must be
+/// NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn lino_options_env(options: *mut LinoOptions, path: *const c_char) -> bool {
+    let (Some(options), Some(path)) = (options.as_mut(), str_arg(path)) else {
+        return false;
+    };
+    options.env = Some(path.to_string());
+    true
+}
+
+/// Free options from [`lino_options_new()`]. NULL is ignored.
+///
+/// # Safety
+///
+/// `options` must come from [`lino_options_new()`] and not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn lino_options_free(options: *mut LinoOptions) {
+    if !options.is_null() {
+        drop(Box::from_raw(options));
+    }
+}
+
+/// Resolve `options` from the command line `argv` (`argc` arguments,
+/// starting with the program name, as given to `main`), the environment,
+/// and the config files. Free the result with [`lino_config_free()`].
+///
+/// Returns NULL on failure, with the message in `*error` if `error` is not
+/// NULL; free it with [`lino_string_free()`]. Unknown or invalid arguments
+/// fail without exiting the process, and so do `--help` and `--version`,
+/// with the help or version text as the message.
+///
+/// # Safety
+///
+/// `options` must come from [`lino_options_new()`], and `argv` must hold
+/// `argc` NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn lino_config_new(
+    options: *const LinoOptions,
+    argc: c_int,
+    argv: *const *const c_char,
+    error: *mut *mut c_char,
+) -> *mut LinoConfig {
+    let fail = |message: String| {
+        if !error.is_null() {
+            *error = into_c_string(message);
+        }
+        ptr::null_mut()
+    };
+    let Some(options) = options.as_ref() else {
+        return fail("options is NULL".to_string());
+    };
+    let mut args = Vec::new();
+    for index in 0..usize::try_from(argc).unwrap_or(0) {
+        match str_arg(*argv.add(index)) {
+            Some(arg) => args.push(arg.to_string()),
+            None => return fail(format!("argument {index} is NULL or not UTF-8")),
+        }
+    }
+    if args.is_empty() {
+        args.push("app".to_string());
+    }
+    let config = lino_arguments::try_make_config_from(args, |c| {
+        if let Some(ref path) = options.lenv {
+            c.lenv(path);
+        }
+        if let Some(ref path) = options.env {
+            c.env(path);
+        }
+        for option in &options.options {
+            if option.flag {
+                c.flag(&option.name, &option.description);
+            } else {
+                c.option(&option.name, &option.description, &option.default);
+            }
+        }
+        c
+    });
+    match config {
+        Ok(config) => Box::into_raw(Box::new(LinoConfig(config))),
+        Err(err) => fail(err.to_string()),
+    }
+}
+
+/// The value of `key`, in any case, or NULL if it is not set. Free it with
+/// [`lino_string_free()`].
+///
+/// # Safety
+///
+/// `config` must come from [`lino_config_new()`], and `key` must be
+/// NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn lino_config_get_string(
+    config: *const LinoConfig,
+    key: *const c_char,
+) -> *mut c_char {
+    match (config.as_ref(), str_arg(key)) {
+        (Some(config), Some(key)) if config.0.has(key) => into_c_string(config.0.get(key)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// The value of `key` as an integer, or `default_value` if it is not set
+/// or not an integer.
+///
+/// # Safety
+///
+/// `config` must come from [`lino_config_new()`], and `key` must be
+/// NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn lino_config_get_int(
+    config: *const LinoConfig,
+    key: *const c_char,
+    default_value: i64,
+) -> i64 {
+    match (config.as_ref(), str_arg(key)) {
+        (Some(config), Some(key)) => config.0.get_int(key, default_value),
+        _ => default_value,
+    }
+}
+
+/// The value of `key` as a boolean (`true`, `1`, `yes`, `on`), or `false`
+/// if it is not set.
+///
+/// # Safety
+///
+/// `config` must come from [`lino_config_new()`], and `key` must be
+/// NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn lino_config_get_bool(
+    config: *const LinoConfig,
+    key: *const c_char,
+) -> bool {
+    match (config.as_ref(), str_arg(key)) {
+        (Some(config), Some(key)) => config.0.get_bool(key),
+        _ => false,
+    }
+}
+
+/// Free a configuration from [`lino_config_new()`]. NULL is ignored.
+///
+/// # Safety
+///
+/// `config` must come from [`lino_config_new()`] and not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn lino_config_free(config: *mut LinoConfig) {
+    if !config.is_null() {
+        drop(Box::from_raw(config));
+    }
+}
+
+/// `value` converted to `case`, named in any case (`upper-snake`,
+/// `UPPER_SNAKE`), or NULL if the case is unknown. Free it with
+/// [`lino_string_free()`].
+///
+/// # Safety
+///
+/// The strings must be NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn lino_convert_case(
+    value: *const c_char,
+    case: *const c_char,
+) -> *mut c_char {
+    let (Some(value), Some(case)) = (str_arg(value), str_arg(case)) else {
+        return ptr::null_mut();
+    };
+    match case.parse::<Case>() {
+        Ok(case) => into_c_string(lino_arguments::convert_case(value, case).into_owned()),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Free a string returned by this library. NULL is ignored.
+///
+/// # Safety
+///
+/// `string` must have been returned by this library and not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn lino_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// The string at `ptr`, or `None` if it is NULL or not UTF-8.
+unsafe fn str_arg<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    CStr::from_ptr(ptr).to_str().ok()
+}
+
+/// `string` as a C string owned by the caller, cut at the first NUL.
+fn into_c_string(string: String) -> *mut c_char {
+    let mut bytes = string.into_bytes();
+    if let Some(nul) = bytes.iter().position(|&byte| byte == 0) {
+        bytes.truncate(nul);
+    }
+    CString::new(bytes).expect("NUL bytes were cut").into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The string returned by the library, freed.
+    unsafe fn take(string: *mut c_char) -> Option<String> {
+        if string.is_null() {
+            return None;
+        }
+        let owned = CStr::from_ptr(string).to_str().unwrap().to_string();
+        lino_string_free(string);
+        Some(owned)
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        unsafe {
+            let options = lino_options_new();
+            assert!(lino_options_add(
+                options,
+                c"capi-port".as_ptr(),
+                c"Port".as_ptr(),
+                c"3000".as_ptr()
+            ));
+            assert!(lino_options_add(
+                options,
+                c"capi-host".as_ptr(),
+                c"Host".as_ptr(),
+                c"localhost".as_ptr()
+            ));
+            assert!(lino_options_add_flag(
+                options,
+                c"capi-verbose".as_ptr(),
+                c"Verbose".as_ptr()
+            ));
+            assert!(!lino_options_add(
+                options,
+                ptr::null(),
+                c"".as_ptr(),
+                c"".as_ptr()
+            ));
+
+            let argv = [
+                c"app".as_ptr(),
+                c"--capi-port".as_ptr(),
+                c"8080".as_ptr(),
+                c"--capi-verbose".as_ptr(),
+            ];
+            let mut error = ptr::null_mut();
+            let config = lino_config_new(options, 4, argv.as_ptr(), &mut error);
+            lino_options_free(options);
+            assert!(!config.is_null());
+            assert!(error.is_null());
+
+            assert_eq!(lino_config_get_int(config, c"CAPI_PORT".as_ptr(), 0), 8080);
+            assert_eq!(
+                take(lino_config_get_string(config, c"capiHost".as_ptr())).as_deref(),
+                Some("localhost")
+            );
+            assert!(take(lino_config_get_string(config, c"capi-missing".as_ptr())).is_none());
+            assert_eq!(lino_config_get_int(config, c"capi-host".as_ptr(), 7), 7);
+            assert!(lino_config_get_bool(config, c"capi-verbose".as_ptr()));
+            lino_config_free(config);
+        }
+    }
+
+    #[test]
+    fn test_config_error() {
+        unsafe {
+            let mut error = ptr::null_mut();
+            let config = lino_config_new(ptr::null(), 0, ptr::null(), &mut error);
+            assert!(config.is_null());
+            assert_eq!(take(error).as_deref(), Some("options is NULL"));
+        }
+    }
+
+    #[test]
+    fn test_config_argument_error() {
+        unsafe {
+            let options = lino_options_new();
+            lino_options_add(
+                options,
+                c"capi-arg-port".as_ptr(),
+                c"Port".as_ptr(),
+                c"3000".as_ptr(),
+            );
+            for arg in [c"--bogus", c"--help"] {
+                let argv = [c"app".as_ptr(), arg.as_ptr()];
+                let mut error = ptr::null_mut();
+                let config = lino_config_new(options, 2, argv.as_ptr(), &mut error);
+                assert!(config.is_null());
+                let message = take(error).unwrap();
+                assert!(message.starts_with("Argument error: "), "{message}");
+                assert!(message.contains("--bogus") || message.contains("--capi-arg-port"));
+            }
+            lino_options_free(options);
+        }
+    }
+
+    #[test]
+    fn test_convert_case() {
+        unsafe {
+            let converted = lino_convert_case(c"api-key".as_ptr(), c"UPPER_SNAKE".as_ptr());
+            assert_eq!(take(converted).as_deref(), Some("API_KEY"));
+            assert!(lino_convert_case(c"api-key".as_ptr(), c"shouting".as_ptr()).is_null());
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Node.js bindings of lino-arguments, so that the npm package resolves
+//! configuration, converts cases, and parses `.lenv` files with the same
+//! code as the Rust crate.
+
+use clap::error::ErrorKind;
+use lino_arguments::{Case, ConfigError, KeySpec, Schema, ValueType};
+use napi::bindgen_prelude::{Object, Result};
+use napi::{Env, Error};
+use napi_derive::napi;
+
+/// An option to resolve, like `.option()` in `yargs`.
+#[napi(object)]
+pub struct OptionSpec {
+    /// The option name, in any case (`api-key`, `apiKey`).
+    pub name: String,
+    pub description: Option<String>,
+    /// The value when no layer sets one.
+    pub default: Option<String>,
+    /// Whether the option is a boolean flag, like `type: 'boolean'`.
+    pub flag: Option<bool>,
+    /// A one-letter alias (`-p`).
+    pub short: Option<String>,
+    /// What the value is validated and returned as: `'string'` (the
+    /// default), `'integer'`, `'number'`, or `'boolean'` for a flag.
+    #[napi(js_name = "type")]
+    pub value_type: Option<String>,
+    /// The environment variable to read instead of the name in UPPER_CASE.
+    pub env: Option<String>,
+}
+
+impl OptionSpec {
+    /// The type the value is validated and returned as.
+    fn value_type(&self) -> Result<ValueType> {
+        match self.value_type.as_deref() {
+            _ if self.flag == Some(true) => Ok(ValueType::Bool),
+            None | Some("string") => Ok(ValueType::String),
+            Some("integer") => Ok(ValueType::Integer),
+            Some("number") => Ok(ValueType::Float),
+            Some("boolean") => Ok(ValueType::Bool),
+            Some(other) => Err(Error::from_reason(format!(
+                "unknown type {other:?} of option {}",
+                self.name
+            ))),
+        }
+    }
+
+    /// The one-letter alias, if any.
+    fn short(&self) -> Result<Option<char>> {
+        let Some(ref short) = self.short else {
+            return Ok(None);
+        };
+        let mut chars = short.chars();
+        match (chars.next(), chars.next()) {
+            (Some(short), None) => Ok(Some(short)),
+            _ => Err(Error::from_reason(format!(
+                "the short name of option {} must be one letter, not {short:?}",
+                self.name
+            ))),
+        }
+    }
+}
+
+/// What `makeConfig()` resolves and from where.
+#[napi(object)]
+pub struct ConfigOptions {
+    /// The command line arguments after the script (`hideBin(process.argv)`).
+    pub argv: Option<Vec<String>>,
+    /// The `.lenv` file to read.
+    pub lenv: Option<String>,
+    /// The `.env` file to read.
+    pub env: Option<String>,
+    pub options: Vec<OptionSpec>,
+    /// The application name, for the help text and its usage line.
+    pub name: Option<String>,
+    /// What the application does, for the help text.
+    pub about: Option<String>,
+    /// The version `--version` prints; there is no `--version` without one.
+    pub version: Option<String>,
+}
+
+/// Resolve `options` from the command line, the environment, and the
+/// config files, returning each value by its camelCase name as a string,
+/// number, or boolean by its type. Numbers without a value are left out.
+///
+/// Failures throw rather than exiting the process, with a `code` telling
+/// them apart: `DisplayHelp` and `DisplayVersion` for `--help` and
+/// `--version`, with the text to print as the message, `ArgumentError` for
+/// bad arguments, with the message clap prints, and `ConfigError` for the
+/// rest.
+#[napi]
+pub fn make_config(env: &Env, config: ConfigOptions) -> Result<Object<'_>, String> {
+    let mut types = Vec::with_capacity(config.options.len());
+    let mut shorts = Vec::with_capacity(config.options.len());
+    let mut schema = Schema::new();
+    for option in &config.options {
+        let value_type = option.value_type().map_err(coded)?;
+        let mut spec = KeySpec::new(&option.name, value_type);
+        if let Some(ref name) = option.env {
+            spec = spec.env(name);
+        }
+        schema = schema.key(spec);
+        types.push(value_type);
+        shorts.push(option.short().map_err(coded)?);
+    }
+    let bin = config.name.clone().unwrap_or_else(|| "node".to_string());
+    let args = std::iter::once(bin).chain(config.argv.unwrap_or_default());
+    let resolved = lino_arguments::try_make_config_from(args, |c| {
+        if let Some(ref name) = config.name {
+            c.name(name);
+        }
+        if let Some(ref about) = config.about {
+            c.about(about);
+        }
+        if let Some(ref version) = config.version {
+            c.version(version);
+        }
+        if let Some(ref path) = config.lenv {
+            c.lenv(path);
+        }
+        if let Some(ref path) = config.env {
+            c.env(path);
+        }
+        for (option, (&value_type, &short)) in config.options.iter().zip(types.iter().zip(&shorts))
+        {
+            let description = option.description.as_deref().unwrap_or_default();
+            let default = option.default.as_deref().unwrap_or_default();
+            match (value_type == ValueType::Bool, short) {
+                (true, Some(short)) => c.flag_short(&option.name, short, description),
+                (true, None) => c.flag(&option.name, description),
+                (false, Some(short)) => c.option_short(&option.name, short, description, default),
+                (false, None) => c.option(&option.name, description, default),
+            };
+        }
+        c.schema(schema)
+    })
+    .map_err(config_error)?;
+
+    let mut object = Object::new(env).map_err(coded)?;
+    for (option, value_type) in config.options.iter().zip(types) {
+        let key = lino_arguments::to_camel_case(&option.name);
+        match value_type {
+            ValueType::Bool => object.set(key, resolved.get_bool(&option.name)),
+            ValueType::Integer | ValueType::Float if resolved.get(&option.name).is_empty() => {
+                Ok(())
+            }
+            ValueType::Integer | ValueType::Float => {
+                object.set(key, resolved.get_float(&option.name, 0.0))
+            }
+            _ => object.set(key, resolved.get(&option.name)),
+        }
+        .map_err(coded)?;
+    }
+    Ok(object)
+}
+
+/// `err` with its status as the `code`.
+fn coded(err: Error) -> Error<String> {
+    Error::new(err.status.as_ref().to_string(), err.reason)
+}
+
+/// The error thrown for `err`, with the `code` [`make_config()`] documents.
+fn config_error(err: ConfigError) -> Error<String> {
+    let (code, message) = match err {
+        ConfigError::ArgumentError(ref err) => {
+            let code = match err.kind() {
+                ErrorKind::DisplayVersion => "DisplayVersion",
+                _ if !err.use_stderr() => "DisplayHelp",
+                _ => "ArgumentError",
+            };
+            (code, err.to_string())
+        }
+        _ => ("ConfigError", err.to_string()),
+    };
+    Error::new(code.to_string(), message)
+}
+
+/// Get an environment variable as an integer, trying every case variant
+/// of `key`.
+#[napi]
+pub fn getenv_int(key: String, default_value: i64) -> i64 {
+    lino_arguments::getenv_int(&key, default_value)
+}
+
+/// Get an environment variable as a number, trying every case variant of
+/// `key`.
+#[napi]
+pub fn getenv_float(key: String, default_value: f64) -> f64 {
+    lino_arguments::getenv_float(&key, default_value)
+}
+
+/// Get an environment variable as a boolean, trying every case variant of
+/// `key`.
+#[napi]
+pub fn getenv_bool(key: String, default_value: bool) -> bool {
+    lino_arguments::getenv_bool(&key, default_value)
+}
+
+/// Get an environment variable, trying every case variant of `key`.
+#[napi]
+pub fn getenv(key: String, default_value: Option<String>) -> String {
+    lino_arguments::getenv(&key, default_value.as_deref().unwrap_or_default())
+}
+
+/// The `KEY: value` pairs of the text of a `.lenv` file, in file order.
+#[napi]
+pub fn parse_lenv(env: &Env, text: String) -> Result<Object<'_>> {
+    let mut object = Object::new(env)?;
+    for (key, value) in lino_arguments::parse_lenv(&text) {
+        object.set(key, value)?;
+    }
+    Ok(object)
+}
+
+/// Convert `value` to `case`, named in any case (`upper-snake`,
+/// `UPPER_SNAKE`).
+#[napi]
+pub fn convert_case(value: String, case: String) -> Result<String> {
+    let case: Case = case
+        .parse()
+        .map_err(|err: lino_arguments::UnknownCase| Error::from_reason(err.to_string()))?;
+    Ok(lino_arguments::convert_case(&value, case).into_owned())
+}
+
+/// The name of the case `value` is written in, if it is in one.
+#[napi]
+pub fn detect_case(value: String) -> Option<&'static str> {
+    lino_arguments::detect_case(&value).map(Case::name)
+}
+
+/// The key every case variant of `key` resolves to.
+#[napi]
+pub fn canonical_key(key: String) -> String {
+    lino_arguments::canonical_key(&key).into_owned()
+}
+
+/// The words of `value`.
+#[napi]
+pub fn split_words(value: String) -> Vec<String> {
+    lino_arguments::split_words(&value)
+        .map(str::to_string)
+        .collect()
+}
+
+#[napi]
+pub fn to_upper_case(value: String) -> String {
+    lino_arguments::to_upper_case(&value)
+}
+
+#[napi]
+pub fn to_camel_case(value: String) -> String {
+    lino_arguments::to_camel_case(&value)
+}
+
+#[napi]
+pub fn to_kebab_case(value: String) -> String {
+    lino_arguments::to_kebab_case(&value)
+}
+
+#[napi]
+pub fn to_snake_case(value: String) -> String {
+    lino_arguments::to_snake_case(&value)
+}
+
+#[napi]
+pub fn to_pascal_case(value: String) -> String {
+    lino_arguments::to_pascal_case(&value)
+}
@@ -0,0 +1,113 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { mkdtempSync, writeFileSync } from 'node:fs';
+import { tmpdir } from 'node:os';
+import { join } from 'node:path';
+import { createRequire } from 'node:module';
+
+const require = createRequire(import.meta.url);
+const lino = require('../index.js');
+
+test('converts cases like the Rust crate', () => {
+  assert.equal(lino.toUpperCase('apiKey'), 'API_KEY');
+  assert.equal(lino.toCamelCase('API_KEY'), 'apiKey');
+  assert.equal(lino.convertCase('api-key', 'UpperSnake'), 'API_KEY');
+  assert.equal(lino.detectCase('api_key'), 'snake');
+  assert.equal(lino.canonicalKey('API_KEY'), 'api-key');
+  assert.throws(() => lino.convertCase('api-key', 'shouting'), /unknown case/);
+});
+
+test('reads environment variables by type in any case', () => {
+  process.env.NATIVE_GETENV_LIMIT = '25';
+  process.env.NATIVE_GETENV_DEBUG = 'yes';
+  try {
+    assert.equal(lino.getenv('nativeGetenvLimit', '10'), '25');
+    assert.equal(lino.getenvInt('native-getenv-limit', 10), 25);
+    assert.equal(lino.getenvFloat('NATIVE_GETENV_MISSING', 0.5), 0.5);
+    assert.equal(lino.getenvBool('nativeGetenvDebug', false), true);
+  } finally {
+    delete process.env.NATIVE_GETENV_LIMIT;
+    delete process.env.NATIVE_GETENV_DEBUG;
+  }
+});
+
+test('parses lenv text in file order', () => {
+  const values = lino.parseLenv('# server\nPORT: 3000\nHOST: localhost\nPORT: 8080\n');
+  assert.deepEqual(Object.entries(values), [
+    ['HOST', 'localhost'],
+    ['PORT', '8080'],
+  ]);
+});
+
+test('resolves options from the command line and lenv files', () => {
+  const dir = mkdtempSync(join(tmpdir(), 'lino-native-'));
+  const lenv = join(dir, '.lenv');
+  writeFileSync(lenv, 'NATIVE_HOST: db.internal\n');
+
+  const config = lino.makeConfig({
+    argv: ['--native-port', '8080', '-v'],
+    lenv,
+    options: [
+      { name: 'native-port', default: '3000' },
+      { name: 'native-host', default: 'localhost' },
+      { name: 'native-verbose', flag: true, short: 'v' },
+    ],
+  });
+  assert.deepEqual(config, {
+    nativePort: '8080',
+    nativeHost: 'db.internal',
+    nativeVerbose: true,
+  });
+});
+
+test('returns values by type and reads renamed variables', () => {
+  process.env.NATIVE_TYPED_RETRIES = '5';
+  try {
+    const config = lino.makeConfig({
+      argv: ['--native-ratio', '0.5'],
+      options: [
+        { name: 'native-port', type: 'integer', default: '3000' },
+        { name: 'native-ratio', type: 'number' },
+        { name: 'native-timeout', type: 'number' },
+        { name: 'native-retries', type: 'integer', env: 'NATIVE_TYPED_RETRIES' },
+        { name: 'native-debug', type: 'boolean' },
+      ],
+    });
+    assert.deepEqual(config, {
+      nativePort: 3000,
+      nativeRatio: 0.5,
+      nativeRetries: 5,
+      nativeDebug: false,
+    });
+    assert.throws(
+      () =>
+        lino.makeConfig({
+          argv: ['--native-port', 'many'],
+          options: [{ name: 'native-port', type: 'integer' }],
+        }),
+      { code: 'ConfigError', message: /native-port.*not a valid integer/ }
+    );
+  } finally {
+    delete process.env.NATIVE_TYPED_RETRIES;
+  }
+});
+
+test('throws on bad arguments instead of exiting', () => {
+  const options = [{ name: 'native-arg-port', default: '3000' }];
+  assert.throws(() => lino.makeConfig({ argv: ['--bogus'], options }), {
+    code: 'ArgumentError',
+    message: /unexpected argument '--bogus'/,
+  });
+  assert.throws(() => lino.makeConfig({ argv: ['--help'], options }), {
+    code: 'DisplayHelp',
+    message: /--native-arg-port/,
+  });
+  assert.throws(
+    () => lino.makeConfig({ argv: ['--version'], options, name: 'app', version: '1.2.3' }),
+    { code: 'DisplayVersion', message: /app 1\.2\.3/ }
+  );
+  assert.throws(
+    () => lino.makeConfig({ argv: [], options: [{ name: 'port', short: 'po' }] }),
+    /must be one letter/
+  );
+});
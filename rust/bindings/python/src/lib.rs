@@ -0,0 +1,206 @@
+//! Python bindings of lino-arguments, so that Python tooling resolves
+//! configuration, reads environment variables, and converts cases with the
+//! same code as the Rust crate.
+
+use std::collections::HashMap;
+
+use lino_arguments::Case;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// An option to resolve, given to `make_config()` as a dict.
+#[derive(FromPyObject)]
+#[pyo3(from_item_all)]
+struct OptionSpec {
+    /// The option name, in any case (`api-key`, `api_key`).
+    name: String,
+    #[pyo3(default)]
+    description: Option<String>,
+    /// The value when no layer sets one.
+    #[pyo3(default)]
+    default: Option<String>,
+    /// Whether the option is a boolean flag, resolved as `"true"` or
+    /// `"false"`.
+    #[pyo3(default)]
+    flag: Option<bool>,
+    /// A one-letter alias (`-p`).
+    #[pyo3(default)]
+    short: Option<String>,
+}
+
+/// Resolve `options` from the command line `argv` (the arguments after the
+/// script, `sys.argv[1:]`), the environment, and the config files,
+/// returning each value by its snake_case name.
+///
+/// Unknown or invalid arguments raise `ValueError` rather than exiting the
+/// interpreter, and so do `--help` and `--version`, with the help or version
+/// text as the message.
+#[pyfunction]
+#[pyo3(signature = (options, argv = None, lenv = None, env = None))]
+fn make_config(
+    options: Vec<OptionSpec>,
+    argv: Option<Vec<String>>,
+    lenv: Option<String>,
+    env: Option<String>,
+) -> PyResult<HashMap<String, String>> {
+    let args = std::iter::once("python".to_string()).chain(argv.unwrap_or_default());
+    let resolved = lino_arguments::try_make_config_from(args, |c| {
+        if let Some(ref path) = lenv {
+            c.lenv(path);
+        }
+        if let Some(ref path) = env {
+            c.env(path);
+        }
+        for option in &options {
+            let description = option.description.as_deref().unwrap_or_default();
+            let short = option
+                .short
+                .as_deref()
+                .and_then(|short| short.chars().next());
+            match (option.flag.unwrap_or(false), short) {
+                (true, Some(short)) => c.flag_short(&option.name, short, description),
+                (true, None) => c.flag(&option.name, description),
+                (false, Some(short)) => c.option_short(
+                    &option.name,
+                    short,
+                    description,
+                    option.default.as_deref().unwrap_or_default(),
+                ),
+                (false, None) => c.option(
+                    &option.name,
+                    description,
+                    option.default.as_deref().unwrap_or_default(),
+                ),
+            };
+        }
+        c
+    })
+    .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(options
+        .iter()
+        .map(|option| {
+            (
+                lino_arguments::to_snake_case(&option.name),
+                resolved.get(&option.name),
+            )
+        })
+        .collect())
+}
+
+/// Get an environment variable, trying every case variant of `key`.
+#[pyfunction]
+#[pyo3(signature = (key, default = ""))]
+fn getenv(key: &str, default: &str) -> String {
+    lino_arguments::getenv(key, default)
+}
+
+/// Get an environment variable as an integer, or `default` if it is not
+/// set or not an integer.
+#[pyfunction]
+#[pyo3(signature = (key, default = 0))]
+fn getenv_int(key: &str, default: i64) -> i64 {
+    lino_arguments::getenv_int(key, default)
+}
+
+/// Get an environment variable as a float, or `default` if it is not set
+/// or not a number.
+#[pyfunction]
+#[pyo3(signature = (key, default = 0.0))]
+fn getenv_float(key: &str, default: f64) -> f64 {
+    lino_arguments::getenv_float(key, default)
+}
+
+/// Get an environment variable as a boolean, or `default` if it is not
+/// set.
+#[pyfunction]
+#[pyo3(signature = (key, default = false))]
+fn getenv_bool(key: &str, default: bool) -> bool {
+    lino_arguments::getenv_bool(key, default)
+}
+
+/// The `KEY: value` pairs of the text of a `.lenv` file, in file order.
+#[pyfunction]
+fn parse_lenv<'py>(py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (key, value) in lino_arguments::parse_lenv(text) {
+        dict.set_item(key, value)?;
+    }
+    Ok(dict)
+}
+
+/// Convert `value` to `case`, named in any case (`upper-snake`,
+/// `UPPER_SNAKE`).
+#[pyfunction]
+fn convert_case(value: &str, case: &str) -> PyResult<String> {
+    let case: Case = case
+        .parse()
+        .map_err(|err: lino_arguments::UnknownCase| PyValueError::new_err(err.to_string()))?;
+    Ok(lino_arguments::convert_case(value, case).into_owned())
+}
+
+/// The name of the case `value` is written in, if it is in one.
+#[pyfunction]
+fn detect_case(value: &str) -> Option<&'static str> {
+    lino_arguments::detect_case(value).map(Case::name)
+}
+
+/// The key every case variant of `key` resolves to.
+#[pyfunction]
+fn canonical_key(key: &str) -> String {
+    lino_arguments::canonical_key(key).into_owned()
+}
+
+/// The words of `value`.
+#[pyfunction]
+fn split_words(value: &str) -> Vec<String> {
+    lino_arguments::split_words(value)
+        .map(str::to_string)
+        .collect()
+}
+
+#[pyfunction]
+fn to_upper_case(value: &str) -> String {
+    lino_arguments::to_upper_case(value)
+}
+
+#[pyfunction]
+fn to_camel_case(value: &str) -> String {
+    lino_arguments::to_camel_case(value)
+}
+
+#[pyfunction]
+fn to_kebab_case(value: &str) -> String {
+    lino_arguments::to_kebab_case(value)
+}
+
+#[pyfunction]
+fn to_snake_case(value: &str) -> String {
+    lino_arguments::to_snake_case(value)
+}
+
+#[pyfunction]
+fn to_pascal_case(value: &str) -> String {
+    lino_arguments::to_pascal_case(value)
+}
+
+#[pymodule]
+#[pyo3(name = "lino_arguments")]
+fn lino_arguments_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(make_config, m)?)?;
+    m.add_function(wrap_pyfunction!(getenv, m)?)?;
+    m.add_function(wrap_pyfunction!(getenv_int, m)?)?;
+    m.add_function(wrap_pyfunction!(getenv_float, m)?)?;
+    m.add_function(wrap_pyfunction!(getenv_bool, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_lenv, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_case, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_case, m)?)?;
+    m.add_function(wrap_pyfunction!(canonical_key, m)?)?;
+    m.add_function(wrap_pyfunction!(split_words, m)?)?;
+    m.add_function(wrap_pyfunction!(to_upper_case, m)?)?;
+    m.add_function(wrap_pyfunction!(to_camel_case, m)?)?;
+    m.add_function(wrap_pyfunction!(to_kebab_case, m)?)?;
+    m.add_function(wrap_pyfunction!(to_snake_case, m)?)?;
+    m.add_function(wrap_pyfunction!(to_pascal_case, m)?)?;
+    Ok(())
+}
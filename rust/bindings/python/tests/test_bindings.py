@@ -0,0 +1,64 @@
+import os
+import tempfile
+import unittest
+from unittest import mock
+
+import lino_arguments as lino
+
+
+class BindingsTest(unittest.TestCase):
+    def test_converts_cases_like_the_rust_crate(self):
+        self.assertEqual(lino.to_upper_case("apiKey"), "API_KEY")
+        self.assertEqual(lino.to_snake_case("API_KEY"), "api_key")
+        self.assertEqual(lino.convert_case("api-key", "UpperSnake"), "API_KEY")
+        self.assertEqual(lino.detect_case("api_key"), "snake")
+        self.assertEqual(lino.canonical_key("API_KEY"), "api-key")
+        with self.assertRaisesRegex(ValueError, "unknown case"):
+            lino.convert_case("api-key", "shouting")
+
+    def test_parses_lenv_text_in_file_order(self):
+        values = lino.parse_lenv("# server\nPORT: 3000\nHOST: localhost\nPORT: 8080\n")
+        self.assertEqual(list(values.items()), [("HOST", "localhost"), ("PORT", "8080")])
+
+    def test_reads_environment_variables_in_any_case(self):
+        env = {"PY_BINDINGS_PORT": "8080", "PY_BINDINGS_DEBUG": "yes"}
+        with mock.patch.dict(os.environ, env):
+            self.assertEqual(lino.getenv("pyBindingsPort"), "8080")
+            self.assertEqual(lino.getenv_int("py-bindings-port"), 8080)
+            self.assertEqual(lino.getenv_float("py-bindings-port"), 8080.0)
+            self.assertTrue(lino.getenv_bool("py_bindings_debug"))
+            self.assertEqual(lino.getenv("py-bindings-missing", "fallback"), "fallback")
+            self.assertEqual(lino.getenv_int("py-bindings-missing", 3), 3)
+
+    def test_resolves_options_from_the_command_line_and_lenv_files(self):
+        with tempfile.TemporaryDirectory() as dir:
+            lenv = os.path.join(dir, ".lenv")
+            with open(lenv, "w") as file:
+                file.write("PY_HOST: db.internal\n")
+
+            config = lino.make_config(
+                [
+                    {"name": "py-port", "default": "3000"},
+                    {"name": "py-host", "default": "localhost"},
+                    {"name": "py-verbose", "flag": True, "short": "v"},
+                ],
+                argv=["--py-port", "8080", "-v"],
+                lenv=lenv,
+            )
+        self.assertEqual(
+            config,
+            {"py_port": "8080", "py_host": "db.internal", "py_verbose": "true"},
+        )
+
+    def test_raises_on_bad_arguments_instead_of_exiting(self):
+        options = [{"name": "py-arg-port", "default": "3000"}]
+        with self.assertRaisesRegex(ValueError, "unexpected argument '--bogus'"):
+            lino.make_config(options, argv=["--bogus"])
+        with self.assertRaisesRegex(ValueError, "unexpected argument 'extra'"):
+            lino.make_config(options, argv=["extra"])
+        with self.assertRaisesRegex(ValueError, "--py-arg-port"):
+            lino.make_config(options, argv=["--help"])
+
+
+if __name__ == "__main__":
+    unittest.main()
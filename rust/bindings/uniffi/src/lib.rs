@@ -0,0 +1,309 @@
+//! Swift and Kotlin bindings of lino-arguments, generated with UniFFI, so
+//! that mobile and desktop apps resolve configuration, read environment
+//! variables, parse values, and convert cases with the same code as the
+//! Rust crate.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use lino_arguments::Case;
+
+uniffi::setup_scaffolding!();
+
+/// Why a call failed, with the message of the Rust error.
+#[derive(Debug, uniffi::Error)]
+pub enum LinoError {
+    /// The configuration could not be resolved.
+    Config { message: String },
+    /// The command line arguments were invalid, or asked for `--help` or
+    /// `--version`, whose text is the message.
+    Arguments { message: String },
+    /// A value could not be parsed.
+    Parse { message: String },
+}
+
+impl std::fmt::Display for LinoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinoError::Config { message }
+            | LinoError::Arguments { message }
+            | LinoError::Parse { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for LinoError {}
+
+/// A value that could not be parsed.
+fn parse_error(err: impl ToString) -> LinoError {
+    LinoError::Parse {
+        message: err.to_string(),
+    }
+}
+
+/// An option to resolve with [`make_config()`].
+#[derive(uniffi::Record)]
+pub struct OptionSpec {
+    /// The option name, in any case (`api-key`, `apiKey`).
+    pub name: String,
+    #[uniffi(default = None)]
+    pub description: Option<String>,
+    /// The value when no layer sets one.
+    #[uniffi(default = None)]
+    pub default_value: Option<String>,
+    /// Whether the option is a boolean flag, resolved as `"true"` or
+    /// `"false"`.
+    #[uniffi(default = false)]
+    pub flag: bool,
+    /// A one-letter alias (`-p`).
+    #[uniffi(default = None)]
+    pub short: Option<String>,
+}
+
+/// Resolve `options` from the command line `argv` (without the program
+/// name), the environment, and the config files, returning each value by
+/// its camelCase name.
+///
+/// Unknown or invalid arguments, `--help`, and `--version` return
+/// [`LinoError::Arguments`] rather than exiting the process.
+#[uniffi::export(default(argv = [], lenv = None, env = None))]
+pub fn make_config(
+    options: Vec<OptionSpec>,
+    argv: Vec<String>,
+    lenv: Option<String>,
+    env: Option<String>,
+) -> Result<HashMap<String, String>, LinoError> {
+    let args = std::iter::once("app".to_string()).chain(argv);
+    let resolved = lino_arguments::try_make_config_from(args, |c| {
+        if let Some(ref path) = lenv {
+            c.lenv(path);
+        }
+        if let Some(ref path) = env {
+            c.env(path);
+        }
+        for option in &options {
+            let description = option.description.as_deref().unwrap_or_default();
+            let short = option
+                .short
+                .as_deref()
+                .and_then(|short| short.chars().next());
+            let default = option.default_value.as_deref().unwrap_or_default();
+            match (option.flag, short) {
+                (true, Some(short)) => c.flag_short(&option.name, short, description),
+                (true, None) => c.flag(&option.name, description),
+                (false, Some(short)) => c.option_short(&option.name, short, description, default),
+                (false, None) => c.option(&option.name, description, default),
+            };
+        }
+        c
+    })
+    .map_err(|err| match err {
+        lino_arguments::ConfigError::ArgumentError(err) => LinoError::Arguments {
+            message: err.to_string(),
+        },
+        err => LinoError::Config {
+            message: err.to_string(),
+        },
+    })?;
+    Ok(options
+        .iter()
+        .map(|option| {
+            (
+                lino_arguments::to_camel_case(&option.name),
+                resolved.get(&option.name),
+            )
+        })
+        .collect())
+}
+
+/// Get an environment variable, trying every case variant of `key`.
+#[uniffi::export(default(default_value = ""))]
+pub fn getenv(key: &str, default_value: &str) -> String {
+    lino_arguments::getenv(key, default_value)
+}
+
+/// Get an environment variable as an integer, or `default_value` if it is
+/// not set or not an integer.
+#[uniffi::export(default(default_value = 0))]
+pub fn getenv_int(key: &str, default_value: i64) -> i64 {
+    lino_arguments::getenv_int(key, default_value)
+}
+
+/// Get an environment variable as a float, or `default_value` if it is not
+/// set or not a number.
+#[uniffi::export(default(default_value = 0.0))]
+pub fn getenv_float(key: &str, default_value: f64) -> f64 {
+    lino_arguments::getenv_float(key, default_value)
+}
+
+/// Get an environment variable as a boolean, or `default_value` if it is
+/// not set.
+#[uniffi::export(default(default_value = false))]
+pub fn getenv_bool(key: &str, default_value: bool) -> bool {
+    lino_arguments::getenv_bool(key, default_value)
+}
+
+/// Get an environment variable as a duration, or `default_value` if it is
+/// not set or not a duration.
+#[uniffi::export]
+pub fn getenv_duration(key: &str, default_value: Duration) -> Duration {
+    lino_arguments::getenv_duration(key, default_value)
+}
+
+/// Get an environment variable as a number of bytes, or `default_value` if
+/// it is not set or not a size.
+#[uniffi::export]
+pub fn getenv_byte_size(key: &str, default_value: u64) -> u64 {
+    lino_arguments::getenv_byte_size(key, default_value)
+}
+
+/// The `KEY: value` pairs of the text of a `.lenv` file.
+#[uniffi::export]
+pub fn parse_lenv(text: &str) -> HashMap<String, String> {
+    lino_arguments::parse_lenv(text).into_iter().collect()
+}
+
+/// Parse a duration such as `250ms` or `1h30m`; a bare number is in
+/// seconds.
+#[uniffi::export]
+pub fn parse_duration(value: &str) -> Result<Duration, LinoError> {
+    lino_arguments::parse_duration(value).map_err(parse_error)
+}
+
+/// Parse a size such as `10KB` (powers of 1000) or `10KiB` (powers of
+/// 1024) into bytes.
+#[uniffi::export]
+pub fn parse_byte_size(value: &str) -> Result<u64, LinoError> {
+    lino_arguments::parse_byte_size(value).map_err(parse_error)
+}
+
+/// The trimmed, non-empty items of a comma-separated list.
+#[uniffi::export]
+pub fn parse_list(value: &str) -> Vec<String> {
+    lino_arguments::parse_list(value).unwrap_or_default()
+}
+
+/// Comma-separated `key=value` pairs.
+#[uniffi::export]
+pub fn parse_map(value: &str) -> Result<HashMap<String, String>, LinoError> {
+    lino_arguments::parse_map(value).map_err(parse_error)
+}
+
+/// Convert `value` to `case`, named in any case (`upper-snake`,
+/// `UPPER_SNAKE`).
+#[uniffi::export]
+pub fn convert_case(value: &str, case: &str) -> Result<String, LinoError> {
+    let case: Case = case.parse().map_err(parse_error)?;
+    Ok(lino_arguments::convert_case(value, case).into_owned())
+}
+
+/// The name of the case `value` is written in, if it is in one.
+#[uniffi::export]
+pub fn detect_case(value: &str) -> Option<String> {
+    lino_arguments::detect_case(value).map(|case| case.name().to_string())
+}
+
+/// The key every case variant of `key` resolves to.
+#[uniffi::export]
+pub fn canonical_key(key: &str) -> String {
+    lino_arguments::canonical_key(key).into_owned()
+}
+
+/// The words of `value`.
+#[uniffi::export]
+pub fn split_words(value: &str) -> Vec<String> {
+    lino_arguments::split_words(value)
+        .map(str::to_string)
+        .collect()
+}
+
+#[uniffi::export]
+pub fn to_upper_case(value: &str) -> String {
+    lino_arguments::to_upper_case(value)
+}
+
+#[uniffi::export]
+pub fn to_camel_case(value: &str) -> String {
+    lino_arguments::to_camel_case(value)
+}
+
+#[uniffi::export]
+pub fn to_kebab_case(value: &str) -> String {
+    lino_arguments::to_kebab_case(value)
+}
+
+#[uniffi::export]
+pub fn to_snake_case(value: &str) -> String {
+    lino_arguments::to_snake_case(value)
+}
+
+#[uniffi::export]
+pub fn to_pascal_case(value: &str) -> String {
+    lino_arguments::to_pascal_case(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option(name: &str, default_value: &str) -> OptionSpec {
+        OptionSpec {
+            name: name.to_string(),
+            description: None,
+            default_value: Some(default_value.to_string()),
+            flag: false,
+            short: None,
+        }
+    }
+
+    #[test]
+    fn test_make_config() {
+        let mut verbose = option("uniffi-verbose", "");
+        verbose.flag = true;
+        verbose.short = Some("v".to_string());
+        let config = make_config(
+            vec![option("uniffi-port", "3000"), verbose],
+            vec![
+                "--uniffi-port".to_string(),
+                "8080".to_string(),
+                "-v".to_string(),
+            ],
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(config["uniffiPort"], "8080");
+        assert_eq!(config["uniffiVerbose"], "true");
+    }
+
+    #[test]
+    fn test_make_config_argument_errors() {
+        for arg in ["--bogus", "--help"] {
+            let result = make_config(
+                vec![option("uniffi-arg-port", "3000")],
+                vec![arg.to_string()],
+                None,
+                None,
+            );
+            let Err(LinoError::Arguments { message }) = result else {
+                panic!("{arg} was accepted");
+            };
+            assert!(message.contains(if arg == "--help" {
+                "--uniffi-arg-port"
+            } else {
+                arg
+            }));
+        }
+    }
+
+    #[test]
+    fn test_parsers() {
+        assert_eq!(parse_duration("1m30s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_byte_size("2KiB").unwrap(), 2048);
+        assert_eq!(parse_list("a, b"), ["a", "b"]);
+        assert_eq!(parse_map("a=1").unwrap()["a"], "1");
+        assert!(matches!(parse_map("a"), Err(LinoError::Parse { .. })));
+        assert_eq!(convert_case("api-key", "upper-snake").unwrap(), "API_KEY");
+        assert!(convert_case("api-key", "sideways").is_err());
+    }
+}
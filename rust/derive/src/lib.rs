@@ -0,0 +1,179 @@
+//! `#[derive(LinoConfig)]` for [lino-arguments](https://docs.rs/lino-arguments).
+//!
+//! Enable the `derive` feature of `lino-arguments` to use it; the crate
+//! re-exports the macro next to the `LinoConfig` trait it implements.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Data, DeriveInput, Error, Expr, Fields, Ident, Lit, Meta, Token, Type};
+
+/// Implement `LinoConfig` for a struct with named fields, declaring one
+/// schema key per field.
+///
+/// Each key is named after its field and typed after the field's type:
+/// integers are `Integer`, `f32` and `f64` are `Float`, `bool` is `Bool`,
+/// `Option<T>` is the type of `T`, and anything else is `String`. The doc
+/// comment of a field is its description, and the contents of its
+/// `#[lino(...)]` attributes are passed on to `KeySpec::from_attributes()`.
+/// Attribute values are string, number, or boolean literals.
+#[proc_macro_derive(LinoConfig, attributes(lino))]
+pub fn derive_lino_config(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "LinoConfig can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "LinoConfig can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut keys = Vec::new();
+    for field in &fields.named {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+        let name = ident.unraw().to_string();
+        let value_type = value_type(&field.ty);
+        let mut items = Vec::new();
+        if let Some(description) = doc_comment(&field.attrs) {
+            items.push(format!("description = {}", quoted(&description)));
+        }
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("lino"))
+        {
+            let parsed = attr.parse_args_with(Punctuated::<Item, Token![,]>::parse_terminated)?;
+            items.extend(parsed.iter().map(Item::to_text));
+        }
+        let attributes = items.join(", ");
+        keys.push(quote! {
+            .key(
+                ::lino_arguments::KeySpec::from_attributes(
+                    #name,
+                    ::lino_arguments::ValueType::#value_type,
+                    #attributes,
+                )
+                .unwrap_or_else(|err| panic!("{err}")),
+            )
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lino_arguments::LinoConfig for #ident #ty_generics #where_clause {
+            fn schema() -> ::lino_arguments::Schema {
+                ::lino_arguments::Schema::new() #(#keys)*
+            }
+        }
+    })
+}
+
+/// The `ValueType` variant of a field of type `ty`.
+fn value_type(ty: &Type) -> Ident {
+    let Type::Path(path) = ty else {
+        return format_ident!("String");
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return format_ident!("String");
+    };
+    let name = segment.ident.to_string();
+    match name.as_str() {
+        "Option" => match &segment.arguments {
+            syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(syn::GenericArgument::Type(inner)) => value_type(inner),
+                _ => format_ident!("String"),
+            },
+            _ => format_ident!("String"),
+        },
+        "bool" => format_ident!("Bool"),
+        "f32" | "f64" => format_ident!("Float"),
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => format_ident!("Integer"),
+        _ => format_ident!("String"),
+    }
+}
+
+/// The lines of the doc comment in `attrs`, trimmed and joined by spaces.
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(text) => Some(text.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    (!lines.is_empty()).then(|| lines.join(" "))
+}
+
+/// `value` as a string in the attribute grammar, with `\` and `"` escaped.
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A `name` or `name = literal` item of a `#[lino(...)]` attribute.
+struct Item {
+    name: Ident,
+    value: Option<String>,
+}
+
+impl Parse for Item {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.call(Ident::parse_any)?;
+        if input.parse::<Option<Token![=]>>()?.is_none() {
+            return Ok(Self { name, value: None });
+        }
+        let negative = input.parse::<Option<Token![-]>>()?.is_some();
+        let lit: Lit = input.parse()?;
+        let value = match &lit {
+            Lit::Str(text) if !negative => text.value(),
+            Lit::Int(number) => number.base10_digits().to_string(),
+            Lit::Float(number) => number.base10_digits().to_string(),
+            Lit::Bool(flag) if !negative => flag.value.to_string(),
+            _ => {
+                return Err(Error::new_spanned(
+                    lit,
+                    "expected a string, number, or boolean",
+                ))
+            }
+        };
+        let value = if negative { format!("-{value}") } else { value };
+        Ok(Self {
+            name,
+            value: Some(value),
+        })
+    }
+}
+
+impl Item {
+    /// The item in the grammar of `KeySpec::from_attributes()`.
+    fn to_text(&self) -> String {
+        match &self.value {
+            Some(value) => format!("{} = {}", self.name, quoted(value)),
+            None => self.name.to_string(),
+        }
+    }
+}
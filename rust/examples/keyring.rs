@@ -0,0 +1,61 @@
+//! Keychain example: store secrets and read them back through a schema
+//!
+//! Secrets are kept in the platform keychain (Secret Service, macOS
+//! Keychain, or Windows Credential Manager) instead of config files, and
+//! resolved for the keys the schema marks sensitive.
+//!
+//! Usage:
+//!   echo sk-live-1234 | cargo run --example keyring --features keyring -- set api-token
+//!   cargo run --example keyring --features keyring -- show
+//!   cargo run --example keyring --features keyring -- delete api-token
+
+use lino_arguments::{
+    delete_keyring_secret, set_keyring_secret, try_make_config_from, KeySpec, Parser, Schema,
+    Subcommand, ValueType,
+};
+
+/// The service the secrets are stored under.
+const SERVICE: &str = "lino-arguments-example";
+
+#[derive(Parser, Debug)]
+#[command(about = "Manage secrets in the platform keychain")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Store a secret, read from the first line of stdin
+    Set { key: String },
+    /// Remove a secret
+    Delete { key: String },
+    /// Resolve the configuration and show where each value came from
+    Show,
+}
+
+fn main() -> Result<(), lino_arguments::ConfigError> {
+    match Args::parse().command {
+        Command::Set { key } => {
+            let mut value = String::new();
+            std::io::stdin().read_line(&mut value)?;
+            set_keyring_secret(SERVICE, &key, value.trim_end_matches(['\r', '\n']))?;
+            println!("Stored {key}");
+        }
+        Command::Delete { key } => {
+            delete_keyring_secret(SERVICE, &key)?;
+            println!("Deleted {key}");
+        }
+        Command::Show => {
+            let schema = Schema::new()
+                .key(KeySpec::new("api-token", ValueType::String).sensitive(true))
+                .key(KeySpec::new("port", ValueType::Integer).default("3000"));
+            let config = try_make_config_from(["keyring"], |c| c.schema(schema).keyring(SERVICE))?;
+            print!("{}", config.to_masked_string());
+            if let Some(source) = config.source("api-token") {
+                println!("api-token was read from the {source}");
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,330 @@
+//! `#[lino(...)]` field attributes. Requires the `std` feature.
+//!
+//! The attribute grammar is parsed at runtime so that a derive macro only
+//! has to forward the attribute text, and so that schemas can be declared
+//! the same way without one.
+
+use std::ops::Bound;
+
+use crate::config::{parse_float, ConfigError};
+use crate::{Deprecation, KeySpec, Schema, Validator, ValueType};
+
+/// A struct whose fields declare configuration keys, usually implemented
+/// with `#[derive(LinoConfig)]` (`derive` feature).
+///
+/// The derive declares one key per field, named after it, typed after the
+/// field's type (`Option<T>` as `T`), described by its doc comment, and
+/// with the contents of its `#[lino(...)]` attributes read as by
+/// [`KeySpec::from_attributes()`]. Pass the schema to
+/// [`ConfigBuilder::schema()`](crate::ConfigBuilder::schema) to define and
+/// check the options, and deserialize the struct with
+/// [`from_sources()`](crate::from_sources) if it also derives `Deserialize`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use lino_arguments::LinoConfig;
+///
+/// #[derive(LinoConfig, serde::Deserialize)]
+/// struct Settings {
+///     /// Server port
+///     #[lino(env = "HTTP_PORT", default = 3000, range = "1..=65535")]
+///     port: u16,
+///     #[lino(sensitive)]
+///     api_key: Option<String>,
+/// }
+///
+/// let settings: Settings =
+///     lino_arguments::from_sources_from(["app", "--port", "8080"], |c| c.schema(Settings::schema()))?;
+/// assert_eq!(settings.port, 8080);
+/// # }
+/// # Ok::<(), lino_arguments::ConfigError>(())
+/// ```
+pub trait LinoConfig {
+    /// The schema the fields declare.
+    ///
+    /// # Panics
+    ///
+    /// If an attribute is invalid, such as an unknown name or a malformed
+    /// range, as [`KeySpec::from_attributes()`] reports.
+    fn schema() -> Schema;
+}
+
+impl KeySpec {
+    /// Declare a key from the contents of a `#[lino(...)]` attribute.
+    ///
+    /// `attributes` is a comma-separated list of `name` or `name = value`
+    /// items, where a value is a string literal or a bare literal such as
+    /// `3000` or `true`:
+    ///
+    /// | Attribute | Effect |
+    /// |-----------|--------|
+    /// | `env = "PORT"` | [`KeySpec::env()`] |
+    /// | `default = 3000` | [`KeySpec::default()`] |
+    /// | `description = "..."` | [`KeySpec::description()`] |
+    /// | `required` | [`KeySpec::required()`] |
+    /// | `sensitive` | [`KeySpec::sensitive()`] |
+    /// | `range = "1..=65535"` | [`Validator::range()`]; `a..b`, `a..`, `..=b` and so on |
+    /// | `matches = "^[a-z]+$"` | [`Validator::matches()`] |
+    /// | `one_of = "json, text"` | [`Validator::one_of()`] |
+    /// | `validate = "readable-dir"` | [`Validator::named()`]; repeatable |
+    /// | `example = "8080"` | [`KeySpec::example()`]; repeatable |
+    /// | `since = "0.4.0"` | [`KeySpec::since()`] |
+    /// | `deprecated` or `deprecated = "..."` | [`KeySpec::deprecated()`] |
+    /// | `replaced_by = "port"` | [`KeySpec::replaced_by()`] |
+    ///
+    /// Returns [`ConfigError::ParseError`] naming the key for malformed
+    /// input, unknown attributes, and invalid ranges or patterns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::{KeySpec, ValueType};
+    ///
+    /// let port = KeySpec::from_attributes(
+    ///     "port",
+    ///     ValueType::Integer,
+    ///     r#"env = "PORT", default = 3000, range = "1..=65535", required"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(port.env.as_deref(), Some("PORT"));
+    /// assert_eq!(port.default.as_deref(), Some("3000"));
+    /// assert!(port.required);
+    /// ```
+    pub fn from_attributes(
+        name: &str,
+        value_type: ValueType,
+        attributes: &str,
+    ) -> Result<Self, ConfigError> {
+        let invalid = |reason: String| {
+            ConfigError::ParseError(format!("invalid #[lino] attribute on `{name}`: {reason}"))
+        };
+        let mut spec = KeySpec::new(name, value_type);
+        for (attribute, value) in parse_items(attributes).map_err(invalid)? {
+            let text = |value: Option<String>| {
+                value.ok_or_else(|| invalid(format!("`{attribute}` needs a value")))
+            };
+            match attribute.as_str() {
+                "env" => spec.env = Some(text(value)?),
+                "default" => spec.default = Some(text(value)?),
+                "description" => spec.description = text(value)?,
+                "required" => spec.required = flag(value).map_err(invalid)?,
+                "sensitive" => spec.sensitive = flag(value).map_err(invalid)?,
+                "range" => {
+                    let range = text(value)?;
+                    let validator = parse_range(&range)
+                        .ok_or_else(|| invalid(format!("`{range}` is not a valid range")))?;
+                    spec.validators.push(validator);
+                }
+                "matches" => {
+                    let pattern = text(value)?;
+                    regex::Regex::new(&pattern).map_err(|err| invalid(err.to_string()))?;
+                    spec.validators.push(Validator::matches(&pattern));
+                }
+                "one_of" => {
+                    let values = text(value)?;
+                    spec.validators
+                        .push(Validator::one_of(values.split(',').map(str::trim)));
+                }
+                "validate" => spec.validators.push(Validator::named(&text(value)?)),
+                "example" => spec.examples.push(text(value)?),
+                "since" => spec.since = Some(text(value)?),
+                "deprecated" => {
+                    let deprecation = spec.deprecated.get_or_insert_with(Deprecation::default);
+                    deprecation.message = value.unwrap_or_default();
+                }
+                "replaced_by" => {
+                    let replacement = text(value)?;
+                    spec.deprecated
+                        .get_or_insert_with(Deprecation::default)
+                        .replacement = Some(replacement);
+                }
+                _ => return Err(invalid(format!("unknown attribute `{attribute}`"))),
+            }
+        }
+        Ok(spec)
+    }
+}
+
+/// A boolean attribute: present without a value, or `= true`/`= false`.
+fn flag(value: Option<String>) -> Result<bool, String> {
+    match value.as_deref() {
+        None | Some("true") => Ok(true),
+        Some("false") => Ok(false),
+        Some(other) => Err(format!("expected `true` or `false`, got `{other}`")),
+    }
+}
+
+/// Split `name = value, name, ...` into its items. String values are
+/// unquoted and unescaped; bare values are trimmed.
+fn parse_items(input: &str) -> Result<Vec<(String, Option<String>)>, String> {
+    let mut items = Vec::new();
+    let mut chars = input.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(items);
+        }
+
+        let mut name = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+            name.push(c);
+        }
+        if name.is_empty() {
+            return Err(format!("expected an attribute name in `{input}`"));
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let value = if chars.next_if_eq(&'=').is_some() {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.next_if_eq(&'"').is_some() {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(c) => value.push(c),
+                            None => return Err(format!("unterminated string for `{name}`")),
+                        },
+                        Some(c) => value.push(c),
+                        None => return Err(format!("unterminated string for `{name}`")),
+                    }
+                }
+                Some(value)
+            } else {
+                let mut value = String::new();
+                while let Some(c) = chars.next_if(|c| *c != ',') {
+                    value.push(c);
+                }
+                let value = value.trim();
+                if value.is_empty() {
+                    return Err(format!("`{name}` needs a value"));
+                }
+                Some(value.to_string())
+            }
+        } else {
+            None
+        };
+        items.push((name, value));
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            None => return Ok(items),
+            Some(',') => {}
+            Some(c) => return Err(format!("expected `,` before `{c}`")),
+        }
+    }
+}
+
+/// Parse Rust range syntax (`1..=65535`, `0.0..1.0`, `10..`, `..=5`).
+fn parse_range(range: &str) -> Option<Validator> {
+    let (start, end) = range.split_once("..")?;
+    let (inclusive, end) = match end.strip_prefix('=') {
+        Some(end) => (true, end.trim()),
+        None => (false, end.trim()),
+    };
+    let number = |text: &str| parse_float(text.trim(), false);
+    let lower = match start.trim() {
+        "" => Bound::Unbounded,
+        start => Bound::Included(number(start)?),
+    };
+    let upper = match end {
+        "" if inclusive => return None,
+        "" => Bound::Unbounded,
+        end if inclusive => Bound::Included(number(end)?),
+        end => Bound::Excluded(number(end)?),
+    };
+    Some(Validator::range((lower, upper)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(attributes: &str) -> Result<KeySpec, String> {
+        KeySpec::from_attributes("port", ValueType::Integer, attributes).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_parse_items() {
+        assert_eq!(
+            parse_items(r#" env = "PORT",default=3000 , required, doc = "a, \"b\"" "#).unwrap(),
+            [
+                ("env".to_string(), Some("PORT".to_string())),
+                ("default".to_string(), Some("3000".to_string())),
+                ("required".to_string(), None),
+                ("doc".to_string(), Some("a, \"b\"".to_string())),
+            ]
+        );
+        assert!(parse_items("").unwrap().is_empty());
+        assert!(parse_items("required,").unwrap().len() == 1);
+        assert!(parse_items(r#"env = "PORT"#).is_err());
+        assert!(parse_items("env = ").is_err());
+        assert!(parse_items("= 3").is_err());
+        assert!(parse_items(r#"env "PORT""#).is_err());
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let check = |range: &str, value: &str| parse_range(range).unwrap().check(value, false);
+        assert!(check("1..=65535", "65535").is_ok());
+        assert!(check("1..65535", "65535").is_err());
+        assert!(check("0.0..1.0", "0.5").is_ok());
+        assert!(check("10..", "5").is_err());
+        assert!(check("..=5", "5").is_ok());
+        assert!(check("-5..5", "-5").is_ok());
+        assert!(parse_range("1..=").is_none());
+        assert!(parse_range("1-10").is_none());
+        assert!(parse_range("a..b").is_none());
+    }
+
+    #[test]
+    fn test_from_attributes() {
+        let spec = parse(
+            r#"env = "HTTP_PORT", default = 3000, range = "1..=65535", required,
+               description = "Server port", example = "8080", example = "443",
+               since = "0.4.0", one_of = "80, 443, 8080", matches = "^[0-9]+$",
+               validate = "free-port""#,
+        )
+        .unwrap();
+        assert_eq!(spec.env.as_deref(), Some("HTTP_PORT"));
+        assert_eq!(spec.default.as_deref(), Some("3000"));
+        assert!(spec.required);
+        assert_eq!(spec.description, "Server port");
+        assert_eq!(spec.examples, ["8080", "443"]);
+        assert_eq!(spec.since.as_deref(), Some("0.4.0"));
+        assert_eq!(spec.validators.len(), 4);
+        assert_eq!(
+            spec.validators[1].check("81", false).unwrap_err(),
+            "must be one of: 80, 443, 8080"
+        );
+
+        let spec =
+            parse(r#"deprecated = "Renamed.", replaced_by = "port", required = false, sensitive"#)
+                .unwrap();
+        assert!(spec.sensitive);
+        let deprecation = spec.deprecated.unwrap();
+        assert_eq!(deprecation.message, "Renamed.");
+        assert_eq!(deprecation.replacement.as_deref(), Some("port"));
+        assert!(!spec.required);
+    }
+
+    #[test]
+    fn test_from_attributes_errors() {
+        assert_eq!(
+            parse("colour = \"red\"").unwrap_err(),
+            "Parse error: invalid #[lino] attribute on `port`: unknown attribute `colour`"
+        );
+        assert_eq!(
+            parse("range = \"1 to 10\"").unwrap_err(),
+            "Parse error: invalid #[lino] attribute on `port`: `1 to 10` is not a valid range"
+        );
+        assert!(parse("env").unwrap_err().ends_with("`env` needs a value"));
+        assert!(parse("required = maybe").is_err());
+        assert!(parse("matches = \"[a-z\"").is_err());
+    }
+}
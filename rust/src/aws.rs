@@ -0,0 +1,282 @@
+//! AWS Systems Manager Parameter Store and Secrets Manager.
+//!
+//! Values are fetched with the `aws` CLI, so credentials, regions, and
+//! profiles are configured the usual way (`AWS_PROFILE`, instance roles,
+//! SSO sessions, ...).
+
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+use crate::canonical_key;
+use crate::config::ConfigError;
+use crate::source::{flatten_json_text, key_below, load_cached, Source};
+
+/// The most parameters a single `GetParameters` call accepts.
+const BATCH_SIZE: usize = 10;
+
+/// What an [`AwsSource`] fetches.
+#[derive(Debug, Clone)]
+enum Target {
+    /// Every parameter under a path, keyed by its name below the path.
+    Path(String),
+    /// Named parameters, keyed by the last segment of their names.
+    Parameters(Vec<String>),
+    /// A secret holding a JSON object, or a single value keyed by the last
+    /// segment of its name.
+    Secret(String),
+}
+
+/// A [`Source`] reading AWS Systems Manager parameters or a Secrets
+/// Manager secret through the `aws` CLI.
+///
+/// `SecureString` parameters are decrypted. With a
+/// [cache file](Self::cache), the last values fetched are saved and used
+/// when AWS cannot be reached.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::{make_config, AwsSource};
+///
+/// // `/myapp/prod/db/password` sets `db-password`
+/// let config = make_config(|c| {
+///     c.source(AwsSource::ssm("/myapp/prod/").cache(".aws-cache.json"))
+///         .option("db-password", "Database password", "")
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct AwsSource {
+    target: Target,
+    region: Option<String>,
+    profile: Option<String>,
+    cache: Option<String>,
+    /// Whether the last load came from the cache.
+    stale: Arc<AtomicBool>,
+}
+
+impl AwsSource {
+    fn new(target: Target) -> Self {
+        Self {
+            target,
+            region: None,
+            profile: None,
+            cache: None,
+            stale: Arc::default(),
+        }
+    }
+
+    /// Every Parameter Store parameter under `path` (`/myapp/prod/`),
+    /// recursively, keyed by its name below the path
+    /// (`/myapp/prod/db/password` sets `db-password`).
+    pub fn ssm(path: &str) -> Self {
+        Self::new(Target::Path(path.to_string()))
+    }
+
+    /// The named Parameter Store parameters, keyed by the last segment of
+    /// their names, fetched ten per request.
+    pub fn ssm_parameters<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(Target::Parameters(
+            names.into_iter().map(Into::into).collect(),
+        ))
+    }
+
+    /// A Secrets Manager secret. A JSON object sets a key for each field
+    /// (nested fields joined with `_`); any other value sets the key named
+    /// by the last segment of `secret_id` (`myapp/prod/api-token`).
+    pub fn secrets_manager(secret_id: &str) -> Self {
+        Self::new(Target::Secret(secret_id.to_string()))
+    }
+
+    /// The AWS region, instead of the CLI's default.
+    pub fn region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// The AWS CLI profile, instead of `AWS_PROFILE`.
+    pub fn profile(mut self, profile: &str) -> Self {
+        self.profile = Some(profile.to_string());
+        self
+    }
+
+    /// Save the values fetched to the file at `path` (readable only by the
+    /// current user on Unix) and use them when AWS cannot be reached, as
+    /// [`Source::cached()`] does.
+    pub fn cache(mut self, path: &str) -> Self {
+        self.cache = Some(path.to_string());
+        self
+    }
+
+    /// Run the `aws` CLI with `args`, returning its JSON output.
+    fn run(&self, args: &[&str]) -> Result<Value, ConfigError> {
+        let mut command = Command::new("aws");
+        command.args(args).args(["--output", "json"]);
+        if let Some(ref region) = self.region {
+            command.args(["--region", region]);
+        }
+        if let Some(ref profile) = self.profile {
+            command.args(["--profile", profile]);
+        }
+        let output = command
+            .output()
+            .map_err(|err| ConfigError::SourceError(format!("could not run aws: {err}")))?;
+        if !output.status.success() {
+            return Err(ConfigError::SourceError(format!(
+                "aws {} failed: {}",
+                args[..2].join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        serde_json::from_slice(&output.stdout)
+            .map_err(|err| ConfigError::SourceError(format!("invalid aws output: {err}")))
+    }
+
+    /// Fetch the values from AWS, only parsing the values of `keys` out of
+    /// a JSON secret if given.
+    fn fetch(
+        &self,
+        keys: Option<&HashSet<String>>,
+    ) -> Result<HashMap<String, String>, ConfigError> {
+        let mut values = HashMap::new();
+        match self.target {
+            Target::Path(ref path) => {
+                let output = self.run(&[
+                    "ssm",
+                    "get-parameters-by-path",
+                    "--path",
+                    path,
+                    "--recursive",
+                    "--with-decryption",
+                ])?;
+                add_parameters(&output, path, &mut values);
+            }
+            Target::Parameters(ref names) => {
+                for batch in names.chunks(BATCH_SIZE) {
+                    let mut args = vec!["ssm", "get-parameters", "--with-decryption", "--names"];
+                    args.extend(batch.iter().map(String::as_str));
+                    let output = self.run(&args)?;
+                    if let Some(missing) = output["InvalidParameters"]
+                        .as_array()
+                        .filter(|missing| !missing.is_empty())
+                    {
+                        return Err(ConfigError::SourceError(format!(
+                            "missing SSM parameters: {}",
+                            missing
+                                .iter()
+                                .filter_map(Value::as_str)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )));
+                    }
+                    add_parameters(&output, "", &mut values);
+                }
+            }
+            Target::Secret(ref id) => {
+                let output =
+                    self.run(&["secretsmanager", "get-secret-value", "--secret-id", id])?;
+                let secret = output["SecretString"].as_str().unwrap_or_default();
+                match serde_json::from_str::<&RawValue>(secret) {
+                    Ok(document) if document.get().starts_with('{') => {
+                        flatten_json_text(secret, keys, &mut values).map_err(|err| {
+                            ConfigError::SourceError(format!("invalid secret {id}: {err}"))
+                        })?;
+                    }
+                    _ => {
+                        values.insert(last_segment(id), secret.to_string());
+                    }
+                }
+            }
+        }
+        Ok(values)
+    }
+}
+
+impl Source for AwsSource {
+    fn name(&self) -> &'static str {
+        match self.target {
+            Target::Secret(_) => "AWS Secrets Manager",
+            _ => "AWS Parameter Store",
+        }
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        match self.cache {
+            Some(ref cache) => load_cached(cache, &self.stale, || self.fetch(None)),
+            None => self.fetch(None),
+        }
+    }
+
+    fn load_keys(&self, keys: &HashSet<String>) -> Result<HashMap<String, String>, ConfigError> {
+        match self.cache {
+            // The cache keeps every value
+            Some(_) => self.load(),
+            None => self.fetch(Some(keys)),
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        self.stale.load(Ordering::SeqCst)
+    }
+}
+
+/// Add the `Parameters` of an SSM response to `values`, keyed by their
+/// names below `path`, or by their last segment without one.
+fn add_parameters(output: &Value, path: &str, values: &mut HashMap<String, String>) {
+    for parameter in output["Parameters"].as_array().into_iter().flatten() {
+        let (Some(name), Some(value)) = (parameter["Name"].as_str(), parameter["Value"].as_str())
+        else {
+            continue;
+        };
+        let key = if path.is_empty() {
+            last_segment(name)
+        } else {
+            key_below(name, path)
+        };
+        values.insert(key, value.to_string());
+    }
+}
+
+/// The canonical key of the last `/`-separated segment of `name`.
+fn last_segment(name: &str) -> String {
+    let segment = name.rsplit('/').find(|s| !s.is_empty()).unwrap_or(name);
+    canonical_key(segment).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_parameters() {
+        let output = serde_json::json!({"Parameters": [
+            {"Name": "/myapp/prod/db/password", "Value": "hunter2", "Type": "SecureString"},
+            {"Name": "/myapp/prod/PORT", "Value": "8080", "Type": "String"},
+            {"Name": "/myapp/prod/broken"},
+        ]});
+        let mut values = HashMap::new();
+        add_parameters(&output, "/myapp/prod/", &mut values);
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["db-password"], "hunter2");
+        assert_eq!(values["port"], "8080");
+
+        let mut values = HashMap::new();
+        add_parameters(&output, "", &mut values);
+        assert_eq!(values["password"], "hunter2");
+    }
+
+    #[test]
+    fn test_last_segment() {
+        assert_eq!(last_segment("/myapp/prod/API_TOKEN"), "api-token");
+        assert_eq!(last_segment("myapp/db-password/"), "db-password");
+        assert_eq!(last_segment("token"), "token");
+    }
+}
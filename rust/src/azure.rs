@@ -0,0 +1,178 @@
+//! Azure App Configuration, with Key Vault references. Requires the `azure`
+//! feature.
+//!
+//! Values are fetched with the `az` CLI, so credentials are configured the
+//! usual way (`az login`, managed identities, or
+//! `AZURE_APPCONFIG_CONNECTION_STRING`).
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use serde_json::Value;
+
+use crate::config::ConfigError;
+use crate::source::{key_below, Source};
+
+/// The content type of a setting referencing a Key Vault secret.
+const KEY_VAULT_REFERENCE: &str = "application/vnd.microsoft.appconfig.keyvaultref+json";
+
+/// A [`Source`] reading the settings of an Azure App Configuration store
+/// through the `az` CLI, keyed by their names below a prefix with `:` and
+/// `/` separators joined with `_` (`MyApp:Db:Host` under `MyApp:` sets
+/// `db-host`).
+///
+/// Settings without a label apply to every profile; a [label](Self::label)
+/// selects a profile whose settings override them. Key Vault references,
+/// which is how App Configuration stores sensitive keys, are resolved to the
+/// secret they point at.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::{make_config, AzureSource};
+///
+/// let config = make_config(|c| {
+///     c.source(AzureSource::new("myapp-config").prefix("MyApp:").label("production"))
+///         .option("db-password", "Database password", "")
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct AzureSource {
+    store: String,
+    prefix: String,
+    label: Option<String>,
+}
+
+impl AzureSource {
+    /// Every setting without a label in the store named `store`.
+    pub fn new(store: &str) -> Self {
+        Self {
+            store: store.to_string(),
+            prefix: String::new(),
+            label: None,
+        }
+    }
+
+    /// Only the settings whose keys start with `prefix` (`MyApp:`), keyed by
+    /// their names below it.
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    /// Also read the settings labelled `label`, the profile (`production`),
+    /// which override the settings without a label.
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Run the `az` CLI with `args`, returning its JSON output.
+    fn run(args: &[&str]) -> Result<Value, ConfigError> {
+        let output = Command::new("az")
+            .args(args)
+            .args(["--output", "json"])
+            .output()
+            .map_err(|err| ConfigError::SourceError(format!("could not run az: {err}")))?;
+        if !output.status.success() {
+            return Err(ConfigError::SourceError(format!(
+                "az {} failed: {}",
+                args[..3].join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        serde_json::from_slice(&output.stdout)
+            .map_err(|err| ConfigError::SourceError(format!("invalid az output: {err}")))
+    }
+
+    /// The settings with `label`, or without a label.
+    fn list(&self, label: Option<&str>) -> Result<Vec<Value>, ConfigError> {
+        let filter = format!("{}*", self.prefix);
+        let mut args = vec![
+            "appconfig",
+            "kv",
+            "list",
+            "--name",
+            &self.store,
+            "--key",
+            &filter,
+            "--all",
+        ];
+        if let Some(label) = label {
+            args.extend(["--label", label]);
+        }
+        match Self::run(&args)? {
+            Value::Array(settings) => Ok(settings),
+            _ => Err(ConfigError::SourceError(
+                "invalid az output: expected a list of settings".to_string(),
+            )),
+        }
+    }
+}
+
+impl Source for AzureSource {
+    fn name(&self) -> &'static str {
+        "Azure App Configuration"
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        let mut settings = self.list(None)?;
+        if let Some(ref label) = self.label {
+            settings.extend(self.list(Some(label))?);
+        }
+        let mut values = HashMap::new();
+        for setting in &settings {
+            let (Some(key), Some(value)) = (setting["key"].as_str(), setting["value"].as_str())
+            else {
+                continue;
+            };
+            let value = match key_vault_uri(setting) {
+                Some(uri) => resolve_reference(key, &uri)?,
+                None => value.to_string(),
+            };
+            let name = key.strip_prefix(self.prefix.as_str()).unwrap_or(key);
+            values.insert(key_below(&name.replace(':', "/"), ""), value);
+        }
+        Ok(values)
+    }
+}
+
+/// The secret URI of a setting that is a Key Vault reference.
+fn key_vault_uri(setting: &Value) -> Option<String> {
+    let content_type = setting["contentType"].as_str()?;
+    if !content_type.starts_with(KEY_VAULT_REFERENCE) {
+        return None;
+    }
+    let reference: Value = serde_json::from_str(setting["value"].as_str()?).ok()?;
+    reference["uri"].as_str().map(str::to_string)
+}
+
+/// The value of the Key Vault secret at `uri`, referenced by the setting
+/// `key`.
+fn resolve_reference(key: &str, uri: &str) -> Result<String, ConfigError> {
+    let secret = AzureSource::run(&["keyvault", "secret", "show", "--id", uri])?;
+    secret["value"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| ConfigError::SourceError(format!("Key Vault reference {key} has no value")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_vault_uri() {
+        let reference = serde_json::json!({
+            "key": "MyApp:Db:Password",
+            "value": "{\"uri\":\"https://myvault.vault.azure.net/secrets/db-password\"}",
+            "contentType": "application/vnd.microsoft.appconfig.keyvaultref+json;charset=utf-8",
+        });
+        assert_eq!(
+            key_vault_uri(&reference).as_deref(),
+            Some("https://myvault.vault.azure.net/secrets/db-password")
+        );
+        let plain = serde_json::json!({"key": "MyApp:Port", "value": "8080", "contentType": null});
+        assert_eq!(key_vault_uri(&plain), None);
+    }
+}
@@ -0,0 +1,34 @@
+//! `lino-arguments case <case> <input>`: the case converters of the
+//! library, for shell scripts.
+
+use std::io::BufRead;
+
+use lino_arguments::{convert_case_with, Case, CaseOptions, ConfigError};
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The case to convert to: camel, pascal, snake, kebab, upper-snake,
+    /// train, dot, screaming-kebab, path, title, or flat
+    case: Case,
+
+    /// The names to convert [default: each line of stdin]
+    inputs: Vec<String>,
+
+    /// A word to keep as written, such as `iOS` (repeatable)
+    #[arg(long = "exception", value_name = "WORD")]
+    exceptions: Vec<String>,
+}
+
+/// Prints each name converted, one per line.
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    let options = CaseOptions::new().exceptions(args.exceptions.iter().cloned());
+    let convert = |input: &str| println!("{}", convert_case_with(input, args.case, &options));
+    if !args.inputs.is_empty() {
+        args.inputs.iter().for_each(|input| convert(input));
+        return Ok(());
+    }
+    for line in std::io::stdin().lock().lines() {
+        convert(&line?);
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! `lino-arguments completions <shell>`: a completion script for this tool,
+//! or for an application whose flags a schema declares.
+
+use clap::CommandFactory;
+use clap_complete::Shell;
+use lino_arguments::{convert_case, Case, ConfigError, Schema, ValueType};
+
+use crate::resolve::read_json_schema;
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The shell to write the script for
+    shell: Shell,
+
+    /// Complete the flags of the application this JSON Schema declares,
+    /// instead of this tool
+    #[arg(long, value_name = "PATH")]
+    schema: Option<String>,
+
+    /// The name the application is invoked by
+    #[arg(long, requires = "schema", default_value = "app")]
+    name: String,
+}
+
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    let mut command = match args.schema {
+        Some(ref path) => application(
+            &args.name,
+            &Schema::from_json_schema(&read_json_schema(path)?),
+        ),
+        None => crate::Cli::command(),
+    };
+    let name = command.get_name().to_string();
+    clap_complete::generate(args.shell, &mut command, name, &mut std::io::stdout());
+    Ok(())
+}
+
+/// The command line of an application built with `schema`: the options
+/// the builder defines for it, with `--configuration`.
+fn application(name: &str, schema: &Schema) -> clap::Command {
+    let configuration = clap::Arg::new("configuration")
+        .long("configuration")
+        .short('c')
+        .help("Path to configuration .lenv file")
+        .value_name("PATH")
+        .value_hint(clap::ValueHint::FilePath);
+    let mut command = clap::Command::new(name.to_string()).arg(configuration);
+    for spec in schema.keys() {
+        let long = convert_case(&spec.name, Case::Kebab).into_owned();
+        let mut arg = clap::Arg::new(long.clone()).long(long);
+        if !spec.description.is_empty() {
+            arg = arg.help(spec.description.clone());
+        }
+        arg = match spec.value_type {
+            ValueType::Bool => arg.action(clap::ArgAction::SetTrue),
+            value_type => arg.value_name(value_type.to_string().to_uppercase()),
+        };
+        command = command.arg(arg);
+    }
+    command
+}
@@ -0,0 +1,247 @@
+//! `lino-arguments convert`: a configuration file in another format, with
+//! nested keys flattened into variable names or nested back.
+
+use lino_arguments::{convert_case, parse_lenv, Case, ConfigError};
+use serde_json::{Map, Value};
+
+use crate::export::{default_case, write, Format};
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The file to convert
+    input: String,
+
+    /// The format to write
+    #[arg(short, long, value_enum)]
+    to: Format,
+
+    /// The format of the input [default: from its file name]
+    #[arg(long, value_enum)]
+    from: Option<Format>,
+
+    /// The case of the keys [default: upper-snake for env and lenv, snake
+    /// otherwise]
+    #[arg(long)]
+    case: Option<Case>,
+
+    /// Join nested keys with this separator when flattening, and split
+    /// variable names on it when nesting [default: join with `_`, and do
+    /// not nest]
+    #[arg(long, value_name = "SEP")]
+    separator: Option<String>,
+}
+
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    let failed = |reason: String| ConfigError::ParseError(format!("{}: {reason}", args.input));
+    let from = match args.from {
+        Some(format) => format,
+        None => detect(&args.input).ok_or_else(|| {
+            failed("cannot tell the format from the file name; pass --from".to_string())
+        })?,
+    };
+    let text = std::fs::read_to_string(&args.input)
+        .map_err(|err| ConfigError::FileError(format!("Failed to read {}: {err}", args.input)))?;
+    let mut tree = parse(from, &text).map_err(failed)?;
+    if let (true, Some(separator)) = (from.is_flat(), &args.separator) {
+        tree = nest(tree, separator).map_err(failed)?;
+    }
+    let case = args.case.unwrap_or(default_case(args.to));
+    let output = if args.to.is_flat() {
+        let mut values = Vec::new();
+        flatten(
+            &tree,
+            &mut Vec::new(),
+            case,
+            args.separator.as_deref(),
+            &mut values,
+        );
+        write(args.to, &values)
+    } else {
+        serialize(args.to, &renamed(tree, case)).map_err(failed)?
+    };
+    print!("{output}");
+    Ok(())
+}
+
+/// The format of the file at `path`, from its name.
+fn detect(path: &str) -> Option<Format> {
+    let name = std::path::Path::new(path).file_name()?.to_str()?;
+    let extension = name.rsplit_once('.').map(|(_, extension)| extension);
+    match extension {
+        Some("json") => Some(Format::Json),
+        Some("yaml" | "yml") => Some(Format::Yaml),
+        Some("toml") => Some(Format::Toml),
+        Some("lenv") => Some(Format::Lenv),
+        _ if name == ".env" || name.starts_with(".env.") || name.ends_with(".env") => {
+            Some(Format::Env)
+        }
+        _ => None,
+    }
+}
+
+/// The keys and values of `text`, written in `format`. The flat formats
+/// give an object of strings.
+fn parse(format: Format, text: &str) -> Result<Value, String> {
+    let tree = match format {
+        Format::Json => serde_json::from_str(text).map_err(|err| err.to_string())?,
+        Format::Yaml => serde_yaml_ng::from_str(text).map_err(|err| err.to_string())?,
+        Format::Toml => toml::from_str(text).map_err(|err| err.to_string())?,
+        Format::Env => dotenvy::from_read_iter(text.as_bytes())
+            .map(|variable| variable.map(|(name, value)| (name, Value::String(value))))
+            .collect::<Result<Map<_, _>, _>>()
+            .map_err(|err| err.to_string())?
+            .into(),
+        Format::Lenv => parse_lenv(text)
+            .into_iter()
+            .map(|(name, value)| (name, Value::String(value)))
+            .collect::<Map<_, _>>()
+            .into(),
+    };
+    match tree {
+        Value::Object(_) => Ok(tree),
+        _ => Err("the document is not a table of keys".to_string()),
+    }
+}
+
+/// The variables of the flat `tree` nested by splitting their names on
+/// `separator`, so that `DB__PASSWORD` sets `password` in `DB`.
+fn nest(tree: Value, separator: &str) -> Result<Value, String> {
+    let Value::Object(variables) = tree else {
+        return Ok(tree);
+    };
+    let mut nested = Map::new();
+    for (name, value) in variables {
+        let mut parts: Vec<&str> = name.split(separator).collect();
+        let last = parts.pop().expect("split yields a part");
+        let mut table = &mut nested;
+        for part in parts {
+            let entry = table
+                .entry(part)
+                .or_insert_with(|| Value::Object(Map::new()));
+            table = entry
+                .as_object_mut()
+                .ok_or_else(|| format!("`{part}` is both a value and a table of keys"))?;
+        }
+        if table.get(last).is_some_and(Value::is_object) {
+            return Err(format!("`{last}` is both a value and a table of keys"));
+        }
+        table.insert(last.to_string(), value);
+    }
+    Ok(Value::Object(nested))
+}
+
+/// Add the scalar values of `tree` under `path` to `values`, named by their
+/// path: joined with `_` and converted to `case` as a whole, as the library
+/// flattens JSON sources, or with each part converted and joined with
+/// `separator`. Arrays and nulls have no flat form and are skipped.
+fn flatten<'a>(
+    tree: &'a Value,
+    path: &mut Vec<&'a str>,
+    case: Case,
+    separator: Option<&str>,
+    values: &mut Vec<(String, String)>,
+) {
+    let name = |path: &[&str]| match separator {
+        None => convert_case(&path.join("_"), case).into_owned(),
+        Some(separator) => {
+            let parts: Vec<_> = path.iter().map(|part| convert_case(part, case)).collect();
+            parts.join(separator)
+        }
+    };
+    let text = match tree {
+        Value::Object(map) => {
+            for (key, value) in map {
+                path.push(key);
+                flatten(value, path, case, separator, values);
+                path.pop();
+            }
+            return;
+        }
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Array(_) | Value::Null => {
+            eprintln!(
+                "warning: skipped `{}`, which has no flat form",
+                path.join(".")
+            );
+            return;
+        }
+    };
+    values.push((name(path), text));
+}
+
+/// `tree` with every key converted to `case`.
+fn renamed(tree: Value, case: Case) -> Value {
+    match tree {
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(key, value)| (convert_case(&key, case).into_owned(), renamed(value, case)))
+            .collect::<Map<_, _>>()
+            .into(),
+        other => other,
+    }
+}
+
+/// `tree` written in one of the nested formats.
+fn serialize(format: Format, tree: &Value) -> Result<String, String> {
+    match format {
+        Format::Yaml => serde_yaml_ng::to_string(tree).map_err(|err| err.to_string()),
+        Format::Toml => toml::to_string(tree).map_err(|err| err.to_string()),
+        _ => serde_json::to_string_pretty(tree)
+            .map(|json| json + "\n")
+            .map_err(|err| err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_nest_and_flatten() {
+        let flat = json!({"DB__HOST": "localhost", "DB__PORT": "5432", "DEBUG": "true"});
+        let nested = nest(flat, "__").unwrap();
+        assert_eq!(
+            nested,
+            json!({"DB": {"HOST": "localhost", "PORT": "5432"}, "DEBUG": "true"})
+        );
+
+        let mut values = Vec::new();
+        flatten(
+            &nested,
+            &mut Vec::new(),
+            Case::UpperSnake,
+            Some("__"),
+            &mut values,
+        );
+        assert_eq!(
+            values,
+            [
+                ("DB__HOST".to_string(), "localhost".to_string()),
+                ("DB__PORT".to_string(), "5432".to_string()),
+                ("DEBUG".to_string(), "true".to_string()),
+            ]
+        );
+
+        let mut values = Vec::new();
+        let tree = json!({"server": {"maxConnections": 10, "hosts": ["a"]}});
+        flatten(&tree, &mut Vec::new(), Case::UpperSnake, None, &mut values);
+        assert_eq!(
+            values,
+            [("SERVER_MAX_CONNECTIONS".to_string(), "10".to_string())]
+        );
+
+        assert!(nest(json!({"A": "1", "A__B": "2"}), "__").is_err());
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect("config/app.toml"), Some(Format::Toml));
+        assert_eq!(detect(".env.local"), Some(Format::Env));
+        assert_eq!(detect("prod.env"), Some(Format::Env));
+        assert_eq!(detect("app.lenv"), Some(Format::Lenv));
+        assert_eq!(detect("config.ini"), None);
+    }
+}
@@ -0,0 +1,54 @@
+//! `lino-arguments decrypt`: the `enc:` values of a configuration file, or
+//! a single value, decrypted with an age identity for inspection.
+
+use lino_arguments::{canonical_key, decrypt_value, ConfigError};
+
+use crate::resolve::read_variables;
+use crate::set::{set_line, write};
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The keys to decrypt [default: every encrypted key of the file]
+    keys: Vec<String>,
+
+    /// The age identity file, as written by `age-keygen`
+    #[arg(short, long, value_name = "PATH")]
+    identity: String,
+
+    /// The file to decrypt: a .lenv file, or a .env file for any other name
+    #[arg(short, long, value_name = "PATH", default_value = ".lenv")]
+    file: String,
+
+    /// Write the plaintext back to the file instead of printing it
+    #[arg(long)]
+    in_place: bool,
+
+    /// Print this `enc:` value decrypted instead of reading a file
+    #[arg(long, conflicts_with_all = ["keys", "file", "in_place"])]
+    value: Option<String>,
+}
+
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    if let Some(ref value) = args.value {
+        println!("{}", decrypt_value(&args.identity, value)?);
+        return Ok(());
+    }
+    let lenv = args.file.ends_with(".lenv");
+    let mut text = std::fs::read_to_string(&args.file)
+        .map_err(|err| ConfigError::FileError(format!("Failed to read {}: {err}", args.file)))?;
+    let wanted: Vec<_> = args.keys.iter().map(|key| canonical_key(key)).collect();
+    for (name, value) in read_variables(&args.file)? {
+        let selected = wanted.is_empty() || wanted.contains(&canonical_key(&name));
+        if selected && value.starts_with("enc:") {
+            let plaintext = decrypt_value(&args.identity, &value)
+                .map_err(|err| ConfigError::ParseError(format!("{name}: {err}")))?;
+            text = set_line(&text, &name, &plaintext, lenv);
+        }
+    }
+    if args.in_place {
+        write(&args.file, &text)
+    } else {
+        print!("{text}");
+        Ok(())
+    }
+}
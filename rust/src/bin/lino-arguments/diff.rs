@@ -0,0 +1,175 @@
+//! `lino-arguments diff`: the keys added, removed, and changed between two
+//! configuration files, or a file and the environment.
+
+use std::collections::BTreeMap;
+
+use lino_arguments::{convert_case, Case, ConfigError, Secret};
+use serde_json::{json, Value};
+
+use crate::output::{print_json, Output};
+use crate::resolve::{looks_sensitive, read_variables};
+use crate::style::{Color, Style};
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The file to compare from
+    from: String,
+
+    /// The file to compare to
+    #[arg(required_unless_present = "against_env")]
+    to: Option<String>,
+
+    /// Compare to the environment: the variables `FROM` sets, as currently
+    /// exported
+    #[arg(long, conflicts_with = "to")]
+    against_env: bool,
+}
+
+/// A difference in one key.
+#[derive(Debug, PartialEq, Eq)]
+enum Change {
+    Added {
+        name: String,
+        value: String,
+    },
+    Removed {
+        name: String,
+        value: String,
+    },
+    Changed {
+        name: String,
+        from: String,
+        to: String,
+    },
+}
+
+pub fn run(args: &Args, output: Output, show_secrets: bool) -> Result<(), ConfigError> {
+    let from = read_variables(&args.from)?;
+    let to = match args.to {
+        Some(ref path) => read_variables(path)?,
+        None => from
+            .iter()
+            .filter_map(|(name, _)| Some((name.clone(), std::env::var(name).ok()?)))
+            .collect(),
+    };
+    let changes = diff(from, to);
+    let shown = |name: &str, value: String| match looks_sensitive(name) && !show_secrets {
+        true => Secret::new(value).masked(4),
+        false => value,
+    };
+    if output == Output::Json {
+        let changes: Vec<Value> = changes
+            .into_iter()
+            .map(|change| change_json(change, shown))
+            .collect();
+        print_json(&json!({ "changes": changes }));
+        return Ok(());
+    }
+    if changes.is_empty() {
+        println!("No differences.");
+    }
+    let style = Style::stdout();
+    for change in changes {
+        let (color, line) = match change {
+            Change::Added { name, value } => {
+                (Color::Green, format!("+ {name}={}", shown(&name, value)))
+            }
+            Change::Removed { name, value } => {
+                (Color::Red, format!("- {name}={}", shown(&name, value)))
+            }
+            Change::Changed { name, from, to } => (
+                Color::Yellow,
+                format!("~ {name}: {} -> {}", shown(&name, from), shown(&name, to)),
+            ),
+        };
+        println!("{}", style.paint(color, &line));
+    }
+    Ok(())
+}
+
+/// `change` as JSON: the variable `name`, the kind of `change`, and the
+/// `old` and `new` values as `shown`, `null` where there is none.
+fn change_json(change: Change, shown: impl Fn(&str, String) -> String) -> Value {
+    let (kind, name, old, new) = match change {
+        Change::Added { name, value } => ("added", name, None, Some(value)),
+        Change::Removed { name, value } => ("removed", name, Some(value), None),
+        Change::Changed { name, from, to } => ("changed", name, Some(from), Some(to)),
+    };
+    json!({
+        "name": name,
+        "change": kind,
+        "old": old.map(|value| shown(&name, value)),
+        "new": new.map(|value| shown(&name, value)),
+    })
+}
+
+/// The changes from `from` to `to`, by key, in key order. Variables that
+/// differ only in case, such as `API_KEY` and `apiKey`, are the same key;
+/// a variable set twice in one file keeps its last value.
+fn diff(from: Vec<(String, String)>, to: Vec<(String, String)>) -> Vec<Change> {
+    let by_key = |variables: Vec<(String, String)>| -> BTreeMap<String, (String, String)> {
+        variables
+            .into_iter()
+            .map(|(name, value)| (convert_case(&name, Case::Kebab).into_owned(), (name, value)))
+            .collect()
+    };
+    let (from, mut to) = (by_key(from), by_key(to));
+    let mut changes = BTreeMap::new();
+    for (key, (name, value)) in from {
+        let change = match to.remove(&key) {
+            None => Change::Removed { name, value },
+            Some((_, new)) if new == value => continue,
+            Some((_, new)) => Change::Changed {
+                name,
+                from: value,
+                to: new,
+            },
+        };
+        changes.insert(key, change);
+    }
+    for (key, (name, value)) in to {
+        changes.insert(key, Change::Added { name, value });
+    }
+    changes.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let from = vars(&[
+            ("PORT", "80"),
+            ("HOST", "a"),
+            ("OLD", "x"),
+            ("PORT", "8080"),
+        ]);
+        let to = vars(&[("host", "a"), ("port", "9090"), ("NEW", "y")]);
+        assert_eq!(
+            diff(from, to),
+            [
+                Change::Added {
+                    name: "NEW".into(),
+                    value: "y".into()
+                },
+                Change::Removed {
+                    name: "OLD".into(),
+                    value: "x".into()
+                },
+                Change::Changed {
+                    name: "PORT".into(),
+                    from: "8080".into(),
+                    to: "9090".into()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! `lino-arguments doctor`: common configuration problems that resolve
+//! without an error but not as intended.
+
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+
+use lino_arguments::{
+    canonical_key, convert_case, parse_lenv, Case, ConfigError, FilePermissions, Schema,
+};
+
+use serde_json::{json, Value};
+
+use crate::output::{print_json, Output};
+use crate::resolve::{env_name, looks_sensitive, Sources};
+use crate::style::{Color, Style};
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    sources: Sources,
+
+    /// Report variables starting with this prefix that no key of the schema
+    /// reads, such as misspelled ones
+    #[arg(long, requires = "schema")]
+    prefix: Option<String>,
+
+    /// Exit with status 1 on warnings too, not only on errors
+    #[arg(long)]
+    strict: bool,
+}
+
+/// A problem found: the check that found it, and whether it breaks the
+/// configuration or only probably changes it.
+struct Problem {
+    check: &'static str,
+    error: bool,
+    message: String,
+}
+
+impl Problem {
+    fn error(check: &'static str, message: String) -> Self {
+        Self {
+            check,
+            error: true,
+            message,
+        }
+    }
+
+    fn warning(check: &'static str, message: String) -> Self {
+        Self {
+            check,
+            error: false,
+            message,
+        }
+    }
+
+    fn color(&self) -> Color {
+        if self.error {
+            Color::Red
+        } else {
+            Color::Yellow
+        }
+    }
+
+    fn level(&self) -> &'static str {
+        if self.error {
+            "error"
+        } else {
+            "warning"
+        }
+    }
+}
+
+/// A variable set in a configuration file.
+struct FileVariable {
+    file: String,
+    name: String,
+    value: String,
+}
+
+/// As JSON, the report lists the `problems`, each with the `check` that
+/// found it. Either way, the exit status is 1 when there are errors, or,
+/// with `--strict`, any problems; warnings alone exit with 0 so that a
+/// report can be read without failing a pipeline.
+pub fn run(args: &Args, output: Output) -> Result<(), ConfigError> {
+    let schema = args.sources.read_schema()?;
+    let mut problems = Vec::new();
+    let variables = read_files(&args.sources, &schema, &mut problems);
+    conflicting_variants(&args.sources, &schema, &mut problems);
+    empty_values(&schema, &variables, &mut problems);
+    if let Some(ref prefix) = args.prefix {
+        unknown_variables(&schema, prefix, &variables, &mut problems);
+    }
+
+    if output == Output::Json {
+        let problems: Vec<Value> = problems
+            .iter()
+            .map(|problem| {
+                json!({
+                    "check": problem.check,
+                    "severity": problem.level(),
+                    "message": problem.message,
+                })
+            })
+            .collect();
+        print_json(&json!({ "problems": problems }));
+    } else {
+        let style = Style::stdout();
+        for problem in &problems {
+            let level = format!("{}:", problem.level());
+            println!(
+                "{} {}",
+                style.paint(problem.color(), &level),
+                problem.message
+            );
+        }
+        match problems.len() {
+            0 => println!("No problems found."),
+            1 => println!("1 problem found"),
+            count => println!("{count} problems found"),
+        }
+    }
+    if problems.iter().any(|problem| problem.error || args.strict) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// The variables of the `.lenv` and `.env` files, reporting files that
+/// exist but cannot be read or parsed, and files setting secrets that every
+/// user can read.
+fn read_files(
+    sources: &Sources,
+    schema: &Schema,
+    problems: &mut Vec<Problem>,
+) -> Vec<FileVariable> {
+    let mut variables = Vec::new();
+    for (path, lenv) in [(&sources.configuration, true), (&sources.env_file, false)] {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => {
+                problems.push(Problem::error(
+                    "unreadable-file",
+                    format!("{path} cannot be read: {err}"),
+                ));
+                continue;
+            }
+        };
+        let parsed = if lenv {
+            parse_lenv(&text)
+        } else {
+            match dotenvy::from_read_iter(text.as_bytes()).collect() {
+                Ok(parsed) => parsed,
+                Err(err) => {
+                    problems.push(Problem::error(
+                        "unreadable-file",
+                        format!("{path} cannot be parsed: {err}"),
+                    ));
+                    continue;
+                }
+            }
+        };
+        let secret = parsed.iter().find(|(name, _)| sensitive(schema, name));
+        let exposed = FilePermissions::world_readable(path).is_some();
+        if let Some((name, _)) = secret.filter(|_| exposed) {
+            problems.push(Problem::warning(
+                "world-readable-secret",
+                format!(
+                    "{path} sets the secret {name} but every user can read it; \
+                 restrict it with `chmod 600 {path}`"
+                ),
+            ));
+        }
+        variables.extend(parsed.into_iter().map(|(name, value)| FileVariable {
+            file: path.clone(),
+            name,
+            value,
+        }));
+    }
+    variables
+}
+
+/// Case variants of one key set together in the environment, such as
+/// `PORT` and `port`, of which only one is read.
+fn conflicting_variants(sources: &Sources, schema: &Schema, problems: &mut Vec<Problem>) {
+    let keys = sources.keys(schema);
+    let mut variants: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, _) in std::env::vars_os() {
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        let key = canonical_key(name);
+        if keys.contains(key.as_ref()) {
+            variants
+                .entry(key.into_owned())
+                .or_default()
+                .push(name.to_string());
+        }
+    }
+    for (key, mut names) in variants {
+        if names.len() < 2 {
+            continue;
+        }
+        names.sort();
+        let set = match names.as_slice() {
+            [first, second] => format!("{first} and {second} are both set"),
+            [rest @ .., last] => format!("{}, and {last} are all set", rest.join(", ")),
+            [] => continue,
+        };
+        let read = read_from(schema, &key);
+        let message = if names.contains(&read) {
+            format!("only {read} is read")
+        } else {
+            format!("`{key}` is read from {read}")
+        };
+        problems.push(Problem::warning(
+            "conflicting-variants",
+            format!("{set} in the environment, but {message}"),
+        ));
+    }
+}
+
+/// Variables set to an empty string, which hides the default of their key
+/// instead of falling back to it.
+fn empty_values(schema: &Schema, variables: &[FileVariable], problems: &mut Vec<Problem>) {
+    for spec in schema.keys() {
+        let Some(ref default) = spec.default else {
+            continue;
+        };
+        let read = env_name(spec);
+        let mut empty: Vec<String> = variables
+            .iter()
+            .filter(|variable| variable.name == read && variable.value.is_empty())
+            .map(|variable| variable.file.clone())
+            .collect();
+        if std::env::var(&read).is_ok_and(|value| value.is_empty()) {
+            empty.insert(0, "the environment".to_string());
+        }
+        for place in empty {
+            problems.push(Problem::warning(
+                "empty-value",
+                format!(
+                    "{read} is set to an empty string in {place}, which hides the default \
+                 {default:?} of `{}`; remove it to use the default",
+                    spec.name
+                ),
+            ));
+        }
+    }
+}
+
+/// Variables starting with `prefix`, in the environment or a file, that no
+/// key of the schema reads.
+fn unknown_variables(
+    schema: &Schema,
+    prefix: &str,
+    variables: &[FileVariable],
+    problems: &mut Vec<Problem>,
+) {
+    let known: Vec<String> = schema.keys().iter().map(env_name).collect();
+    let unknown = |name: &str| name.starts_with(prefix) && !known.iter().any(|known| known == name);
+    let mut environment: Vec<String> = std::env::vars_os()
+        .filter_map(|(name, _)| name.into_string().ok())
+        .filter(|name| unknown(name))
+        .collect();
+    environment.sort();
+    let places = environment
+        .into_iter()
+        .map(|name| (name, "the environment".to_string()))
+        .chain(
+            variables
+                .iter()
+                .filter(|variable| unknown(&variable.name))
+                .map(|variable| (variable.name.clone(), variable.file.clone())),
+        );
+    for (name, place) in places {
+        problems.push(Problem::warning(
+            "unknown-variable",
+            format!("{name} is set in {place}, but no key of the schema reads it"),
+        ));
+    }
+}
+
+/// The environment variable `key` is read from.
+fn read_from(schema: &Schema, key: &str) -> String {
+    match schema.get(key) {
+        Some(spec) => env_name(spec),
+        None => convert_case(key, Case::UpperSnake).into_owned(),
+    }
+}
+
+/// Whether the variable `name` holds a secret: its key is sensitive in the
+/// schema or, when undeclared, its name suggests a credential.
+fn sensitive(schema: &Schema, name: &str) -> bool {
+    match schema.keys().iter().find(|spec| env_name(spec) == name) {
+        Some(spec) => spec.sensitive,
+        None => looks_sensitive(name),
+    }
+}
@@ -0,0 +1,48 @@
+//! `lino-arguments encrypt`: the values of a configuration file, or a
+//! single value, encrypted to an age recipient as `enc:` values.
+
+use lino_arguments::{canonical_key, encrypt_value, ConfigError};
+
+use crate::resolve::read_variables;
+use crate::set::{set_line, write};
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The keys to encrypt [default: every key of the file]
+    keys: Vec<String>,
+
+    /// The age recipient (`age1...`) to encrypt to
+    #[arg(short, long)]
+    recipient: String,
+
+    /// The file to encrypt in place: a .lenv file, or a .env file for any
+    /// other name
+    #[arg(short, long, value_name = "PATH", default_value = ".lenv")]
+    file: String,
+
+    /// Print this value encrypted instead of editing a file
+    #[arg(long, conflicts_with_all = ["keys", "file"])]
+    value: Option<String>,
+}
+
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    if let Some(ref value) = args.value {
+        println!("{}", encrypt_value(&args.recipient, value)?);
+        return Ok(());
+    }
+    let lenv = args.file.ends_with(".lenv");
+    let mut text = std::fs::read_to_string(&args.file)
+        .map_err(|err| ConfigError::FileError(format!("Failed to read {}: {err}", args.file)))?;
+    let wanted: Vec<_> = args.keys.iter().map(|key| canonical_key(key)).collect();
+    let mut encrypted = 0;
+    for (name, value) in read_variables(&args.file)? {
+        let selected = wanted.is_empty() || wanted.contains(&canonical_key(&name));
+        if selected && !value.starts_with("enc:") {
+            text = set_line(&text, &name, &encrypt_value(&args.recipient, &value)?, lenv);
+            encrypted += 1;
+        }
+    }
+    write(&args.file, &text)?;
+    eprintln!("Encrypted {encrypted} value(s) in {}.", args.file);
+    Ok(())
+}
@@ -0,0 +1,225 @@
+//! `lino-arguments explain <key>`: every candidate value of a key along the
+//! priority chain, and which one won.
+
+use lino_arguments::{convert_case, Case, ConfigError, KeySpec, Secret, ValueSource};
+use serde_json::{json, Value};
+
+use crate::inspect::print_table;
+use crate::output::{print_json, Output};
+use crate::resolve::{env_name, Sources};
+use crate::style::Color;
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The key to explain, in any case (`port`, `API_KEY`)
+    key: String,
+
+    #[command(flatten)]
+    sources: Sources,
+
+    /// The command line the application would be started with
+    #[arg(last = true, value_name = "ARGS")]
+    args: Vec<String>,
+}
+
+/// A value considered for the key: its layer, the argument, variable, or
+/// file it was found under, the file and line, the value if set, and a note
+/// on why it did not win.
+struct Candidate {
+    layer: ValueSource,
+    name: String,
+    file: Option<(String, Option<usize>)>,
+    value: Option<String>,
+    note: Option<String>,
+}
+
+impl Candidate {
+    fn new(layer: ValueSource, name: String, value: Option<String>) -> Self {
+        Self {
+            layer,
+            name,
+            file: None,
+            value,
+            note: None,
+        }
+    }
+}
+
+/// As JSON, the report has the `key`, the `value` used and its `source`,
+/// and every candidate with its `status`: `used`, `overridden`, `ignored`,
+/// or `unset`.
+pub fn run(args: &Args, output: Output, show_secrets: bool) -> Result<(), ConfigError> {
+    let key = convert_case(&args.key, Case::Kebab).into_owned();
+    let schema = args.sources.relaxed_schema(Some(&key))?;
+    let spec = schema.get(&key).cloned().expect("the key is declared");
+    // The environment is read before resolving, which loads the files into it
+    let mut candidates = environment(&spec);
+    candidates.extend(files(&args.sources, &spec));
+    let config = args.sources.resolve_with(schema, &args.args)?;
+
+    let cli = (config.source(&key) == Some(ValueSource::Cli)).then(|| config.get(&key));
+    candidates.insert(0, Candidate::new(ValueSource::Cli, format!("--{key}"), cli));
+    candidates.push(Candidate::new(
+        ValueSource::Default,
+        "default".to_string(),
+        spec.default.clone(),
+    ));
+
+    let winner = config.source(&key).and_then(|source| {
+        candidates
+            .iter()
+            .position(|candidate| candidate.layer == source && candidate.note.is_none())
+    });
+    let shown = |value: &String| match spec.sensitive && !show_secrets {
+        true => Secret::new(value.clone()).masked(4),
+        false => value.clone(),
+    };
+    if output == Output::Json {
+        let candidates: Vec<Value> = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let status = match candidate.note {
+                    _ if Some(index) == winner => "used",
+                    Some(_) => "ignored",
+                    None if candidate.value.is_some() => "overridden",
+                    None => "unset",
+                };
+                let file = candidate.file.as_ref();
+                json!({
+                    "layer": candidate.layer.to_string(),
+                    "name": candidate.name,
+                    "file": file.map(|(path, _)| path),
+                    "line": file.and_then(|(_, line)| *line),
+                    "value": candidate.value.as_ref().map(shown),
+                    "status": status,
+                    "note": candidate.note,
+                })
+            })
+            .collect();
+        let used = winner.map(|index| &candidates[index]);
+        print_json(&json!({
+            "key": key,
+            "value": used.map(|candidate| &candidate["value"]),
+            "source": used.map(|candidate| &candidate["layer"]),
+            "candidates": candidates,
+        }));
+        return Ok(());
+    }
+    let rows: Vec<[String; 4]> = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| {
+            let value = match candidate.value {
+                Some(ref value) => shown(value),
+                None => "(not set)".to_string(),
+            };
+            let status = match candidate.note {
+                _ if Some(index) == winner => "used".to_string(),
+                Some(ref note) => note.clone(),
+                None if candidate.value.is_some() => "overridden".to_string(),
+                None => String::new(),
+            };
+            let name = match candidate.file {
+                Some((ref path, Some(line))) if candidate.value.is_some() => {
+                    format!("{} ({path}:{line})", candidate.name)
+                }
+                Some((ref path, _)) if candidate.value.is_some() => {
+                    format!("{} ({path})", candidate.name)
+                }
+                _ => candidate.name.clone(),
+            };
+            [candidate.layer.to_string(), name, value, status]
+        })
+        .collect();
+    print_table(
+        ["LAYER", "NAME", "VALUE", "STATUS"],
+        &rows,
+        |column, cell| match (column, cell) {
+            (3, "used") => Some(Color::Green),
+            (3, "overridden") => Some(Color::Dim),
+            (3, _) => Some(Color::Yellow),
+            _ => None,
+        },
+    );
+    if winner.is_none() {
+        println!("\n`{key}` is not set.");
+    }
+    Ok(())
+}
+
+/// The environment variable the key is read from, then every other case
+/// variant that is set, which the resolver does not read.
+fn environment(spec: &KeySpec) -> Vec<Candidate> {
+    let read = env_name(spec);
+    let variants = [
+        Case::UpperSnake,
+        Case::Camel,
+        Case::Kebab,
+        Case::Snake,
+        Case::Pascal,
+    ]
+    .map(|case| convert_case(&spec.name, case).into_owned());
+    let mut candidates = vec![Candidate::new(
+        ValueSource::Environment,
+        read.clone(),
+        std::env::var(&read).ok(),
+    )];
+    for variant in variants {
+        if variant == read || candidates.iter().any(|c| c.name == variant) {
+            continue;
+        }
+        if let Ok(value) = std::env::var(&variant) {
+            candidates.push(Candidate {
+                note: Some(format!("ignored, only {read} is read")),
+                ..Candidate::new(ValueSource::Environment, variant, Some(value))
+            });
+        }
+    }
+    candidates
+}
+
+/// The lines of the `.lenv` and `.env` files that set the key.
+fn files(sources: &Sources, spec: &KeySpec) -> Vec<Candidate> {
+    let read = env_name(spec);
+    let layers = [
+        (
+            ValueSource::LenvFile,
+            &sources.configuration,
+            sources.lenv_entries(),
+        ),
+        (
+            ValueSource::EnvFile,
+            &sources.env_file,
+            sources.env_file_entries(),
+        ),
+    ];
+    let mut candidates = Vec::new();
+    for (layer, path, entries) in layers {
+        let found = entries
+            .into_iter()
+            .rev()
+            .find(|(name, _, _)| same_variable(name, &read));
+        candidates.push(match found {
+            Some((name, value, line)) => Candidate {
+                file: Some((path.clone(), line)),
+                ..Candidate::new(layer, name, Some(value))
+            },
+            None => Candidate {
+                file: Some((path.clone(), None)),
+                ..Candidate::new(layer, path.clone(), None)
+            },
+        });
+    }
+    candidates
+}
+
+/// Whether `a` and `b` name the same environment variable on this
+/// platform.
+fn same_variable(a: &str, b: &str) -> bool {
+    if cfg!(windows) {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
@@ -0,0 +1,128 @@
+//! `lino-arguments export`: the resolved configuration as JSON, YAML, TOML,
+//! or a `.env` or `.lenv` file.
+
+use lino_arguments::{convert_case, Case, ConfigError, ValueEnum};
+
+use crate::resolve::Sources;
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The format to write
+    #[arg(short, long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+
+    /// The case of the keys [default: upper-snake for env and lenv, snake
+    /// otherwise]
+    #[arg(long)]
+    case: Option<Case>,
+
+    #[command(flatten)]
+    sources: Sources,
+}
+
+/// A format configuration is written in.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+    /// `KEY=value` lines, as read by dotenv
+    Env,
+    /// `KEY: value` lines, as read by lino-env
+    Lenv,
+}
+
+impl Format {
+    /// Whether the format has one line per variable, without nesting.
+    pub fn is_flat(self) -> bool {
+        matches!(self, Format::Env | Format::Lenv)
+    }
+}
+
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    let schema = args.sources.relaxed_schema(None)?;
+    let config = args.sources.resolve_with(schema.clone(), &[])?;
+    let case = args.case.unwrap_or(default_case(args.format));
+    let mut values: Vec<(String, String)> = schema
+        .keys()
+        .iter()
+        .filter(|spec| config.has(&spec.name))
+        .map(|spec| {
+            let name = match spec.env {
+                Some(ref env) if args.format.is_flat() && args.case.is_none() => env.clone(),
+                _ => convert_case(&spec.name, case).into_owned(),
+            };
+            (name, config.get(&spec.name))
+        })
+        .collect();
+    values.sort();
+    print!("{}", write(args.format, &values));
+    Ok(())
+}
+
+/// `values` written in `format`.
+pub fn write(format: Format, values: &[(String, String)]) -> String {
+    match format {
+        Format::Json => {
+            let map: serde_json::Map<String, serde_json::Value> = values
+                .iter()
+                .map(|(key, value)| (key.clone(), value.as_str().into()))
+                .collect();
+            let mut json = serde_json::to_string_pretty(&map).unwrap_or_default();
+            json.push('\n');
+            json
+        }
+        // A JSON string is also a YAML double-quoted scalar and a TOML basic
+        // string
+        Format::Yaml => lines(values, |key, value| format!("{key}: {}", quoted(value))),
+        Format::Toml => lines(values, |key, value| format!("{key} = {}", quoted(value))),
+        Format::Env => lines(values, |key, value| format!("{key}={}", env_value(value))),
+        Format::Lenv => lines(values, |key, value| format!("{key}: {value}")),
+    }
+}
+
+/// The case of the keys written in `format`: environment variable names
+/// for the flat formats, snake_case otherwise.
+pub fn default_case(format: Format) -> Case {
+    if format.is_flat() {
+        Case::UpperSnake
+    } else {
+        Case::Snake
+    }
+}
+
+/// One line per value, written by `line`.
+fn lines(values: &[(String, String)], line: impl Fn(&str, &str) -> String) -> String {
+    values
+        .iter()
+        .map(|(key, value)| line(key, value) + "\n")
+        .collect()
+}
+
+/// `value` as a double-quoted JSON string.
+fn quoted(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// `value` bare if dotenv reads it back unchanged, or double-quoted.
+pub fn env_value(value: &str) -> String {
+    let bare = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-./:@,+%".contains(c));
+    if bare {
+        return value.to_string();
+    }
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '\\' | '"' | '$' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
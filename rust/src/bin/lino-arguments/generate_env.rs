@@ -0,0 +1,19 @@
+//! `lino-arguments generate-env`: a commented `.env.example` template
+//! generated from a schema.
+
+use lino_arguments::{ConfigError, Schema};
+
+use crate::resolve::read_json_schema;
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The JSON Schema declaring the keys
+    #[arg(long, value_name = "PATH")]
+    schema: String,
+}
+
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    let schema = Schema::from_json_schema(&read_json_schema(&args.schema)?);
+    print!("{}", schema.render_env_example());
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! `lino-arguments get <key>`: the value a configuration file sets for a
+//! key.
+
+use lino_arguments::{canonical_key, ConfigError};
+
+use crate::resolve::read_variables;
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The key to read, in any case (`API_KEY`, `api-key`)
+    key: String,
+
+    /// The file to read: a .lenv file, or a .env file for any other name
+    #[arg(short, long, value_name = "PATH", default_value = ".lenv")]
+    file: String,
+}
+
+/// Prints the value, or exits with status 1 when the file does not set the
+/// key, like `git config`.
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    let key = canonical_key(&args.key);
+    let variables = match std::path::Path::new(&args.file).exists() {
+        true => read_variables(&args.file)?,
+        false => Vec::new(),
+    };
+    let value = variables
+        .into_iter()
+        .rev()
+        .find(|(name, _)| canonical_key(name) == key);
+    match value {
+        Some((_, value)) => println!("{value}"),
+        None => std::process::exit(1),
+    }
+    Ok(())
+}
@@ -0,0 +1,293 @@
+//! `lino-arguments init`: a starter `.lenv`, `.env.example`, and optionally
+//! a JSON Schema for a project, from an existing schema, the `--help` of a
+//! clap application, keys given as flags, or answers to prompts.
+
+use std::io::{BufRead, IsTerminal, Read, Write};
+use std::path::Path;
+
+use lino_arguments::{convert_case, Case, ConfigError, KeySpec, Schema, ValueType};
+
+use crate::resolve::{env_name, looks_sensitive, read_json_schema};
+use crate::set::write;
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// A JSON Schema declaring the keys
+    #[arg(long, value_name = "PATH")]
+    schema: Option<String>,
+
+    /// The `--help` output of a clap application to read its options from,
+    /// or `-` for stdin
+    #[arg(long, value_name = "PATH")]
+    from_help: Option<String>,
+
+    /// A key to declare, with an optional default, such as `port=3000`
+    #[arg(short, long = "key", value_name = "KEY[=DEFAULT]")]
+    keys: Vec<String>,
+
+    /// Also write the keys as a JSON Schema to this file
+    #[arg(long, value_name = "PATH")]
+    write_schema: Option<String>,
+
+    /// The .lenv file to create
+    #[arg(short, long, value_name = "PATH", default_value = ".lenv")]
+    configuration: String,
+
+    /// The example file to create
+    #[arg(long, value_name = "PATH", default_value = ".env.example")]
+    example: String,
+
+    /// Ask for keys and values even when stdin is not a terminal
+    #[arg(short, long, conflicts_with = "yes")]
+    interactive: bool,
+
+    /// Ask nothing, even on a terminal, and use the defaults
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Overwrite the files if they exist
+    #[arg(long)]
+    force: bool,
+}
+
+/// Prompts are asked when stdin is a terminal, unless `--yes`: keys when
+/// none were found, a value for every key, and where to write a schema.
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    let mut schema = match args.schema {
+        Some(ref path) => Schema::from_json_schema(&read_json_schema(path)?),
+        None => Schema::new(),
+    };
+    if let Some(ref path) = args.from_help {
+        for spec in parse_help(&read_help(path)?) {
+            schema = declare(schema, spec);
+        }
+    }
+    for key in &args.keys {
+        let (name, default) = match key.split_once('=') {
+            Some((name, default)) => (name, Some(default)),
+            None => (key.as_str(), None),
+        };
+        schema = declare(schema, inferred_spec(name, default));
+    }
+
+    let interactive = args.interactive || (!args.yes && std::io::stdin().is_terminal());
+    let mut input = std::io::stdin().lock();
+    if interactive && schema.keys().is_empty() {
+        loop {
+            let name = ask(&mut input, "Key name (empty to finish): ")?;
+            if name.is_empty() {
+                break;
+            }
+            let default = ask(
+                &mut input,
+                &format!("Default for {name} (empty for none): "),
+            )?;
+            let default = (!default.is_empty()).then_some(default.as_str());
+            schema = declare(schema, inferred_spec(&name, default));
+        }
+    }
+    let mut values = Vec::new();
+    for spec in schema.keys() {
+        let mut value = spec.default.clone().unwrap_or_default();
+        if interactive {
+            let answer = ask(&mut input, &format!("{} [{value}]: ", env_name(spec)))?;
+            if !answer.is_empty() {
+                value = answer;
+            }
+        }
+        values.push(value);
+    }
+    let mut schema_path = args.write_schema.clone();
+    if interactive && schema_path.is_none() && args.schema.is_none() {
+        let answer = ask(&mut input, "JSON Schema file to write (empty for none): ")?;
+        schema_path = (!answer.is_empty()).then_some(answer);
+    }
+
+    let mut files = vec![
+        (args.configuration.clone(), starter_lenv(&schema, &values)),
+        (args.example.clone(), schema.render_env_example()),
+    ];
+    if let Some(path) = schema_path {
+        let json = serde_json::to_string_pretty(&schema.to_json_schema()).unwrap_or_default();
+        files.push((path, json + "\n"));
+    }
+    if !args.force {
+        if let Some((path, _)) = files.iter().find(|(path, _)| Path::new(path).exists()) {
+            return Err(ConfigError::FileError(format!(
+                "{path} already exists; pass --force to overwrite it"
+            )));
+        }
+    }
+    for (path, text) in &files {
+        write(path, text)?;
+        println!("Created {path}");
+    }
+    Ok(())
+}
+
+/// `schema` with `spec`, unless it declares the key already.
+fn declare(schema: Schema, spec: KeySpec) -> Schema {
+    match schema.get(&spec.name) {
+        Some(_) => schema,
+        None => schema.key(spec),
+    }
+}
+
+/// A key named `name` in any case, typed after its default, sensitive when
+/// the name suggests a credential.
+fn inferred_spec(name: &str, default: Option<&str>) -> KeySpec {
+    let name = convert_case(name, Case::Kebab);
+    let value_type = match default {
+        Some(default) if default.parse::<i64>().is_ok() => ValueType::Integer,
+        Some(default) if default.parse::<f64>().is_ok() => ValueType::Float,
+        Some("true" | "false") => ValueType::Bool,
+        _ => ValueType::String,
+    };
+    let mut spec = KeySpec::new(&name, value_type).sensitive(looks_sensitive(&name));
+    if let Some(default) = default {
+        spec = spec.default(default);
+    }
+    spec
+}
+
+/// The text of the file at `path`, or of stdin for `-`.
+fn read_help(path: &str) -> Result<String, ConfigError> {
+    if path == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        return Ok(text);
+    }
+    std::fs::read_to_string(path)
+        .map_err(|err| ConfigError::FileError(format!("Failed to read {path}: {err}")))
+}
+
+/// The answer to `question`, trimmed; empty at the end of the input.
+fn ask(input: &mut impl BufRead, question: &str) -> Result<String, ConfigError> {
+    print!("{question}");
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+/// A `.lenv` file setting every key of `schema` to its value in `values`,
+/// under its description. Keys without a value are commented out, since an
+/// empty value would hide a default given elsewhere.
+fn starter_lenv(schema: &Schema, values: &[String]) -> String {
+    let mut text = String::new();
+    for (spec, value) in schema.keys().iter().zip(values) {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        if !spec.description.is_empty() {
+            text.push_str(&format!("# {}\n", spec.description));
+        }
+        let name = env_name(spec);
+        match value.is_empty() {
+            true => text.push_str(&format!("# {name}:\n")),
+            false => text.push_str(&format!("{name}: {value}\n")),
+        }
+    }
+    text
+}
+
+/// The options of the `--help` output of a clap application, as keys:
+/// `--name <VALUE>` is a string, or a number if its default is one, and
+/// `--name` alone a flag. The description, `[default: ...]`, and
+/// `[env: NAME=]` are read from the same line or the indented lines below
+/// it. `--help` and `--version` are left out.
+fn parse_help(text: &str) -> Vec<KeySpec> {
+    let mut options: Vec<(String, bool, String)> = Vec::new();
+    let mut open = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('-') {
+            let (signature, rest) = trimmed.split_once("  ").unwrap_or((trimmed, ""));
+            let long = signature
+                .split([',', ' ', '=', '['])
+                .find_map(|word| word.strip_prefix("--"))
+                .filter(|name| !name.is_empty() && !matches!(*name, "help" | "version"));
+            open = long.is_some();
+            if let Some(name) = long {
+                let flag = !signature.contains('<');
+                options.push((name.to_string(), flag, rest.trim().to_string()));
+            }
+        } else if !line.starts_with(' ') && !trimmed.is_empty() {
+            open = false;
+        } else if let Some((_, _, help)) = options.last_mut().filter(|_| open) {
+            if !trimmed.is_empty() {
+                help.push(' ');
+                help.push_str(trimmed);
+            }
+        }
+    }
+    options
+        .into_iter()
+        .map(|(name, flag, mut help)| {
+            let default = take_bracket(&mut help, "default");
+            let env = take_bracket(&mut help, "env");
+            take_bracket(&mut help, "possible values");
+            take_bracket(&mut help, "aliases");
+            let mut spec = match flag {
+                true => KeySpec::new(&name, ValueType::Bool).sensitive(looks_sensitive(&name)),
+                false => inferred_spec(&name, default.as_deref()),
+            };
+            let description = help.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !description.is_empty() {
+                spec = spec.description(&description);
+            }
+            if let Some(env) = env {
+                let name = env.split('=').next().unwrap_or_default();
+                spec = spec.env(name);
+            }
+            spec
+        })
+        .collect()
+}
+
+/// The text of the `[label: ...]` annotation of `help`, removed from it.
+fn take_bracket(help: &mut String, label: &str) -> Option<String> {
+    let start = help.find(&format!("[{label}: "))?;
+    let end = start + help[start..].find(']')?;
+    let value = help[start + label.len() + 3..end].to_string();
+    help.replace_range(start..=end, "");
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_help() {
+        let help = "\
+Serves the app
+
+Usage: app [OPTIONS]
+
+Options:
+  -p, --port <PORT>      Server port [env: APP_PORT=] [default: 3000]
+      --api-key <KEY>    The key of the API,
+                         from the dashboard
+      --verbose          Log more
+      --mode <MODE>      [default: fast] [possible values: fast, slow]
+  -h, --help             Print help
+  -V, --version          Print version
+";
+        let specs = parse_help(help);
+        let names: Vec<&str> = specs.iter().map(|spec| spec.name.as_str()).collect();
+        assert_eq!(names, ["port", "api-key", "verbose", "mode"]);
+        assert_eq!(specs[0].value_type, ValueType::Integer);
+        assert_eq!(specs[0].default.as_deref(), Some("3000"));
+        assert_eq!(specs[0].env.as_deref(), Some("APP_PORT"));
+        assert_eq!(specs[0].description, "Server port");
+        assert!(specs[1].sensitive);
+        assert_eq!(
+            specs[1].description,
+            "The key of the API, from the dashboard"
+        );
+        assert_eq!(specs[2].value_type, ValueType::Bool);
+        assert_eq!(specs[3].default.as_deref(), Some("fast"));
+        assert_eq!(specs[3].description, "");
+    }
+}
@@ -0,0 +1,128 @@
+//! `lino-arguments inspect`: every resolved key, its value, and its source.
+
+use lino_arguments::{Config, ConfigError};
+use serde_json::Value;
+
+use crate::output::{issue_json, print_json, Output};
+use crate::resolve::Sources;
+use crate::style::{Color, Style};
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    sources: Sources,
+}
+
+/// As JSON, the report is the [provenance](Config::provenance_json) of the
+/// configuration with its `warnings`.
+pub fn run(args: &Args, output: Output, show_secrets: bool) -> Result<(), ConfigError> {
+    let config = args.sources.resolve()?;
+    if output == Output::Json {
+        let mut report = provenance(&config, show_secrets);
+        report["warnings"] = config.warnings().iter().map(issue_json).collect();
+        print_json(&report);
+        return Ok(());
+    }
+    print_warnings(&config);
+    print_config(&config, show_secrets);
+    Ok(())
+}
+
+/// The [provenance](Config::provenance_json) of `config`, with the values
+/// of sensitive keys masked unless `show_secrets`.
+pub fn provenance(config: &Config, show_secrets: bool) -> Value {
+    let mut provenance = config.provenance_json();
+    if show_secrets {
+        for (key, entry) in provenance["keys"].as_object_mut().into_iter().flatten() {
+            entry["value"] = config.get(key).into();
+        }
+    }
+    provenance
+}
+
+/// Print the warnings about `config` to stderr.
+pub fn print_warnings(config: &Config) {
+    let style = Style::stderr();
+    for warning in config.warnings() {
+        eprintln!("{} {warning}", style.paint(Color::Yellow, "warning:"));
+    }
+}
+
+/// Print every key of `config` with its value and source, with the values
+/// of sensitive keys masked unless `show_secrets`.
+pub fn print_config(config: &Config, show_secrets: bool) {
+    let provenance = provenance(config, show_secrets);
+    let rows: Vec<[String; 3]> = provenance["keys"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(key, entry)| [key.clone(), text(&entry["value"]), describe(entry)])
+        .collect();
+    if rows.is_empty() {
+        println!("No keys are set.");
+    } else {
+        print_table(["KEY", "VALUE", "SOURCE"], &rows, |column, _| {
+            (column == 2).then_some(Color::Dim)
+        });
+    }
+}
+
+/// The layer of a provenance entry, with the file and line or the
+/// variable it was read from.
+fn describe(entry: &Value) -> String {
+    let source = text(&entry["source"]);
+    match (entry["file"].as_str(), entry["line"].as_u64()) {
+        (Some(file), Some(line)) => format!("{source} ({file}:{line})"),
+        _ if source == "environment" => format!("{source} ({})", text(&entry["variant"])),
+        _ => source,
+    }
+}
+
+/// A JSON string without quotes; anything else as JSON.
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Print `rows` under `header`, with the columns padded to line up. When
+/// standard output is colored, the header is bold and each cell is in the
+/// color `color` gives for its column and text, if any.
+pub fn print_table<const N: usize>(
+    header: [&str; N],
+    rows: &[[String; N]],
+    color: impl Fn(usize, &str) -> Option<Color>,
+) {
+    let style = Style::stdout();
+    let mut widths = header.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    // Padding is added after the color, so that escape codes do not count
+    // toward the width
+    let line = |cells: [&str; N], color: &dyn Fn(usize, &str) -> Option<Color>| {
+        let mut line = String::new();
+        for (column, (cell, width)) in cells.iter().zip(widths).enumerate() {
+            if column > 0 {
+                line.push_str("  ");
+            }
+            let padding = width - cell.chars().count();
+            match color(column, cell) {
+                Some(color) if !cell.is_empty() => line.push_str(&style.paint(color, cell)),
+                _ => line.push_str(cell),
+            }
+            if column + 1 < N {
+                line.push_str(&" ".repeat(padding));
+            }
+        }
+        println!("{}", line.trim_end());
+    };
+    line(header, &|_, _| Some(Color::Bold));
+    for row in rows {
+        line(row.each_ref().map(String::as_str), &color);
+    }
+}
@@ -0,0 +1,145 @@
+//! lino-arguments CLI binary
+//!
+//! Inspects the configuration an application would resolve in the current
+//! directory and environment, using the same priority chain as the library:
+//! command line, environment, `.lenv` file, `.env` file, then defaults.
+
+mod case;
+mod completions;
+mod convert;
+#[cfg(feature = "age")]
+mod decrypt;
+mod diff;
+mod doctor;
+#[cfg(feature = "age")]
+mod encrypt;
+mod explain;
+mod export;
+mod generate_env;
+mod get;
+mod init;
+mod inspect;
+mod output;
+mod resolve;
+mod run;
+mod schema;
+mod set;
+mod style;
+mod validate;
+#[cfg(feature = "watch")]
+mod watch;
+
+use lino_arguments::{ConfigError, Parser, Subcommand};
+
+use crate::output::Output;
+use crate::style::{Color, Style};
+
+/// Inspect and manage lino configuration.
+#[derive(Parser, Debug)]
+#[command(name = "lino-arguments")]
+#[command(about = "Inspect and manage lino configuration")]
+#[command(version)]
+struct Cli {
+    /// How inspect, explain, diff, doctor, and validate print their report
+    #[arg(long, global = true, value_enum, default_value_t = Output::Text)]
+    output: Output,
+
+    /// Show the values of sensitive keys in inspect, explain, diff, and
+    /// watch instead of masking them
+    #[arg(long, global = true)]
+    show_secrets: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Show every resolved key with its value and source
+    Inspect(inspect::Args),
+    /// Show every value considered for a key and which one won
+    Explain(explain::Args),
+    /// Write the resolved configuration as JSON, YAML, TOML, or a .env file
+    Export(export::Args),
+    /// Convert a configuration file between JSON, YAML, TOML, .env, and .lenv
+    Convert(convert::Args),
+    /// Check the configuration files and environment against a JSON Schema
+    Validate(validate::Args),
+    /// Show the keys added, removed, and changed between two configurations
+    Diff(diff::Args),
+    /// Create a starter .lenv, .env.example, and schema for a project
+    Init(init::Args),
+    /// Write the declared schema as a JSON Schema or Markdown reference
+    Schema(schema::Args),
+    /// Write a commented .env.example template with every key of a schema
+    GenerateEnv(generate_env::Args),
+    /// Write a shell completion script for this tool or an application
+    Completions(completions::Args),
+    /// Check for configuration that resolves, but probably not as intended
+    Doctor(doctor::Args),
+    /// Print the value a configuration file sets for a key
+    Get(get::Args),
+    /// Change or add a key in a configuration file, keeping its comments
+    Set(set::Args),
+    /// Run a command with the resolved configuration in its environment
+    Run(run::Args),
+    /// Convert names to another case with the library's rules
+    Case(case::Args),
+    /// Show the resolved configuration again whenever it changes
+    #[cfg(feature = "watch")]
+    Watch(watch::Args),
+    /// Encrypt the values of a configuration file to an age recipient
+    #[cfg(feature = "age")]
+    Encrypt(encrypt::Args),
+    /// Decrypt the encrypted values of a configuration file for inspection
+    #[cfg(feature = "age")]
+    Decrypt(decrypt::Args),
+}
+
+fn main() {
+    resolve::forget_startup_files();
+    let cli = Cli::parse();
+    let (output, show_secrets) = (cli.output, cli.show_secrets);
+    let result: Result<(), ConfigError> = match cli.command {
+        Command::Inspect(args) => inspect::run(&args, output, show_secrets),
+        Command::Explain(args) => explain::run(&args, output, show_secrets),
+        Command::Export(args) => export::run(&args),
+        Command::Convert(args) => convert::run(&args),
+        Command::Validate(args) => validate::run(&args, output),
+        Command::Diff(args) => diff::run(&args, output, show_secrets),
+        Command::Init(args) => init::run(&args),
+        Command::Schema(args) => schema::run(&args),
+        Command::GenerateEnv(args) => generate_env::run(&args),
+        Command::Completions(args) => completions::run(&args),
+        Command::Doctor(args) => doctor::run(&args, output),
+        Command::Get(args) => get::run(&args),
+        Command::Set(args) => set::run(&args),
+        Command::Run(args) => run::run(&args),
+        Command::Case(args) => case::run(&args),
+        #[cfg(feature = "watch")]
+        Command::Watch(args) => watch::run(&args, show_secrets),
+        #[cfg(feature = "age")]
+        Command::Encrypt(args) => encrypt::run(&args),
+        #[cfg(feature = "age")]
+        Command::Decrypt(args) => decrypt::run(&args),
+    };
+    let error = Style::stderr().paint(Color::Red, "error:");
+    match result {
+        Ok(()) => {}
+        Err(ConfigError::Invalid(issues)) => {
+            for issue in &issues {
+                eprintln!("{error} {issue}");
+            }
+            match issues.len() {
+                1 => eprintln!("1 problem found"),
+                count => eprintln!("{count} problems found"),
+            }
+            std::process::exit(1);
+        }
+        Err(ConfigError::ArgumentError(err)) => err.exit(),
+        Err(err) => {
+            eprintln!("{error} {err}");
+            std::process::exit(1);
+        }
+    }
+}
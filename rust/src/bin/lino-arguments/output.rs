@@ -0,0 +1,39 @@
+//! The formats reports are printed in, chosen with `--output`.
+
+use lino_arguments::{Issue, Severity, ValueEnum};
+use serde_json::{json, Value};
+
+/// How a report is printed.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Output {
+    /// Tables and messages, for people
+    #[default]
+    Text,
+    /// A single JSON document on stdout, for scripts
+    Json,
+}
+
+/// Print `report` as indented JSON.
+pub fn print_json(report: &Value) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(report).unwrap_or_default()
+    );
+}
+
+/// A problem with the configuration as JSON, with its location split into
+/// `file`, `line`, and `column`.
+pub fn issue_json(issue: &Issue) -> Value {
+    let location = issue.location.as_ref();
+    json!({
+        "key": issue.key,
+        "message": issue.message,
+        "value": issue.value,
+        "source": issue.source.map(|source| source.to_string()),
+        "file": location.map(|location| &location.file),
+        "line": location.map(|location| location.line),
+        "column": location.map(|location| location.column),
+        "hint": issue.hint,
+        "severity": if issue.severity == Severity::Warning { "warning" } else { "error" },
+    })
+}
@@ -0,0 +1,193 @@
+//! Resolving the configuration of the current directory and environment.
+
+use std::collections::BTreeSet;
+
+use lino_arguments::{
+    convert_case, parse_lenv, split_words, try_make_config_from, Args, Case, Config, ConfigError,
+    KeySpec, Schema, ValueType,
+};
+
+/// Words that mark a key as a credential when no schema says otherwise.
+const SENSITIVE_WORDS: &[&str] = &["key", "token", "secret", "password", "passwd", "credential"];
+
+/// Where the configuration is read from.
+#[derive(Args, Debug, Clone)]
+pub struct Sources {
+    /// The .lenv file to read
+    #[arg(short, long, value_name = "PATH", default_value = ".lenv")]
+    pub configuration: String,
+
+    /// The .env file to read
+    #[arg(long, value_name = "PATH", default_value = ".env")]
+    pub env_file: String,
+
+    /// A JSON Schema declaring the keys, their defaults, and which are
+    /// sensitive
+    #[arg(long, value_name = "PATH")]
+    pub schema: Option<String>,
+}
+
+impl Sources {
+    /// The schema given with `--schema`, or an empty one.
+    pub fn read_schema(&self) -> Result<Schema, ConfigError> {
+        Ok(match self.read_json_schema()? {
+            Some(document) => Schema::from_json_schema(&document),
+            None => Schema::new(),
+        })
+    }
+
+    /// The JSON Schema document given with `--schema`, if any.
+    pub fn read_json_schema(&self) -> Result<Option<serde_json::Value>, ConfigError> {
+        self.schema.as_deref().map(read_json_schema).transpose()
+    }
+
+    /// The variables set in the `.lenv` and `.env` files, as written, in
+    /// file order. Missing files set none.
+    pub fn file_variables(&self) -> Vec<(String, String)> {
+        let lenv = self.lenv_entries().into_iter();
+        let env = self.env_file_entries().into_iter();
+        lenv.chain(env)
+            .map(|(name, value, _)| (name, value))
+            .collect()
+    }
+
+    /// The variables set in the `.lenv` file, with the line of each.
+    pub fn lenv_entries(&self) -> Vec<(String, String, Option<usize>)> {
+        let text = std::fs::read_to_string(&self.configuration).unwrap_or_default();
+        with_lines(&text, parse_lenv(&text), ':')
+    }
+
+    /// The variables set in the `.env` file, with the line of each.
+    pub fn env_file_entries(&self) -> Vec<(String, String, Option<usize>)> {
+        let text = std::fs::read_to_string(&self.env_file).unwrap_or_default();
+        let values = dotenvy::from_read_iter(text.as_bytes()).flatten().collect();
+        with_lines(&text, values, '=')
+    }
+
+    /// Every key declared in the schema or set in a file, in kebab-case and
+    /// sorted.
+    pub fn keys(&self, schema: &Schema) -> BTreeSet<String> {
+        let declared = schema.keys().iter().map(|spec| spec.name.clone());
+        let written = self.file_variables().into_iter().map(|(name, _)| name);
+        declared
+            .chain(written)
+            .map(|name| convert_case(&name, Case::Kebab).into_owned())
+            .collect()
+    }
+
+    /// A schema declaring every key and `extra`, without the constraints of
+    /// the one given with `--schema`, so that invalid configuration can
+    /// still be shown. Undeclared keys are strings.
+    pub fn relaxed_schema(&self, extra: Option<&str>) -> Result<Schema, ConfigError> {
+        let declared = self.read_schema()?;
+        let mut keys = self.keys(&declared);
+        keys.extend(extra.map(|key| convert_case(key, Case::Kebab).into_owned()));
+        let mut schema = Schema::new();
+        for key in keys {
+            schema = schema.key(match declared.get(&key) {
+                Some(spec) => relaxed(spec),
+                None => KeySpec::new(&key, ValueType::String).sensitive(looks_sensitive(&key)),
+            });
+        }
+        Ok(schema)
+    }
+
+    /// Resolve every key, without failing on values the schema rejects.
+    pub fn resolve(&self) -> Result<Config, ConfigError> {
+        self.resolve_with(self.relaxed_schema(None)?, &[])
+    }
+
+    /// Resolve the keys of `schema`, with `args` as the command line.
+    pub fn resolve_with(&self, schema: Schema, args: &[String]) -> Result<Config, ConfigError> {
+        let args = std::iter::once("lino-arguments").chain(args.iter().map(String::as_str));
+        try_make_config_from(args, |c| {
+            c.lenv(&self.configuration)
+                .env(&self.env_file)
+                .schema(schema)
+        })
+    }
+}
+
+/// The JSON Schema document at `path`.
+pub fn read_json_schema(path: &str) -> Result<serde_json::Value, ConfigError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| ConfigError::FileError(format!("Failed to read {path}: {err}")))?;
+    serde_json::from_str(&text).map_err(|err| ConfigError::ParseError(format!("{path}: {err}")))
+}
+
+/// The variables set in the file at `path`, in file order: a `.lenv` file
+/// when the name ends in `.lenv`, a `.env` file otherwise.
+pub fn read_variables(path: &str) -> Result<Vec<(String, String)>, ConfigError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| ConfigError::FileError(format!("Failed to read {path}: {err}")))?;
+    if path.ends_with(".lenv") {
+        return Ok(parse_lenv(&text));
+    }
+    dotenvy::from_read_iter(text.as_bytes())
+        .collect::<Result<_, _>>()
+        .map_err(|err| ConfigError::ParseError(format!("{path}: {err}")))
+}
+
+/// `spec` with its type, default, environment variable, and sensitivity,
+/// but none of its constraints.
+fn relaxed(spec: &KeySpec) -> KeySpec {
+    let mut relaxed = spec.clone();
+    if relaxed.value_type != ValueType::Bool {
+        relaxed.value_type = ValueType::String;
+    }
+    relaxed.required = false;
+    relaxed.sources.clear();
+    relaxed.validators.clear();
+    relaxed
+}
+
+/// The environment variable the key of `spec` is read from.
+pub fn env_name(spec: &KeySpec) -> String {
+    spec.env
+        .clone()
+        .unwrap_or_else(|| convert_case(&spec.name, Case::UpperSnake).into_owned())
+}
+
+/// Whether the name of `key` suggests a credential, such as `api-key`.
+pub fn looks_sensitive(key: &str) -> bool {
+    split_words(key).any(|word| SENSITIVE_WORDS.contains(&word.to_lowercase().as_str()))
+}
+
+/// `variables`, read from `text`, with the last line each is set on, where
+/// the name is followed by `separator`.
+fn with_lines(
+    text: &str,
+    variables: Vec<(String, String)>,
+    separator: char,
+) -> Vec<(String, String, Option<usize>)> {
+    let lines: Vec<&str> = text.lines().collect();
+    variables
+        .into_iter()
+        .map(|(name, value)| {
+            let line = lines.iter().rposition(|line| {
+                let line = line.trim_start();
+                let line = line.strip_prefix("export ").unwrap_or(line);
+                line.strip_prefix(name.as_str())
+                    .is_some_and(|rest| rest.trim_start().starts_with(separator))
+            });
+            (name, value, line.map(|index| index + 1))
+        })
+        .collect()
+}
+
+/// Undo the loading of `.lenv` and `.env` in the current directory into
+/// the environment at startup, so that their values are reported as coming
+/// from the files. A variable exported with the same value as a file sets
+/// counts as coming from the file.
+pub fn forget_startup_files() {
+    let defaults = Sources {
+        configuration: ".lenv".to_string(),
+        env_file: ".env".to_string(),
+        schema: None,
+    };
+    for (name, value) in defaults.file_variables() {
+        if std::env::var(&name).is_ok_and(|current| current == value) {
+            std::env::remove_var(&name);
+        }
+    }
+}
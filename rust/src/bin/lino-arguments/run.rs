@@ -0,0 +1,53 @@
+//! `lino-arguments run -- <command>`: a command started with the resolved
+//! configuration in its environment, like `dotenv` or `env`.
+
+use std::process::Command;
+
+use lino_arguments::ConfigError;
+
+use crate::resolve::{env_name, Sources};
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    sources: Sources,
+
+    /// The command to run, and its arguments
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    command: Vec<String>,
+}
+
+/// Every key with a value is exported under its environment variable name.
+/// With `--schema`, the command only starts when the configuration is
+/// valid.
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    let schema = args.sources.relaxed_schema(None)?;
+    let config = args.sources.resolve_with(schema.clone(), &[])?;
+    if args.sources.schema.is_some() {
+        args.sources.read_schema()?.validate(&config)?;
+    }
+    let (program, arguments) = args.command.split_first().expect("the command is required");
+    let mut command = Command::new(program);
+    command.args(arguments);
+    for spec in schema.keys().iter().filter(|spec| config.has(&spec.name)) {
+        command.env(env_name(spec), config.get(&spec.name));
+    }
+    let failed =
+        |err: std::io::Error| ConfigError::FileError(format!("Failed to run {program}: {err}"));
+    exec(command).map_err(failed)
+}
+
+/// Replace this process with `command`.
+#[cfg(unix)]
+fn exec(mut command: Command) -> std::io::Result<()> {
+    use std::os::unix::process::CommandExt;
+    Err(command.exec())
+}
+
+/// Run `command` and exit with its status, where processes cannot be
+/// replaced.
+#[cfg(not(unix))]
+fn exec(mut command: Command) -> std::io::Result<()> {
+    let status = command.status()?;
+    std::process::exit(status.code().unwrap_or(1));
+}
@@ -0,0 +1,51 @@
+//! `lino-arguments schema`: the declared configuration schema, as a JSON
+//! Schema or a Markdown reference.
+
+use lino_arguments::{ConfigError, KeySpec, Schema, ValueEnum, ValueType};
+
+use crate::output::print_json;
+use crate::resolve::{looks_sensitive, Sources};
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The format to write
+    #[arg(short, long, value_enum, default_value_t = Format::JsonSchema)]
+    format: Format,
+
+    #[command(flatten)]
+    sources: Sources,
+}
+
+/// A format a schema is written in.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// A JSON Schema (draft 2020-12) document, for editors and validators
+    JsonSchema,
+    /// A Markdown table of the keys, for documentation
+    Markdown,
+}
+
+/// The schema is the one given with `--schema`, normalized. Without one,
+/// it is inferred from the keys the `.lenv` and `.env` files set, as
+/// strings, sensitive when their name suggests a credential.
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    let schema = match args.sources.schema {
+        Some(_) => args.sources.read_schema()?,
+        None => inferred(&args.sources),
+    };
+    match args.format {
+        Format::JsonSchema => print_json(&schema.to_json_schema()),
+        Format::Markdown => print!("{}", schema.render_markdown()),
+    }
+    Ok(())
+}
+
+/// A schema declaring every key set in the files of `sources`.
+fn inferred(sources: &Sources) -> Schema {
+    sources
+        .keys(&Schema::new())
+        .into_iter()
+        .fold(Schema::new(), |schema, key| {
+            schema.key(KeySpec::new(&key, ValueType::String).sensitive(looks_sensitive(&key)))
+        })
+}
@@ -0,0 +1,100 @@
+//! `lino-arguments set <key> <value>`: change or add a key in a
+//! configuration file, keeping its comments and order.
+
+use lino_arguments::{canonical_key, ConfigError};
+
+use crate::export::env_value;
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    /// The key to set; an existing line for it keeps its name, a new one is
+    /// written as given
+    key: String,
+
+    /// The value to set
+    value: String,
+
+    /// The file to edit, created if missing: a .lenv file, or a .env file
+    /// for any other name
+    #[arg(short, long, value_name = "PATH", default_value = ".lenv")]
+    file: String,
+}
+
+pub fn run(args: &Args) -> Result<(), ConfigError> {
+    let lenv = args.file.ends_with(".lenv");
+    if lenv && args.value.contains('\n') {
+        return Err(ConfigError::ParseError(
+            "a .lenv value cannot span lines".to_string(),
+        ));
+    }
+    let text = match std::fs::read_to_string(&args.file) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            return Err(ConfigError::FileError(format!(
+                "Failed to read {}: {err}",
+                args.file
+            )))
+        }
+    };
+    write(&args.file, &set_line(&text, &args.key, &args.value, lenv))
+}
+
+/// Replace the file at `path` with `text`.
+pub fn write(path: &str, text: &str) -> Result<(), ConfigError> {
+    std::fs::write(path, text)
+        .map_err(|err| ConfigError::FileError(format!("Failed to write {path}: {err}")))
+}
+
+/// `text` with the last line setting `key` changed to `value`, or a line
+/// appended for it. Other lines are kept as they are.
+pub fn set_line(text: &str, key: &str, value: &str, lenv: bool) -> String {
+    let separator = if lenv { ": " } else { "=" };
+    let wanted = canonical_key(key);
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let existing = lines.iter().rposition(|line| {
+        let line = line.trim_start();
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        !line.starts_with('#')
+            && line
+                .split_once(separator)
+                .is_some_and(|(name, _)| canonical_key(name.trim()) == wanted)
+    });
+    let value = if lenv {
+        value.to_string()
+    } else {
+        env_value(value)
+    };
+    match existing {
+        Some(index) => {
+            let line = &lines[index];
+            let (name, _) = line.split_once(separator).expect("the line sets a key");
+            lines[index] = format!("{name}{separator}{value}");
+        }
+        None => lines.push(format!("{key}{separator}{value}")),
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_line() {
+        let text = "# server\nPORT: 80\n\n  api_key: old\n# PORT: 1\n";
+        assert_eq!(
+            set_line(text, "API_KEY", "new value", true),
+            "# server\nPORT: 80\n\n  api_key: new value\n# PORT: 1\n"
+        );
+        assert_eq!(
+            set_line(text, "HOST", "db", true),
+            "# server\nPORT: 80\n\n  api_key: old\n# PORT: 1\nHOST: db\n"
+        );
+        assert_eq!(
+            set_line("export NOTE=a\nNOTE=b", "NOTE", "say \"hi\"", false),
+            "export NOTE=a\nNOTE=\"say \\\"hi\\\"\"\n"
+        );
+        assert_eq!(set_line("", "PORT", "80", false), "PORT=80\n");
+    }
+}
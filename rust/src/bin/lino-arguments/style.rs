@@ -0,0 +1,53 @@
+//! Colors for the text output, used when it goes to a terminal.
+
+use std::io::IsTerminal;
+
+/// An ANSI color or attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Bold = 1,
+    Dim = 2,
+    Red = 31,
+    Green = 32,
+    Yellow = 33,
+}
+
+/// Whether text written to a stream is colored.
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    enabled: bool,
+}
+
+impl Style {
+    /// The style of standard output.
+    pub fn stdout() -> Self {
+        Self::detect(std::io::stdout().is_terminal())
+    }
+
+    /// The style of standard error.
+    pub fn stderr() -> Self {
+        Self::detect(std::io::stderr().is_terminal())
+    }
+
+    /// Colors for a terminal, unless `TERM` is `dumb`. A non-empty
+    /// `NO_COLOR` turns them off, and otherwise `FORCE_COLOR` on (or off,
+    /// when `0` or `false`), whether or not the stream is a terminal.
+    fn detect(terminal: bool) -> Self {
+        let var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let enabled = match (var("NO_COLOR"), var("FORCE_COLOR")) {
+            (Some(_), _) => false,
+            (None, Some(force)) => force != "0" && force != "false",
+            (None, None) => terminal && var("TERM").as_deref() != Some("dumb"),
+        };
+        Self { enabled }
+    }
+
+    /// `text` in `color`, if colors are enabled.
+    pub fn paint(self, color: Color, text: &str) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{text}\x1b[0m", color as u8)
+        } else {
+            text.to_string()
+        }
+    }
+}
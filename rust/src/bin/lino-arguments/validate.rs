@@ -0,0 +1,60 @@
+//! `lino-arguments validate`: check the files and the environment against a
+//! schema, failing with every problem found.
+
+use lino_arguments::{try_make_config_from, ConfigError};
+use serde_json::json;
+
+use crate::inspect::print_warnings;
+use crate::output::{issue_json, print_json, Output};
+use crate::resolve::Sources;
+use crate::style::{Color, Style};
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    sources: Sources,
+}
+
+/// The `.lenv` and `.env` files are checked line by line first, so that a
+/// problem is reported where it was written even when the environment
+/// overrides the value; once they pass, the resolved configuration is.
+///
+/// As JSON, the report tells whether the configuration is `valid`, with
+/// the `problems` that make it invalid and the `warnings`.
+pub fn run(args: &Args, output: Output) -> Result<(), ConfigError> {
+    let sources = &args.sources;
+    let (Some(path), Some(document)) = (&sources.schema, sources.read_json_schema()?) else {
+        return Err(ConfigError::FileError(
+            "validate needs a schema, given with --schema".to_string(),
+        ));
+    };
+    let schema = sources.read_schema()?;
+    let resolved = try_make_config_from(["lino-arguments"], |c| {
+        c.lenv(&sources.configuration)
+            .env(&sources.env_file)
+            .json_schema(&document)
+            .schema(schema)
+    });
+    if output == Output::Json {
+        let (problems, warnings) = match resolved {
+            Ok(ref config) => (&[][..], config.warnings()),
+            Err(ConfigError::Invalid(ref issues)) => (&issues[..], &[][..]),
+            Err(err) => return Err(err),
+        };
+        print_json(&json!({
+            "valid": problems.is_empty(),
+            "schema": path,
+            "problems": problems.iter().map(issue_json).collect::<Vec<_>>(),
+            "warnings": warnings.iter().map(issue_json).collect::<Vec<_>>(),
+        }));
+        if !problems.is_empty() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    let config = resolved?;
+    print_warnings(&config);
+    let message = format!("The configuration matches {path}.");
+    println!("{}", Style::stdout().paint(Color::Green, &message));
+    Ok(())
+}
@@ -0,0 +1,57 @@
+//! `lino-arguments watch`: the resolved configuration, shown again whenever
+//! a change to the files changes it.
+
+use std::io::{IsTerminal, Write};
+
+use lino_arguments::{try_make_shared_config_from, Config, ConfigError};
+
+use crate::inspect::{print_config, provenance};
+use crate::resolve::Sources;
+
+#[derive(lino_arguments::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    sources: Sources,
+
+    /// Print the provenance of every key as one JSON line on each change,
+    /// instead of a table
+    #[arg(long)]
+    json: bool,
+}
+
+/// Runs until interrupted. The keys are those of the schema and the files
+/// when the command starts.
+pub fn run(args: &Args, show_secrets: bool) -> Result<(), ConfigError> {
+    let schema = args.sources.relaxed_schema(None)?;
+    let shared = try_make_shared_config_from(["lino-arguments"], |c| {
+        c.lenv(&args.sources.configuration)
+            .env(&args.sources.env_file)
+            .schema(schema)
+    })?;
+    let json = args.json;
+    let show = move |config: &Config| {
+        if json {
+            println!("{}", provenance(config, show_secrets));
+        } else {
+            if std::io::stdout().is_terminal() {
+                // Clear the screen and move to its top left corner
+                print!("\x1b[2J\x1b[H");
+            } else if config.generation() > 1 {
+                println!();
+            }
+            print_config(config, show_secrets);
+            println!(
+                "\n(generation {}; press Ctrl-C to stop)",
+                config.generation()
+            );
+        }
+        let _ = std::io::stdout().flush();
+    };
+    let current = shared.clone();
+    shared.on_change(move |_| show(&current.snapshot()));
+    let _watcher = shared.watch_files()?;
+    show(&shared.snapshot());
+    loop {
+        std::thread::park();
+    }
+}
@@ -0,0 +1,1737 @@
+//! Case conversion utilities
+//!
+//! Every converter is a thin wrapper around [`convert_case()`], so all
+//! casings share one word-splitting core and agree on where words begin
+//! and end.
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+#[cfg(feature = "std")]
+use std::sync::{Arc, RwLock};
+
+/// A naming convention that keys can be converted to.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{convert_case, Case};
+///
+/// assert_eq!(convert_case("api-key", Case::UpperSnake), "API_KEY");
+/// assert_eq!(convert_case("API_KEY", Case::Camel), "apiKey");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Case {
+    /// `apiKey`
+    Camel,
+    /// `ApiKey`
+    Pascal,
+    /// `api_key`
+    Snake,
+    /// `api-key`
+    Kebab,
+    /// `API_KEY`
+    UpperSnake,
+    /// `Api-Key`
+    Train,
+    /// `api.key`
+    Dot,
+    /// `API-KEY`
+    ScreamingKebab,
+    /// `api/key`
+    Path,
+    /// `Api Key`
+    Title,
+    /// `apikey`
+    Flat,
+}
+
+impl Case {
+    /// Every case, in declaration order.
+    pub const ALL: [Case; 11] = [
+        Case::Camel,
+        Case::Pascal,
+        Case::Snake,
+        Case::Kebab,
+        Case::UpperSnake,
+        Case::Train,
+        Case::Dot,
+        Case::ScreamingKebab,
+        Case::Path,
+        Case::Title,
+        Case::Flat,
+    ];
+
+    /// The name of the case in kebab-case (`"upper-snake"`), as parsed by
+    /// [`str::parse()`] in any case (`"UpperSnake"`, `"upper_snake"`), for
+    /// bindings and config files that name a case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::Case;
+    ///
+    /// assert_eq!(Case::UpperSnake.name(), "upper-snake");
+    /// assert_eq!("UpperSnake".parse(), Ok(Case::UpperSnake));
+    /// assert!("shouting".parse::<Case>().is_err());
+    /// ```
+    pub fn name(self) -> &'static str {
+        match self {
+            Case::Camel => "camel",
+            Case::Pascal => "pascal",
+            Case::Snake => "snake",
+            Case::Kebab => "kebab",
+            Case::UpperSnake => "upper-snake",
+            Case::Train => "train",
+            Case::Dot => "dot",
+            Case::ScreamingKebab => "screaming-kebab",
+            Case::Path => "path",
+            Case::Title => "title",
+            Case::Flat => "flat",
+        }
+    }
+}
+
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl core::str::FromStr for Case {
+    type Err = UnknownCase;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let kebab = convert_case(name, Case::Kebab);
+        Case::ALL
+            .into_iter()
+            .find(|case| case.name() == kebab)
+            .ok_or_else(|| UnknownCase(name.into()))
+    }
+}
+
+/// The error of parsing a [`Case`] from a name that is not one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownCase(String);
+
+impl fmt::Display for UnknownCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown case {:?}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnknownCase {}
+
+/// How the letters of a single word are rendered by [`convert_with_separator()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WordCase {
+    /// `api`
+    Lower,
+    /// `API`
+    Upper,
+    /// `Api`
+    Capitalized,
+}
+
+/// How registered acronyms are rendered in capitalized words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AcronymStyle {
+    /// Acronyms are capitalized like any other word: `UserId`, `HttpServer`
+    /// (the default).
+    #[default]
+    Capitalize,
+    /// Acronyms keep their registered spelling: `UserID`, `HTTPServer`,
+    /// `OAuthToken`.
+    Preserve,
+}
+
+/// Options for [`convert_case_with()`] and, via [`set_case_options()`], for
+/// every other converter.
+///
+/// Registered acronyms and exceptions are kept as one word by the splitter,
+/// so `OAuthToken` splits into `OAuth` and `Token` instead of `O`, `Auth` and
+/// `Token`. They are matched case-sensitively inside mixed-case input and
+/// only where they end on a word boundary (`IDs` and `IDENTITY` are left
+/// alone).
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{convert_case_with, AcronymStyle, Case, CaseOptions};
+///
+/// let options = CaseOptions::new()
+///     .acronyms(["HTTP", "ID", "OAuth"])
+///     .acronym_style(AcronymStyle::Preserve);
+/// assert_eq!(convert_case_with("user_id", Case::Pascal, &options), "UserID");
+/// assert_eq!(convert_case_with("OAuthToken", Case::UpperSnake, &options), "OAUTH_TOKEN");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CaseOptions {
+    acronyms: Vec<String>,
+    acronym_style: AcronymStyle,
+    exceptions: Vec<String>,
+}
+
+impl CaseOptions {
+    /// Create options with the default behavior of [`convert_case()`].
+    pub const fn new() -> Self {
+        Self {
+            acronyms: Vec::new(),
+            acronym_style: AcronymStyle::Capitalize,
+            exceptions: Vec::new(),
+        }
+    }
+
+    /// Register acronyms that are kept as one word (`HTTP`, `URL`, `OAuth`).
+    pub fn acronyms<I, S>(mut self, acronyms: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.acronyms.extend(
+            acronyms
+                .into_iter()
+                .map(Into::into)
+                .filter(|a: &String| !a.is_empty()),
+        );
+        self
+    }
+
+    /// Set how registered acronyms are rendered in capitalized words.
+    pub fn acronym_style(mut self, style: AcronymStyle) -> Self {
+        self.acronym_style = style;
+        self
+    }
+
+    /// Register words that keep their exact spelling in every case
+    /// (`iOS`, `macOS`, `OAuth2`), even in `UPPER_CASE` and kebab-case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::{convert_case_with, Case, CaseOptions};
+    ///
+    /// let options = CaseOptions::new().exceptions(["iOS", "macOS"]);
+    /// assert_eq!(convert_case_with("iOSVersion", Case::Kebab, &options), "iOS-version");
+    /// assert_eq!(convert_case_with("min_macos", Case::UpperSnake, &options), "MIN_macOS");
+    /// ```
+    pub fn exceptions<I, S>(mut self, exceptions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.exceptions.extend(
+            exceptions
+                .into_iter()
+                .map(Into::into)
+                .filter(|e: &String| !e.is_empty()),
+        );
+        self
+    }
+
+    /// The registered spelling of `word` if it is an acronym, optionally
+    /// followed by digits (`OAuth2`), along with those digits.
+    fn acronym_of<'a>(&'a self, word: &'a str) -> Option<(&'a str, &'a str)> {
+        registered_spelling(&self.acronyms, word)
+    }
+
+    /// The exact spelling of `word` if it is an exception, optionally
+    /// followed by digits, along with those digits.
+    fn exception_of<'a>(&'a self, word: &'a str) -> Option<(&'a str, &'a str)> {
+        registered_spelling(&self.exceptions, word)
+    }
+
+    /// Acronyms and exceptions: the words the splitter keeps intact.
+    fn intact(&self) -> impl Iterator<Item = &str> + Clone {
+        self.acronyms
+            .iter()
+            .chain(&self.exceptions)
+            .map(String::as_str)
+    }
+}
+
+/// The entry of `list` that `word` spells case-insensitively, optionally
+/// followed by digits, along with those digits.
+fn registered_spelling<'a>(list: &'a [String], word: &'a str) -> Option<(&'a str, &'a str)> {
+    list.iter().find_map(|entry| {
+        let (head, digits) = word.split_at_checked(entry.len())?;
+        (head.eq_ignore_ascii_case(entry) && digits.chars().all(char::is_numeric))
+            .then_some((entry.as_str(), digits))
+    })
+}
+
+/// Options used by [`convert_case()`] and the `to_*_case` converters, or
+/// `None` for the defaults.
+#[cfg(feature = "std")]
+static GLOBAL_OPTIONS: RwLock<Option<Arc<CaseOptions>>> = RwLock::new(None);
+
+/// The default options, used until [`set_case_options()`] is called.
+static DEFAULT_OPTIONS: CaseOptions = CaseOptions::new();
+
+/// The options set by [`set_case_options()`] when it was taken, held without
+/// keeping the lock. Without the `std` feature there is no global state and
+/// it is always the defaults.
+struct GlobalOptions {
+    #[cfg(feature = "std")]
+    options: Option<Arc<CaseOptions>>,
+}
+
+impl GlobalOptions {
+    fn get() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            options: GLOBAL_OPTIONS
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone(),
+        }
+    }
+}
+
+impl Deref for GlobalOptions {
+    type Target = CaseOptions;
+
+    fn deref(&self) -> &CaseOptions {
+        #[cfg(feature = "std")]
+        if let Some(options) = &self.options {
+            return options;
+        }
+        &DEFAULT_OPTIONS
+    }
+}
+
+/// Run `f` with the options set by [`set_case_options()`].
+fn with_global_options<R>(f: impl FnOnce(&CaseOptions) -> R) -> R {
+    f(&GlobalOptions::get())
+}
+
+/// Set the options used by [`convert_case()`], the `to_*_case` converters
+/// and environment variable lookups throughout the crate.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{set_case_options, to_pascal_case, CaseOptions, AcronymStyle};
+///
+/// set_case_options(
+///     CaseOptions::new()
+///         .acronyms(["ID"])
+///         .acronym_style(AcronymStyle::Preserve),
+/// );
+/// assert_eq!(to_pascal_case("user-id"), "UserID");
+/// ```
+#[cfg(feature = "std")]
+pub fn set_case_options(options: CaseOptions) {
+    *GLOBAL_OPTIONS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(options));
+}
+
+impl Case {
+    /// The string placed between words.
+    fn separator(self) -> &'static str {
+        match self {
+            Case::Camel | Case::Pascal | Case::Flat => "",
+            Case::Snake | Case::UpperSnake => "_",
+            Case::Kebab | Case::Train | Case::ScreamingKebab => "-",
+            Case::Dot => ".",
+            Case::Path => "/",
+            Case::Title => " ",
+        }
+    }
+
+    /// How the word at `index` is rendered.
+    fn word_case(self, index: usize) -> WordCase {
+        match self {
+            Case::Camel if index == 0 => WordCase::Lower,
+            Case::Camel | Case::Pascal | Case::Train | Case::Title => WordCase::Capitalized,
+            Case::Snake | Case::Kebab | Case::Dot | Case::Path | Case::Flat => WordCase::Lower,
+            Case::UpperSnake | Case::ScreamingKebab => WordCase::Upper,
+        }
+    }
+}
+
+/// Convert a string to the given case.
+///
+/// The input may be in any supported case (or a mix of them); it is split
+/// into words first and then rendered in the target case. Input that is
+/// already in the target case is returned borrowed, without allocating.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{convert_case, Case};
+/// use std::borrow::Cow;
+///
+/// assert_eq!(convert_case("myVariableName", Case::Kebab), "my-variable-name");
+/// assert_eq!(convert_case("my-variable-name", Case::Pascal), "MyVariableName");
+/// assert!(matches!(convert_case("API_KEY", Case::UpperSnake), Cow::Borrowed(_)));
+/// ```
+pub fn convert_case(input: &str, target: Case) -> Cow<'_, str> {
+    with_global_options(|options| convert_case_with(input, target, options))
+}
+
+/// Convert a string to the given case with explicit options instead of the
+/// ones set by [`set_case_options()`].
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{convert_case_with, Case, CaseOptions};
+///
+/// let options = CaseOptions::new().acronyms(["OAuth"]);
+/// assert_eq!(convert_case_with("OAuthToken", Case::Kebab, &options), "oauth-token");
+/// ```
+pub fn convert_case_with<'a>(input: &'a str, target: Case, options: &CaseOptions) -> Cow<'a, str> {
+    join_words(input, target.separator(), options, |i| target.word_case(i))
+}
+
+/// Write a string converted to the given case into `out`.
+///
+/// Like [`convert_case()`], but writes into an existing buffer or formatter
+/// so hot paths can reuse one allocation across many keys.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{write_case, Case};
+///
+/// let mut buffer = String::new();
+/// write_case(&mut buffer, "api-key", Case::UpperSnake).unwrap();
+/// assert_eq!(buffer, "API_KEY");
+///
+/// buffer.clear();
+/// write_case(&mut buffer, "maxRetries", Case::Kebab).unwrap();
+/// assert_eq!(buffer, "max-retries");
+/// ```
+pub fn write_case(out: &mut impl fmt::Write, input: &str, target: Case) -> fmt::Result {
+    with_global_options(|options| write_case_with(out, input, target, options))
+}
+
+/// Write a string converted to the given case into `out`, with explicit
+/// options instead of the ones set by [`set_case_options()`].
+pub fn write_case_with(
+    out: &mut impl fmt::Write,
+    input: &str,
+    target: Case,
+    options: &CaseOptions,
+) -> fmt::Result {
+    write_words(out, input, target.separator(), options, |i| {
+        target.word_case(i)
+    })
+}
+
+/// Convert a string to a custom style: every word rendered in `casing`,
+/// joined by `separator`.
+///
+/// Uses the same word splitting as [`convert_case()`], for styles that are
+/// not covered by [`Case`].
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{convert_with_separator, WordCase};
+///
+/// assert_eq!(convert_with_separator("apiKey", ":", WordCase::Lower), "api:key");
+/// assert_eq!(convert_with_separator("api-key", "::", WordCase::Upper), "API::KEY");
+/// ```
+pub fn convert_with_separator<'a>(
+    input: &'a str,
+    separator: &str,
+    casing: WordCase,
+) -> Cow<'a, str> {
+    with_global_options(|options| join_words(input, separator, options, |_| casing))
+}
+
+/// Render the words of `input` with `word_case(index)` and join them with
+/// `separator`, borrowing `input` if it is already rendered that way.
+fn join_words<'a>(
+    input: &'a str,
+    separator: &str,
+    options: &CaseOptions,
+    word_case: impl Fn(usize) -> WordCase,
+) -> Cow<'a, str> {
+    let mut out = CowWriter::new(input);
+    // Writing to a `CowWriter` never fails
+    let _ = write_words(&mut out, input, separator, options, word_case);
+    out.finish()
+}
+
+/// Write the words of `input` to `out`, rendered with `word_case(index)` and
+/// joined by `separator`.
+fn write_words(
+    out: &mut impl fmt::Write,
+    input: &str,
+    separator: &str,
+    options: &CaseOptions,
+    word_case: impl Fn(usize) -> WordCase,
+) -> fmt::Result {
+    for (i, word) in segment(input, options).enumerate() {
+        if i > 0 {
+            out.write_str(separator)?;
+        }
+        write_word(out, word, word_case(i), options)?;
+    }
+    Ok(())
+}
+
+/// A string being written that stays borrowed from `input` for as long as
+/// the written text matches it, and is only copied on the first difference.
+struct CowWriter<'a> {
+    input: &'a str,
+    matched: usize,
+    owned: Option<String>,
+}
+
+impl<'a> CowWriter<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            matched: 0,
+            owned: None,
+        }
+    }
+
+    fn finish(self) -> Cow<'a, str> {
+        match self.owned {
+            Some(owned) => Cow::Owned(owned),
+            None => Cow::Borrowed(&self.input[..self.matched]),
+        }
+    }
+}
+
+impl fmt::Write for CowWriter<'_> {
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        match &mut self.owned {
+            Some(owned) => owned.push(c),
+            None if self.input[self.matched..].starts_with(c) => self.matched += c.len_utf8(),
+            None => return self.write_str(c.encode_utf8(&mut [0; 4])),
+        }
+        Ok(())
+    }
+
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(owned) = &mut self.owned {
+            owned.push_str(s);
+        } else if self.input[self.matched..].starts_with(s) {
+            self.matched += s.len();
+        } else {
+            let mut owned = String::with_capacity(self.input.len() + 4);
+            owned.push_str(&self.input[..self.matched]);
+            owned.push_str(s);
+            self.owned = Some(owned);
+        }
+        Ok(())
+    }
+}
+
+/// Convert a key to its canonical form: lowercase words joined by `-`.
+///
+/// Every case variant of a key has the same canonical form, so it can be
+/// used to index all variants under one entry. For keys whose words are at
+/// least two characters long and start with a letter (digits may follow or
+/// sit inside, as in `v2`, `ipv6`, or `s3bucket`), converting to any
+/// [`Case`] other than [`Case::Flat`] and back is stable, unless a word of
+/// one letter and digits comes before a word whose second character is a
+/// digit (`v2-s3`, whose PascalCase `V2S3` reads as the single word `v2s3`
+/// in UPPER_CASE):
+///
+/// - `canonical_key(canonical_key(k)) == canonical_key(k)`
+/// - `canonical_key(convert_case(k, case)) == canonical_key(k)`
+/// - `convert_case(canonical_key(k), case) == convert_case(k, case)`
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::canonical_key;
+///
+/// assert_eq!(canonical_key("API_KEY"), "api-key");
+/// assert_eq!(canonical_key("apiKey"), canonical_key("Api-Key"));
+/// ```
+pub fn canonical_key(key: &str) -> Cow<'_, str> {
+    convert_case(key, Case::Kebab)
+}
+
+/// Convert every key in `keys` to `target` case.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{convert_all, Case};
+///
+/// assert_eq!(
+///     convert_all(["apiKey", "MAX_RETRIES"], Case::Kebab),
+///     ["api-key", "max-retries"]
+/// );
+/// ```
+pub fn convert_all<I>(keys: I, target: Case) -> Vec<String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    with_global_options(|options| convert_all_with(keys, target, options))
+}
+
+/// Convert every key in `keys` to `target` case using explicit options
+/// instead of the global ones.
+pub fn convert_all_with<I>(keys: I, target: Case, options: &CaseOptions) -> Vec<String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    keys.into_iter()
+        .map(|key| convert_case_with(key.as_ref(), target, options).into_owned())
+        .collect()
+}
+
+/// A bidirectional map between keys and their names in one [`Case`].
+///
+/// Built once from a set of keys, it renames them to the target case and
+/// back without re-running the conversion, which suits tools that rewrite
+/// whole config files. Keys are looked up by [`canonical_key()`], so any
+/// case variant of a key finds its entry; converted names are looked up by
+/// exact spelling. When several keys share a canonical form, the first one
+/// wins.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{Case, KeyMap};
+///
+/// let map = KeyMap::new(["apiKey", "maxRetries"], Case::UpperSnake);
+/// assert_eq!(map.convert("api-key"), Some("API_KEY"));
+/// assert_eq!(map.original("MAX_RETRIES"), Some("maxRetries"));
+/// assert_eq!(map.convert("timeout"), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMap {
+    case: Case,
+    entries: Vec<(String, String)>,
+    by_key: BTreeMap<String, usize>,
+    by_name: BTreeMap<String, usize>,
+}
+
+impl KeyMap {
+    /// Build a map from `keys` to their names in `target` case.
+    pub fn new<I>(keys: I, target: Case) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        with_global_options(|options| Self::with_options(keys, target, options))
+    }
+
+    /// Build a map using explicit options instead of the global ones.
+    pub fn with_options<I>(keys: I, target: Case, options: &CaseOptions) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut map = Self {
+            case: target,
+            entries: Vec::new(),
+            by_key: BTreeMap::new(),
+            by_name: BTreeMap::new(),
+        };
+        for key in keys {
+            let key = key.as_ref();
+            let canonical = convert_case_with(key, Case::Kebab, options).into_owned();
+            if map.by_key.contains_key(&canonical) {
+                continue;
+            }
+            let name = convert_case_with(key, target, options).into_owned();
+            let index = map.entries.len();
+            map.by_key.insert(canonical, index);
+            map.by_name.entry(name.clone()).or_insert(index);
+            map.entries.push((key.into(), name));
+        }
+        map
+    }
+
+    /// The case keys are converted to.
+    pub fn case(&self) -> Case {
+        self.case
+    }
+
+    /// The converted name of `key`, given in any case.
+    pub fn convert(&self, key: &str) -> Option<&str> {
+        let index = *self.by_key.get(canonical_key(key).as_ref())?;
+        Some(&self.entries[index].1)
+    }
+
+    /// The original key a converted `name` came from.
+    pub fn original(&self, name: &str) -> Option<&str> {
+        let index = *self.by_name.get(name)?;
+        Some(&self.entries[index].0)
+    }
+
+    /// `(key, name)` pairs in the order the keys were given.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(key, name)| (key.as_str(), name.as_str()))
+    }
+
+    /// Number of keys in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the map holds no keys.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Detect which case a string is written in.
+///
+/// Returns the case whose conversion leaves `input` unchanged. A single
+/// lowercase word (`port`) fits several cases at once; the first matching
+/// variant in declaration order of [`Case`] is reported. Returns `None` for
+/// input without words and for mixed styles (`api_Key`, `HTTPServer`).
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{detect_case, Case};
+///
+/// assert_eq!(detect_case("API_KEY"), Some(Case::UpperSnake));
+/// assert_eq!(detect_case("api-key"), Some(Case::Kebab));
+/// assert_eq!(detect_case("api_Key"), None);
+/// ```
+pub fn detect_case(input: &str) -> Option<Case> {
+    split_words(input).next()?;
+    DETECTION_ORDER
+        .into_iter()
+        .find(|&case| convert_case(input, case) == input)
+}
+
+/// Cases tried by [`detect_case()`], in declaration order of [`Case`].
+///
+/// [`Case::Flat`] is left out: a flatcase string cannot be told apart from a
+/// single lowercase word.
+const DETECTION_ORDER: [Case; 10] = [
+    Case::Camel,
+    Case::Pascal,
+    Case::Snake,
+    Case::Kebab,
+    Case::UpperSnake,
+    Case::Train,
+    Case::Dot,
+    Case::ScreamingKebab,
+    Case::Path,
+    Case::Title,
+];
+
+/// Write a word to `out`, rendered in the given word case.
+fn write_word(
+    out: &mut impl fmt::Write,
+    word: &str,
+    case: WordCase,
+    options: &CaseOptions,
+) -> fmt::Result {
+    if let Some((exception, digits)) = options.exception_of(word) {
+        out.write_str(exception)?;
+        return out.write_str(digits);
+    }
+    if case == WordCase::Capitalized && options.acronym_style == AcronymStyle::Preserve {
+        if let Some((acronym, digits)) = options.acronym_of(word) {
+            out.write_str(acronym)?;
+            return out.write_str(digits);
+        }
+    }
+
+    // A word already in the case is written whole, which keeps a
+    // `CowWriter` borrowing with a single comparison
+    if is_rendered(word, case) {
+        return out.write_str(word);
+    }
+    for (i, c) in word.chars().enumerate() {
+        match (upper_at(case, i), c.is_ascii()) {
+            (true, true) => out.write_char(c.to_ascii_uppercase())?,
+            (false, true) => out.write_char(c.to_ascii_lowercase())?,
+            (true, false) => c.to_uppercase().try_for_each(|c| out.write_char(c))?,
+            (false, false) => c.to_lowercase().try_for_each(|c| out.write_char(c))?,
+        }
+    }
+    Ok(())
+}
+
+/// Whether the character at `index` of a word is uppercase in `case`.
+fn upper_at(case: WordCase, index: usize) -> bool {
+    match case {
+        WordCase::Lower => false,
+        WordCase::Upper => true,
+        WordCase::Capitalized => index == 0,
+    }
+}
+
+/// Whether `word` reads the same once rendered in `case`.
+fn is_rendered(word: &str, case: WordCase) -> bool {
+    word.chars()
+        .enumerate()
+        .all(|(i, c)| match (upper_at(case, i), c.is_ascii()) {
+            (true, true) => !c.is_ascii_lowercase(),
+            (false, true) => !c.is_ascii_uppercase(),
+            (true, false) => c.to_uppercase().eq([c]),
+            (false, false) => c.to_lowercase().eq([c]),
+        })
+}
+
+/// Split a string into words.
+///
+/// Any character that is not alphanumeric (`-`, `_`, space, ...) separates
+/// words. Inside a run of letters and digits, a new word starts at an
+/// uppercase letter that follows a lowercase letter (`apiKey`), and at the
+/// last uppercase letter of an acronym that is followed by a lowercase letter
+/// (`HTTPServer`). Digits never start a word on their own and are skipped
+/// when looking at the letter before, so `v2Api` and `ipv6Address` split
+/// after the digits while `V2API` and `S3BUCKET` stay one word, like `v2api`
+/// and `s3bucket`.
+///
+/// This is the tokenizer behind every converter, so casings built on top of
+/// it agree with the crate's environment and CLI naming. Acronyms registered
+/// with [`set_case_options()`] are kept intact; the iterator holds on to the
+/// options set when it was created, without locking them, and yields words
+/// lazily as slices of `s`.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{set_case_options, split_words, CaseOptions};
+///
+/// let words: Vec<&str> = split_words("HTTPServer_v2Api").collect();
+/// assert_eq!(words, ["HTTP", "Server", "v2", "Api"]);
+///
+/// let mut words = split_words("OAuthToken");
+/// assert_eq!(words.next(), Some("O"));
+/// set_case_options(CaseOptions::new().acronyms(["OAuth"]));
+/// assert_eq!(words.collect::<Vec<_>>(), ["Auth", "Token"]);
+/// assert_eq!(split_words("OAuthToken").next(), Some("OAuth"));
+/// ```
+pub fn split_words(s: &str) -> impl Iterator<Item = &str> {
+    segment(s, GlobalOptions::get())
+}
+
+/// Split a string into words like [`split_words()`], with explicit options
+/// instead of the ones set by [`set_case_options()`].
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{split_words_with, CaseOptions};
+///
+/// let options = CaseOptions::new().acronyms(["OAuth"]);
+/// let words: Vec<&str> = split_words_with("OAuthToken", &options).collect();
+/// assert_eq!(words, ["OAuth", "Token"]);
+/// ```
+pub fn split_words_with<'a, 'b>(
+    s: &'a str,
+    options: &'b CaseOptions,
+) -> impl Iterator<Item = &'a str> + 'b
+where
+    'a: 'b,
+{
+    segment(s, options)
+}
+
+/// Split a string into words like [`split_words()`], keeping registered
+/// acronyms and exceptions that start a word intact (`OAuthToken` →
+/// `OAuth`, `Token`). Digits right after them attach to the word (`OAuth2`).
+fn segment<O: Deref<Target = CaseOptions>>(s: &str, options: O) -> Words<'_, O> {
+    Words {
+        s,
+        options,
+        chars: s.char_indices().peekable(),
+        start: None,
+        prev: None,
+        letter: None,
+        intact_end: None,
+    }
+}
+
+/// Lazy word iterator returned by [`segment()`], holding the options by
+/// reference or, for [`split_words()`], as [`GlobalOptions`].
+struct Words<'a, O> {
+    s: &'a str,
+    options: O,
+    chars: core::iter::Peekable<core::str::CharIndices<'a>>,
+    start: Option<usize>,
+    prev: Option<char>,
+    // The last letter of the current run, before any digits since
+    letter: Option<char>,
+    // End of the acronym or exception the current word starts with, if any
+    intact_end: Option<usize>,
+}
+
+impl<'a, O: Deref<Target = CaseOptions>> Iterator for Words<'a, O> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        while let Some((i, c)) = self.chars.next() {
+            if !c.is_alphanumeric() {
+                self.prev = None;
+                self.letter = None;
+                self.intact_end = None;
+                if let Some(word_start) = self.start.take() {
+                    return Some(&self.s[word_start..i]);
+                }
+                continue;
+            }
+
+            let starts_word = match (self.start, self.prev, self.intact_end) {
+                (Some(_), Some(_), Some(end)) => i >= end && !c.is_numeric(),
+                (Some(_), Some(_), None) => {
+                    let next_is_lower = self.chars.peek().is_some_and(|&(_, n)| n.is_lowercase());
+                    let after_upper = self.letter.is_some_and(char::is_uppercase);
+                    c.is_uppercase() && (!after_upper || next_is_lower)
+                }
+                _ => true,
+            };
+            self.prev = Some(c);
+            if !c.is_numeric() {
+                self.letter = Some(c);
+            }
+
+            if starts_word {
+                self.intact_end = match_intact(self.s, i, &self.options);
+                if let Some(word_start) = self.start.replace(i) {
+                    return Some(&self.s[word_start..i]);
+                }
+            }
+        }
+
+        self.start.take().map(|word_start| &self.s[word_start..])
+    }
+}
+
+/// The end of the longest registered acronym or exception that starts at
+/// `i` and can end a word where it stops.
+fn match_intact(s: &str, i: usize, options: &CaseOptions) -> Option<usize> {
+    options
+        .intact()
+        .filter(|word| s[i..].starts_with(word))
+        .map(|word| i + word.len())
+        .filter(|&end| ends_word(s, end, options))
+        .max()
+}
+
+/// Whether an acronym or exception ending at `end` can end a word there: at
+/// the end of input, before a separator or digit, or before something that
+/// starts a new word (an uppercase letter followed by a lowercase one, or
+/// another registered acronym or exception).
+fn ends_word(s: &str, end: usize, options: &CaseOptions) -> bool {
+    let rest = &s[end..];
+    let mut chars = rest.chars();
+    match chars.next() {
+        None => true,
+        Some(n) if !n.is_alphanumeric() || n.is_numeric() => true,
+        Some(n) if n.is_lowercase() => false,
+        Some(_) => {
+            s[..end].chars().next_back().is_some_and(char::is_lowercase)
+                || chars.next().is_some_and(char::is_lowercase)
+                || options.intact().any(|word| rest.starts_with(word))
+        }
+    }
+}
+
+/// Convert string to UPPER_CASE (for environment variables)
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_upper_case;
+///
+/// assert_eq!(to_upper_case("apiKey"), "API_KEY");
+/// assert_eq!(to_upper_case("my-variable-name"), "MY_VARIABLE_NAME");
+/// assert_eq!(to_upper_case("v2ApiKey"), "V2_API_KEY");
+/// ```
+pub fn to_upper_case(s: &str) -> String {
+    convert_case(s, Case::UpperSnake).into_owned()
+}
+
+/// Convert string to camelCase (for config object keys)
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_camel_case;
+///
+/// assert_eq!(to_camel_case("api-key"), "apiKey");
+/// assert_eq!(to_camel_case("API_KEY"), "apiKey");
+/// assert_eq!(to_camel_case("IPV6_ADDRESS"), "ipv6Address");
+/// ```
+pub fn to_camel_case(s: &str) -> String {
+    convert_case(s, Case::Camel).into_owned()
+}
+
+/// Convert string to kebab-case (for CLI options)
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_kebab_case;
+///
+/// assert_eq!(to_kebab_case("apiKey"), "api-key");
+/// assert_eq!(to_kebab_case("API_KEY"), "api-key");
+/// ```
+pub fn to_kebab_case(s: &str) -> String {
+    convert_case(s, Case::Kebab).into_owned()
+}
+
+/// Convert string to snake_case
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_snake_case;
+///
+/// assert_eq!(to_snake_case("apiKey"), "api_key");
+/// assert_eq!(to_snake_case("API_KEY"), "api_key");
+/// ```
+pub fn to_snake_case(s: &str) -> String {
+    convert_case(s, Case::Snake).into_owned()
+}
+
+/// Convert string to PascalCase
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_pascal_case;
+///
+/// assert_eq!(to_pascal_case("api-key"), "ApiKey");
+/// assert_eq!(to_pascal_case("api_key"), "ApiKey");
+/// ```
+pub fn to_pascal_case(s: &str) -> String {
+    convert_case(s, Case::Pascal).into_owned()
+}
+
+/// Write string as UPPER_CASE into `out` (see [`to_upper_case()`])
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::write_upper_case;
+///
+/// let mut name = String::from("APP_");
+/// write_upper_case(&mut name, "apiKey").unwrap();
+/// assert_eq!(name, "APP_API_KEY");
+/// ```
+pub fn write_upper_case(out: &mut impl fmt::Write, s: &str) -> fmt::Result {
+    write_case(out, s, Case::UpperSnake)
+}
+
+/// Write string as camelCase into `out` (see [`to_camel_case()`])
+pub fn write_camel_case(out: &mut impl fmt::Write, s: &str) -> fmt::Result {
+    write_case(out, s, Case::Camel)
+}
+
+/// Write string as kebab-case into `out` (see [`to_kebab_case()`])
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::write_kebab_case;
+///
+/// let mut flag = String::from("--");
+/// write_kebab_case(&mut flag, "API_KEY").unwrap();
+/// assert_eq!(flag, "--api-key");
+/// ```
+pub fn write_kebab_case(out: &mut impl fmt::Write, s: &str) -> fmt::Result {
+    write_case(out, s, Case::Kebab)
+}
+
+/// Write string as snake_case into `out` (see [`to_snake_case()`])
+pub fn write_snake_case(out: &mut impl fmt::Write, s: &str) -> fmt::Result {
+    write_case(out, s, Case::Snake)
+}
+
+/// Write string as PascalCase into `out` (see [`to_pascal_case()`])
+pub fn write_pascal_case(out: &mut impl fmt::Write, s: &str) -> fmt::Result {
+    write_case(out, s, Case::Pascal)
+}
+
+/// Convert string to Train-Case (for HTTP header style names)
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_train_case;
+///
+/// assert_eq!(to_train_case("api-key"), "Api-Key");
+/// assert_eq!(to_train_case("apiKey"), "Api-Key");
+/// ```
+pub fn to_train_case(s: &str) -> String {
+    convert_case(s, Case::Train).into_owned()
+}
+
+/// Convert string to dot.case (for Java style property names)
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_dot_case;
+///
+/// assert_eq!(to_dot_case("apiKey"), "api.key");
+/// assert_eq!(to_dot_case("API_KEY"), "api.key");
+/// ```
+pub fn to_dot_case(s: &str) -> String {
+    convert_case(s, Case::Dot).into_owned()
+}
+
+/// Convert string to SCREAMING-KEBAB-CASE (for Makefile style names)
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_screaming_kebab_case;
+///
+/// assert_eq!(to_screaming_kebab_case("apiKey"), "API-KEY");
+/// assert_eq!(to_screaming_kebab_case("api_key"), "API-KEY");
+/// ```
+pub fn to_screaming_kebab_case(s: &str) -> String {
+    convert_case(s, Case::ScreamingKebab).into_owned()
+}
+
+/// Convert string to path/case (for hierarchical stores such as Consul KV or Vault)
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_path_case;
+///
+/// assert_eq!(to_path_case("apiKey"), "api/key");
+/// assert_eq!(to_path_case("DATABASE_URL"), "database/url");
+/// ```
+pub fn to_path_case(s: &str) -> String {
+    convert_case(s, Case::Path).into_owned()
+}
+
+/// Convert string to Title Case (for human-friendly labels in help text and tables)
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_title_case;
+///
+/// assert_eq!(to_title_case("max-retries"), "Max Retries");
+/// assert_eq!(to_title_case("apiKey"), "Api Key");
+/// ```
+pub fn to_title_case(s: &str) -> String {
+    convert_case(s, Case::Title).into_owned()
+}
+
+/// Convert string to flatcase (for systems that strip separators)
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::to_flat_case;
+///
+/// assert_eq!(to_flat_case("apiKey"), "apikey");
+/// assert_eq!(to_flat_case("API_KEY"), "apikey");
+/// ```
+pub fn to_flat_case(s: &str) -> String {
+    convert_case(s, Case::Flat).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    mod case_conversion {
+        use super::*;
+
+        #[test]
+        fn test_to_upper_case() {
+            assert_eq!(to_upper_case("apiKey"), "API_KEY");
+            assert_eq!(to_upper_case("myVariableName"), "MY_VARIABLE_NAME");
+            assert_eq!(to_upper_case("api-key"), "API_KEY");
+            assert_eq!(to_upper_case("API_KEY"), "API_KEY");
+        }
+
+        #[test]
+        fn test_to_camel_case() {
+            assert_eq!(to_camel_case("api-key"), "apiKey");
+            assert_eq!(to_camel_case("API_KEY"), "apiKey");
+            assert_eq!(to_camel_case("my_variable_name"), "myVariableName");
+        }
+
+        #[test]
+        fn test_to_kebab_case() {
+            assert_eq!(to_kebab_case("apiKey"), "api-key");
+            assert_eq!(to_kebab_case("API_KEY"), "api-key");
+            assert_eq!(to_kebab_case("MyVariableName"), "my-variable-name");
+        }
+
+        #[test]
+        fn test_to_snake_case() {
+            assert_eq!(to_snake_case("apiKey"), "api_key");
+            assert_eq!(to_snake_case("api-key"), "api_key");
+            assert_eq!(to_snake_case("API_KEY"), "api_key");
+        }
+
+        #[test]
+        fn test_to_pascal_case() {
+            assert_eq!(to_pascal_case("api-key"), "ApiKey");
+            assert_eq!(to_pascal_case("api_key"), "ApiKey");
+            assert_eq!(to_pascal_case("my-variable-name"), "MyVariableName");
+        }
+
+        #[test]
+        fn test_convert_case_matches_converters() {
+            for key in ["apiKey", "API_KEY", "my-variable-name", "v2ApiKey"] {
+                assert_eq!(convert_case(key, Case::UpperSnake), to_upper_case(key));
+                assert_eq!(convert_case(key, Case::Camel), to_camel_case(key));
+                assert_eq!(convert_case(key, Case::Kebab), to_kebab_case(key));
+                assert_eq!(convert_case(key, Case::Snake), to_snake_case(key));
+                assert_eq!(convert_case(key, Case::Pascal), to_pascal_case(key));
+                assert_eq!(convert_case(key, Case::Train), to_train_case(key));
+                assert_eq!(convert_case(key, Case::Dot), to_dot_case(key));
+                assert_eq!(
+                    convert_case(key, Case::ScreamingKebab),
+                    to_screaming_kebab_case(key)
+                );
+                assert_eq!(convert_case(key, Case::Path), to_path_case(key));
+                assert_eq!(convert_case(key, Case::Title), to_title_case(key));
+                assert_eq!(convert_case(key, Case::Flat), to_flat_case(key));
+            }
+        }
+
+        #[test]
+        fn test_to_train_case() {
+            assert_eq!(to_train_case("api-key"), "Api-Key");
+            assert_eq!(to_train_case("API_KEY"), "Api-Key");
+            assert_eq!(to_train_case("contentType"), "Content-Type");
+        }
+
+        #[test]
+        fn test_to_dot_case() {
+            assert_eq!(to_dot_case("apiKey"), "api.key");
+            assert_eq!(
+                to_dot_case("server-max-connections"),
+                "server.max.connections"
+            );
+            assert_eq!(to_dot_case("api.key"), "api.key");
+        }
+
+        #[test]
+        fn test_to_screaming_kebab_case() {
+            assert_eq!(to_screaming_kebab_case("apiKey"), "API-KEY");
+            assert_eq!(to_screaming_kebab_case("API_KEY"), "API-KEY");
+            assert_eq!(to_screaming_kebab_case("v2-api"), "V2-API");
+        }
+
+        #[test]
+        fn test_to_path_case() {
+            assert_eq!(to_path_case("apiKey"), "api/key");
+            assert_eq!(to_path_case("/service/db-url/"), "service/db/url");
+            assert_eq!(to_path_case("api/key"), "api/key");
+        }
+
+        #[test]
+        fn test_to_title_case() {
+            assert_eq!(to_title_case("max-retries"), "Max Retries");
+            assert_eq!(to_title_case("HTTP_TIMEOUT"), "Http Timeout");
+            assert_eq!(to_title_case("  api   key "), "Api Key");
+        }
+
+        #[test]
+        fn test_to_flat_case() {
+            assert_eq!(to_flat_case("apiKey"), "apikey");
+            assert_eq!(to_flat_case("V2_API_KEY"), "v2apikey");
+            assert_eq!(detect_case(&to_flat_case("api-key")), Some(Case::Camel));
+        }
+
+        #[test]
+        fn test_convert_with_separator() {
+            assert_eq!(
+                convert_with_separator("apiKey", ":", WordCase::Lower),
+                "api:key"
+            );
+            assert_eq!(
+                convert_with_separator("api_key", "::", WordCase::Upper),
+                "API::KEY"
+            );
+            assert_eq!(
+                convert_with_separator("max-retries", " / ", WordCase::Capitalized),
+                "Max / Retries"
+            );
+            assert_eq!(
+                convert_with_separator("apiKey", "_", WordCase::Lower),
+                to_snake_case("apiKey")
+            );
+            assert_eq!(convert_with_separator("", ":", WordCase::Upper), "");
+        }
+
+        #[test]
+        fn test_convert_case_borrows_input_in_target_case() {
+            for (key, case) in [
+                ("api_key", Case::Snake),
+                ("API_KEY", Case::UpperSnake),
+                ("apiKey", Case::Camel),
+                ("Api-Key", Case::Train),
+                ("größe_maß", Case::Snake),
+                ("Über-Straße", Case::Train),
+                ("", Case::Kebab),
+            ] {
+                assert!(
+                    matches!(convert_case(key, case), Cow::Borrowed(k) if k == key),
+                    "{key:?} in {case:?}"
+                );
+            }
+            assert!(matches!(
+                convert_case("api-key-", Case::Kebab),
+                Cow::Borrowed("api-key")
+            ));
+        }
+
+        #[test]
+        fn test_convert_case_owns_converted_output() {
+            assert!(
+                matches!(convert_case("api_key", Case::Kebab), Cow::Owned(k) if k == "api-key")
+            );
+            assert!(
+                matches!(convert_case("api_KEY", Case::Snake), Cow::Owned(k) if k == "api_key")
+            );
+            assert!(matches!(convert_case("_api", Case::Snake), Cow::Owned(k) if k == "api"));
+            assert!(
+                matches!(convert_case("über_größe", Case::Pascal), Cow::Owned(k) if k == "ÜberGröße")
+            );
+        }
+
+        #[test]
+        fn test_write_case_matches_convert_case() {
+            let mut buffer = String::new();
+            for key in ["apiKey", "API_KEY", "my-variable-name", "v2ApiKey", ""] {
+                for case in DETECTION_ORDER {
+                    buffer.clear();
+                    write_case(&mut buffer, key, case).unwrap();
+                    assert_eq!(buffer, convert_case(key, case), "{key:?} in {case:?}");
+                }
+            }
+        }
+
+        #[test]
+        fn test_write_converters_append() {
+            let mut out = String::from("x=");
+            write_upper_case(&mut out, "apiKey").unwrap();
+            out.push(',');
+            write_camel_case(&mut out, "api-key").unwrap();
+            out.push(',');
+            write_kebab_case(&mut out, "apiKey").unwrap();
+            out.push(',');
+            write_snake_case(&mut out, "apiKey").unwrap();
+            out.push(',');
+            write_pascal_case(&mut out, "api-key").unwrap();
+            assert_eq!(out, "x=API_KEY,apiKey,api-key,api_key,ApiKey");
+        }
+
+        #[test]
+        fn test_write_case_propagates_errors() {
+            struct Full;
+            impl fmt::Write for Full {
+                fn write_str(&mut self, _: &str) -> fmt::Result {
+                    Err(fmt::Error)
+                }
+            }
+            assert!(write_upper_case(&mut Full, "apiKey").is_err());
+            assert!(write_upper_case(&mut Full, "").is_ok());
+        }
+
+        #[test]
+        fn test_convert_case_empty_input() {
+            assert_eq!(convert_case("", Case::Camel), "");
+            assert_eq!(convert_case("--", Case::Kebab), "");
+        }
+    }
+
+    mod case_detection {
+        use super::*;
+
+        #[test]
+        fn test_detect_case() {
+            assert_eq!(detect_case("apiKey"), Some(Case::Camel));
+            assert_eq!(detect_case("ApiKey"), Some(Case::Pascal));
+            assert_eq!(detect_case("api_key"), Some(Case::Snake));
+            assert_eq!(detect_case("api-key"), Some(Case::Kebab));
+            assert_eq!(detect_case("API_KEY"), Some(Case::UpperSnake));
+            assert_eq!(detect_case("v2_api_key"), Some(Case::Snake));
+        }
+
+        #[test]
+        fn test_detect_case_ambiguous_single_word() {
+            assert_eq!(detect_case("port"), Some(Case::Camel));
+            assert_eq!(detect_case("Port"), Some(Case::Pascal));
+            assert_eq!(detect_case("PORT"), Some(Case::UpperSnake));
+            assert_eq!(detect_case("Content-Type"), Some(Case::Train));
+            assert_eq!(detect_case("server.port"), Some(Case::Dot));
+            assert_eq!(detect_case("API-KEY"), Some(Case::ScreamingKebab));
+            assert_eq!(detect_case("secret/api/key"), Some(Case::Path));
+            assert_eq!(detect_case("Max Retries"), Some(Case::Title));
+        }
+
+        #[test]
+        fn test_detect_case_unknown() {
+            assert_eq!(detect_case(""), None);
+            assert_eq!(detect_case("--"), None);
+            assert_eq!(detect_case("api_Key"), None);
+            assert_eq!(detect_case("api-key_name"), None);
+            assert_eq!(detect_case("HTTPServer"), None);
+        }
+
+        #[test]
+        fn test_case_names_round_trip() {
+            for case in Case::ALL {
+                assert_eq!(case.name().parse(), Ok(case));
+                assert_eq!(convert_case(case.name(), case).parse(), Ok(case));
+            }
+            assert_eq!(Case::ScreamingKebab.to_string(), "screaming-kebab");
+            assert_eq!(
+                "constant".parse::<Case>().unwrap_err().to_string(),
+                "unknown case \"constant\""
+            );
+        }
+
+        #[test]
+        fn test_detect_case_round_trips_convert_case() {
+            for case in DETECTION_ORDER {
+                let converted = convert_case("my-variable-name", case);
+                assert_eq!(detect_case(&converted), Some(case));
+            }
+        }
+    }
+
+    mod acronyms {
+        use super::*;
+
+        fn split<'a>(s: &'a str, acronyms: &[&str]) -> Vec<&'a str> {
+            segment(s, &CaseOptions::new().acronyms(acronyms.iter().copied())).collect()
+        }
+
+        #[test]
+        fn test_split_words_keeps_acronyms_intact() {
+            let list = ["HTTP", "URL", "XML", "OAuth", "ID"];
+            assert_eq!(split("OAuthToken", &list), vec!["OAuth", "Token"]);
+            assert_eq!(split("myOAuthToken", &list), vec!["my", "OAuth", "Token"]);
+            assert_eq!(
+                split("XMLHTTPRequest", &list),
+                vec!["XML", "HTTP", "Request"]
+            );
+            assert_eq!(split("HTTPURLParser", &list), vec!["HTTP", "URL", "Parser"]);
+            assert_eq!(split("userID", &list), vec!["user", "ID"]);
+            assert_eq!(split("OAuth2Token", &list), vec!["OAuth2", "Token"]);
+        }
+
+        #[test]
+        fn test_split_words_ignores_acronyms_inside_words() {
+            let list = ["ID", "URL"];
+            assert_eq!(split("IDENTITY", &list), vec!["IDENTITY"]);
+            assert_eq!(split("identity", &list), vec!["identity"]);
+            assert_eq!(split("userIdentity", &list), vec!["user", "Identity"]);
+            assert_eq!(split("URLs", &list), split("URLs", &[]));
+        }
+
+        #[test]
+        fn test_acronym_styles() {
+            let capitalize = CaseOptions::new().acronyms(["ID", "HTTP", "OAuth"]);
+            let preserve = capitalize.clone().acronym_style(AcronymStyle::Preserve);
+
+            assert_eq!(
+                convert_case_with("userID", Case::Pascal, &capitalize),
+                "UserId"
+            );
+            assert_eq!(
+                convert_case_with("userID", Case::Pascal, &preserve),
+                "UserID"
+            );
+            assert_eq!(
+                convert_case_with("user_id", Case::Camel, &preserve),
+                "userID"
+            );
+            assert_eq!(
+                convert_case_with("id_token", Case::Camel, &preserve),
+                "idToken"
+            );
+            assert_eq!(
+                convert_case_with("http-server", Case::Train, &preserve),
+                "HTTP-Server"
+            );
+            assert_eq!(
+                convert_case_with("oauth2_token", Case::Pascal, &preserve),
+                "OAuth2Token"
+            );
+            assert_eq!(
+                convert_case_with("userID", Case::UpperSnake, &preserve),
+                "USER_ID"
+            );
+            assert_eq!(
+                convert_case_with("OAuthToken", Case::Snake, &preserve),
+                "oauth_token"
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn test_global_case_options() {
+            // Uses an acronym no other test converts, since the options are global
+            set_case_options(
+                CaseOptions::new()
+                    .acronyms(["XYZZY"])
+                    .acronym_style(AcronymStyle::Preserve),
+            );
+            let pascal = to_pascal_case("xyzzy-value");
+            let upper = to_upper_case("XYZZYValue");
+            set_case_options(CaseOptions::new());
+
+            assert_eq!(pascal, "XYZZYValue");
+            assert_eq!(upper, "XYZZY_VALUE");
+        }
+    }
+
+    mod exceptions {
+        use super::*;
+
+        #[test]
+        fn test_exceptions_keep_exact_spelling() {
+            let options = CaseOptions::new().exceptions(["iOS", "macOS", "OAuth2"]);
+            let convert = |key, case| convert_case_with(key, case, &options).into_owned();
+
+            assert_eq!(convert("iOSVersion", Case::Snake), "iOS_version");
+            assert_eq!(convert("iOSVersion", Case::UpperSnake), "iOS_VERSION");
+            assert_eq!(convert("ios-version", Case::Camel), "iOSVersion");
+            assert_eq!(
+                convert("min-macos-version", Case::Pascal),
+                "MinmacOSVersion"
+            );
+            assert_eq!(convert("OAuth2Token", Case::Kebab), "OAuth2-token");
+            assert_eq!(convert("oauth2_token", Case::Title), "OAuth2 Token");
+        }
+
+        #[test]
+        fn test_exceptions_split_like_acronyms() {
+            let options = CaseOptions::new().exceptions(["iOS"]);
+            let words: Vec<&str> = segment("iOSApp", &options).collect();
+            assert_eq!(words, vec!["iOS", "App"]);
+            let words: Vec<&str> = segment("iOS17Build", &options).collect();
+            assert_eq!(words, vec!["iOS17", "Build"]);
+        }
+
+        #[test]
+        fn test_exceptions_take_precedence_over_acronym_style() {
+            let options = CaseOptions::new()
+                .acronyms(["OS"])
+                .exceptions(["macOS"])
+                .acronym_style(AcronymStyle::Preserve);
+            assert_eq!(
+                convert_case_with("macos_os", Case::Pascal, &options),
+                "macOSOS"
+            );
+        }
+    }
+
+    mod canonical {
+        use super::*;
+        use alloc::format;
+
+        /// Keys made of every pair of sample words joined by every separator,
+        /// covering acronyms and words with digits, except for the pairs that
+        /// [`canonical_key()`] leaves out.
+        fn sample_keys() -> Vec<String> {
+            let words = [
+                "api",
+                "Key",
+                "HTTP",
+                "v2",
+                "ipv6",
+                "Server",
+                "ID",
+                "oauth2",
+                "URL",
+                "s3bucket",
+                "abc123def",
+                "X9",
+            ];
+            let digit_second = |word: &str| word[1..].starts_with(|c: char| c.is_ascii_digit());
+            let letter_and_digits = |word: &str| word[1..].chars().all(|c| c.is_ascii_digit());
+            let separators = ["_", "-", ".", "/", " ", "__"];
+            let mut keys = Vec::new();
+            for first in words {
+                for second in words {
+                    if letter_and_digits(first) && digit_second(second)
+                        || letter_and_digits(second) && digit_second(first)
+                    {
+                        continue;
+                    }
+                    for separator in separators {
+                        keys.push(format!("{first}{separator}{second}"));
+                        keys.push(format!("{first}{separator}{second}{separator}{first}"));
+                    }
+                }
+            }
+            keys
+        }
+
+        #[test]
+        fn test_canonical_key() {
+            assert_eq!(canonical_key("API_KEY"), "api-key");
+            assert_eq!(canonical_key("apiKey"), "api-key");
+            assert_eq!(canonical_key("api.key"), "api-key");
+            assert_eq!(canonical_key("Max Retries"), "max-retries");
+            assert_eq!(canonical_key("ABC123DEF"), canonical_key("abc123def"));
+            assert_eq!(canonical_key("S3BUCKET"), "s3bucket");
+            assert_eq!(canonical_key("S3Bucket"), "s3-bucket");
+            assert!(matches!(canonical_key("api-key"), Cow::Borrowed("api-key")));
+        }
+
+        #[test]
+        fn test_canonical_key_is_idempotent() {
+            for key in sample_keys() {
+                let canonical = canonical_key(&key);
+                assert_eq!(canonical_key(&canonical), canonical, "{key:?}");
+            }
+        }
+
+        #[test]
+        fn test_canonical_key_is_shared_by_all_cases() {
+            for key in sample_keys() {
+                let canonical = canonical_key(&key);
+                for case in DETECTION_ORDER {
+                    let converted = convert_case(&key, case);
+                    assert_eq!(
+                        canonical_key(&converted),
+                        canonical,
+                        "{key:?} via {converted:?}"
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn test_conversion_from_canonical_key_is_stable() {
+            for key in sample_keys() {
+                let canonical = canonical_key(&key);
+                for case in DETECTION_ORDER {
+                    assert_eq!(
+                        convert_case(&canonical, case),
+                        convert_case(&key, case),
+                        "{key:?} in {case:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    mod batch {
+        use super::*;
+
+        #[test]
+        fn test_convert_all() {
+            assert_eq!(
+                convert_all(["apiKey", "MAX_RETRIES", "db.host"], Case::UpperSnake),
+                vec!["API_KEY", "MAX_RETRIES", "DB_HOST"]
+            );
+            let keys = vec![String::from("api-key")];
+            assert_eq!(convert_all(&keys, Case::Camel), vec!["apiKey"]);
+            assert!(convert_all(Vec::<&str>::new(), Case::Kebab).is_empty());
+        }
+
+        #[test]
+        fn test_convert_all_with_options() {
+            let options = CaseOptions::new().acronyms(["HTTP"]);
+            assert_eq!(
+                convert_all_with(["http_port"], Case::Camel, &options),
+                vec!["httpPort"]
+            );
+            assert_eq!(
+                convert_all_with(["HTTPServer"], Case::Kebab, &options),
+                vec!["http-server"]
+            );
+        }
+
+        #[test]
+        fn test_key_map_both_directions() {
+            let map = KeyMap::new(["apiKey", "max-retries"], Case::UpperSnake);
+            assert_eq!(map.case(), Case::UpperSnake);
+            assert_eq!(map.len(), 2);
+            assert_eq!(map.convert("apiKey"), Some("API_KEY"));
+            assert_eq!(map.convert("api_key"), Some("API_KEY"));
+            assert_eq!(map.convert("MaxRetries"), Some("MAX_RETRIES"));
+            assert_eq!(map.original("API_KEY"), Some("apiKey"));
+            assert_eq!(map.original("MAX_RETRIES"), Some("max-retries"));
+            assert_eq!(map.original("api_key"), None);
+            assert_eq!(map.convert("port"), None);
+        }
+
+        #[test]
+        fn test_key_map_keeps_first_duplicate() {
+            let map = KeyMap::new(["api_key", "apiKey", "port"], Case::Kebab);
+            assert_eq!(map.len(), 2);
+            assert_eq!(map.original("api-key"), Some("api_key"));
+            assert_eq!(
+                map.iter().collect::<Vec<_>>(),
+                vec![("api_key", "api-key"), ("port", "port")]
+            );
+        }
+
+        #[test]
+        fn test_key_map_empty() {
+            let map = KeyMap::new(Vec::<&str>::new(), Case::Kebab);
+            assert!(map.is_empty());
+            assert_eq!(map.convert("anything"), None);
+        }
+    }
+
+    mod word_splitting {
+        use super::*;
+
+        fn words(s: &str) -> Vec<&str> {
+            split_words(s).collect()
+        }
+
+        #[test]
+        fn test_split_words_with_options() {
+            let options = CaseOptions::new().acronyms(["OAuth"]);
+            let words: Vec<&str> = split_words_with("myOAuthToken", &options).collect();
+            assert_eq!(words, vec!["my", "OAuth", "Token"]);
+        }
+
+        #[test]
+        fn test_split_words_separators() {
+            assert_eq!(words("api-key"), vec!["api", "key"]);
+            assert_eq!(words("__api__key__"), vec!["api", "key"]);
+            assert_eq!(words("my variable_name"), vec!["my", "variable", "name"]);
+            assert!(words("").is_empty());
+            assert!(words("-_ ").is_empty());
+        }
+
+        #[test]
+        fn test_split_words_case_boundaries() {
+            assert_eq!(words("apiKey"), vec!["api", "Key"]);
+            assert_eq!(words("HTTPServer"), vec!["HTTP", "Server"]);
+            assert_eq!(words("API_KEY"), vec!["API", "KEY"]);
+            assert_eq!(words("PORT"), vec!["PORT"]);
+        }
+
+        #[test]
+        fn test_split_words_digits() {
+            assert_eq!(words("v2ApiKey"), vec!["v2", "Api", "Key"]);
+            assert_eq!(words("ipv6Address"), vec!["ipv6", "Address"]);
+            assert_eq!(words("IPV6Address"), vec!["IPV6", "Address"]);
+            assert_eq!(words("V2_API_KEY"), vec!["V2", "API", "KEY"]);
+            assert_eq!(words("V2API"), vec!["V2API"]);
+            assert_eq!(words("HTTP2Server"), vec!["HTTP2", "Server"]);
+            assert_eq!(words("oauth2"), vec!["oauth2"]);
+            assert_eq!(words("404Page"), vec!["404", "Page"]);
+        }
+    }
+}
@@ -0,0 +1,3257 @@
+//! Configuration from environment variables, `.lenv`/`.env` files and CLI
+//! arguments. Requires the `std` feature.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+use crate::keys::{KeyBuf, KeyTable};
+use crate::secret::mask;
+use crate::trace;
+use crate::{
+    canonical_key, convert_all, convert_case, Case, Issue, Location, Schema, Secret, Severity,
+    Source,
+};
+use clap::Parser;
+use lino_env::read_lino_env;
+
+/// The current time, or the Unix epoch on `wasm32-unknown-unknown`, which
+/// has no clock and panics when asked for one.
+fn now() -> SystemTime {
+    if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        SystemTime::UNIX_EPOCH
+    } else {
+        SystemTime::now()
+    }
+}
+
+// ============================================================================
+// Error Types
+// ============================================================================
+
+/// Errors that can occur during configuration
+///
+/// New variants may be added in minor releases, so matches need a wildcard
+/// arm.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum ConfigError {
+    #[error("Environment variable error: {0}")]
+    EnvError(String),
+
+    #[error("Parse error: {0}")]
+    ParseError(String),
+
+    #[error("Configuration file error: {0}")]
+    FileError(String),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid configuration:{}", format_issues(.0))]
+    Invalid(Vec<Issue>),
+
+    #[error("Keyring error: {0}")]
+    KeyringError(String),
+
+    #[error("Source error: {0}")]
+    SourceError(String),
+
+    #[error("Argument error: {}", format_argument_error(.0))]
+    ArgumentError(Arc<clap::Error>),
+}
+
+/// I/O errors are cloned as a new error of the same kind and message.
+impl Clone for ConfigError {
+    fn clone(&self) -> Self {
+        match self {
+            Self::EnvError(message) => Self::EnvError(message.clone()),
+            Self::ParseError(message) => Self::ParseError(message.clone()),
+            Self::FileError(message) => Self::FileError(message.clone()),
+            Self::IoError(err) => Self::IoError(std::io::Error::new(err.kind(), err.to_string())),
+            Self::Invalid(issues) => Self::Invalid(issues.clone()),
+            Self::KeyringError(message) => Self::KeyringError(message.clone()),
+            Self::SourceError(message) => Self::SourceError(message.clone()),
+            Self::ArgumentError(err) => Self::ArgumentError(Arc::clone(err)),
+        }
+    }
+}
+
+/// The message of a command line error without clap's `error: ` prefix.
+fn format_argument_error(err: &clap::Error) -> String {
+    let message = err.to_string();
+    let message = message.trim_end();
+    message
+        .strip_prefix("error: ")
+        .unwrap_or(message)
+        .to_string()
+}
+
+/// Put a single issue on the error line and list several below it.
+fn format_issues(issues: &[Issue]) -> String {
+    match issues {
+        [issue] => format!(" {issue}"),
+        _ => issues
+            .iter()
+            .map(|issue| format!("\n  - {issue}"))
+            .collect(),
+    }
+}
+
+// ============================================================================
+// Auto-initialization via ctor
+// ============================================================================
+
+/// Automatically load `.lenv` and `.env` files at program startup.
+///
+/// This runs before `main()`, so by the time `Args::parse()` is called,
+/// all values from `.lenv` and `.env` are already in the process environment.
+/// This is what makes the drop-in replacement work: just change the import
+/// from `use clap::Parser` to `use lino_arguments::Parser` and everything
+/// else stays the same.
+#[ctor::ctor]
+fn auto_init() {
+    init();
+}
+
+// ============================================================================
+// init() — Load .lenv and .env files into the process environment
+// ============================================================================
+
+/// Load `.lenv` and `.env` files into the process environment.
+///
+/// Loads `.lenv` first (higher priority), then `.env` (lower priority).
+/// Neither overwrites existing environment variables.
+///
+/// This is called automatically at program startup. You only need to call
+/// it manually if you want to reload files after the program has started,
+/// or if you're using [`init_with()`] with custom paths.
+pub fn init() {
+    load_lenv_file(".lenv").ok();
+    load_env_file(".env").ok();
+}
+
+/// Load specified `.lenv` and `.env` files into the process environment.
+///
+/// Like [`init()`], but with custom file paths.
+///
+/// ```rust,ignore
+/// lino_arguments::init_with(Some("config/app.lenv"), Some(".env.local"));
+/// let args = Args::parse();
+/// ```
+pub fn init_with(lenv_path: Option<&str>, env_path: Option<&str>) {
+    if let Some(path) = lenv_path {
+        load_lenv_file(path).ok();
+    }
+    if let Some(path) = env_path {
+        load_env_file(path).ok();
+    }
+}
+
+// ============================================================================
+// LinoParser Trait — convenience extension for custom file paths
+// ============================================================================
+
+/// Extension trait for `clap::Parser` that provides methods for parsing
+/// with custom `.lenv`/`.env` file paths.
+///
+/// Automatically implemented for any type that derives `Parser`.
+///
+/// For standard usage, you don't need this trait at all — just use
+/// `Args::parse()` directly and `.lenv`/`.env` files are loaded
+/// automatically at startup.
+///
+/// Use `LinoParser` methods only when you need custom file paths:
+///
+/// ```rust,ignore
+/// use lino_arguments::{Parser, LinoParser};
+///
+/// #[derive(Parser, Debug)]
+/// struct Args {
+///     #[arg(long, env = "PORT", default_value = "3000")]
+///     port: u16,
+/// }
+///
+/// // Standard usage — just parse(), .lenv/.env already loaded:
+/// let args = Args::parse();
+///
+/// // Custom file paths:
+/// let args = Args::lino_parse_from_with(
+///     ["app"], Some("custom.lenv"), Some("custom.env")
+/// );
+/// ```
+pub trait LinoParser: Parser {
+    /// Parse CLI arguments after loading `.lenv` and `.env` files.
+    /// Equivalent to `Args::parse()` (since auto-init already loads files).
+    fn lino_parse() -> Self {
+        init();
+        <Self as Parser>::parse()
+    }
+
+    /// Parse CLI arguments after loading specified `.lenv` and `.env` files.
+    fn lino_parse_with(lenv_path: Option<&str>, env_path: Option<&str>) -> Self {
+        init_with(lenv_path, env_path);
+        <Self as Parser>::parse()
+    }
+
+    /// Parse from custom arguments after loading `.lenv` and `.env` files.
+    /// Useful for testing.
+    fn lino_parse_from<I, T>(args: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        init();
+        <Self as Parser>::parse_from(args)
+    }
+
+    /// Parse from custom arguments after loading specified config files.
+    /// Useful for testing.
+    fn lino_parse_from_with<I, T>(args: I, lenv_path: Option<&str>, env_path: Option<&str>) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        init_with(lenv_path, env_path);
+        <Self as Parser>::parse_from(args)
+    }
+}
+
+/// Blanket implementation: any type that derives `clap::Parser`
+/// automatically gets `LinoParser` methods.
+impl<T: Parser> LinoParser for T {}
+
+// ============================================================================
+// .lenv File Loading
+// ============================================================================
+
+/// Load environment variables from a `.lenv` file.
+///
+/// This function reads a `.lenv` configuration file and sets the values
+/// as environment variables. If the file doesn't exist, this function
+/// returns Ok without setting any variables.
+///
+/// Note: Existing environment variables are NOT overwritten. This follows
+/// the principle that environment variables have higher priority than
+/// configuration files.
+///
+/// # Arguments
+///
+/// * `file_path` - Path to the `.lenv` file
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use lino_arguments::load_lenv_file;
+///
+/// // Load from default .lenv file
+/// load_lenv_file(".lenv").ok();
+///
+/// // Load from custom path
+/// load_lenv_file("config/production.lenv")?;
+/// ```
+pub fn load_lenv_file(file_path: &str) -> Result<usize, ConfigError> {
+    apply_lenv_file(
+        file_path,
+        false,
+        &GetenvOptions::default(),
+        &mut EnvLayer::default(),
+    )
+    .map(|keys| keys.len())
+}
+
+/// Load environment variables from a `.lenv` file, overwriting existing values.
+///
+/// Unlike `load_lenv_file`, this function will overwrite any existing
+/// environment variables with the values from the file.
+///
+/// # Arguments
+///
+/// * `file_path` - Path to the `.lenv` file
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use lino_arguments::load_lenv_file_override;
+///
+/// // Force load values, overwriting any existing env vars
+/// load_lenv_file_override("config/override.lenv")?;
+/// ```
+pub fn load_lenv_file_override(file_path: &str) -> Result<usize, ConfigError> {
+    apply_lenv_file(
+        file_path,
+        true,
+        &GetenvOptions::default(),
+        &mut EnvLayer::default(),
+    )
+    .map(|keys| keys.len())
+}
+
+/// Load a `.lenv` file into `layer` and return the names of the variables
+/// that were actually set.
+fn apply_lenv_file(
+    file_path: &str,
+    overwrite: bool,
+    options: &GetenvOptions,
+    layer: &mut EnvLayer,
+) -> Result<Vec<String>, ConfigError> {
+    let lenv = read_lino_env(file_path)?;
+    let mut loaded = Vec::new();
+
+    for key in lenv.keys() {
+        // Only set if not already present in environment (unless overriding)
+        if overwrite || !layer.is_set(&key, options) {
+            if let Some(value) = lenv.get(&key) {
+                layer.set(&key, value);
+                loaded.push(key);
+            }
+        }
+    }
+
+    Ok(loaded)
+}
+
+// ============================================================================
+// .env File Loading (standard dotenv format, for compatibility)
+// ============================================================================
+
+/// Load environment variables from a `.env` file (standard `KEY=VALUE` format).
+///
+/// Uses the [dotenvy](https://docs.rs/dotenvy) crate under the hood.
+/// Existing environment variables are NOT overwritten.
+///
+/// # Arguments
+///
+/// * `file_path` - Path to the `.env` file
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use lino_arguments::load_env_file;
+///
+/// // Load from default .env file
+/// load_env_file(".env").ok();
+/// ```
+pub fn load_env_file(file_path: &str) -> Result<usize, ConfigError> {
+    apply_env_file(
+        file_path,
+        false,
+        &GetenvOptions::default(),
+        &mut EnvLayer::default(),
+    )
+    .map(|keys| keys.len())
+}
+
+/// Load environment variables from a `.env` file, overwriting existing values.
+///
+/// # Arguments
+///
+/// * `file_path` - Path to the `.env` file
+pub fn load_env_file_override(file_path: &str) -> Result<usize, ConfigError> {
+    apply_env_file(
+        file_path,
+        true,
+        &GetenvOptions::default(),
+        &mut EnvLayer::default(),
+    )
+    .map(|keys| keys.len())
+}
+
+/// Load a `.env` file into `layer` and return the names of the variables
+/// that were actually set.
+fn apply_env_file(
+    file_path: &str,
+    overwrite: bool,
+    options: &GetenvOptions,
+    layer: &mut EnvLayer,
+) -> Result<Vec<String>, ConfigError> {
+    let path = std::path::Path::new(file_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let iter = dotenvy::from_path_iter(path)
+        .map_err(|e| ConfigError::FileError(format!("Failed to read {}: {}", file_path, e)))?;
+
+    let mut loaded = Vec::new();
+    for item in iter {
+        match item {
+            Ok((key, value)) => {
+                // Only set if not already present in environment (unless overriding)
+                if overwrite || !layer.is_set(&key, options) {
+                    layer.set(&key, value);
+                    loaded.push(key);
+                }
+            }
+            Err(_) => continue,
+        }
+    }
+
+    Ok(loaded)
+}
+
+// ============================================================================
+// Config File Entries (for validation)
+// ============================================================================
+
+/// A variable defined in a config file, with where its value was written.
+pub(crate) struct FileEntry {
+    pub(crate) key: String,
+    pub(crate) value: String,
+    /// 1-based line number.
+    pub(crate) line: usize,
+    /// 1-based column, in characters, of the first character of the value.
+    pub(crate) column: usize,
+}
+
+impl FileEntry {
+    /// Where the value was written, in `file`.
+    pub(crate) fn location(&self, file: &str) -> Location {
+        Location {
+            file: file.to_string(),
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+/// `entries` by canonical key, so that a key is found in any case with one
+/// lookup. The first entry of a key in file order is kept.
+pub(crate) fn index_entries(entries: &[FileEntry]) -> HashMap<String, &FileEntry> {
+    let mut index = HashMap::with_capacity(entries.len());
+    for entry in entries {
+        index
+            .entry(canonical_key(&entry.key).into_owned())
+            .or_insert(entry);
+    }
+    index
+}
+
+/// Read the effective entries of a `.lenv` file, following the same rules
+/// as lino-env: `KEY: value` lines, `#` comments, and the last definition of
+/// a key wins. A missing file has no entries.
+pub(crate) fn read_lenv_entries(file_path: &str) -> Result<Vec<FileEntry>, ConfigError> {
+    let path = std::path::Path::new(file_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(parse_lenv_entries(&std::fs::read_to_string(path)?))
+}
+
+/// Parse the text of a `.lenv` file into its `KEY: value` pairs, in file
+/// order, following the same rules as lino-env: `#` comments and blank
+/// lines are skipped, and the last definition of a key wins.
+///
+/// # Example
+///
+/// ```
+/// use lino_arguments::parse_lenv;
+///
+/// let pairs = parse_lenv("# server\nPORT: 3000\nHOST: localhost\nPORT: 8080\n");
+/// assert_eq!(
+///     pairs,
+///     [
+///         ("HOST".to_string(), "localhost".to_string()),
+///         ("PORT".to_string(), "8080".to_string()),
+///     ]
+/// );
+/// ```
+pub fn parse_lenv(text: &str) -> Vec<(String, String)> {
+    parse_lenv_entries(text)
+        .into_iter()
+        .map(|entry| (entry.key, entry.value))
+        .collect()
+}
+
+/// The effective entries of the `.lenv` `text`, as in [`read_lenv_entries()`].
+pub(crate) fn parse_lenv_entries(text: &str) -> Vec<FileEntry> {
+    let mut entries: Vec<FileEntry> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(": ") {
+            let entry = FileEntry {
+                key: key.trim().to_string(),
+                value: value.to_string(),
+                line: index + 1,
+                column: key.chars().count() + 3,
+            };
+            entries.retain(|existing| existing.key != entry.key);
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+/// The values of the secrets tier by canonical key, with where they were
+/// read from.
+type Secrets = HashMap<String, (String, ValueSource)>;
+
+/// What a [`Source`] reported about the values it set.
+#[derive(Debug, Clone)]
+struct Origin {
+    /// The revision of the values, see [`Source::revision()`].
+    revision: Option<String>,
+    /// Whether the values are a cached copy, see [`Source::is_stale()`].
+    stale: bool,
+}
+
+/// Where Docker mounts the secrets of a service.
+const DOCKER_SECRETS_DIR: &str = "/run/secrets";
+
+/// The variable systemd points at the credentials of a service.
+const CREDENTIALS_DIRECTORY: &str = "CREDENTIALS_DIRECTORY";
+
+/// The values of the files in a secrets or ConfigMap directory, by the
+/// canonical form of their file names, without a trailing newline. Hidden
+/// files, such as the `..data` links of Kubernetes volumes, and files that
+/// are not UTF-8 are skipped. A missing directory has no values.
+pub(crate) fn read_secrets_dir(dir: &str) -> HashMap<String, String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            if name.starts_with('.') || !path.is_file() {
+                return None;
+            }
+            let value = read_secret_file(&path).ok()?;
+            Some((canonical_key(name).into_owned(), value))
+        })
+        .collect()
+}
+
+/// The contents of a file holding a single value, without a trailing
+/// newline.
+pub(crate) fn read_secret_file(path: impl AsRef<std::path::Path>) -> std::io::Result<String> {
+    let mut value = std::fs::read_to_string(path)?;
+    if value.ends_with('\n') {
+        value.pop();
+        if value.ends_with('\r') {
+            value.pop();
+        }
+    }
+    Ok(value)
+}
+
+/// The suffix of the variable naming the file a value is read from.
+const FILE_VAR_SUFFIX: &str = "_FILE";
+
+/// Read the effective entries of a `.env` file. Values are parsed by
+/// dotenvy; each entry points at the last line assigning its key. A missing
+/// file has no entries.
+pub(crate) fn read_env_entries(file_path: &str) -> Result<Vec<FileEntry>, ConfigError> {
+    let path = std::path::Path::new(file_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = text.lines().collect();
+    // The line and column of the value of the last assignment to `key`.
+    let assigned_on = |key: &str| {
+        lines.iter().enumerate().rev().find_map(|(index, line)| {
+            let rest = line.trim_start();
+            let rest = rest.strip_prefix("export ").unwrap_or(rest).trim_start();
+            let value = rest.strip_prefix(key)?.trim_start().strip_prefix('=')?;
+            let column = line.chars().count() - value.trim_start().chars().count() + 1;
+            Some((index + 1, column))
+        })
+    };
+
+    let iter = dotenvy::from_path_iter(path)
+        .map_err(|e| ConfigError::FileError(format!("Failed to read {}: {}", file_path, e)))?;
+    let mut entries: Vec<FileEntry> = Vec::new();
+    for (key, value) in iter.flatten() {
+        let (line, column) = assigned_on(&key).unwrap_or((0, 0));
+        entries.retain(|existing| existing.key != key);
+        entries.push(FileEntry {
+            key,
+            value,
+            line,
+            column,
+        });
+    }
+    Ok(entries)
+}
+
+// ============================================================================
+// Environment Variable Helper
+// ============================================================================
+
+/// How a variable that is set to the empty string (`PORT=""`) is treated.
+///
+/// CI systems frequently export empty strings for unset secrets and inputs.
+/// With [`EmptyValues::Unset`] such values no longer shadow lower priority
+/// sources and defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyValues {
+    /// An empty string is a value like any other (the default).
+    #[default]
+    Keep,
+    /// An empty string counts as unset and resolution falls through to the
+    /// next case variant, lower priority source, or default.
+    Unset,
+}
+
+/// Options for the `getenv*_with` family of functions.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{EmptyValues, GetenvOptions};
+///
+/// let options = GetenvOptions::new().empty_values(EmptyValues::Unset);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GetenvOptions {
+    empty: EmptyValues,
+    strict_numbers: bool,
+    normalize: bool,
+    extra_cases: Vec<Case>,
+}
+
+impl GetenvOptions {
+    /// Create options with the default behavior of [`getenv()`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how variables that are set to an empty string are treated.
+    pub fn empty_values(mut self, policy: EmptyValues) -> Self {
+        self.empty = policy;
+        self
+    }
+
+    /// Only accept plain numbers (`1000`, `-5`, `2.5`) in the numeric getters,
+    /// rejecting digit separators (`1_000`, `1,000`) and a leading `+`.
+    pub fn strict_numbers(mut self, strict: bool) -> Self {
+        self.strict_numbers = strict;
+        self
+    }
+
+    /// Trim surrounding whitespace and matching quotes from values before
+    /// they are returned or parsed (see [`normalize_value()`]).
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Also look the key up in `case` after the built-in variants
+    /// (the key as given, `UPPER_CASE`, camelCase, kebab-case, snake_case
+    /// and PascalCase).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::{getenv_with, Case, GetenvOptions};
+    ///
+    /// std::env::set_var("DOC-BUILD-DIR", "out");
+    /// let options = GetenvOptions::new().lookup_case(Case::ScreamingKebab);
+    /// assert_eq!(getenv_with("docBuildDir", "build", &options), "out");
+    /// ```
+    pub fn lookup_case(mut self, case: Case) -> Self {
+        self.extra_cases.push(case);
+        self
+    }
+
+    /// Apply normalization and the empty-value policy to a raw value.
+    /// Returns `None` if the value counts as unset.
+    fn accept<'a>(&self, raw: &'a str) -> Option<&'a str> {
+        let value = if self.normalize {
+            normalize_value(raw)
+        } else {
+            raw
+        };
+        if value.is_empty() && self.empty == EmptyValues::Unset {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+/// The environment a build reads, with the values of the `.lenv` and `.env`
+/// files on top.
+///
+/// By default the file values are set in the process environment, as
+/// [`load_lenv_file()`] does. A layer [kept in memory](Self::in_memory)
+/// holds them itself instead, for reloads, which run on background threads
+/// where changing the environment is unsound.
+#[derive(Debug, Default)]
+pub(crate) struct EnvLayer<'a> {
+    in_memory: bool,
+    /// File values by [`env_name_key()`], when kept in memory.
+    files: HashMap<String, String>,
+    /// Variables an earlier build set in the environment from the files,
+    /// with the values it set, which count as unset while they keep them.
+    stale: &'a [(String, String)],
+}
+
+impl<'a> EnvLayer<'a> {
+    /// A layer that keeps the file values in memory and ignores the `stale`
+    /// variables an earlier build set from the files.
+    pub(crate) fn in_memory(stale: &'a [(String, String)]) -> Self {
+        Self {
+            in_memory: true,
+            files: HashMap::new(),
+            stale,
+        }
+    }
+
+    /// The value of the variable `name`.
+    fn var(&self, name: &str) -> Option<String> {
+        if let Some(value) = self.files.get(&env_name_key(name)) {
+            return Some(value.clone());
+        }
+        let value = env::var(name).ok()?;
+        let stale = self
+            .stale
+            .iter()
+            .any(|(stale, old)| same_env_name(stale, name) && *old == value);
+        (!stale).then_some(value)
+    }
+
+    /// Whether the variable `name` is set, following the empty-value policy.
+    fn is_set(&self, name: &str, options: &GetenvOptions) -> bool {
+        self.var(name)
+            .is_some_and(|value| options.accept(&value).is_some())
+    }
+
+    fn set(&mut self, name: &str, value: String) {
+        if self.in_memory {
+            self.files.insert(env_name_key(name), value);
+        } else {
+            env::set_var(name, value);
+        }
+    }
+}
+
+/// Trim surrounding whitespace and one pair of matching surrounding quotes.
+///
+/// Values exported by CI YAML frequently carry stray quotes or spaces.
+/// Whitespace inside the quotes is trimmed as well.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::normalize_value;
+///
+/// assert_eq!(normalize_value(r#"" 8080 ""#), "8080");
+/// assert_eq!(normalize_value("  'text'  "), "text");
+/// assert_eq!(normalize_value(r#""mismatched'"#), r#""mismatched'"#);
+/// ```
+pub fn normalize_value(value: &str) -> &str {
+    let trimmed = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = trimmed
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner.trim();
+        }
+    }
+    trimmed
+}
+
+/// Get environment variable with default value and case conversion.
+/// Tries multiple case formats to find the variable.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::getenv;
+///
+/// // Try to get API_KEY, apiKey, api-key, etc.
+/// let api_key = getenv("apiKey", "default-key");
+/// let port = getenv("PORT", "3000");
+/// ```
+///
+/// On Windows, where environment variable names are case-insensitive,
+/// variants that differ only by letter case are looked up once.
+///
+/// Each call reads the environment once per variant; to look up many keys,
+/// take an [`EnvSnapshot`] instead.
+pub fn getenv(key: &str, default: &str) -> String {
+    getenv_with(key, default, &GetenvOptions::default())
+}
+
+/// Get environment variable with default value, case conversion, and options.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{getenv_with, EmptyValues, GetenvOptions};
+///
+/// std::env::set_var("DOC_EMPTY_PORT", "");
+/// let options = GetenvOptions::new().empty_values(EmptyValues::Unset);
+/// assert_eq!(getenv_with("DOC_EMPTY_PORT", "3000", &options), "3000");
+/// ```
+pub fn getenv_with(key: &str, default: &str, options: &GetenvOptions) -> String {
+    lookup_env(key, options).unwrap_or_else(|| {
+        trace::fallback(key, &default);
+        default.to_string()
+    })
+}
+
+/// The value of the first case variant of `key` set in the environment and
+/// accepted by `options`.
+pub(crate) fn lookup_env(key: &str, options: &GetenvOptions) -> Option<String> {
+    for variant in env_lookup_names(key, options).iter() {
+        if let Ok(raw) = env::var(variant) {
+            if let Some(value) = options.accept(&raw) {
+                trace::env_lookup(key, Some(variant));
+                return Some(value.to_string());
+            }
+        }
+    }
+    trace::env_lookup(key, None);
+    None
+}
+
+/// A snapshot of the environment indexed by canonical key, so that looking
+/// a key up in every case [`getenv()`] tries is a single hash lookup
+/// instead of one `env::var` call per variant.
+///
+/// Names are canonicalized once, when the snapshot is taken, and later
+/// changes to the environment are not seen. Lookups try the variants in the
+/// order of [`getenv()`] and follow the [`GetenvOptions`] given.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::EnvSnapshot;
+///
+/// std::env::set_var("DOC_SNAPSHOT_PORT", "8080");
+/// let env = EnvSnapshot::capture();
+/// assert_eq!(env.getenv("docSnapshotPort", "3000"), "8080");
+/// assert_eq!(env.getenv("doc-snapshot-host", "localhost"), "localhost");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EnvSnapshot {
+    vars: HashMap<String, Vec<SnapshotVar>>,
+}
+
+/// A variable of an [`EnvSnapshot`], with the [`LOOKUP_CASES`] its name is
+/// spelled in as bits, in their order.
+#[derive(Debug, Clone)]
+struct SnapshotVar {
+    name: String,
+    value: String,
+    cases: u8,
+}
+
+impl EnvSnapshot {
+    /// Take a snapshot of the environment of the process. Variables whose
+    /// name or value is not valid Unicode are left out, as `env::var` would
+    /// not read them.
+    pub fn capture() -> Self {
+        Self::from_vars(env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        }))
+    }
+
+    /// A snapshot of the variables `vars` instead of the environment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::EnvSnapshot;
+    ///
+    /// let env = EnvSnapshot::from_vars([("apiKey", "a"), ("API_KEY", "b")]);
+    /// assert_eq!(env.getenv("api-key", ""), "b");
+    /// ```
+    pub fn from_vars<K, V>(vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut snapshot = Self::default();
+        for (name, value) in vars {
+            let name = name.into();
+            let cases = LOOKUP_CASES
+                .iter()
+                .enumerate()
+                .filter(|&(_, &case)| spelled_in(&name, case))
+                .fold(0, |cases, (index, _)| cases | 1 << index);
+            let key = canonical_key(&name).into_owned();
+            let var = SnapshotVar {
+                name,
+                value: value.into(),
+                cases,
+            };
+            let vars = snapshot.vars.entry(key).or_default();
+            // On Windows, a later variable replaces one differing only by letter case
+            vars.retain(|other| !same_env_name(&other.name, &var.name));
+            vars.push(var);
+        }
+        snapshot
+    }
+
+    /// The value of `key` in the snapshot, like [`getenv()`].
+    pub fn getenv(&self, key: &str, default: &str) -> String {
+        self.getenv_with(key, default, &GetenvOptions::default())
+    }
+
+    /// The value of `key` in the snapshot, like [`getenv_with()`].
+    pub fn getenv_with(&self, key: &str, default: &str, options: &GetenvOptions) -> String {
+        match self.lookup(key, options) {
+            Some(value) => value.to_string(),
+            None => {
+                trace::fallback(key, &default);
+                default.to_string()
+            }
+        }
+    }
+
+    /// The value of the first case variant of `key` in the snapshot that
+    /// `options` accept.
+    pub(crate) fn lookup(&self, key: &str, options: &GetenvOptions) -> Option<&str> {
+        // A variable of the same canonical key is the variant of `key` in a
+        // case exactly when its name is spelled in that case
+        let rank = |var: &SnapshotVar| match var.cases {
+            _ if same_env_name(&var.name, key) => Some(0),
+            0 => None,
+            cases => Some(cases.trailing_zeros() + 1),
+        };
+        let found = self
+            .vars
+            .get(&*KeyBuf::convert(key, Case::Kebab))
+            .into_iter()
+            .flatten()
+            .filter_map(|var| Some((rank(var)?, var, options.accept(&var.value)?)))
+            .min_by_key(|&(rank, _, _)| rank)
+            .map(|(_, var, value)| (var, value));
+        // A variant may split into other words than `key` (`S3BUCKET` is
+        // `s3-bucket` but `s3bucket` is one word), and extra cases may lose
+        // word boundaries, so on a miss the variants are looked up by name
+        let found = found.or_else(|| {
+            let mut canonical = KeyBuf::new();
+            env_lookup_names(key, options).iter().find_map(|variant| {
+                canonical.write(variant, Case::Kebab);
+                let vars = self.vars.get(&*canonical)?;
+                let var = vars.iter().find(|var| same_env_name(&var.name, variant))?;
+                Some((var, options.accept(&var.value)?))
+            })
+        });
+        trace::env_lookup(key, found.map(|(var, _)| var.name.as_str()));
+        found.map(|(_, value)| value)
+    }
+}
+
+/// Whether the platform treats environment variable names case-insensitively.
+const CASE_INSENSITIVE_ENV: bool = cfg!(windows);
+
+/// The cases [`getenv()`] tries, in order, after the key as given.
+const LOOKUP_CASES: [Case; 5] = [
+    Case::UpperSnake,
+    Case::Camel,
+    Case::Kebab,
+    Case::Snake,
+    Case::Pascal,
+];
+
+/// Whether `a` and `b` name the same environment variable on this platform.
+fn same_env_name(a: &str, b: &str) -> bool {
+    if CASE_INSENSITIVE_ENV {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// Whether the variable `name` is spelled in `case`, following the
+/// platform's case sensitivity.
+fn spelled_in(name: &str, case: Case) -> bool {
+    same_env_name(&KeyBuf::convert(name, case), name)
+}
+
+/// Names to try, in order, when looking up `key` in the environment.
+fn env_lookup_names<'a>(key: &'a str, options: &GetenvOptions) -> LookupNames<'a> {
+    lookup_names(key, &options.extra_cases, CASE_INSENSITIVE_ENV)
+}
+
+/// Case variants of `key` followed by its conversions to `extra_cases`,
+/// without duplicates. When `case_insensitive` is set, variants that differ
+/// only by letter case count as duplicates.
+fn lookup_names<'a>(key: &'a str, extra_cases: &[Case], case_insensitive: bool) -> LookupNames<'a> {
+    let mut names = LookupNames {
+        key,
+        variants: std::array::from_fn(|_| KeyBuf::new()),
+        len: 0,
+        extra: Vec::new(),
+    };
+    let mut variant = KeyBuf::new();
+    for &case in LOOKUP_CASES.iter().chain(extra_cases) {
+        variant.write(key, case);
+        let seen = names.iter().any(|name| {
+            if case_insensitive {
+                name.eq_ignore_ascii_case(&variant)
+            } else {
+                name == &*variant
+            }
+        });
+        if seen {
+            continue;
+        }
+        let variant = std::mem::replace(&mut variant, KeyBuf::new());
+        match names.variants.get_mut(names.len) {
+            Some(slot) => {
+                *slot = variant;
+                names.len += 1;
+            }
+            None => names.extra.push(variant),
+        }
+    }
+    names
+}
+
+/// The names [`lookup_names()`] found: the key, then the variants of the
+/// [`LOOKUP_CASES`] converted on the stack, then those of extra cases.
+struct LookupNames<'a> {
+    key: &'a str,
+    variants: [KeyBuf; LOOKUP_CASES.len()],
+    len: usize,
+    extra: Vec<KeyBuf>,
+}
+
+impl LookupNames<'_> {
+    fn iter(&self) -> impl Iterator<Item = &str> {
+        let variants = self.variants[..self.len].iter().chain(&self.extra);
+        std::iter::once(self.key).chain(variants.map(|variant| &**variant))
+    }
+}
+
+/// Normalize an environment variable name for comparisons, following the
+/// platform's case sensitivity.
+fn env_name_key(name: &str) -> String {
+    if CASE_INSENSITIVE_ENV {
+        name.to_ascii_uppercase()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Get environment variable as integer with default value.
+/// Tries multiple case formats to find the variable.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::getenv_int;
+///
+/// let port = getenv_int("PORT", 3000);
+/// ```
+///
+/// Digit separators (`1_000`, `1,000`) and a leading `+` are accepted.
+/// Parsing never depends on the process locale.
+pub fn getenv_int(key: &str, default: i64) -> i64 {
+    getenv_int_with(key, default, &GetenvOptions::default())
+}
+
+/// Get environment variable as integer with default value and options.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{getenv_int_with, GetenvOptions};
+///
+/// std::env::set_var("DOC_STRICT_LIMIT", "1,000");
+/// let strict = GetenvOptions::new().strict_numbers(true);
+/// assert_eq!(getenv_int_with("DOC_STRICT_LIMIT", 10, &GetenvOptions::new()), 1000);
+/// assert_eq!(getenv_int_with("DOC_STRICT_LIMIT", 10, &strict), 10);
+/// ```
+pub fn getenv_int_with(key: &str, default: i64, options: &GetenvOptions) -> i64 {
+    let Some(value) = lookup_env(key, options).filter(|value| !value.is_empty()) else {
+        trace::fallback(key, &default);
+        return default;
+    };
+    parse_int(&value, options.strict_numbers).unwrap_or_else(|| {
+        trace::parse_failed(key, "integer", &default);
+        default
+    })
+}
+
+/// Get environment variable as a floating point number with default value.
+/// Tries multiple case formats to find the variable.
+///
+/// Digit separators before the decimal point (`1_000.5`, `1,000.5`) and a
+/// leading `+` are accepted. The decimal separator is always `.`,
+/// regardless of the process locale.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::getenv_float;
+///
+/// let ratio = getenv_float("RATIO", 0.5);
+/// ```
+pub fn getenv_float(key: &str, default: f64) -> f64 {
+    getenv_float_with(key, default, &GetenvOptions::default())
+}
+
+/// Get environment variable as a floating point number with default value and options.
+pub fn getenv_float_with(key: &str, default: f64, options: &GetenvOptions) -> f64 {
+    let Some(value) = lookup_env(key, options).filter(|value| !value.is_empty()) else {
+        trace::fallback(key, &default);
+        return default;
+    };
+    parse_float(&value, options.strict_numbers).unwrap_or_else(|| {
+        trace::parse_failed(key, "number", &default);
+        default
+    })
+}
+
+/// Parse an integer, accepting digit separators and a leading `+` unless strict.
+pub(crate) fn parse_int(value: &str, strict: bool) -> Option<i64> {
+    if strict {
+        return if value.starts_with('+') {
+            None
+        } else {
+            value.parse().ok()
+        };
+    }
+    strip_digit_separators(value)?.parse().ok()
+}
+
+/// Parse a float, accepting digit separators and a leading `+` unless strict.
+pub(crate) fn parse_float(value: &str, strict: bool) -> Option<f64> {
+    if strict {
+        return if value.starts_with('+') {
+            None
+        } else {
+            value.parse().ok()
+        };
+    }
+    strip_digit_separators(value)?.parse().ok()
+}
+
+/// Remove `_` and `,` digit separators from a number.
+///
+/// `_` may appear anywhere between two digits of the integer part. `,` is
+/// only accepted as a thousands separator (followed by exactly three
+/// digits), so that a decimal comma such as `1,5` is rejected instead of
+/// silently read as `15`. Neither is accepted in the fraction or the
+/// exponent, so `1.234,567` is rejected rather than read as `1.234567`.
+fn strip_digit_separators(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut result = String::with_capacity(value.len());
+    let mut integer_part = true;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            '_' | ',' => {
+                let after_digit = i > 0 && bytes[i - 1].is_ascii_digit();
+                let group = bytes[i + 1..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+                if !integer_part || !after_digit || group == 0 || (c == ',' && group != 3) {
+                    return None;
+                }
+            }
+            '.' | 'e' | 'E' => {
+                integer_part = false;
+                result.push(c);
+            }
+            _ => result.push(c),
+        }
+    }
+
+    Some(result)
+}
+
+/// Get environment variable as boolean with default value.
+/// Tries multiple case formats to find the variable.
+/// Accepts: "true", "false", "1", "0", "yes", "no" (case-insensitive)
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::getenv_bool;
+///
+/// let debug = getenv_bool("DEBUG", false);
+/// ```
+pub fn getenv_bool(key: &str, default: bool) -> bool {
+    let options = GetenvOptions::default();
+    let Some(value) = lookup_env(key, &options).filter(|value| !value.is_empty()) else {
+        trace::fallback(key, &default);
+        return default;
+    };
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => true,
+        "false" | "0" | "no" | "off" => false,
+        _ => {
+            trace::parse_failed(key, "boolean", &default);
+            default
+        }
+    }
+}
+
+// ============================================================================
+// Value Sources and Access Audit
+// ============================================================================
+
+/// The layer of the priority chain a configuration value was resolved from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValueSource {
+    /// Passed on the command line.
+    Cli,
+    /// Read from the process environment.
+    Environment,
+    /// Loaded from a `.lenv` file configured on the builder.
+    LenvFile,
+    /// Loaded from a `.env` file configured on the builder.
+    EnvFile,
+    /// Read from a file in a secrets directory, such as Docker's
+    /// `/run/secrets`.
+    SecretsDir,
+    /// Read from the file named by a `<NAME>_FILE` environment variable;
+    /// see [`ConfigBuilder::file_vars()`].
+    FileVar,
+    /// Read from the platform keychain. Requires the `keyring` feature.
+    Keyring,
+    /// Decrypted from a sops-encrypted file configured on the builder.
+    SopsFile,
+    /// Fetched from a [`Source`](crate::Source), such as the AWS Parameter
+    /// Store, named by [`Source::name()`](crate::Source::name).
+    Remote(&'static str),
+    /// The option's default value.
+    Default,
+}
+
+impl std::fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ValueSource::Cli => "command line",
+            ValueSource::Environment => "environment",
+            ValueSource::LenvFile => ".lenv file",
+            ValueSource::EnvFile => ".env file",
+            ValueSource::SecretsDir => "secrets directory",
+            ValueSource::FileVar => "file named by a _FILE variable",
+            ValueSource::Keyring => "keyring",
+            ValueSource::SopsFile => "sops file",
+            ValueSource::Remote(name) => name,
+            ValueSource::Default => "default value",
+        })
+    }
+}
+
+/// The reads of a configuration key, as recorded by [`Config::audit()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// The key as passed to the accessor (e.g. `"api-key"`).
+    pub key: String,
+    /// Where the value came from, or `None` if the key was not found.
+    pub source: Option<ValueSource>,
+    /// When the key was first read.
+    pub first_read: SystemTime,
+    /// When the key was last read.
+    pub last_read: SystemTime,
+    /// How many times the key was read.
+    pub reads: u64,
+}
+
+/// The audit log of a [`Config`]: one record per key, in the order the keys
+/// were first read, so repeated reads neither grow it nor need the write
+/// lock.
+#[derive(Debug, Default)]
+struct AuditLog {
+    index: HashMap<String, usize>,
+    records: Vec<AuditRecord>,
+}
+
+#[derive(Debug)]
+struct AuditRecord {
+    key: String,
+    source: Option<ValueSource>,
+    first_read: SystemTime,
+    /// Nanoseconds from the first read to the last.
+    last_read: AtomicU64,
+    reads: AtomicU64,
+}
+
+impl AuditRecord {
+    fn read(&self) {
+        let since_first = now()
+            .duration_since(self.first_read)
+            .unwrap_or_default()
+            .as_nanos();
+        self.last_read.fetch_max(
+            u64::try_from(since_first).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+        self.reads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn entry(&self) -> AuditEntry {
+        AuditEntry {
+            key: self.key.clone(),
+            source: self.source,
+            first_read: self.first_read,
+            last_read: self.first_read
+                + Duration::from_nanos(self.last_read.load(Ordering::Relaxed)),
+            reads: self.reads.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Get a credential from the environment as a [`Secret`], which prints as
+/// `[REDACTED]`. Tries multiple case formats to find the variable, like
+/// [`getenv()`]. Returns `None` if no variant is set.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::getenv_secret;
+///
+/// std::env::set_var("DOC_API_TOKEN", "sk-live-1234");
+/// let token = getenv_secret("docApiToken").unwrap();
+/// assert_eq!(token.to_string(), "[REDACTED]");
+/// assert_eq!(token.expose(), "sk-live-1234");
+/// ```
+pub fn getenv_secret(key: &str) -> Option<Secret<String>> {
+    let options = GetenvOptions::default();
+    for variant in env_lookup_names(key, &options).iter() {
+        if let Ok(raw) = env::var(variant) {
+            if let Some(value) = options.accept(&raw) {
+                let secret = Secret::fetched(value.to_string());
+                #[cfg(feature = "zeroize")]
+                drop(zeroize::Zeroizing::new(raw));
+                return Some(secret);
+            }
+        }
+    }
+    None
+}
+
+/// Store `value` in the platform keychain (Secret Service, macOS Keychain,
+/// or Windows Credential Manager) as the secret for `key` of `service`,
+/// where [`ConfigBuilder::keyring()`] finds it. Requires the `keyring`
+/// feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::set_keyring_secret;
+///
+/// set_keyring_secret("my-server", "api-token", "sk-live-1234").unwrap();
+/// ```
+#[cfg(feature = "keyring")]
+pub fn set_keyring_secret(service: &str, key: &str, value: &str) -> Result<(), ConfigError> {
+    keyring_entry(service, key)?
+        .set_password(value)
+        .map_err(|err| ConfigError::KeyringError(err.to_string()))
+}
+
+/// Remove the secret for `key` of `service` from the platform keychain.
+/// Removing a secret that is not stored is not an error. Requires the
+/// `keyring` feature.
+#[cfg(feature = "keyring")]
+pub fn delete_keyring_secret(service: &str, key: &str) -> Result<(), ConfigError> {
+    match keyring_entry(service, key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(err) => Err(ConfigError::KeyringError(err.to_string())),
+    }
+}
+
+/// The keychain entry for `key`, named by its [`canonical_key()`] so that
+/// any case variant finds it.
+#[cfg(feature = "keyring")]
+fn keyring_entry(service: &str, key: &str) -> Result<keyring::Entry, ConfigError> {
+    keyring::Entry::new(service, &canonical_key(key))
+        .map_err(|err| ConfigError::KeyringError(err.to_string()))
+}
+
+// ============================================================================
+// Functional Configuration API (like JavaScript's makeConfig)
+// ============================================================================
+
+/// What a [`Config`] was resolved from besides the arguments, the
+/// environment, and the config files, for [`Config::preflight()`] to check
+/// again.
+#[derive(Clone, Default)]
+struct Inputs {
+    secrets_dirs: Vec<String>,
+    sops_paths: Vec<String>,
+    /// The `<NAME>_FILE` variables values were read through, and the files
+    /// they name.
+    file_vars: Vec<(String, String)>,
+    sources: Vec<Arc<dyn Source>>,
+}
+
+impl std::fmt::Debug for Inputs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sources: Vec<&str> = self.sources.iter().map(|source| source.name()).collect();
+        f.debug_struct("Inputs")
+            .field("secrets_dirs", &self.secrets_dirs)
+            .field("sops_paths", &self.sops_paths)
+            .field("file_vars", &self.file_vars)
+            .field("sources", &sources)
+            .finish()
+    }
+}
+
+/// Resolved configuration values from the functional API.
+///
+/// Contains all parsed configuration values accessible by key name.
+/// Values are stored as strings and can be retrieved with type conversion.
+/// Keys are indexed by [`canonical_key()`], so any case variant of a key
+/// (`api-key`, `apiKey`, `API_KEY`) finds the same value.
+///
+/// Every read through the accessors is counted in an in-memory audit log
+/// with one entry per key (see [`Config::audit()`]). Clones share the same
+/// log.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The values and their sources, by interned canonical key.
+    values: KeyTable,
+    audit: Arc<RwLock<AuditLog>>,
+    strict_numbers: bool,
+    warnings: Vec<Issue>,
+    /// The schema the configuration was built with, for [`Config::preflight()`].
+    schema: Option<Schema>,
+    /// The config files the builder was given.
+    files: Vec<(String, ValueSource)>,
+    /// The other inputs the values were resolved from.
+    inputs: Inputs,
+    /// What the sources values were resolved from reported, by key.
+    origins: HashMap<String, Origin>,
+    /// The argument or variable each value was matched by, by key.
+    variants: HashMap<String, String>,
+    /// Which resolution of a [`SharedConfig`](crate::SharedConfig) this is.
+    generation: u64,
+    resolved_at: SystemTime,
+}
+
+impl Config {
+    /// Get a configuration value as a string.
+    /// Returns empty string if the key is not found.
+    pub fn get(&self, key: &str) -> String {
+        let resolved = self.values.get(key);
+        self.record(key, resolved.map(|(_, source)| source));
+        resolved
+            .map(|(value, _)| value.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Get the layer a configuration value was resolved from.
+    /// Returns `None` if the key is not found.
+    pub fn source(&self, key: &str) -> Option<ValueSource> {
+        self.values.source(key)
+    }
+
+    /// Get the revision of the [`Source`] a configuration value was resolved
+    /// from, such as the commit of a [`GitSource`](crate::GitSource).
+    /// Returns `None` for other layers and sources without revisions.
+    pub fn revision(&self, key: &str) -> Option<&str> {
+        self.origins
+            .get(canonical_key(key).as_ref())?
+            .revision
+            .as_deref()
+    }
+
+    /// Whether a configuration value was resolved from the cached copy of a
+    /// [`Source`] that could not be reached, such as a
+    /// [`Cached`](crate::Cached) source.
+    pub fn is_stale(&self, key: &str) -> bool {
+        self.origins
+            .get(canonical_key(key).as_ref())
+            .is_some_and(|origin| origin.stale)
+    }
+
+    /// Which resolution this is: 1 for a configuration built once, counting
+    /// up with every reload of a [`SharedConfig`](crate::SharedConfig).
+    /// A request handler holding a snapshot can compare its generation with
+    /// the current one to tell that the configuration changed mid-flight,
+    /// and log the generation that served the request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lino_arguments::try_make_shared_config_from;
+    ///
+    /// let shared = try_make_shared_config_from(["app"], |c| {
+    ///     c.option("doc-generation-port", "Port", "3000")
+    /// })?;
+    /// let config = shared.snapshot();
+    /// assert_eq!(config.generation(), 1);
+    ///
+    /// shared.reload()?;
+    /// assert_eq!(shared.snapshot().generation(), 2);
+    /// assert!(shared.snapshot().generation() > config.generation());
+    /// # Ok::<(), lino_arguments::ConfigError>(())
+    /// ```
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// When the values were resolved (the Unix epoch on
+    /// `wasm32-unknown-unknown`, which has no clock).
+    pub fn resolved_at(&self) -> SystemTime {
+        self.resolved_at
+    }
+
+    /// Number the configuration as the reload following `previous`.
+    pub(crate) fn follow(&mut self, previous: &Config) {
+        self.generation = previous.generation + 1;
+    }
+
+    /// Get every key read so far, in the order it was first read, with the
+    /// source the value was resolved from, when it was first and last read,
+    /// and how many times. Each key is listed once however often it is
+    /// read, so the log stays as small as the set of keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lino_arguments::{make_config_from, ValueSource};
+    ///
+    /// let config = make_config_from(["app", "--audit-port", "9090"], |c| {
+    ///     c.option("audit-port", "Server port", "3000")
+    ///      .option("audit-host", "Server host", "localhost")
+    /// });
+    ///
+    /// config.get("audit-port");
+    /// config.get("audit-port");
+    ///
+    /// let audit = config.audit();
+    /// assert_eq!(audit.len(), 1);
+    /// assert_eq!(audit[0].key, "audit-port");
+    /// assert_eq!(audit[0].source, Some(ValueSource::Cli));
+    /// assert_eq!(audit[0].reads, 2);
+    /// ```
+    pub fn audit(&self) -> Vec<AuditEntry> {
+        self.audit
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .records
+            .iter()
+            .map(AuditRecord::entry)
+            .collect()
+    }
+
+    fn record(&self, key: &str, source: Option<ValueSource>) {
+        {
+            let log = self
+                .audit
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if let Some(&i) = log.index.get(key) {
+                log.records[i].read();
+                return;
+            }
+        }
+        let mut log = self
+            .audit
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(&i) = log.index.get(key) {
+            log.records[i].read();
+            return;
+        }
+        let i = log.records.len();
+        log.index.insert(key.to_string(), i);
+        log.records.push(AuditRecord {
+            key: key.to_string(),
+            source,
+            first_read: now(),
+            last_read: AtomicU64::new(0),
+            reads: AtomicU64::new(1),
+        });
+    }
+
+    /// Get a configuration value as an integer.
+    /// Returns the default if the key is not found or cannot be parsed.
+    ///
+    /// Digit separators (`1_000`, `1,000`) and a leading `+` are accepted
+    /// unless the builder enabled [`ConfigBuilder::strict_numbers()`].
+    pub fn get_int(&self, key: &str, default: i64) -> i64 {
+        let val = self.get(key);
+        if val.is_empty() {
+            return default;
+        }
+        parse_int(&val, self.strict_numbers).unwrap_or_else(|| {
+            trace::parse_failed(key, "integer", &default);
+            default
+        })
+    }
+
+    /// Get a configuration value as a floating point number.
+    /// Returns the default if the key is not found or cannot be parsed.
+    pub fn get_float(&self, key: &str, default: f64) -> f64 {
+        let val = self.get(key);
+        if val.is_empty() {
+            return default;
+        }
+        parse_float(&val, self.strict_numbers).unwrap_or_else(|| {
+            trace::parse_failed(key, "number", &default);
+            default
+        })
+    }
+
+    /// Get a configuration value as a boolean.
+    /// Accepts: "true", "false", "1", "0", "yes", "no", "on", "off" (case-insensitive).
+    /// Returns false if the key is not found.
+    pub fn get_bool(&self, key: &str) -> bool {
+        let val = self.get(key);
+        matches!(val.to_lowercase().as_str(), "true" | "1" | "yes" | "on")
+    }
+
+    /// Get a configuration value as a [`Secret`], which prints as
+    /// `[REDACTED]`. Use it for keys declared with
+    /// [`KeySpec::sensitive()`](crate::KeySpec::sensitive).
+    /// Returns an empty secret if the key is not found.
+    pub fn get_secret(&self, key: &str) -> Secret<String> {
+        Secret::fetched(self.get(key))
+    }
+
+    /// Check if a configuration key exists.
+    /// Checking does not count as a read and is not recorded in the audit log.
+    pub fn has(&self, key: &str) -> bool {
+        self.values.contains(key)
+    }
+
+    /// Problems that did not stop the configuration from building: failed
+    /// validators with [`Severity::Warning`], and deprecated keys that were
+    /// set, which carry the suggested migration in [`Issue::hint`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lino_arguments::{make_config_from, KeySpec, Schema, ValueType};
+    ///
+    /// let schema = Schema::new()
+    ///     .key(KeySpec::new("port", ValueType::Integer).default("3000"))
+    ///     .key(KeySpec::new("listen", ValueType::Integer).replaced_by("port"));
+    ///
+    /// let config = make_config_from(["app", "--listen", "8080"], |c| c.schema(schema));
+    /// assert_eq!(config.get("listen"), "8080");
+    ///
+    /// let warning = &config.warnings()[0];
+    /// assert_eq!(warning.key, "listen");
+    /// assert_eq!(warning.hint.as_deref(), Some("use `--port` / `PORT` instead"));
+    /// ```
+    pub fn warnings(&self) -> &[Issue] {
+        &self.warnings
+    }
+
+    /// Verify the configuration eagerly, so a service can fail at startup
+    /// rather than after binding sockets or spawning workers.
+    ///
+    /// Every input the values were resolved from is read again, and what
+    /// building skips or tolerates fails here:
+    ///
+    /// - every configured `.lenv` and `.env` file that exists must be
+    ///   readable and well-formed, while building skips lines and files it
+    ///   cannot read;
+    /// - every file in the secrets directories must be readable as UTF-8,
+    ///   while building skips the files it cannot read;
+    /// - every sops file that exists is decrypted again and parsed in
+    ///   full, while building only parses the keys of the options out of
+    ///   the decrypted document;
+    /// - every file named by a `<NAME>_FILE` variable a value was read
+    ///   through (see [`ConfigBuilder::file_vars()`]) must still be
+    ///   readable;
+    /// - every [`Source`] is loaded again in full with
+    ///   [`Source::load()`], rather than [`Source::load_keys()`], so a
+    ///   remote store that has gone away since the build, or a document
+    ///   broken outside the keys the options read, is reported before the
+    ///   service starts.
+    ///
+    /// The resolved values are then checked against the schema again, so
+    /// validators that inspect the outside world (such as whether a
+    /// directory exists) run once more. Missing config files, sops files,
+    /// and secrets directories pass, as they do when building. The first
+    /// failure is returned. Preflight does not count as a read and is not
+    /// recorded in the audit log.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lino_arguments::{make_config_from, KeySpec, Schema, ValueType};
+    ///
+    /// let schema = Schema::new().key(KeySpec::new("port", ValueType::Integer).default("3000"));
+    /// let config = make_config_from(["app"], |c| c.lenv(".lenv").schema(schema));
+    ///
+    /// config.preflight().expect("invalid configuration");
+    /// ```
+    pub fn preflight(&self) -> Result<(), ConfigError> {
+        let failed = |path: &dyn std::fmt::Display, err: &dyn std::fmt::Display| {
+            ConfigError::FileError(format!("Failed to read {path}: {err}"))
+        };
+        for (path, source) in &self.files {
+            if !std::path::Path::new(path).exists() {
+                continue;
+            }
+            if *source == ValueSource::LenvFile {
+                std::fs::read_to_string(path).map_err(|err| failed(path, &err))?;
+            } else {
+                for item in dotenvy::from_path_iter(path).map_err(|err| failed(path, &err))? {
+                    item.map_err(|err| failed(path, &err))?;
+                }
+            }
+        }
+        for dir in &self.inputs.secrets_dirs {
+            if !std::path::Path::new(dir).exists() {
+                continue;
+            }
+            for entry in std::fs::read_dir(dir).map_err(|err| failed(dir, &err))? {
+                let path = entry.map_err(|err| failed(dir, &err))?.path();
+                let hidden = path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+                if !hidden && path.is_file() {
+                    read_secret_file(&path).map_err(|err| failed(&path.display(), &err))?;
+                }
+            }
+        }
+        for path in &self.inputs.sops_paths {
+            crate::sops::decrypt(path, None)?;
+        }
+        for (name, path) in &self.inputs.file_vars {
+            read_secret_file(path).map_err(|err| failed(&format!("{path} ({name})"), &err))?;
+        }
+        for source in &self.inputs.sources {
+            source.load()?;
+        }
+        match self.schema {
+            Some(ref schema) => schema.validate(self),
+            None => Ok(()),
+        }
+    }
+
+    /// A dump of every value with sensitive keys masked as `sk-****abcd`,
+    /// one `key: value` line per key in key order, safe to print in a
+    /// startup banner or paste into an issue. Keys are shown in kebab-case.
+    /// See [`masked()`](Self::masked) to reveal fewer characters or to
+    /// serialize the dump.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lino_arguments::{make_config_from, KeySpec, Schema, ValueType};
+    ///
+    /// let schema = Schema::new()
+    ///     .key(KeySpec::new("port", ValueType::Integer).default("3000"))
+    ///     .key(KeySpec::new("api-key", ValueType::String).default("sk-live-51HqLyjWDarjtT1zdp7dcabcd").sensitive(true));
+    /// let config = make_config_from(["app"], |c| c.schema(schema));
+    ///
+    /// assert_eq!(config.to_masked_string(), "api-key: sk-****abcd\nport: 3000\n");
+    /// ```
+    pub fn to_masked_string(&self) -> String {
+        self.masked().to_string()
+    }
+
+    /// A view of the configuration with sensitive values masked, which
+    /// implements [`Display`](std::fmt::Display) like
+    /// [`to_masked_string()`](Self::to_masked_string) and
+    /// [`Serialize`](serde::Serialize) as a map from key to value.
+    /// Masking does not count as a read and is not recorded in the audit
+    /// log.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lino_arguments::{make_config_from, KeySpec, Schema, ValueType};
+    ///
+    /// let schema = Schema::new()
+    ///     .key(KeySpec::new("api-key", ValueType::String).default("sk-live-51HqLyjWDarjtT1zdp7dcabcd").sensitive(true));
+    /// let config = make_config_from(["app"], |c| c.schema(schema));
+    ///
+    /// let json = serde_json::to_string(&config.masked().reveal(2)).unwrap();
+    /// assert_eq!(json, r#"{"api-key":"sk-****cd"}"#);
+    /// ```
+    pub fn masked(&self) -> MaskedConfig<'_> {
+        MaskedConfig {
+            config: self,
+            reveal: 4,
+        }
+    }
+
+    /// Where every value came from, as a JSON document for dashboards and
+    /// other tools: the [`generation()`](Self::generation), when it was
+    /// resolved (in milliseconds since the Unix epoch), and for each key its
+    /// value (masked like [`masked()`](Self::masked) if sensitive), the
+    /// layer it was resolved from, the argument or variable it was matched
+    /// by, the file, line, and column it was written at for `.lenv` and
+    /// `.env` values, and the revision of its [`Source`], if any. Keys are
+    /// in kebab-case, and fields that do not apply are `null`. Files are
+    /// read again to find the lines, so they reflect the files as they are
+    /// now. Exporting does not count as a read and is not recorded in the
+    /// audit log.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lino_arguments::make_config_from;
+    ///
+    /// let config = make_config_from(["app", "--port", "8080"], |c| {
+    ///     c.option("port", "Server port", "3000")
+    ///      .option("host", "Server host", "localhost")
+    /// });
+    ///
+    /// let json = config.provenance_json();
+    /// assert_eq!(json["generation"], 1);
+    /// assert_eq!(json["keys"]["port"]["value"], "8080");
+    /// assert_eq!(json["keys"]["port"]["source"], "command line");
+    /// assert_eq!(json["keys"]["port"]["variant"], "port");
+    /// assert_eq!(json["keys"]["host"]["source"], "default value");
+    /// assert!(json["keys"]["host"]["file"].is_null());
+    /// ```
+    pub fn provenance_json(&self) -> serde_json::Value {
+        let read: Vec<Vec<FileEntry>> = self
+            .files
+            .iter()
+            .map(|(path, source)| match source {
+                ValueSource::LenvFile => read_lenv_entries(path).unwrap_or_default(),
+                _ => read_env_entries(path).unwrap_or_default(),
+            })
+            .collect();
+        let mut entries = HashMap::new();
+        for ((path, source), read) in self.files.iter().zip(&read) {
+            entries.insert(*source, (path, index_entries(read)));
+        }
+        let masked = self.masked();
+        let keys: serde_json::Map<String, serde_json::Value> = masked
+            .entries()
+            .into_iter()
+            .map(|(key, value)| {
+                let source = self.values.source(key).expect("the key has a value");
+                let variant = self.variants.get(key);
+                let location = entries.get(&source).and_then(|(path, entries)| {
+                    let entry = entries.get(canonical_key(variant?).as_ref())?;
+                    Some(entry.location(path))
+                });
+                let entry = serde_json::json!({
+                    "value": value,
+                    "source": source.to_string(),
+                    "variant": variant,
+                    "file": location.as_ref().map(|location| &location.file),
+                    "line": location.as_ref().map(|location| location.line),
+                    "column": location.as_ref().map(|location| location.column),
+                    "revision": self.revision(key),
+                });
+                (key.to_string(), entry)
+            })
+            .collect();
+        let resolved_at = self
+            .resolved_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        serde_json::json!({
+            "generation": self.generation,
+            "resolved_at": resolved_at,
+            "keys": keys,
+        })
+    }
+
+    /// Whether `key` is declared sensitive in the schema.
+    fn is_sensitive(&self, key: &str) -> bool {
+        self.schema
+            .as_ref()
+            .and_then(|schema| schema.get(key))
+            .is_some_and(|spec| spec.sensitive)
+    }
+
+    /// The value of `key` and its source, without recording a read.
+    pub(crate) fn resolved(&self, key: &str) -> Option<(&str, ValueSource)> {
+        self.values.get(key)
+    }
+
+    /// The type `key` is declared with in the schema, if any.
+    #[cfg(feature = "figment")]
+    pub(crate) fn value_type(&self, key: &str) -> Option<crate::ValueType> {
+        let spec = self.schema.as_ref()?.get(key)?;
+        Some(spec.value_type)
+    }
+
+    /// Every key with a value, in no particular order.
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.iter().map(|(key, _, _)| key)
+    }
+
+    pub(crate) fn strict_numbers(&self) -> bool {
+        self.strict_numbers
+    }
+}
+
+/// Wipe the values of sensitive keys from memory.
+#[cfg(feature = "zeroize")]
+impl Drop for Config {
+    fn drop(&mut self) {
+        let Some(ref schema) = self.schema else {
+            return;
+        };
+        for (key, value, _) in self.values.iter_mut() {
+            if schema.get(key).is_some_and(|spec| spec.sensitive) {
+                zeroize::Zeroize::zeroize(value);
+            }
+        }
+    }
+}
+
+/// A [`Config`] with sensitive values masked, from [`Config::masked()`].
+#[derive(Debug, Clone, Copy)]
+pub struct MaskedConfig<'a> {
+    config: &'a Config,
+    reveal: usize,
+}
+
+impl MaskedConfig<'_> {
+    /// Show the last `reveal` characters of sensitive values (4 by
+    /// default). With `0`, sensitive values are shown as `****`.
+    pub fn reveal(mut self, reveal: usize) -> Self {
+        self.reveal = reveal;
+        self
+    }
+
+    /// Every key with its value, masked if sensitive, in key order.
+    fn entries(&self) -> Vec<(&str, Cow<'_, str>)> {
+        let mut entries: Vec<(&str, Cow<'_, str>)> = self
+            .config
+            .values
+            .iter()
+            .map(|(key, value, _)| {
+                let value = if self.config.is_sensitive(key) {
+                    Cow::Owned(mask(value, self.reveal))
+                } else {
+                    Cow::Borrowed(value)
+                };
+                (key, value)
+            })
+            .collect();
+        entries.sort();
+        entries
+    }
+}
+
+impl std::fmt::Display for MaskedConfig<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in self.entries() {
+            writeln!(f, "{key}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl serde::Serialize for MaskedConfig<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.entries())
+    }
+}
+
+/// Option definition for the functional configuration API.
+#[derive(Debug, Clone)]
+struct OptionDef {
+    name: String,
+    description: String,
+    default: String,
+    is_flag: bool,
+    short: Option<char>,
+    /// Environment variable name, if not derived from the name.
+    env: Option<String>,
+}
+
+impl OptionDef {
+    /// The environment variable the option is read from.
+    fn env_var(&self) -> Cow<'_, str> {
+        match self.env {
+            Some(ref env) => Cow::Borrowed(env),
+            None => convert_case(&self.name, Case::UpperSnake),
+        }
+    }
+}
+
+/// What happens when a `.lenv` or `.env` file setting a
+/// [sensitive](crate::KeySpec::sensitive) key can be read by every user
+/// (mode `0644` or `0666`). Only checked on Unix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilePermissions {
+    /// Do not check file permissions.
+    Ignore,
+    /// Report a warning in [`Config::warnings()`] (the default).
+    #[default]
+    Warn,
+    /// Fail with [`ConfigError::Invalid`].
+    Deny,
+}
+
+impl FilePermissions {
+    /// The permission bits of the file at `path`, if every user can read
+    /// it: the files the policy applies to. Always `None` outside Unix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::FilePermissions;
+    ///
+    /// assert_eq!(FilePermissions::world_readable("does-not-exist.lenv"), None);
+    /// ```
+    #[cfg(unix)]
+    pub fn world_readable(path: &str) -> Option<u32> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = std::fs::metadata(path).ok()?.permissions().mode() & 0o777;
+        (mode & 0o004 != 0).then_some(mode)
+    }
+
+    /// The permission bits of the file at `path`, if every user can read
+    /// it: the files the policy applies to. Always `None` outside Unix.
+    #[cfg(not(unix))]
+    pub fn world_readable(_path: &str) -> Option<u32> {
+        None
+    }
+}
+
+/// Builder for functional-style configuration.
+///
+/// Provides a chainable API for defining configuration options, similar to
+/// the JavaScript `makeConfig` API.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use lino_arguments::make_config;
+///
+/// let config = make_config(|c| {
+///     c.lenv(".lenv")
+///      .option("port", "Server port", "3000")
+///      .option_short("api-key", 'k', "API key", "")
+///      .flag("verbose", "Enable verbose logging")
+/// });
+/// ```
+pub struct ConfigBuilder {
+    options: Vec<OptionDef>,
+    lenv_path: Option<String>,
+    lenv_override: bool,
+    env_path: Option<String>,
+    env_override: bool,
+    secrets_dirs: Vec<String>,
+    sops_paths: Vec<String>,
+    sources: Vec<Arc<dyn Source>>,
+    file_vars: bool,
+    #[cfg(feature = "age")]
+    age_identity: Option<String>,
+    #[cfg(feature = "keyring")]
+    keyring_service: Option<String>,
+    app_name: Option<String>,
+    app_about: Option<String>,
+    app_version: Option<String>,
+    lookup: GetenvOptions,
+    schema: Option<Schema>,
+    file_schema: Option<Schema>,
+    permissions: FilePermissions,
+}
+
+impl ConfigBuilder {
+    pub(crate) fn new() -> Self {
+        ConfigBuilder {
+            options: Vec::new(),
+            lenv_path: None,
+            lenv_override: false,
+            env_path: None,
+            env_override: false,
+            secrets_dirs: Vec::new(),
+            sops_paths: Vec::new(),
+            sources: Vec::new(),
+            file_vars: false,
+            #[cfg(feature = "age")]
+            age_identity: None,
+            #[cfg(feature = "keyring")]
+            keyring_service: None,
+            app_name: None,
+            app_about: None,
+            app_version: None,
+            lookup: GetenvOptions::default(),
+            schema: None,
+            file_schema: None,
+            permissions: FilePermissions::default(),
+        }
+    }
+
+    /// Set the application name for help text.
+    pub fn name(&mut self, name: &str) -> &mut Self {
+        self.app_name = Some(name.to_string());
+        self
+    }
+
+    /// Set the application description for help text.
+    pub fn about(&mut self, about: &str) -> &mut Self {
+        self.app_about = Some(about.to_string());
+        self
+    }
+
+    /// Set the application version for --version flag.
+    pub fn version(&mut self, version: &str) -> &mut Self {
+        self.app_version = Some(version.to_string());
+        self
+    }
+
+    /// Load a .lenv configuration file (without overriding existing env vars).
+    pub fn lenv(&mut self, path: &str) -> &mut Self {
+        self.lenv_path = Some(path.to_string());
+        self.lenv_override = false;
+        self
+    }
+
+    /// Load a .lenv configuration file, overriding existing env vars.
+    pub fn lenv_override(&mut self, path: &str) -> &mut Self {
+        self.lenv_path = Some(path.to_string());
+        self.lenv_override = true;
+        self
+    }
+
+    /// Load a .env configuration file (without overriding existing env vars).
+    pub fn env(&mut self, path: &str) -> &mut Self {
+        self.env_path = Some(path.to_string());
+        self.env_override = false;
+        self
+    }
+
+    /// Load a .env configuration file, overriding existing env vars.
+    pub fn env_override(&mut self, path: &str) -> &mut Self {
+        self.env_path = Some(path.to_string());
+        self.env_override = true;
+        self
+    }
+
+    /// Read values from the files of a secrets directory, one value per
+    /// file named after its key (`db_password` or `DB_PASSWORD` for the
+    /// `db-password` option). A single trailing newline is removed.
+    ///
+    /// Secrets take priority over the `.lenv` and `.env` files and defaults,
+    /// but not over environment variables and CLI arguments. Unlike file
+    /// values they are not exported to the process environment. A missing
+    /// directory is ignored. When several directories are given, a key is
+    /// read from the first one that has it.
+    pub fn secrets_dir(&mut self, path: &str) -> &mut Self {
+        self.secrets_dirs.push(path.to_string());
+        self
+    }
+
+    /// Load a [sops](https://github.com/getsops/sops)-encrypted JSON or
+    /// YAML file, decrypted with the `sops` binary and the age, PGP, or KMS
+    /// keys it finds through its usual environment variables
+    /// (`SOPS_AGE_KEY_FILE`, `AWS_PROFILE`, ...).
+    ///
+    /// Nested keys are joined with `_` (`db: {password: ..}` sets
+    /// `db-password`). Values rank with the secrets directories, below
+    /// environment variables and above the `.lenv` and `.env` files, and
+    /// are not exported to the process environment. A missing file is
+    /// ignored; a file that cannot be decrypted fails the build with
+    /// [`ConfigError::FileError`].
+    pub fn sops(&mut self, path: &str) -> &mut Self {
+        self.sops_paths.push(path.to_string());
+        self
+    }
+
+    /// Add a layer of values fetched from outside the process, such as
+    /// [`AwsSource`](crate::AwsSource). Sources rank with the secrets
+    /// directories, below environment variables and above the `.lenv` and
+    /// `.env` files; when several have a key, the first one added wins. A
+    /// source that fails to load fails the build.
+    pub fn source(&mut self, source: impl Source + 'static) -> &mut Self {
+        self.sources.push(Arc::new(source));
+        self
+    }
+
+    /// Decrypt resolved values of the form `enc:...`, produced by
+    /// [`encrypt_value()`](crate::encrypt_value), with the age identities
+    /// in the file at `path` (as written by `age-keygen`). Requires the
+    /// `age` feature.
+    ///
+    /// Values are decrypted whichever source they come from, before they
+    /// are validated, so config files can mix plaintext and encrypted
+    /// values. An unreadable identity file fails the build with
+    /// [`ConfigError::FileError`], and values that cannot be decrypted with
+    /// [`ConfigError::Invalid`].
+    #[cfg(feature = "age")]
+    pub fn age_identity(&mut self, path: &str) -> &mut Self {
+        self.age_identity = Some(path.to_string());
+        self
+    }
+
+    /// Resolve keys marked [sensitive](crate::KeySpec::sensitive) in the
+    /// schema from the platform keychain, where they are stored under
+    /// `service` with [`set_keyring_secret()`]. Requires the `keyring`
+    /// feature.
+    ///
+    /// The keychain ranks with the secrets directories, below environment
+    /// variables and above the `.lenv` and `.env` files. A keychain that
+    /// cannot be reached is skipped.
+    #[cfg(feature = "keyring")]
+    pub fn keyring(&mut self, service: &str) -> &mut Self {
+        self.keyring_service = Some(service.to_string());
+        self
+    }
+
+    /// Read the value of an option whose variable is unset from the file
+    /// named by the same variable with a `_FILE` suffix, the convention
+    /// Docker images use to pass secrets (`DB_PASSWORD_FILE=/run/secrets/db`
+    /// for the `db-password` option). A single trailing newline is removed.
+    ///
+    /// The file ranks with the variable it stands in for, below CLI
+    /// arguments and above the secrets directories and config files, and
+    /// the value is reported as [`ValueSource::FileVar`]. A file that
+    /// cannot be read fails the build with [`ConfigError::FileError`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::{make_config_from, ValueSource};
+    ///
+    /// let path = std::env::temp_dir().join("doc-file-var-token");
+    /// std::fs::write(&path, "sk-live-1234\n").unwrap();
+    /// std::env::set_var("DOC_FILE_VAR_TOKEN_FILE", &path);
+    ///
+    /// let config = make_config_from(["app"], |c| {
+    ///     c.file_vars().option("doc-file-var-token", "API token", "")
+    /// });
+    /// assert_eq!(config.get("doc-file-var-token"), "sk-live-1234");
+    /// assert_eq!(config.source("doc-file-var-token"), Some(ValueSource::FileVar));
+    /// ```
+    pub fn file_vars(&mut self) -> &mut Self {
+        self.file_vars = true;
+        self
+    }
+
+    /// Read Docker secrets from `/run/secrets`; see
+    /// [`secrets_dir()`](Self::secrets_dir).
+    pub fn docker_secrets(&mut self) -> &mut Self {
+        self.secrets_dir(DOCKER_SECRETS_DIR)
+    }
+
+    /// Read the credentials systemd passes to the service
+    /// (`LoadCredential=`, `SetCredential=`) from `$CREDENTIALS_DIRECTORY`,
+    /// if it is set; see [`secrets_dir()`](Self::secrets_dir).
+    ///
+    /// ```ini
+    /// [Service]
+    /// LoadCredential=db_password:/etc/myapp/db_password
+    /// ```
+    pub fn systemd_credentials(&mut self) -> &mut Self {
+        if let Some(dir) = env::var_os(CREDENTIALS_DIRECTORY) {
+            self.secrets_dir(&dir.to_string_lossy());
+        }
+        self
+    }
+
+    /// Set how environment variables that are set to an empty string are treated.
+    ///
+    /// With [`EmptyValues::Unset`], `PORT=""` no longer shadows `.lenv`/`.env`
+    /// values or the option's default.
+    pub fn empty_values(&mut self, policy: EmptyValues) -> &mut Self {
+        self.lookup.empty = policy;
+        self
+    }
+
+    /// Make [`Config::get_int()`] and [`Config::get_float()`] reject digit
+    /// separators and a leading `+`.
+    pub fn strict_numbers(&mut self, strict: bool) -> &mut Self {
+        self.lookup.strict_numbers = strict;
+        self
+    }
+
+    /// Trim surrounding whitespace and matching quotes from resolved values
+    /// (`PORT=" 8080 "` becomes `8080`). See [`normalize_value()`].
+    pub fn normalize_values(&mut self, normalize: bool) -> &mut Self {
+        self.lookup.normalize = normalize;
+        self
+    }
+
+    /// Define a string/number option with a long name, description, and default value.
+    pub fn option(&mut self, name: &str, description: &str, default: &str) -> &mut Self {
+        self.options.push(OptionDef {
+            name: name.to_string(),
+            description: description.to_string(),
+            default: default.to_string(),
+            is_flag: false,
+            short: None,
+            env: None,
+        });
+        self
+    }
+
+    /// Define a string/number option with both short and long names.
+    pub fn option_short(
+        &mut self,
+        name: &str,
+        short: char,
+        description: &str,
+        default: &str,
+    ) -> &mut Self {
+        self.options.push(OptionDef {
+            name: name.to_string(),
+            description: description.to_string(),
+            default: default.to_string(),
+            is_flag: false,
+            short: Some(short),
+            env: None,
+        });
+        self
+    }
+
+    /// Define a boolean flag (defaults to false).
+    pub fn flag(&mut self, name: &str, description: &str) -> &mut Self {
+        self.options.push(OptionDef {
+            name: name.to_string(),
+            description: description.to_string(),
+            default: String::new(),
+            is_flag: true,
+            short: None,
+            env: None,
+        });
+        self
+    }
+
+    /// Define a boolean flag with a short name.
+    pub fn flag_short(&mut self, name: &str, short: char, description: &str) -> &mut Self {
+        self.options.push(OptionDef {
+            name: name.to_string(),
+            description: description.to_string(),
+            default: String::new(),
+            is_flag: true,
+            short: Some(short),
+            env: None,
+        });
+        self
+    }
+
+    /// Define an option for every key of `schema` and validate the resolved
+    /// values against it.
+    ///
+    /// [`ValueType::Bool`](crate::ValueType::Bool) keys become flags. Keys
+    /// already defined through [`option()`](Self::option) and friends keep
+    /// that definition (e.g. its short name), except for an environment
+    /// variable name set with [`KeySpec::env()`], and are still validated.
+    pub fn schema(&mut self, schema: Schema) -> &mut Self {
+        let defined: HashMap<String, usize> = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(position, opt)| (canonical_key(&opt.name).into_owned(), position))
+            .collect();
+        for spec in schema.keys() {
+            if let Some(&position) = defined.get(canonical_key(&spec.name).as_ref()) {
+                let opt = &mut self.options[position];
+                opt.env = spec.env.clone().or(opt.env.take());
+                continue;
+            }
+            self.options.push(OptionDef {
+                name: spec.name.clone(),
+                description: spec.description.clone(),
+                default: spec.default.clone().unwrap_or_default(),
+                is_flag: spec.value_type == crate::ValueType::Bool,
+                short: None,
+                env: spec.env.clone(),
+            });
+        }
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Validate the `.lenv` and `.env` files configured on the builder
+    /// against an external [JSON Schema](https://json-schema.org) document
+    /// before any value is resolved.
+    ///
+    /// Violations are reported with the file and line they were found on.
+    /// See [`Schema::from_json_schema()`] for the supported keywords. A file
+    /// passed with `--configuration` is not checked.
+    pub fn json_schema(&mut self, document: &serde_json::Value) -> &mut Self {
+        self.file_schema = Some(Schema::from_json_schema(document));
+        self
+    }
+
+    /// Set what happens when the `.lenv` or `.env` file sets a sensitive
+    /// key and can be read by every user. Defaults to
+    /// [`FilePermissions::Warn`].
+    pub fn file_permissions(&mut self, policy: FilePermissions) -> &mut Self {
+        self.permissions = policy;
+        self
+    }
+
+    /// Build the configuration from the defined options.
+    ///
+    /// This parses CLI arguments using clap and resolves values from:
+    /// 1. CLI arguments (highest priority)
+    /// 2. Environment variables
+    /// 3. Secrets directories, sops files, remote sources, and the keychain
+    /// 4. .lenv file
+    /// 5. .env file
+    /// 6. Default values (lowest priority)
+    fn build(&self) -> Result<Config, ConfigError> {
+        self.try_build_from(env::args_os().collect())
+    }
+
+    /// Build the configuration from custom arguments and validate it against
+    /// the schema, if one was given.
+    pub(crate) fn try_build_from(
+        &self,
+        args: Vec<std::ffi::OsString>,
+    ) -> Result<Config, ConfigError> {
+        self.try_build_with(args, EnvLayer::default())
+    }
+
+    /// Like [`try_build_from()`](Self::try_build_from), reading the
+    /// environment and the file values through `layer`.
+    pub(crate) fn try_build_with(
+        &self,
+        args: Vec<std::ffi::OsString>,
+        layer: EnvLayer<'_>,
+    ) -> Result<Config, ConfigError> {
+        let _span = trace::resolving(self.options.len());
+        let mut warnings = split_warnings(self.check_permissions())?;
+        if let Some(ref schema) = self.file_schema {
+            let mut issues = Vec::new();
+            let strict = self.lookup.strict_numbers;
+            if let Some(ref path) = self.lenv_path {
+                if let Ok(entries) = read_lenv_entries(path) {
+                    schema.check_entries(
+                        &entries,
+                        path,
+                        ValueSource::LenvFile,
+                        strict,
+                        &mut issues,
+                    );
+                }
+            }
+            if let Some(ref path) = self.env_path {
+                if let Ok(entries) = read_env_entries(path) {
+                    schema.check_entries(&entries, path, ValueSource::EnvFile, strict, &mut issues);
+                }
+            }
+            warnings.extend(split_warnings(issues)?);
+        }
+
+        let (secrets, mut origins) = self.read_secrets()?;
+        let mut config = self.build_from(args, &secrets, layer)?;
+        origins.retain(|key, _| matches!(config.values.source(key), Some(ValueSource::Remote(_))));
+        config.origins = origins;
+        #[cfg(feature = "age")]
+        self.decrypt_values(&mut config)?;
+        if let Some(ref schema) = self.schema {
+            let mut issues = schema.check_config(&config);
+            issues.extend(schema.deprecation_warnings(&config));
+            self.locate(schema, &mut issues);
+            warnings.extend(split_warnings(issues)?);
+        }
+        warnings.iter().for_each(trace::warning);
+        config.warnings = warnings;
+        Ok(config)
+    }
+
+    /// The environment variables of the options that building `config` set
+    /// from the `.lenv` and `.env` files, with their values, so that
+    /// building again in an [`EnvLayer::in_memory()`] reads the files afresh
+    /// instead of seeing their old values as the environment.
+    pub(crate) fn file_env_vars(&self, config: &Config) -> Vec<(String, String)> {
+        self.options
+            .iter()
+            .filter(|opt| {
+                matches!(
+                    config.source(&opt.name),
+                    Some(ValueSource::LenvFile | ValueSource::EnvFile)
+                )
+            })
+            .filter_map(|opt| {
+                let var = opt.env_var().into_owned();
+                let value = env::var(&var).ok()?;
+                Some((var, value))
+            })
+            .collect()
+    }
+
+    /// The config files and secrets directories the values are read from.
+    #[cfg(feature = "watch")]
+    pub(crate) fn watched_paths(&self) -> Vec<std::path::PathBuf> {
+        self.lenv_path
+            .iter()
+            .chain(&self.env_path)
+            .chain(&self.sops_paths)
+            .chain(&self.secrets_dirs)
+            .map(std::path::PathBuf::from)
+            .collect()
+    }
+
+    /// Report sensitive keys set in configured files that every user can
+    /// read, according to the [`FilePermissions`] policy.
+    fn check_permissions(&self) -> Vec<Issue> {
+        let severity = match self.permissions {
+            FilePermissions::Ignore => return Vec::new(),
+            FilePermissions::Warn => Severity::Warning,
+            FilePermissions::Deny => Severity::Error,
+        };
+        let Some(schema) = self.schema.as_ref().or(self.file_schema.as_ref()) else {
+            return Vec::new();
+        };
+        let mut issues = Vec::new();
+        if let Some(ref path) = self.lenv_path {
+            if let (Some(mode), Ok(entries)) = (
+                FilePermissions::world_readable(path),
+                read_lenv_entries(path),
+            ) {
+                issues.extend(schema.exposed_secrets(
+                    &entries,
+                    path,
+                    ValueSource::LenvFile,
+                    mode,
+                    severity,
+                ));
+            }
+        }
+        if let Some(ref path) = self.env_path {
+            if let (Some(mode), Ok(entries)) = (
+                FilePermissions::world_readable(path),
+                read_env_entries(path),
+            ) {
+                issues.extend(schema.exposed_secrets(
+                    &entries,
+                    path,
+                    ValueSource::EnvFile,
+                    mode,
+                    severity,
+                ));
+            }
+        }
+        issues
+    }
+
+    /// The values of the secrets directories, sops files, and sources, by
+    /// canonical key, with where they were read from, and what the sources
+    /// reported about the values they set. The first source with a key
+    /// wins. Only the keys of the options are parsed out of sops files and
+    /// sources.
+    fn read_secrets(&self) -> Result<(Secrets, HashMap<String, Origin>), ConfigError> {
+        let mut secrets = HashMap::new();
+        let mut origins = HashMap::new();
+        let keys: HashSet<String> = self
+            .options
+            .iter()
+            .map(|opt| canonical_key(&opt.env_var()).into_owned())
+            .collect();
+        for dir in &self.secrets_dirs {
+            let start = trace::start();
+            let values = read_secrets_dir(dir);
+            trace::loaded(ValueSource::SecretsDir, dir, values.len(), start);
+            for (key, value) in values {
+                secrets
+                    .entry(key)
+                    .or_insert((value, ValueSource::SecretsDir));
+            }
+        }
+        for path in &self.sops_paths {
+            let start = trace::start();
+            let values = trace::traced(
+                ValueSource::SopsFile,
+                path,
+                start,
+                crate::sops::decrypt(path, Some(&keys)),
+            )?;
+            for (key, value) in values {
+                secrets.entry(key).or_insert((value, ValueSource::SopsFile));
+            }
+        }
+        for source in &self.sources {
+            let start = trace::start();
+            let layer = ValueSource::Remote(source.name());
+            let values = trace::traced(layer, source.name(), start, source.load_keys(&keys))?;
+            let origin = Origin {
+                revision: source.revision(),
+                stale: source.is_stale(),
+            };
+            for (key, value) in values {
+                let key = canonical_key(&key).into_owned();
+                if secrets.contains_key(&key) {
+                    continue;
+                }
+                if origin.revision.is_some() || origin.stale {
+                    origins.insert(key.clone(), origin.clone());
+                }
+                secrets.insert(key, (value, ValueSource::Remote(source.name())));
+            }
+        }
+        Ok((secrets, origins))
+    }
+
+    /// Replace the `enc:` values of `config` with their plaintext.
+    #[cfg(feature = "age")]
+    fn decrypt_values(&self, config: &mut Config) -> Result<(), ConfigError> {
+        use crate::encryption::{decrypt_with, read_identities, PREFIX};
+
+        let Some(ref path) = self.age_identity else {
+            return Ok(());
+        };
+        let identities = read_identities(path)?;
+        let mut issues = Vec::new();
+        for (key, value, source) in config.values.iter_mut() {
+            if !value.starts_with(PREFIX) {
+                continue;
+            }
+            match decrypt_with(&identities, value) {
+                Ok(plaintext) => *value = plaintext,
+                Err(reason) => issues.push(Issue {
+                    key: key.to_string(),
+                    value: None,
+                    source: Some(source),
+                    location: None,
+                    message: format!("could not be decrypted: {reason}"),
+                    hint: Some(format!("check that it was encrypted to a key in {path}")),
+                    severity: Severity::Error,
+                }),
+            }
+        }
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            issues.sort_by(|a, b| a.key.cmp(&b.key));
+            Err(ConfigError::Invalid(issues))
+        }
+    }
+
+    /// The name of the `<NAME>_FILE` variable standing in for the variable
+    /// `env_name`, the file it names, and its contents, if it is set.
+    fn read_file_var(
+        &self,
+        env_name: &str,
+        layer: &EnvLayer<'_>,
+    ) -> Result<Option<(String, String, String)>, ConfigError> {
+        let name = format!("{env_name}{FILE_VAR_SUFFIX}");
+        let Some(path) = layer
+            .var(&name)
+            .filter(|path| self.lookup.accept(path).is_some())
+        else {
+            return Ok(None);
+        };
+        let value = read_secret_file(&path).map_err(|err| {
+            ConfigError::FileError(format!("Failed to read {path} ({name}): {err}"))
+        })?;
+        Ok(Some((name, path, value)))
+    }
+
+    /// The keychain value of `key`, if it is sensitive in the schema.
+    #[cfg(feature = "keyring")]
+    fn keyring_value(&self, key: &str) -> Option<(String, ValueSource)> {
+        let service = self.keyring_service.as_deref()?;
+        let spec = self
+            .schema
+            .as_ref()?
+            .get(key)
+            .filter(|spec| spec.sensitive)?;
+        let value = keyring_entry(service, &spec.name)
+            .ok()?
+            .get_password()
+            .ok()?;
+        Some((value, ValueSource::Keyring))
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    fn keyring_value(&self, _key: &str) -> Option<(String, ValueSource)> {
+        None
+    }
+
+    /// Point issues about values read from the configured files at where
+    /// the values were written.
+    fn locate(&self, schema: &Schema, issues: &mut [Issue]) {
+        if issues.is_empty() {
+            return;
+        }
+        if let Some(ref path) = self.lenv_path {
+            if let Ok(entries) = read_lenv_entries(path) {
+                schema.locate(issues, &entries, path, ValueSource::LenvFile);
+            }
+        }
+        if let Some(ref path) = self.env_path {
+            if let Ok(entries) = read_env_entries(path) {
+                schema.locate(issues, &entries, path, ValueSource::EnvFile);
+            }
+        }
+    }
+
+    /// The clap command parsing the options, reading the environment
+    /// variables `env_names` unless `layer` is kept in memory.
+    fn command(
+        &self,
+        arg_ids: &[String],
+        env_names: &[String],
+        layer: &EnvLayer<'_>,
+    ) -> clap::Command {
+        let mut cmd =
+            clap::Command::new(self.app_name.clone().unwrap_or_else(|| "app".to_string()));
+
+        if let Some(ref about) = self.app_about {
+            cmd = cmd.about(about.clone());
+        }
+
+        if let Some(ref version) = self.app_version {
+            cmd = cmd.version(version.clone());
+        }
+
+        // Add --configuration option for dynamic .lenv loading
+        cmd = cmd.arg(
+            clap::Arg::new("configuration")
+                .long("configuration")
+                .short('c')
+                .help("Path to configuration .lenv file")
+                .value_name("PATH"),
+        );
+
+        // Add user-defined options
+        for ((opt, arg_id), env_name) in self.options.iter().zip(arg_ids).zip(env_names) {
+            let mut arg = clap::Arg::new(arg_id.clone()).long(arg_id.clone());
+
+            // Set help text
+            arg = arg.help(opt.description.clone());
+
+            if let Some(short) = opt.short {
+                arg = arg.short(short);
+            }
+
+            if opt.is_flag {
+                arg = arg.action(clap::ArgAction::SetTrue);
+            } else {
+                // Use clap's env feature so it picks up values from env vars
+                // (which include the values loaded from the .lenv, .env, and
+                // --configuration files), unless they are kept in memory and
+                // looked up when the values are collected
+                // A variable that counts as unset must not reach clap at all
+                let unset = env::var(env_name).is_ok_and(|v| self.lookup.accept(&v).is_none());
+                if !layer.in_memory && !unset {
+                    arg = arg.env(env_name.clone());
+                }
+                if !opt.default.is_empty() {
+                    arg = arg.default_value(opt.default.clone());
+                }
+            }
+
+            cmd = cmd.arg(arg);
+        }
+
+        cmd
+    }
+
+    /// Build the configuration from custom arguments (for testing).
+    fn build_from(
+        &self,
+        args: Vec<std::ffi::OsString>,
+        secrets: &Secrets,
+        mut layer: EnvLayer<'_>,
+    ) -> Result<Config, ConfigError> {
+        // Step 1: Load .lenv file if configured (higher priority than .env)
+        let mut from_lenv = HashSet::new();
+        if let Some(ref path) = self.lenv_path {
+            let start = trace::start();
+            let applied = apply_lenv_file(path, self.lenv_override, &self.lookup, &mut layer);
+            if let Ok(keys) = trace::traced(ValueSource::LenvFile, path, start, applied) {
+                from_lenv.extend(keys.iter().map(|k| env_name_key(k)));
+            }
+        }
+
+        // Step 2: Load .env file if configured (lower priority than .lenv)
+        let mut from_env_file = HashSet::new();
+        if let Some(ref path) = self.env_path {
+            let start = trace::start();
+            let applied = apply_env_file(path, self.env_override, &self.lookup, &mut layer);
+            if let Ok(keys) = trace::traced(ValueSource::EnvFile, path, start, applied) {
+                from_env_file.extend(keys.iter().map(|k| env_name_key(k)));
+            }
+        }
+
+        // Step 3: Name the options by their kebab-case argument IDs (which
+        // double as canonical keys) and, unless the schema names one,
+        // UPPER_CASE env variables
+        let arg_ids = convert_all(self.options.iter().map(|opt| &opt.name), Case::Kebab);
+        let env_names: Vec<String> = self
+            .options
+            .iter()
+            .map(|opt| opt.env_var().into_owned())
+            .collect();
+
+        // Step 4: Parse arguments
+        let parse = |layer: &EnvLayer<'_>| {
+            self.command(&arg_ids, &env_names, layer)
+                .try_get_matches_from(&args)
+                .map_err(|err| ConfigError::ArgumentError(Arc::new(err)))
+        };
+        let mut matches = parse(&layer)?;
+
+        // Step 5: Load --configuration file if provided, overriding the
+        // environment, and parse again so that clap sees its values
+        if let Some(config_path) = matches.get_one::<String>("configuration").cloned() {
+            let start = trace::start();
+            let applied = apply_lenv_file(&config_path, true, &self.lookup, &mut layer);
+            if let Ok(keys) = trace::traced(ValueSource::LenvFile, &config_path, start, applied) {
+                from_lenv.extend(keys.iter().map(|k| env_name_key(k)));
+            }
+            matches = parse(&layer)?;
+        }
+
+        // Step 6: Collect values into Config, with secrets outranking files
+        let mut values = HashMap::new();
+        let mut sources = HashMap::new();
+        let mut variants = HashMap::new();
+        let mut file_vars = Vec::new();
+
+        for ((opt, arg_id), env_name) in self.options.iter().zip(&arg_ids).zip(&env_names) {
+            let start = trace::start();
+            let key = arg_id.clone();
+
+            let from_cli =
+                matches.value_source(arg_id) == Some(clap::parser::ValueSource::CommandLine);
+            // Clap does not see the environment of a layer kept in memory
+            let layer_value = if layer.in_memory && !opt.is_flag && !from_cli {
+                layer
+                    .var(env_name)
+                    .filter(|value| self.lookup.accept(value).is_some())
+            } else {
+                None
+            };
+            let from_env = layer_value.is_some()
+                || matches.value_source(arg_id) == Some(clap::parser::ValueSource::EnvVariable);
+            let source = if from_cli {
+                ValueSource::Cli
+            } else if from_env {
+                let env_name = env_name_key(env_name);
+                if from_lenv.contains(&env_name) {
+                    ValueSource::LenvFile
+                } else if from_env_file.contains(&env_name) {
+                    ValueSource::EnvFile
+                } else {
+                    ValueSource::Environment
+                }
+            } else {
+                ValueSource::Default
+            };
+            let file_var = match source {
+                ValueSource::LenvFile | ValueSource::EnvFile | ValueSource::Default
+                    if self.file_vars && !opt.is_flag =>
+                {
+                    self.read_file_var(env_name, &layer)?
+                }
+                _ => None,
+            };
+            let secret = match source {
+                ValueSource::LenvFile | ValueSource::EnvFile | ValueSource::Default => secrets
+                    .get(canonical_key(env_name).as_ref())
+                    .and_then(|(value, source)| {
+                        Some((self.lookup.accept(value)?.to_string(), *source))
+                    })
+                    .or_else(|| self.keyring_value(&opt.name)),
+                _ => None,
+            };
+
+            if opt.is_flag {
+                let val = matches.get_flag(arg_id);
+                values.insert(key.clone(), val.to_string());
+                sources.insert(key, source);
+            } else if let Some((name, path, val)) = &file_var {
+                values.insert(key.clone(), val.clone());
+                sources.insert(key, ValueSource::FileVar);
+                file_vars.push((name.clone(), path.clone()));
+            } else if let Some((val, source)) = secret {
+                values.insert(key.clone(), val);
+                sources.insert(key, source);
+            } else if let Some(val) = layer_value.or_else(|| matches.get_one(arg_id).cloned()) {
+                let val = if self.lookup.normalize {
+                    normalize_value(&val)
+                } else {
+                    &val
+                };
+                values.insert(key.clone(), val.to_string());
+                sources.insert(key, source);
+            }
+            if let Some(&source) = sources.get(arg_id) {
+                let variant = match source {
+                    ValueSource::Cli => Some(arg_id.as_str()),
+                    ValueSource::Environment | ValueSource::LenvFile | ValueSource::EnvFile => {
+                        Some(env_name.as_str())
+                    }
+                    ValueSource::FileVar => file_var.as_ref().map(|(name, ..)| name.as_str()),
+                    _ => None,
+                };
+                trace::resolved(arg_id, variant, source, start);
+                if let Some(variant) = variant {
+                    variants.insert(arg_id.clone(), variant.to_string());
+                }
+            }
+        }
+
+        Ok(Config {
+            values: values
+                .into_iter()
+                .map(|(key, value)| {
+                    let source = sources[&key];
+                    (key, value, source)
+                })
+                .collect(),
+            audit: Arc::default(),
+            strict_numbers: self.lookup.strict_numbers,
+            warnings: Vec::new(),
+            schema: self.schema.clone(),
+            files: [
+                (&self.lenv_path, ValueSource::LenvFile),
+                (&self.env_path, ValueSource::EnvFile),
+            ]
+            .into_iter()
+            .filter_map(|(path, source)| Some((path.clone()?, source)))
+            .collect(),
+            inputs: Inputs {
+                secrets_dirs: self.secrets_dirs.clone(),
+                sops_paths: self.sops_paths.clone(),
+                file_vars,
+                sources: self.sources.clone(),
+            },
+            origins: HashMap::new(),
+            variants,
+            generation: 1,
+            resolved_at: now(),
+        })
+    }
+}
+
+/// Create a unified configuration using a functional builder API.
+///
+/// This is the Rust equivalent of the JavaScript `makeConfig` function.
+/// It combines .lenv file loading, .env file loading, environment variables,
+/// and CLI argument parsing into a single configuration step.
+///
+/// Priority (highest to lowest):
+/// 1. CLI arguments
+/// 2. Environment variables
+/// 3. Secrets directories (via builder `.secrets_dir()`, `.docker_secrets()`
+///    or `.systemd_credentials()`), sops files (via builder `.sops()`),
+///    remote sources (via builder `.source()`), then the keychain (via
+///    builder `.keyring()`)
+/// 4. .lenv file (via `--configuration` flag or builder `.lenv()`)
+/// 5. .env file (via builder `.env()`)
+/// 6. Default values
+///
+/// If a [`Schema`] was given and the resolved values violate it, the error is
+/// printed and the process exits with status 2, as clap does for invalid
+/// arguments. Invalid arguments, `--help`, and `--version` are printed by
+/// clap, which exits. Use [`try_make_config()`] to handle them instead.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use lino_arguments::make_config;
+///
+/// let config = make_config(|c| {
+///     c.lenv(".lenv")
+///      .env(".env")
+///      .option("port", "Server port", "3000")
+///      .option_short("api-key", 'k', "API key", "")
+///      .flag("verbose", "Enable verbose logging")
+/// });
+///
+/// let port: u16 = config.get("port").parse().unwrap();
+/// let api_key = config.get("api-key");
+/// let verbose = config.get_bool("verbose");
+/// ```
+pub fn make_config<F>(configure: F) -> Config
+where
+    F: FnOnce(&mut ConfigBuilder) -> &mut ConfigBuilder,
+{
+    try_make_config(configure).unwrap_or_else(|err| exit_invalid(&err))
+}
+
+/// Like [`make_config()`], but returns schema validation failures, and
+/// invalid arguments, `--help`, and `--version` as
+/// [`ConfigError::ArgumentError`], instead of exiting.
+pub fn try_make_config<F>(configure: F) -> Result<Config, ConfigError>
+where
+    F: FnOnce(&mut ConfigBuilder) -> &mut ConfigBuilder,
+{
+    let mut builder = ConfigBuilder::new();
+    configure(&mut builder);
+    builder.build()
+}
+
+/// Create a unified configuration using a functional builder API with custom arguments.
+///
+/// Same as `make_config` but accepts custom arguments for testing purposes.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use lino_arguments::make_config_from;
+///
+/// let args = vec!["my-app", "--port", "9090", "--verbose"];
+/// let config = make_config_from(args, |c| {
+///     c.option("port", "Server port", "3000")
+///      .flag("verbose", "Enable verbose logging")
+/// });
+///
+/// assert_eq!(config.get("port"), "9090");
+/// assert!(config.get_bool("verbose"));
+/// ```
+pub fn make_config_from<I, T, F>(args: I, configure: F) -> Config
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString>,
+    F: FnOnce(&mut ConfigBuilder) -> &mut ConfigBuilder,
+{
+    try_make_config_from(args, configure).unwrap_or_else(|err| exit_invalid(&err))
+}
+
+/// Like [`make_config_from()`], but returns schema validation failures, and
+/// invalid arguments, `--help`, and `--version` as
+/// [`ConfigError::ArgumentError`], instead of exiting.
+///
+/// # Example
+///
+/// ```
+/// use lino_arguments::{try_make_config_from, ConfigError, KeySpec, Schema, ValueType};
+///
+/// let schema = Schema::new().key(KeySpec::new("port", ValueType::Integer));
+/// let result = try_make_config_from(["app", "--port", "http"], |c| c.schema(schema));
+/// assert!(matches!(result, Err(ConfigError::Invalid(_))));
+///
+/// let result = try_make_config_from(["app", "--bogus"], |c| c.option("port", "Port", "3000"));
+/// assert!(matches!(result, Err(ConfigError::ArgumentError(_))));
+/// ```
+pub fn try_make_config_from<I, T, F>(args: I, configure: F) -> Result<Config, ConfigError>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString>,
+    F: FnOnce(&mut ConfigBuilder) -> &mut ConfigBuilder,
+{
+    let mut builder = ConfigBuilder::new();
+    configure(&mut builder);
+    builder.try_build_from(args.into_iter().map(|a| a.into()).collect())
+}
+
+/// The warnings among `issues`, or [`ConfigError::Invalid`] with the errors
+/// if there are any.
+fn split_warnings(issues: Vec<Issue>) -> Result<Vec<Issue>, ConfigError> {
+    let (errors, warnings): (Vec<Issue>, Vec<Issue>) = issues
+        .into_iter()
+        .partition(|issue| issue.severity == Severity::Error);
+    if errors.is_empty() {
+        Ok(warnings)
+    } else {
+        Err(ConfigError::Invalid(errors))
+    }
+}
+
+/// Report an invalid configuration the way clap reports invalid arguments,
+/// and let clap report the arguments themselves.
+fn exit_invalid(err: &ConfigError) -> ! {
+    if let ConfigError::ArgumentError(err) = err {
+        err.exit()
+    }
+    #[cfg(feature = "diagnostics")]
+    eprintln!("{:?}", err.to_report());
+    #[cfg(not(feature = "diagnostics"))]
+    eprintln!("error: {err}");
+    std::process::exit(2)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod getenv_tests {
+        use super::*;
+        use std::env;
+
+        #[test]
+        fn test_getenv_with_default() {
+            let result = getenv("NON_EXISTENT_VAR_12345", "default");
+            assert_eq!(result, "default");
+        }
+
+        #[test]
+        fn test_getenv_finds_var() {
+            env::set_var("TEST_LINO_VAR", "test_value");
+            let result = getenv("TEST_LINO_VAR", "default");
+            assert_eq!(result, "test_value");
+            env::remove_var("TEST_LINO_VAR");
+        }
+
+        #[test]
+        fn test_getenv_int() {
+            env::set_var("TEST_PORT", "8080");
+            let result = getenv_int("TEST_PORT", 3000);
+            assert_eq!(result, 8080);
+            env::remove_var("TEST_PORT");
+        }
+
+        #[test]
+        fn test_getenv_bool() {
+            env::set_var("TEST_DEBUG", "true");
+            let result = getenv_bool("TEST_DEBUG", false);
+            assert!(result);
+            env::remove_var("TEST_DEBUG");
+
+            env::set_var("TEST_DEBUG", "1");
+            let result = getenv_bool("TEST_DEBUG", false);
+            assert!(result);
+            env::remove_var("TEST_DEBUG");
+        }
+
+        #[test]
+        fn test_parse_int_lenient() {
+            assert_eq!(parse_int("1_000", false), Some(1000));
+            assert_eq!(parse_int("1,000,000", false), Some(1_000_000));
+            assert_eq!(parse_int("+42", false), Some(42));
+            assert_eq!(parse_int("-1_000", false), Some(-1000));
+            assert_eq!(parse_int("1,5", false), None);
+            assert_eq!(parse_int("_1", false), None);
+            assert_eq!(parse_int("1_", false), None);
+            assert_eq!(parse_int("1__0", false), None);
+        }
+
+        #[test]
+        fn test_parse_int_strict() {
+            assert_eq!(parse_int("1000", true), Some(1000));
+            assert_eq!(parse_int("-5", true), Some(-5));
+            assert_eq!(parse_int("1_000", true), None);
+            assert_eq!(parse_int("1,000", true), None);
+            assert_eq!(parse_int("+42", true), None);
+        }
+
+        #[test]
+        fn test_parse_float() {
+            assert_eq!(parse_float("1,000.5", false), Some(1000.5));
+            assert_eq!(parse_float("+0.25", false), Some(0.25));
+            assert_eq!(parse_float("1_000.0001", false), Some(1000.0001));
+            assert_eq!(parse_float("1,000.5e3", false), Some(1000.5e3));
+            assert_eq!(parse_float("2,5", false), None);
+            // Separators are only accepted before the decimal point and exponent
+            assert_eq!(parse_float("1.234,567", false), None);
+            assert_eq!(parse_float("1_000.000_1", false), None);
+            assert_eq!(parse_float("1e1_0", false), None);
+            assert_eq!(parse_float("1,000.5", true), None);
+            assert_eq!(parse_float("2.5", true), Some(2.5));
+        }
+
+        #[test]
+        fn test_lookup_names_case_sensitive() {
+            assert_eq!(
+                lookup_names("api-key", &[], false)
+                    .iter()
+                    .collect::<Vec<_>>(),
+                vec!["api-key", "API_KEY", "apiKey", "api_key", "ApiKey"]
+            );
+        }
+
+        #[test]
+        fn test_lookup_names_case_insensitive() {
+            // On Windows `API_KEY`/`api_key` and `apiKey`/`ApiKey` name the same variable
+            assert_eq!(
+                lookup_names("api-key", &[], true)
+                    .iter()
+                    .collect::<Vec<_>>(),
+                vec!["api-key", "API_KEY", "apiKey"]
+            );
+            assert_eq!(
+                lookup_names("API_KEY", &[], true)
+                    .iter()
+                    .collect::<Vec<_>>(),
+                vec!["API_KEY", "apiKey", "api-key"]
+            );
+        }
+
+        #[test]
+        fn test_lookup_names_extra_cases() {
+            assert_eq!(
+                lookup_names("api-key", &[Case::ScreamingKebab, Case::Kebab], false)
+                    .iter()
+                    .collect::<Vec<_>>(),
+                vec!["api-key", "API_KEY", "apiKey", "api_key", "ApiKey", "API-KEY"]
+            );
+            // `API-KEY` names the same variable as `api-key` on Windows
+            assert_eq!(
+                lookup_names("api-key", &[Case::ScreamingKebab], true)
+                    .iter()
+                    .collect::<Vec<_>>(),
+                vec!["api-key", "API_KEY", "apiKey"]
+            );
+        }
+
+        #[test]
+        fn test_env_snapshot_lookup_order() {
+            let mut vars = vec![
+                ("Api_Key", "mixed"),
+                ("ApiKey", "pascal"),
+                ("api_key", "snake"),
+                ("api-key", "kebab"),
+                ("apiKey", "camel"),
+                ("API_KEY", "upper"),
+            ];
+            // Each variant wins over the ones after it, like with `getenv()`
+            let order = if CASE_INSENSITIVE_ENV {
+                ["camel", "upper", "kebab"].as_slice()
+            } else {
+                ["camel", "upper", "kebab", "snake", "pascal"].as_slice()
+            };
+            let key = "apiKey";
+            for expected in order {
+                let env = EnvSnapshot::from_vars(vars.clone());
+                assert_eq!(env.getenv(key, "none"), *expected);
+                vars.retain(|(_, value)| value != expected);
+            }
+            assert_eq!(EnvSnapshot::from_vars(vars).getenv(key, "none"), "none");
+        }
+
+        #[test]
+        fn test_env_snapshot_options() {
+            let env = EnvSnapshot::from_vars([("API_KEY", ""), ("apiKey", " 'x' ")]);
+            assert_eq!(env.getenv("api-key", "none"), "");
+            let options = GetenvOptions::new().empty_values(EmptyValues::Unset);
+            assert_eq!(env.getenv_with("api-key", "none", &options), " 'x' ");
+            let options = options.normalize(true);
+            assert_eq!(env.getenv_with("api-key", "none", &options), "x");
+
+            let env = EnvSnapshot::from_vars([("apikey", "flat"), ("API-KEY", "kebab")]);
+            assert_eq!(env.getenv("api-key", "none"), "none");
+            let options = GetenvOptions::new()
+                .lookup_case(Case::Flat)
+                .lookup_case(Case::ScreamingKebab);
+            assert_eq!(env.getenv_with("api-key", "none", &options), "flat");
+        }
+
+        #[test]
+        fn test_env_snapshot_matches_getenv_with_digits() {
+            let names = [
+                "SNAPSHOT_DIGITS_S3BUCKET",
+                "snapshotDigitsS3bucket",
+                "SNAPSHOT_DIGITS_ABC123DEF",
+                "snapshot-digits-abc123def",
+                "SnapshotDigitsOauth2Token",
+            ];
+            let keys = [
+                "snapshotDigitsS3bucket",
+                "SNAPSHOT_DIGITS_S3BUCKET",
+                "snapshot-digits-s3-bucket",
+                "snapshot_digits_abc123def",
+                "SNAPSHOT_DIGITS_ABC123DEF",
+                "snapshot-digits-abc123-def",
+                "snapshotDigitsOauth2Token",
+                "SNAPSHOT_DIGITS_OAUTH2_TOKEN",
+            ];
+            for name in names {
+                env::set_var(name, "set");
+                let snapshot = EnvSnapshot::capture();
+                for key in keys {
+                    let expected = getenv(key, "none");
+                    assert_eq!(snapshot.getenv(key, "none"), expected, "{key} with {name}");
+                }
+                env::remove_var(name);
+            }
+        }
+    }
+}
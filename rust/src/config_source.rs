@@ -0,0 +1,97 @@
+//! A resolved [`Config`] as a source of the [config](https://docs.rs/config)
+//! crate. Requires the `config-rs` feature.
+
+use config_rs::{Map, Source, Value};
+
+use crate::config::Config;
+use crate::to_snake_case;
+
+/// Provides every resolved value, by its snake_case name, to a
+/// `config::Config`, so that an application built on the `config` crate can
+/// move to lino one layer at a time.
+///
+/// Values are strings, which the `config` crate converts to the types being
+/// deserialized, and each records the layer it was resolved from (such as
+/// `command line` or `.lenv file`) as its origin.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::make_config_from;
+///
+/// let lino = make_config_from(["app", "--port", "8080"], |c| {
+///     c.option("port", "Server port", "3000")
+///         .option("log-level", "Log level", "info")
+/// });
+/// let settings = config_rs::Config::builder()
+///     .set_default("port", 80)?
+///     .add_source(lino)
+///     .build()?;
+/// assert_eq!(settings.get::<u16>("port")?, 8080);
+/// assert_eq!(settings.get_string("log_level")?, "info");
+/// # Ok::<(), config_rs::ConfigError>(())
+/// ```
+impl Source for Config {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, config_rs::ConfigError> {
+        Ok(self
+            .keys()
+            .filter_map(|key| {
+                let (value, source) = self.resolved(key)?;
+                let origin = source.to_string();
+                Some((to_snake_case(key), Value::new(Some(&origin), value)))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use crate::make_config_from;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct App {
+        port: u16,
+        verbose: bool,
+        api_key: String,
+    }
+
+    #[test]
+    fn test_deserialize_values() {
+        let lino = make_config_from(["app", "--port", "8080", "--verbose"], |c| {
+            c.option("port", "Server port", "3000")
+                .flag("verbose", "Verbose output")
+                .option("api-key", "API key", "1234")
+        });
+        let settings = config_rs::Config::builder()
+            .add_source(lino)
+            .build()
+            .unwrap();
+        assert_eq!(
+            settings.try_deserialize::<App>().unwrap(),
+            App {
+                port: 8080,
+                verbose: true,
+                api_key: "1234".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_values_record_their_layer() {
+        let lino = make_config_from(["app", "--port", "eighty"], |c| {
+            c.option("port", "Server port", "3000")
+        });
+        let settings = config_rs::Config::builder()
+            .add_source(lino)
+            .build()
+            .unwrap();
+        let err = settings.get::<u16>("port").unwrap_err();
+        assert!(err.to_string().contains("in command line"), "{err}");
+    }
+}
@@ -0,0 +1,396 @@
+//! Deserializing structs from the environment and the resolution layers,
+//! like [envy](https://docs.rs/envy).
+
+use std::ffi::OsString;
+use std::fmt;
+
+use serde::de::value::SeqDeserializer;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::config::{
+    parse_float, parse_int, try_make_config, try_make_config_from, Config, ConfigBuilder,
+    ConfigError, EnvSnapshot, GetenvOptions,
+};
+
+/// Deserialize a struct from the environment, looking up each field in
+/// every case, like [`getenv()`](crate::getenv): `api_key` is read from
+/// `API_KEY`, `apiKey`, `api-key`, and so on.
+///
+/// Values are parsed as the field's type: integers and floats accept digit
+/// separators, booleans are `true`/`false`, `1`/`0`, `yes`/`no` or
+/// `on`/`off`, sequences are comma-separated, and enums are unit variants by
+/// name. Fields that are not set are `None` if they are `Option`s, take
+/// their `#[serde(default)]`, or fail as missing.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Settings {
+///     doc_envy_port: u16,
+///     doc_envy_hosts: Vec<String>,
+///     doc_envy_token: Option<String>,
+/// }
+///
+/// std::env::set_var("DOC_ENVY_PORT", "8080");
+/// std::env::set_var("docEnvyHosts", "a.internal,b.internal");
+/// let settings: Settings = lino_arguments::from_env()?;
+/// assert_eq!(settings.doc_envy_port, 8080);
+/// assert_eq!(settings.doc_envy_hosts, ["a.internal", "b.internal"]);
+/// assert_eq!(settings.doc_envy_token, None);
+/// # Ok::<(), lino_arguments::ConfigError>(())
+/// ```
+pub fn from_env<T: DeserializeOwned>() -> Result<T, ConfigError> {
+    let (env, options) = (EnvSnapshot::capture(), GetenvOptions::default());
+    deserialize(|key| env.lookup(key, &options).map(str::to_string))
+}
+
+/// Resolve a configuration with the process arguments, like
+/// [`try_make_config()`](crate::try_make_config), and deserialize a struct
+/// from it.
+///
+/// Each field takes the resolved value of the option with its name, in any
+/// case, so the command line, `.lenv`, and `.env` layers and the defaults
+/// apply; fields without an option are read from the environment like
+/// [`from_env()`]. Values are parsed as by [`from_env()`].
+pub fn from_sources<T, F>(configure: F) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned,
+    F: FnOnce(&mut ConfigBuilder) -> &mut ConfigBuilder,
+{
+    from_config(&try_make_config(configure)?)
+}
+
+/// Same as [`from_sources()`] but with custom arguments.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Settings {
+///     port: u16,
+///     verbose: bool,
+/// }
+///
+/// let settings: Settings = lino_arguments::from_sources_from(["app", "--verbose"], |c| {
+///     c.option("port", "Server port", "3000")
+///         .flag("verbose", "Verbose output")
+/// })?;
+/// assert_eq!(settings.port, 3000);
+/// assert!(settings.verbose);
+/// # Ok::<(), lino_arguments::ConfigError>(())
+/// ```
+pub fn from_sources_from<T, I, A, F>(args: I, configure: F) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = A>,
+    A: Into<OsString>,
+    F: FnOnce(&mut ConfigBuilder) -> &mut ConfigBuilder,
+{
+    from_config(&try_make_config_from(args, configure)?)
+}
+
+/// A struct with the values of `config`, falling back to the environment.
+fn from_config<T: DeserializeOwned>(config: &Config) -> Result<T, ConfigError> {
+    let (env, options) = (EnvSnapshot::capture(), GetenvOptions::default());
+    deserialize(|key| {
+        if config.has(key) {
+            Some(config.get(key))
+        } else {
+            env.lookup(key, &options).map(str::to_string)
+        }
+    })
+}
+
+/// A struct with each field looked up by `lookup`.
+fn deserialize<T: DeserializeOwned>(
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<T, ConfigError> {
+    T::deserialize(Fields { lookup: &lookup }).map_err(|err| ConfigError::ParseError(err.0))
+}
+
+/// A failure to deserialize a value.
+#[derive(Debug)]
+struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+/// Deserializes a struct, looking up the values of its fields.
+struct Fields<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl<'de> de::Deserializer<'de> for Fields<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(de::Error::custom("only structs can be deserialized"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_map(FieldValues {
+            lookup: self.lookup,
+            fields: fields.iter(),
+            value: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// The fields of a struct that have a value.
+struct FieldValues<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+    fields: std::slice::Iter<'static, &'static str>,
+    value: Option<(&'static str, String)>,
+}
+
+impl<'de> MapAccess<'de> for FieldValues<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        for &field in self.fields.by_ref() {
+            if let Some(value) = (self.lookup)(field) {
+                self.value = Some((field, value));
+                return seed.deserialize(field.into_deserializer()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (field, value) = self.value.take().expect("value requested after its key");
+        seed.deserialize(Value(value))
+            .map_err(|err| Error(format!("{field}: {err}")))
+    }
+}
+
+/// Deserializes a single value as the type asked for.
+struct Value(String);
+
+impl Value {
+    fn int(&self) -> Result<i64, Error> {
+        parse_int(self.0.trim(), false)
+            .ok_or_else(|| Error(format!("invalid integer `{}`", self.0)))
+    }
+
+    fn float(&self) -> Result<f64, Error> {
+        parse_float(self.0.trim(), false)
+            .ok_or_else(|| Error(format!("invalid number `{}`", self.0)))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => visitor.visit_bool(true),
+            "false" | "0" | "no" | "off" => visitor.visit_bool(false),
+            _ => Err(Error(format!("invalid boolean `{}`", self.0))),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.int()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.int()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.int()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.int()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.int()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.int()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.int()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.int()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(self.float()?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(self.float()?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let items = self
+            .0
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| Value(item.to_string()));
+        SeqDeserializer::new(items).deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Level {
+        Debug,
+        Info,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Port(u16);
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Settings {
+        port: Port,
+        ratio: f32,
+        verbose: bool,
+        level: Level,
+        hosts: Vec<String>,
+        token: Option<String>,
+        #[serde(default)]
+        workers: usize,
+    }
+
+    fn from_map<T: DeserializeOwned>(values: &[(&str, &str)]) -> Result<T, ConfigError> {
+        let values: HashMap<_, _> = values.iter().copied().collect();
+        deserialize(|key| values.get(key).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn test_deserialize_field_types() {
+        let settings: Settings = from_map(&[
+            ("port", "8_080"),
+            ("ratio", "0.5"),
+            ("verbose", "Yes"),
+            ("level", "info"),
+            ("hosts", "a, b,"),
+        ])
+        .unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                port: Port(8080),
+                ratio: 0.5,
+                verbose: true,
+                level: Level::Info,
+                hosts: vec!["a".to_string(), "b".to_string()],
+                token: None,
+                workers: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_errors_name_the_field() {
+        let base = [
+            ("ratio", "1"),
+            ("verbose", "no"),
+            ("level", "debug"),
+            ("hosts", ""),
+        ];
+        let err = from_map::<Settings>(&[&base[..], &[("port", "eighty")]].concat()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parse error: port: invalid integer `eighty`"
+        );
+        let err = from_map::<Settings>(&[&base[..], &[("port", "70000")]].concat()).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Parse error: port: invalid value"));
+        let err = from_map::<Settings>(&base).unwrap_err();
+        assert_eq!(err.to_string(), "Parse error: missing field `port`");
+    }
+
+    #[test]
+    fn test_deserialize_only_structs() {
+        assert!(from_map::<HashMap<String, String>>(&[]).is_err());
+    }
+}
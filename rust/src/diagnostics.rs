@@ -0,0 +1,196 @@
+//! [miette](https://docs.rs/miette) diagnostics for configuration errors.
+//! Requires the `diagnostics` feature.
+//!
+//! Problems with values read from `.lenv` and `.env` files are rendered
+//! with the offending line of the file, the value underlined.
+
+use miette::{Diagnostic, NamedSource, Report, SourceSpan};
+use thiserror::Error;
+
+use crate::{ConfigError, Issue, Location};
+
+/// Every problem found in a configuration, each as its own diagnostic.
+#[derive(Debug, Error, Diagnostic)]
+#[error("Invalid configuration")]
+#[diagnostic(code(lino_arguments::invalid))]
+struct InvalidConfig {
+    #[related]
+    issues: Vec<IssueDiagnostic>,
+}
+
+/// A single [`Issue`], with the file it was found in as source code.
+#[derive(Debug, Error, Diagnostic)]
+#[error("{message}")]
+struct IssueDiagnostic {
+    message: String,
+    #[source_code]
+    file: Option<NamedSource<String>>,
+    #[label("{label}")]
+    span: Option<SourceSpan>,
+    label: String,
+    #[help]
+    hint: Option<String>,
+}
+
+impl IssueDiagnostic {
+    /// The file is not shown for issues that leave the value out, since it
+    /// is sensitive.
+    fn new(issue: &Issue) -> Self {
+        let location = issue.location.as_ref().filter(|_| issue.value.is_some());
+        let (file, span) = match location {
+            Some(location) => match std::fs::read_to_string(&location.file) {
+                Ok(text) => {
+                    let span = value_span(&text, location);
+                    (Some(NamedSource::new(&location.file, text)), span)
+                }
+                Err(_) => (None, None),
+            },
+            None => (None, None),
+        };
+        let unlocated = Issue {
+            location: None,
+            hint: None,
+            ..issue.clone()
+        };
+        Self {
+            message: unlocated.to_string(),
+            file,
+            span,
+            label: issue.message.clone(),
+            hint: issue.hint.clone(),
+        }
+    }
+}
+
+/// The bytes of `text` from `location` to the end of its line, ignoring
+/// trailing whitespace.
+fn value_span(text: &str, location: &Location) -> Option<SourceSpan> {
+    let mut offset = 0;
+    let line = text
+        .split_inclusive('\n')
+        .inspect(|line| offset += line.len())
+        .nth(location.line.checked_sub(1)?)?;
+    let start = offset - line.len();
+    let line = line.trim_end();
+    let column = line
+        .char_indices()
+        .nth(location.column.checked_sub(1)?)
+        .map_or(line.len(), |(index, _)| index);
+    Some(SourceSpan::new(
+        (start + column).into(),
+        line.len() - column,
+    ))
+}
+
+impl ConfigError {
+    /// The error as a miette [`Report`]. For an invalid configuration, each
+    /// issue becomes a related diagnostic showing the line of the config
+    /// file the value came from.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use lino_arguments::try_make_config;
+    ///
+    /// fn main() -> miette::Result<()> {
+    ///     let config = try_make_config(|c| c.lenv(".lenv"))?;
+    ///     println!("{}", config.get("port"));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn to_report(&self) -> Report {
+        match self {
+            ConfigError::Invalid(issues) => Report::new(InvalidConfig {
+                issues: issues.iter().map(IssueDiagnostic::new).collect(),
+            }),
+            other => Report::msg(other.to_string()),
+        }
+    }
+}
+
+impl From<ConfigError> for Report {
+    fn from(err: ConfigError) -> Self {
+        err.to_report()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Severity, ValueSource};
+
+    #[test]
+    fn test_value_span() {
+        let text = "# comment\nPORT: 70000  \r\nNAME: été\n";
+        let at = |line, column| Location {
+            file: ".lenv".to_string(),
+            line,
+            column,
+        };
+        let slice = |span: SourceSpan| &text[span.offset()..span.offset() + span.len()];
+        assert_eq!(slice(value_span(text, &at(2, 7)).unwrap()), "70000");
+        assert_eq!(slice(value_span(text, &at(3, 7)).unwrap()), "été");
+        assert_eq!(value_span(text, &at(3, 20)).unwrap().len(), 0);
+        assert!(value_span(text, &at(9, 1)).is_none());
+        assert!(value_span(text, &at(0, 1)).is_none());
+    }
+
+    #[test]
+    fn test_issue_diagnostic() {
+        let dir = std::env::temp_dir().join("lino-arguments-test-diagnostics");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(".lenv");
+        std::fs::write(&path, "PORT: 70000\n").unwrap();
+        let issue = Issue {
+            key: "PORT".to_string(),
+            value: Some("70000".to_string()),
+            source: Some(ValueSource::LenvFile),
+            location: Some(Location {
+                file: path.to_str().unwrap().to_string(),
+                line: 1,
+                column: 7,
+            }),
+            message: "must be at most 65535".to_string(),
+            hint: Some("lower it".to_string()),
+            severity: Severity::Error,
+        };
+
+        let diagnostic = IssueDiagnostic::new(&issue);
+        assert_eq!(
+            diagnostic.to_string(),
+            "`PORT` must be at most 65535 (got \"70000\" from the .lenv file)"
+        );
+        assert_eq!(diagnostic.span, Some(SourceSpan::new(6.into(), 5)));
+        let label = diagnostic.labels().unwrap().next().unwrap();
+        assert_eq!(label.label(), Some("must be at most 65535"));
+        assert_eq!(diagnostic.help().unwrap().to_string(), "lower it");
+
+        let report = ConfigError::Invalid(vec![issue.clone()]).to_report();
+        assert_eq!(report.to_string(), "Invalid configuration");
+        assert_eq!(report.related().unwrap().count(), 1);
+
+        let missing = Issue {
+            location: Some(Location {
+                file: dir.join("missing").to_str().unwrap().to_string(),
+                line: 1,
+                column: 7,
+            }),
+            ..issue.clone()
+        };
+        let diagnostic = IssueDiagnostic::new(&missing);
+        assert!(diagnostic.file.is_none() && diagnostic.span.is_none());
+
+        let sensitive = Issue {
+            value: None,
+            ..issue.clone()
+        };
+        let diagnostic = IssueDiagnostic::new(&sensitive);
+        assert!(diagnostic.file.is_none() && diagnostic.span.is_none());
+    }
+
+    #[test]
+    fn test_other_errors_keep_their_message() {
+        let report = ConfigError::ParseError("bad".to_string()).to_report();
+        assert_eq!(report.to_string(), "Parse error: bad");
+    }
+}
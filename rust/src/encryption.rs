@@ -0,0 +1,155 @@
+//! Inline `enc:` values encrypted with [age](https://age-encryption.org).
+//! Requires the `age` feature.
+//!
+//! An encrypted value is `enc:` followed by the base64 of the binary age
+//! ciphertext, so it fits on one line of a `.lenv` or `.env` file next to
+//! plaintext values.
+
+use std::io::Read;
+
+use age::x25519::{Identity, Recipient};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::config::ConfigError;
+
+/// The prefix marking an encrypted value.
+pub(crate) const PREFIX: &str = "enc:";
+
+/// Encrypt `value` to the age `recipient` (`age1...`), producing an
+/// `enc:` value that [`ConfigBuilder::age_identity()`] decrypts when the
+/// configuration is resolved. Requires the `age` feature.
+///
+/// [`ConfigBuilder::age_identity()`]: crate::ConfigBuilder::age_identity
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::encrypt_value;
+///
+/// let recipient = "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p";
+/// let value = encrypt_value(recipient, "sk-live-1234").unwrap();
+/// assert!(value.starts_with("enc:"));
+/// ```
+pub fn encrypt_value(recipient: &str, value: &str) -> Result<String, ConfigError> {
+    let recipient: Recipient = recipient
+        .trim()
+        .parse()
+        .map_err(|err| ConfigError::ParseError(format!("invalid age recipient: {err}")))?;
+    let ciphertext = age::encrypt(&recipient, value.as_bytes())
+        .map_err(|err| ConfigError::ParseError(format!("encryption failed: {err}")))?;
+    Ok(format!("{PREFIX}{}", STANDARD.encode(ciphertext)))
+}
+
+/// Decrypt an `enc:` value, such as one made by [`encrypt_value()`], with
+/// the age identities in the file at `identity_path`, as written by
+/// `age-keygen`. Requires the `age` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::decrypt_value;
+///
+/// let token = decrypt_value("/etc/myapp/key.txt", "enc:YWdlLWVuY3J5cHRpb24...").unwrap();
+/// ```
+pub fn decrypt_value(identity_path: &str, value: &str) -> Result<String, ConfigError> {
+    decrypt_with(&read_identities(identity_path)?, value).map_err(|reason| {
+        ConfigError::ParseError(format!("value could not be decrypted: {reason}"))
+    })
+}
+
+/// Decrypt an `enc:` value with the first of `identities` it was encrypted
+/// to.
+pub(crate) fn decrypt_with(identities: &[Identity], value: &str) -> Result<String, String> {
+    let encoded = value
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| format!("does not start with `{PREFIX}`"))?;
+    let ciphertext = STANDARD
+        .decode(encoded.trim())
+        .map_err(|err| format!("is not valid base64: {err}"))?;
+    let decryptor = age::Decryptor::new_buffered(&ciphertext[..]).map_err(|err| err.to_string())?;
+    let mut reader = decryptor
+        .decrypt(
+            identities
+                .iter()
+                .map(|identity| identity as &dyn age::Identity),
+        )
+        .map_err(|err| err.to_string())?;
+    let mut plaintext = String::new();
+    reader
+        .read_to_string(&mut plaintext)
+        .map_err(|err| err.to_string())?;
+    Ok(plaintext)
+}
+
+/// Read the age identities (`AGE-SECRET-KEY-1...`) of an identity file, as
+/// written by `age-keygen`. Comment and blank lines are skipped.
+pub(crate) fn read_identities(path: &str) -> Result<Vec<Identity>, ConfigError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| ConfigError::FileError(format!("Failed to read {path}: {err}")))?;
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.parse().map_err(|err| {
+                ConfigError::FileError(format!("Invalid age identity in {path}: {err}"))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use age::secrecy::ExposeSecret;
+
+    #[test]
+    fn test_round_trip() {
+        let identity = Identity::generate();
+        let recipient = identity.to_public().to_string();
+        let value = encrypt_value(&recipient, "sk-live-1234").unwrap();
+        assert!(!value.contains("sk-live-1234"));
+        assert_eq!(
+            decrypt_with(&[Identity::generate(), identity.clone()], &value).unwrap(),
+            "sk-live-1234"
+        );
+        assert!(decrypt_with(&[Identity::generate()], &value).is_err());
+        assert!(decrypt_with(std::slice::from_ref(&identity), "enc:???").is_err());
+        assert!(decrypt_with(&[identity], "plain").is_err());
+        assert!(encrypt_value("age1nope", "x").is_err());
+    }
+
+    #[test]
+    fn test_read_identities() {
+        let dir = std::env::temp_dir().join("lino-arguments-test-identities");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("key.txt");
+        let identity = Identity::generate();
+        std::fs::write(
+            &path,
+            format!(
+                "# created: now\n# public key: {}\n{}\n",
+                identity.to_public(),
+                identity.to_string().expose_secret()
+            ),
+        )
+        .unwrap();
+        let identities = read_identities(path.to_str().unwrap()).unwrap();
+        assert_eq!(identities.len(), 1);
+        assert_eq!(
+            identities[0].to_public().to_string(),
+            identity.to_public().to_string()
+        );
+
+        let value = encrypt_value(&identity.to_public().to_string(), "sk-live-1234").unwrap();
+        assert_eq!(
+            decrypt_value(path.to_str().unwrap(), &value).unwrap(),
+            "sk-live-1234"
+        );
+        assert!(decrypt_value(path.to_str().unwrap(), "enc:???").is_err());
+
+        std::fs::write(&path, "AGE-SECRET-KEY-NOPE\n").unwrap();
+        assert!(read_identities(path.to_str().unwrap()).is_err());
+        assert!(read_identities(dir.join("missing").to_str().unwrap()).is_err());
+    }
+}
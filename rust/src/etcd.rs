@@ -0,0 +1,163 @@
+//! etcd v3 key-value store.
+//!
+//! Keys are read and watched with `etcdctl`, so endpoints, TLS, and
+//! authentication can also be configured through its `ETCDCTL_*`
+//! environment variables.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::Value;
+
+use crate::config::ConfigError;
+use crate::source::{key_below, Source, Watcher};
+
+/// A [`Source`] reading every key under a prefix of an etcd v3 cluster
+/// through `etcdctl`, keyed by its name below the prefix
+/// (`/myapp/db/password` under `/myapp/` sets `db-password`).
+///
+/// The source can be [watched](Source::watch) for changes under the prefix.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::{make_config, EtcdSource};
+///
+/// let config = make_config(|c| {
+///     c.source(EtcdSource::new("/myapp/").endpoints(["http://etcd:2379"]))
+///         .option("db-password", "Database password", "")
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct EtcdSource {
+    prefix: String,
+    endpoints: Vec<String>,
+}
+
+impl EtcdSource {
+    /// Every key under `prefix`.
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            endpoints: Vec::new(),
+        }
+    }
+
+    /// The cluster endpoints, instead of `ETCDCTL_ENDPOINTS` or
+    /// `127.0.0.1:2379`.
+    pub fn endpoints<I, S>(mut self, endpoints: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.endpoints = endpoints.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// `etcdctl` with the endpoints and `args`, printing JSON.
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new("etcdctl");
+        if !self.endpoints.is_empty() {
+            command.arg(format!("--endpoints={}", self.endpoints.join(",")));
+        }
+        command
+            .args(args)
+            .args(["--prefix", &self.prefix, "-w", "json"]);
+        command
+    }
+}
+
+impl Source for EtcdSource {
+    fn name(&self) -> &'static str {
+        "etcd"
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        let output = self
+            .command(&["get"])
+            .output()
+            .map_err(|err| ConfigError::SourceError(format!("could not run etcdctl: {err}")))?;
+        if !output.status.success() {
+            return Err(ConfigError::SourceError(format!(
+                "etcdctl get failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let response: Value = serde_json::from_slice(&output.stdout)
+            .map_err(|err| ConfigError::SourceError(format!("invalid etcdctl output: {err}")))?;
+        Ok(parse_kvs(&response, &self.prefix))
+    }
+
+    /// Run `etcdctl watch` on the prefix, calling `changed` for every
+    /// response it prints.
+    fn watch(
+        &self,
+        changed: Box<dyn Fn() + Send + 'static>,
+    ) -> Result<Option<Watcher>, ConfigError> {
+        let mut child = self
+            .command(&["watch"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| ConfigError::SourceError(format!("could not run etcdctl: {err}")))?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                match line {
+                    Ok(line) if !line.trim().is_empty() => changed(),
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+        });
+        let child: Arc<Mutex<Child>> = Arc::new(Mutex::new(child));
+        Ok(Some(Watcher::new(move || {
+            let mut child = child.lock().unwrap_or_else(|err| err.into_inner());
+            let _ = child.kill();
+            let _ = child.wait();
+        })))
+    }
+}
+
+/// The key-value pairs of an etcd range response, whose keys and values
+/// are base64, keyed by their names below `prefix`.
+fn parse_kvs(response: &Value, prefix: &str) -> HashMap<String, String> {
+    let decode = |value: &Value| {
+        let bytes = STANDARD.decode(value.as_str()?).ok()?;
+        String::from_utf8(bytes).ok()
+    };
+    response["kvs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|kv| {
+            let key = decode(&kv["key"])?;
+            let value = decode(&kv["value"]).unwrap_or_default();
+            Some((key_below(&key, prefix), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kvs() {
+        // `/myapp/db/password` = `hunter2`, `/myapp/PORT` = `8080`
+        let response = serde_json::json!({"count": 2, "kvs": [
+            {"key": "L215YXBwL2RiL3Bhc3N3b3Jk", "value": "aHVudGVyMg=="},
+            {"key": "L215YXBwL1BPUlQ=", "value": "ODA4MA=="},
+            {"key": "not base64"},
+        ]});
+        let values = parse_kvs(&response, "/myapp/");
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["db-password"], "hunter2");
+        assert_eq!(values["port"], "8080");
+        assert!(parse_kvs(&serde_json::json!({"count": 0}), "/myapp/").is_empty());
+    }
+}
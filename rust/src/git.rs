@@ -0,0 +1,233 @@
+//! Config files kept in git repositories.
+//!
+//! Repositories are cloned and fetched with `git`, so credentials come from
+//! its usual configuration (SSH keys, credential helpers, ...).
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+use crate::canonical_key;
+use crate::config::{parse_lenv_entries, ConfigError};
+use crate::source::{flatten_json_text, Source};
+
+/// A [`Source`] reading a config file from a git repository at a branch,
+/// tag, or commit, for configuration managed GitOps-style.
+///
+/// Remote repositories are cloned on first use and fetched on every load;
+/// local checkouts are read in place. The file is read from the commit the
+/// ref points at, not from the working tree, and that commit is reported by
+/// [`Config::revision()`](crate::Config::revision) for the values it sets.
+///
+/// `.json` files set a key for each field (nested fields joined with `_`),
+/// `.env` files are parsed as by [`ConfigBuilder::env()`], and any other
+/// file as a `.lenv` file.
+///
+/// [`ConfigBuilder::env()`]: crate::ConfigBuilder::env
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::{make_config, GitSource};
+///
+/// let config = make_config(|c| {
+///     c.source(
+///         GitSource::new("https://github.com/acme/config.git", "myapp/production.lenv")
+///             .reference("release"),
+///     )
+///     .option("log-level", "Log level", "info")
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct GitSource {
+    repository: String,
+    file: String,
+    reference: String,
+    checkout: Option<String>,
+    /// The commit of the last load.
+    commit: Arc<Mutex<Option<String>>>,
+}
+
+impl GitSource {
+    /// The config file at `file` in `repository`, a URL or the path of a
+    /// local checkout, at `HEAD`.
+    pub fn new(repository: &str, file: &str) -> Self {
+        Self {
+            repository: repository.to_string(),
+            file: file.to_string(),
+            reference: "HEAD".to_string(),
+            checkout: None,
+            commit: Arc::default(),
+        }
+    }
+
+    /// The branch, tag, or commit to read the file from, instead of `HEAD`.
+    pub fn reference(mut self, reference: &str) -> Self {
+        self.reference = reference.to_string();
+        self
+    }
+
+    /// Where to clone a remote repository, instead of a directory under the
+    /// system's temporary directory.
+    pub fn checkout(mut self, path: &str) -> Self {
+        self.checkout = Some(path.to_string());
+        self
+    }
+
+    /// The git directory to read from, cloned or brought up to date with the
+    /// remote repository.
+    fn sync(&self) -> Result<PathBuf, ConfigError> {
+        let local = Path::new(&self.repository);
+        if local.is_dir() {
+            return Ok(local.to_path_buf());
+        }
+        let dir = match self.checkout {
+            Some(ref path) => PathBuf::from(path),
+            None => {
+                let mut hasher = DefaultHasher::new();
+                self.repository.hash(&mut hasher);
+                std::env::temp_dir().join(format!("lino-arguments-git-{:x}", hasher.finish()))
+            }
+        };
+        if dir.exists() {
+            git(
+                &dir,
+                &[
+                    "fetch",
+                    "--quiet",
+                    "--prune",
+                    "origin",
+                    "+refs/heads/*:refs/heads/*",
+                    "+refs/tags/*:refs/tags/*",
+                ],
+            )?;
+        } else {
+            let dir = dir.to_string_lossy();
+            git(
+                Path::new("."),
+                &["clone", "--quiet", "--bare", &self.repository, &dir],
+            )?;
+        }
+        Ok(dir)
+    }
+}
+
+impl Source for GitSource {
+    fn name(&self) -> &'static str {
+        "git repository"
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        self.fetch(None)
+    }
+
+    fn load_keys(&self, keys: &HashSet<String>) -> Result<HashMap<String, String>, ConfigError> {
+        self.fetch(Some(keys))
+    }
+
+    fn revision(&self) -> Option<String> {
+        self.commit
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+}
+
+impl GitSource {
+    /// Fetch the file at the reference and parse its values, only for
+    /// `keys` if given.
+    fn fetch(
+        &self,
+        keys: Option<&HashSet<String>>,
+    ) -> Result<HashMap<String, String>, ConfigError> {
+        let dir = self.sync()?;
+        let commit = git(
+            &dir,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", self.reference),
+            ],
+        )
+        .map_err(|_| {
+            ConfigError::SourceError(format!("{} has no ref {}", self.repository, self.reference))
+        })?;
+        let commit = commit.trim().to_string();
+        let text = git(&dir, &["show", &format!("{commit}:{}", self.file)])?;
+        let values = parse_file(&self.file, &text, keys)?;
+        *self.commit.lock().unwrap_or_else(|err| err.into_inner()) = Some(commit);
+        Ok(values)
+    }
+}
+
+/// Run `git` in `dir` with `args`, returning its output.
+fn git(dir: &Path, args: &[&str]) -> Result<String, ConfigError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|err| ConfigError::SourceError(format!("could not run git: {err}")))?;
+    if !output.status.success() {
+        return Err(ConfigError::SourceError(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The values of the config file `file`, by the format its extension names.
+/// JSON documents are only parsed as far as `keys` need, if given.
+fn parse_file(
+    file: &str,
+    text: &str,
+    keys: Option<&HashSet<String>>,
+) -> Result<HashMap<String, String>, ConfigError> {
+    let mut values = HashMap::new();
+    match Path::new(file).extension().and_then(|ext| ext.to_str()) {
+        Some("json") => {
+            flatten_json_text(text, keys, &mut values)
+                .map_err(|err| ConfigError::FileError(format!("Failed to parse {file}: {err}")))?;
+        }
+        Some("env") => {
+            for item in dotenvy::from_read_iter(text.as_bytes()) {
+                let (key, value) = item.map_err(|err| {
+                    ConfigError::FileError(format!("Failed to parse {file}: {err}"))
+                })?;
+                values.insert(canonical_key(&key).into_owned(), value);
+            }
+        }
+        _ => {
+            for entry in parse_lenv_entries(text) {
+                values.insert(canonical_key(&entry.key).into_owned(), entry.value);
+            }
+        }
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_file() {
+        let lenv =
+            parse_file("prod.lenv", "# comment\nLOG_LEVEL: debug\nPORT: 80\n", None).unwrap();
+        assert_eq!(lenv["log-level"], "debug");
+        assert_eq!(lenv["port"], "80");
+
+        let env = parse_file("prod.env", "LOG_LEVEL=\"debug\"\n", None).unwrap();
+        assert_eq!(env["log-level"], "debug");
+
+        let json = parse_file("prod.json", r#"{"log": {"level": "debug"}}"#, None).unwrap();
+        assert_eq!(json["log-level"], "debug");
+        assert!(parse_file("prod.json", "{", None).is_err());
+    }
+}
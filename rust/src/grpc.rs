@@ -0,0 +1,205 @@
+//! Configuration services speaking the `lino.config.v1.ConfigService` gRPC
+//! protocol.
+//!
+//! Calls are made with `grpcurl`, which is given the protocol definition,
+//! so services do not need to enable server reflection.
+
+use std::collections::HashMap;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+use crate::canonical_key;
+use crate::config::ConfigError;
+use crate::source::{Source, Watcher};
+
+/// The protocol definition of the configuration service read by
+/// [`GrpcSource`], for services to implement.
+pub const CONFIG_SERVICE_PROTO: &str = include_str!("../proto/config_service.proto");
+
+/// The full name of the service in [`CONFIG_SERVICE_PROTO`].
+const SERVICE: &str = "lino.config.v1.ConfigService";
+
+/// A [`Source`] reading the values of an application from a configuration
+/// service implementing [`CONFIG_SERVICE_PROTO`], through `grpcurl`.
+///
+/// The source can be [watched](Source::watch) through the service's
+/// `WatchConfig` stream.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::{make_config, GrpcSource};
+///
+/// let source = GrpcSource::new("config.internal:443", "myapp")
+///     .profile("production")
+///     .header("authorization", "Bearer token");
+/// let config = make_config(|c| {
+///     c.source(source).option("log-level", "Log level", "info")
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct GrpcSource {
+    address: String,
+    application: String,
+    profile: String,
+    headers: Vec<String>,
+    plaintext: bool,
+}
+
+impl GrpcSource {
+    /// The values of `application` from the service at `address`
+    /// (`host:port`), over TLS.
+    pub fn new(address: &str, application: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            application: application.to_string(),
+            profile: String::new(),
+            headers: Vec::new(),
+            plaintext: false,
+        }
+    }
+
+    /// The profile to ask for (`production`), instead of the default.
+    pub fn profile(mut self, profile: &str) -> Self {
+        self.profile = profile.to_string();
+        self
+    }
+
+    /// Send the metadata `name: value` with every call.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push(format!("{name}: {value}"));
+        self
+    }
+
+    /// Connect without TLS.
+    pub fn plaintext(mut self) -> Self {
+        self.plaintext = true;
+        self
+    }
+
+    /// `grpcurl` calling `method` with the request for the application.
+    fn command(&self, method: &str) -> Result<Command, ConfigError> {
+        let request = serde_json::json!({
+            "application": self.application,
+            "profile": self.profile,
+        });
+        let mut command = Command::new("grpcurl");
+        if self.plaintext {
+            command.arg("-plaintext");
+        }
+        for header in &self.headers {
+            command.args(["-H", header]);
+        }
+        command
+            .arg("-import-path")
+            .arg(proto_dir()?)
+            .args(["-proto", "config_service.proto", "-d"])
+            .arg(request.to_string())
+            .arg(&self.address)
+            .arg(format!("{SERVICE}/{method}"));
+        Ok(command)
+    }
+}
+
+impl Source for GrpcSource {
+    fn name(&self) -> &'static str {
+        "config service"
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        let output = self
+            .command("GetConfig")?
+            .output()
+            .map_err(|err| ConfigError::SourceError(format!("could not run grpcurl: {err}")))?;
+        if !output.status.success() {
+            return Err(ConfigError::SourceError(format!(
+                "GetConfig failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let snapshot: Value = serde_json::from_slice(&output.stdout)
+            .map_err(|err| ConfigError::SourceError(format!("invalid grpcurl output: {err}")))?;
+        Ok(snapshot_values(&snapshot))
+    }
+
+    /// Call `WatchConfig`, calling `changed` for every snapshot after the
+    /// first, which holds the current values.
+    fn watch(
+        &self,
+        changed: Box<dyn Fn() + Send + 'static>,
+    ) -> Result<Option<Watcher>, ConfigError> {
+        let mut child = self
+            .command("WatchConfig")?
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| ConfigError::SourceError(format!("could not run grpcurl: {err}")))?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        std::thread::spawn(move || {
+            // grpcurl prints each message as an indented JSON object
+            let snapshots = serde_json::Deserializer::from_reader(stdout).into_iter::<Value>();
+            for snapshot in snapshots.skip(1) {
+                match snapshot {
+                    Ok(_) => changed(),
+                    Err(_) => break,
+                }
+            }
+        });
+        let child: Arc<Mutex<Child>> = Arc::new(Mutex::new(child));
+        Ok(Some(Watcher::new(move || {
+            let mut child = child.lock().unwrap_or_else(|err| err.into_inner());
+            let _ = child.kill();
+            let _ = child.wait();
+        })))
+    }
+}
+
+/// A directory holding [`CONFIG_SERVICE_PROTO`] for `grpcurl` to read.
+fn proto_dir() -> Result<std::path::PathBuf, ConfigError> {
+    let dir = std::env::temp_dir().join(format!("lino-arguments-proto-{}", std::process::id()));
+    let path = dir.join("config_service.proto");
+    if !path.exists() {
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(&path, CONFIG_SERVICE_PROTO)?;
+    }
+    Ok(dir)
+}
+
+/// The values of a `ConfigSnapshot`, by canonical key.
+fn snapshot_values(snapshot: &Value) -> HashMap<String, String> {
+    snapshot["values"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| {
+            Some((canonical_key(key).into_owned(), value.as_str()?.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_values() {
+        let snapshot = serde_json::json!({
+            "values": {"LOG_LEVEL": "debug", "port": "8080"},
+            "revision": "3",
+        });
+        let values = snapshot_values(&snapshot);
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["log-level"], "debug");
+        assert_eq!(values["port"], "8080");
+        // proto3 leaves out empty maps
+        assert!(snapshot_values(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_proto_declares_service() {
+        assert!(CONFIG_SERVICE_PROTO.contains("package lino.config.v1;"));
+        assert!(CONFIG_SERVICE_PROTO.contains("service ConfigService"));
+    }
+}
@@ -0,0 +1,321 @@
+//! The resolved values of a [`Config`](crate::Config), stored by interned
+//! canonical key, and a stack buffer for converting keys that are only
+//! looked up. Requires the `std` feature.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::RwLock;
+
+use crate::config::ValueSource;
+use crate::{write_case, Case};
+
+/// How many bytes of a converted key [`KeyBuf`] keeps on the stack.
+const KEY_BUF_LEN: usize = 64;
+
+/// A key converted to a case for a lookup or comparison, kept on the stack
+/// while it fits in [`KEY_BUF_LEN`] bytes and moved to the heap past it, so
+/// typical keys are converted without allocating. Conversions that are
+/// returned use [`convert_case()`](crate::convert_case) instead, which
+/// borrows the key when it is already in the case.
+pub(crate) struct KeyBuf {
+    bytes: [u8; KEY_BUF_LEN],
+    len: usize,
+    spilled: Option<String>,
+}
+
+impl KeyBuf {
+    pub(crate) fn new() -> Self {
+        Self {
+            bytes: [0; KEY_BUF_LEN],
+            len: 0,
+            spilled: None,
+        }
+    }
+
+    /// `key` converted to `case`.
+    pub(crate) fn convert(key: &str, case: Case) -> Self {
+        let mut buf = Self::new();
+        buf.write(key, case);
+        buf
+    }
+
+    /// Replace the contents with `key` converted to `case`.
+    pub(crate) fn write(&mut self, key: &str, case: Case) {
+        self.clear();
+        // Writing to a `KeyBuf` never fails
+        let _ = write_case(self, key, case);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.len = 0;
+        self.spilled = None;
+    }
+}
+
+impl Deref for KeyBuf {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self.spilled {
+            Some(ref spilled) => spilled,
+            // Only whole strings are copied in, so the bytes are UTF-8
+            None => std::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Write for KeyBuf {
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        match self.bytes.get_mut(self.len) {
+            Some(byte) if c.is_ascii() && self.spilled.is_none() => {
+                *byte = c as u8;
+                self.len += 1;
+                Ok(())
+            }
+            _ => self.write_str(c.encode_utf8(&mut [0; 4])),
+        }
+    }
+
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if let Some(ref mut spilled) = self.spilled {
+            spilled.push_str(s);
+        } else if end <= KEY_BUF_LEN {
+            self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+        } else {
+            let mut spilled = String::with_capacity(end * 2);
+            spilled.push_str(&self[..]);
+            spilled.push_str(s);
+            self.spilled = Some(spilled);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for KeyBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self[..], f)
+    }
+}
+
+/// How many spellings other than the canonical keys a [`KeyTable`]
+/// remembers, so looking up arbitrary names cannot grow it without bound.
+const SPELLING_LIMIT: usize = 1024;
+
+/// The position of a canonical key in a [`KeyTable`].
+type KeyId = u32;
+
+/// Values by canonical key, each interned once to a [`KeyId`] indexing the
+/// values. A key spelled canonically (`port`, `api-key`) is found with a
+/// single hash of the spelling. Other spellings (`apiKey`, `API_KEY`) are
+/// converted the first time and remembered, so repeated lookups neither
+/// convert nor hash the canonical key again.
+pub(crate) struct KeyTable {
+    ids: HashMap<Box<str>, KeyId>,
+    entries: Vec<Entry>,
+    spellings: RwLock<HashMap<Box<str>, Option<KeyId>>>,
+}
+
+struct Entry {
+    key: Box<str>,
+    value: String,
+    source: ValueSource,
+}
+
+impl KeyTable {
+    /// The id of the key spelled `spelling` in any case.
+    fn id(&self, spelling: &str) -> Option<KeyId> {
+        if let Some(&id) = self.ids.get(spelling) {
+            return Some(id);
+        }
+        let spellings = self
+            .spellings
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(&id) = spellings.get(spelling) {
+            return id;
+        }
+        drop(spellings);
+        let id = self
+            .ids
+            .get(&*KeyBuf::convert(spelling, Case::Kebab))
+            .copied();
+        let mut spellings = self
+            .spellings
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if spellings.len() < SPELLING_LIMIT {
+            spellings.insert(spelling.into(), id);
+        }
+        id
+    }
+
+    /// The value of the key spelled `spelling` and its source.
+    pub(crate) fn get(&self, spelling: &str) -> Option<(&str, ValueSource)> {
+        let entry = &self.entries[self.id(spelling)? as usize];
+        Some((&entry.value, entry.source))
+    }
+
+    /// The source of the value of the key spelled `spelling`.
+    pub(crate) fn source(&self, spelling: &str) -> Option<ValueSource> {
+        Some(self.entries[self.id(spelling)? as usize].source)
+    }
+
+    /// Whether the key spelled `spelling` has a value.
+    pub(crate) fn contains(&self, spelling: &str) -> bool {
+        self.id(spelling).is_some()
+    }
+
+    /// Every canonical key with its value and source, in the order they
+    /// were interned.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, &str, ValueSource)> {
+        self.entries
+            .iter()
+            .map(|entry| (&*entry.key, entry.value.as_str(), entry.source))
+    }
+
+    /// Like [`iter()`](Self::iter), with the values mutable.
+    #[cfg_attr(not(any(feature = "age", feature = "zeroize")), allow(dead_code))]
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut String, ValueSource)> {
+        self.entries
+            .iter_mut()
+            .map(|entry| (&*entry.key, &mut entry.value, entry.source))
+    }
+}
+
+/// A table of `(canonical key, value, source)`; a repeated key keeps its
+/// first value.
+impl FromIterator<(String, String, ValueSource)> for KeyTable {
+    fn from_iter<I: IntoIterator<Item = (String, String, ValueSource)>>(iter: I) -> Self {
+        let mut ids = HashMap::new();
+        let mut entries = Vec::new();
+        for (key, value, source) in iter {
+            let key = key.into_boxed_str();
+            if ids.contains_key(&key) {
+                continue;
+            }
+            ids.insert(key.clone(), entries.len() as KeyId);
+            entries.push(Entry { key, value, source });
+        }
+        Self {
+            ids,
+            entries,
+            spellings: RwLock::default(),
+        }
+    }
+}
+
+impl Clone for KeyTable {
+    fn clone(&self) -> Self {
+        let spellings = self
+            .spellings
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        Self {
+            ids: self.ids.clone(),
+            entries: self
+                .entries
+                .iter()
+                .map(|entry| Entry {
+                    key: entry.key.clone(),
+                    value: entry.value.clone(),
+                    source: entry.source,
+                })
+                .collect(),
+            spellings: RwLock::new(spellings),
+        }
+    }
+}
+
+impl fmt::Debug for KeyTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.iter()
+                    .map(|(key, value, source)| (key, (value, source))),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write;
+
+    #[test]
+    fn test_key_buf_converts_on_the_stack() {
+        let buf = KeyBuf::convert("maxConnectionsPerHost", Case::UpperSnake);
+        assert_eq!(&*buf, "MAX_CONNECTIONS_PER_HOST");
+        assert!(buf.spilled.is_none());
+        let mut buf = KeyBuf::convert("", Case::Kebab);
+        assert_eq!(&*buf, "");
+        buf.write("ÜberGröße", Case::Snake);
+        assert_eq!(&*buf, "über_größe");
+    }
+
+    #[test]
+    fn test_key_buf_spills_long_keys() {
+        let key = "aVeryLongKeyName".repeat(8);
+        let mut buf = KeyBuf::convert(&key, Case::Kebab);
+        assert!(buf.spilled.is_some());
+        assert_eq!(&*buf, crate::convert_case(&key, Case::Kebab));
+        buf.write_str("-end").unwrap();
+        assert!(buf.ends_with("name-end"));
+        buf.write("apiKey", Case::Kebab);
+        assert!(buf.spilled.is_none());
+        assert_eq!(&*buf, "api-key");
+    }
+
+    fn table() -> KeyTable {
+        [
+            ("port", "3000", ValueSource::Cli),
+            ("api-key", "secret", ValueSource::Environment),
+            ("port", "4000", ValueSource::Default),
+        ]
+        .into_iter()
+        .map(|(key, value, source)| (key.to_string(), value.to_string(), source))
+        .collect()
+    }
+
+    #[test]
+    fn test_key_table_finds_any_spelling() {
+        let table = table();
+        for spelling in ["api-key", "apiKey", "API_KEY", "apiKey"] {
+            assert_eq!(
+                table.get(spelling),
+                Some(("secret", ValueSource::Environment))
+            );
+        }
+        assert_eq!(table.get("port"), Some(("3000", ValueSource::Cli)));
+        assert!(!table.contains("host"));
+        assert!(!table.contains("host"));
+        let spellings = table.spellings.read().unwrap();
+        assert_eq!(spellings.len(), 3);
+        assert_eq!(spellings.get("host"), Some(&None));
+    }
+
+    #[test]
+    fn test_key_table_remembers_bounded_spellings() {
+        let table = table();
+        for index in 0..SPELLING_LIMIT + 10 {
+            assert!(!table.contains(&format!("missingKey{index}")));
+        }
+        assert_eq!(table.spellings.read().unwrap().len(), SPELLING_LIMIT);
+        assert_eq!(table.source("API_KEY"), Some(ValueSource::Environment));
+    }
+
+    #[test]
+    fn test_key_table_iterates_in_order() {
+        let mut table = table();
+        for (_, value, _) in table.iter_mut() {
+            value.push('!');
+        }
+        let keys: Vec<(&str, &str)> = table.iter().map(|(key, value, _)| (key, value)).collect();
+        assert_eq!(keys, [("port", "3000!"), ("api-key", "secret!")]);
+        assert_eq!(format!("{:?}", table.clone()), format!("{table:?}"));
+    }
+}
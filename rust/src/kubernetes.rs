@@ -0,0 +1,160 @@
+//! Kubernetes ConfigMap volumes and Downward API files.
+
+use std::collections::HashMap;
+
+use crate::canonical_key;
+use crate::config::{read_secrets_dir, ConfigError};
+use crate::source::Source;
+
+/// Where the Kubernetes documentation mounts ConfigMaps.
+const CONFIG_MAP_DIR: &str = "/etc/config";
+
+/// Where the Kubernetes documentation mounts Downward API volumes.
+const DOWNWARD_API_DIR: &str = "/etc/podinfo";
+
+/// The variable Kubernetes sets in every container.
+const SERVICE_HOST: &str = "KUBERNETES_SERVICE_HOST";
+
+/// A [`Source`] reading the volumes Kubernetes mounts into a pod:
+/// ConfigMaps, one value per file named after its key, and Downward API
+/// files such as the pod's name and labels.
+///
+/// Downward API `labels` and `annotations` files set one key per entry,
+/// prefixed with the file name (`app="web"` in `labels` sets `labels-app`);
+/// other files set the key they are named after. Directories that are not
+/// mounted are skipped, so the source can be added unconditionally. Values
+/// the Downward API exposes as environment variables are read with the
+/// rest of the environment.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::{make_config, KubernetesSource};
+///
+/// // ConfigMaps at /etc/config, Downward API at /etc/podinfo
+/// let config = make_config(|c| {
+///     c.source(KubernetesSource::new())
+///         .option("log-level", "Log level", "info")
+///         .option("pod-name", "Pod name", "")
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct KubernetesSource {
+    config_maps: Vec<String>,
+    downward_api: Vec<String>,
+}
+
+impl KubernetesSource {
+    /// The ConfigMap and Downward API volumes at the paths used throughout
+    /// the Kubernetes documentation, `/etc/config` and `/etc/podinfo`.
+    pub fn new() -> Self {
+        Self::empty()
+            .config_map(CONFIG_MAP_DIR)
+            .downward_api(DOWNWARD_API_DIR)
+    }
+
+    /// No volumes; add them with [`config_map()`](Self::config_map) and
+    /// [`downward_api()`](Self::downward_api).
+    pub fn empty() -> Self {
+        Self {
+            config_maps: Vec::new(),
+            downward_api: Vec::new(),
+        }
+    }
+
+    /// Read a ConfigMap mounted at `path`. When several ConfigMaps have a
+    /// key, the first one added wins.
+    pub fn config_map(mut self, path: &str) -> Self {
+        self.config_maps.push(path.to_string());
+        self
+    }
+
+    /// Read a Downward API volume mounted at `path`.
+    pub fn downward_api(mut self, path: &str) -> Self {
+        self.downward_api.push(path.to_string());
+        self
+    }
+
+    /// Whether the process runs in a Kubernetes pod.
+    pub fn in_cluster() -> bool {
+        std::env::var_os(SERVICE_HOST).is_some()
+    }
+}
+
+impl Default for KubernetesSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Source for KubernetesSource {
+    fn name(&self) -> &'static str {
+        "Kubernetes volume"
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        let mut values = HashMap::new();
+        for dir in &self.config_maps {
+            for (key, value) in read_secrets_dir(dir) {
+                values.entry(key).or_insert(value);
+            }
+        }
+        for dir in &self.downward_api {
+            for (key, value) in read_secrets_dir(dir) {
+                if key == "labels" || key == "annotations" {
+                    for (name, value) in parse_labels(&value) {
+                        let key = canonical_key(&format!("{key}_{name}")).into_owned();
+                        values.entry(key).or_insert(value);
+                    }
+                } else {
+                    values.entry(key).or_insert(value);
+                }
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// The `key="value"` lines of a Downward API `labels` or `annotations`
+/// file. Values are quoted like Go strings.
+fn parse_labels(text: &str) -> Vec<(&str, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let (name, quoted) = line.split_once('=')?;
+            let quoted = quoted.trim().strip_prefix('"')?.strip_suffix('"')?;
+            let mut value = String::new();
+            let mut chars = quoted.chars();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    value.push(c);
+                    continue;
+                }
+                match chars.next()? {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    escaped => value.push(escaped),
+                }
+            }
+            Some((name.trim(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_labels() {
+        let labels =
+            parse_labels("app=\"web\"\ntier=\"front\\\"end\"\nnote=\"a\\nb\"\nbroken=unquoted\n");
+        assert_eq!(
+            labels,
+            [
+                ("app", "web".to_string()),
+                ("tier", "front\"end".to_string()),
+                ("note", "a\nb".to_string()),
+            ]
+        );
+    }
+}
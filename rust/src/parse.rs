@@ -0,0 +1,268 @@
+//! Parsers for durations, byte sizes, lists, and maps, shared by the
+//! `getenv_*` helpers and clap.
+//!
+//! Every parser is a `fn(&str) -> Result<T, ConfigError>`, which clap
+//! accepts as a value parser, so values given on the command line parse
+//! exactly like those read from the environment.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::Duration;
+
+use crate::config::{lookup_env, ConfigError, GetenvOptions};
+use crate::trace;
+
+/// Parse a duration: a number followed by a unit (`ns`, `us`, `ms`, `s`,
+/// `m`, `h`, or `d`), or several of them (`1h30m`). A bare number is in
+/// seconds. Fractions are accepted (`1.5s`).
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use lino_arguments::parse_duration;
+///
+/// assert_eq!(parse_duration("250ms")?, Duration::from_millis(250));
+/// assert_eq!(parse_duration("1h 30m")?, Duration::from_secs(5400));
+/// assert_eq!(parse_duration("30")?, Duration::from_secs(30));
+/// assert!(parse_duration("soon").is_err());
+///
+/// // As a clap value parser
+/// #[derive(lino_arguments::Parser)]
+/// struct Args {
+///     #[arg(long, value_parser = parse_duration, default_value = "30s")]
+///     timeout: Duration,
+/// }
+/// # Ok::<(), lino_arguments::ConfigError>(())
+/// ```
+pub fn parse_duration(value: &str) -> Result<Duration, ConfigError> {
+    let invalid = || ConfigError::ParseError(format!("invalid duration `{value}`"));
+    let text = value.trim();
+    if text.is_empty() {
+        return Err(invalid());
+    }
+    if let Ok(seconds) = text.parse::<f64>() {
+        return Duration::try_from_secs_f64(seconds).map_err(|_| invalid());
+    }
+    let mut total = Duration::ZERO;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (number, after) = split_number(rest);
+        let (unit, after) = split_unit(after.trim_start());
+        let seconds = match unit {
+            "ns" => 1e-9,
+            "us" | "µs" => 1e-6,
+            "ms" => 1e-3,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            "d" => 86400.0,
+            _ => return Err(invalid()),
+        };
+        let number: f64 = number.parse().map_err(|_| invalid())?;
+        let part = Duration::try_from_secs_f64(number * seconds).map_err(|_| invalid())?;
+        total = total.checked_add(part).ok_or_else(invalid)?;
+        rest = after.trim_start();
+    }
+    Ok(total)
+}
+
+/// Parse a size in bytes: a number with an optional unit. `KB`, `MB`, `GB`,
+/// and `TB` are powers of 1000, and `KiB`, `MiB`, `GiB`, and `TiB`, like the
+/// single letters `K`, `M`, `G`, and `T`, powers of 1024. Units are not
+/// case-sensitive, and fractions are accepted (`1.5GB`).
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::parse_byte_size;
+///
+/// assert_eq!(parse_byte_size("512")?, 512);
+/// assert_eq!(parse_byte_size("10KB")?, 10_000);
+/// assert_eq!(parse_byte_size("10KiB")?, 10_240);
+/// assert_eq!(parse_byte_size("256m")?, 256 << 20);
+/// # Ok::<(), lino_arguments::ConfigError>(())
+/// ```
+pub fn parse_byte_size(value: &str) -> Result<u64, ConfigError> {
+    let invalid = || ConfigError::ParseError(format!("invalid byte size `{value}`"));
+    let (number, unit) = split_number(value.trim());
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1000,
+        "mb" => 1000u64.pow(2),
+        "gb" => 1000u64.pow(3),
+        "tb" => 1000u64.pow(4),
+        "k" | "kib" => 1 << 10,
+        "m" | "mib" => 1 << 20,
+        "g" | "gib" => 1 << 30,
+        "t" | "tib" => 1 << 40,
+        _ => return Err(invalid()),
+    };
+    if let Ok(whole) = number.parse::<u64>() {
+        return whole.checked_mul(multiplier).ok_or_else(invalid);
+    }
+    let bytes = number.parse::<f64>().map_err(|_| invalid())? * multiplier as f64;
+    if bytes.is_finite() && bytes >= 0.0 && bytes < u64::MAX as f64 {
+        Ok(bytes.round() as u64)
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Parse a comma-separated list, trimming the items and dropping empty
+/// ones.
+///
+/// As a clap value parser, spell the field type `std::vec::Vec<String>`,
+/// since clap reads a plain `Vec` as an option given several times.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::parse_list;
+///
+/// assert_eq!(parse_list("a, b,,c")?, ["a", "b", "c"]);
+/// # Ok::<(), lino_arguments::ConfigError>(())
+/// ```
+pub fn parse_list(value: &str) -> Result<Vec<String>, ConfigError> {
+    Ok(value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Parse comma-separated `key=value` pairs. Keys and values are trimmed,
+/// and a later pair replaces an earlier one with the same key.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::parse_map;
+///
+/// let labels = parse_map("team=core, tier = web")?;
+/// assert_eq!(labels["team"], "core");
+/// assert_eq!(labels["tier"], "web");
+/// assert!(parse_map("team").is_err());
+/// # Ok::<(), lino_arguments::ConfigError>(())
+/// ```
+pub fn parse_map(value: &str) -> Result<HashMap<String, String>, ConfigError> {
+    parse_list(value)?
+        .into_iter()
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(ConfigError::ParseError(format!(
+                "invalid key=value pair `{pair}`"
+            ))),
+        })
+        .collect()
+}
+
+/// Get an environment variable as a [`Duration`], parsed by
+/// [`parse_duration()`]. Returns the default if the variable is not set or
+/// cannot be parsed.
+pub fn getenv_duration(key: &str, default: Duration) -> Duration {
+    getenv_parsed(key, "duration", parse_duration, default)
+}
+
+/// Get an environment variable as a number of bytes, parsed by
+/// [`parse_byte_size()`]. Returns the default if the variable is not set or
+/// cannot be parsed.
+pub fn getenv_byte_size(key: &str, default: u64) -> u64 {
+    getenv_parsed(key, "byte size", parse_byte_size, default)
+}
+
+/// Get an environment variable as a list, parsed by [`parse_list()`].
+/// Returns an empty list if the variable is not set.
+pub fn getenv_list(key: &str) -> Vec<String> {
+    getenv_parsed(key, "list", parse_list, Vec::new())
+}
+
+/// Get an environment variable as a map, parsed by [`parse_map()`].
+/// Returns an empty map if the variable is not set or cannot be parsed.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::getenv_map;
+///
+/// std::env::set_var("DOC_LABELS", "team=core,tier=web");
+/// assert_eq!(getenv_map("docLabels")["tier"], "web");
+/// ```
+pub fn getenv_map(key: &str) -> HashMap<String, String> {
+    getenv_parsed(key, "map", parse_map, HashMap::new())
+}
+
+/// The environment variable `key`, in any case, parsed by `parse` as a
+/// `kind`, or `default`.
+fn getenv_parsed<T: Debug>(
+    key: &str,
+    kind: &str,
+    parse: fn(&str) -> Result<T, ConfigError>,
+    default: T,
+) -> T {
+    let Some(value) = lookup_env(key, &GetenvOptions::default()) else {
+        trace::fallback(key, &default);
+        return default;
+    };
+    parse(&value).unwrap_or_else(|_| {
+        trace::parse_failed(key, kind, &default);
+        default
+    })
+}
+
+/// The leading number of `text` and the rest.
+fn split_number(text: &str) -> (&str, &str) {
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    text.split_at(end)
+}
+
+/// The leading unit of `text` and the rest.
+fn split_unit(text: &str) -> (&str, &str) {
+    let end = text
+        .find(|c: char| c.is_ascii_digit() || c == '.' || c.is_whitespace())
+        .unwrap_or(text.len());
+    text.split_at(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1.5s").unwrap(), Duration::from_millis(1500));
+        assert_eq!(parse_duration("2d").unwrap(), Duration::from_secs(172_800));
+        assert_eq!(parse_duration("1m30s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("10us").unwrap(), Duration::from_micros(10));
+        assert_eq!(parse_duration(" 5 ms ").unwrap(), Duration::from_millis(5));
+        for invalid in ["", "s", "5x", "-5s", "1h-", "5 5"] {
+            assert!(parse_duration(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_byte_size() {
+        assert_eq!(parse_byte_size("1.5GB").unwrap(), 1_500_000_000);
+        assert_eq!(parse_byte_size("2 MiB").unwrap(), 2 << 20);
+        assert_eq!(parse_byte_size("1t").unwrap(), 1 << 40);
+        assert_eq!(parse_byte_size("7b").unwrap(), 7);
+        for invalid in ["", "KB", "5 parsecs", "-1", "99999999999TB"] {
+            assert!(parse_byte_size(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_map() {
+        let map = parse_map("a=1,b = x=y,a=2").unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["a"], "2");
+        assert_eq!(map["b"], "x=y");
+        assert!(parse_map("").unwrap().is_empty());
+        assert!(parse_map("=1").is_err());
+    }
+}
@@ -0,0 +1,126 @@
+//! Polling on a schedule, for sources that cannot be watched.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::source::Watcher;
+
+/// How often to poll: every interval, plus a random jitter, backing off
+/// after failures.
+///
+/// After a failed poll the delay doubles, up to the
+/// [maximum backoff](Self::max_backoff), and goes back to the interval
+/// after the next successful one.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use lino_arguments::PollPolicy;
+///
+/// let policy = PollPolicy::every(Duration::from_secs(30))
+///     .jitter(Duration::from_secs(5))
+///     .max_backoff(Duration::from_secs(600));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollPolicy {
+    interval: Duration,
+    jitter: Duration,
+    max_backoff: Duration,
+}
+
+impl PollPolicy {
+    /// Poll every `interval`, without jitter, backing off up to ten times
+    /// the interval.
+    pub fn every(interval: Duration) -> Self {
+        Self {
+            interval,
+            jitter: Duration::ZERO,
+            max_backoff: interval.saturating_mul(10),
+        }
+    }
+
+    /// Add a random delay of up to `jitter` to every wait, so that many
+    /// instances started together do not poll together.
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// The longest wait after failures, not counting the jitter.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// The wait after `failures` failed polls in a row, with `random`
+    /// picking the jitter.
+    fn delay(&self, failures: u32, random: u64) -> Duration {
+        let backoff = self
+            .interval
+            .saturating_mul(2u32.saturating_pow(failures))
+            .min(self.max_backoff.max(self.interval));
+        let jitter = match self.jitter.as_nanos() {
+            0 => Duration::ZERO,
+            nanos => Duration::from_nanos((u128::from(random) % (nanos + 1)) as u64),
+        };
+        backoff.saturating_add(jitter)
+    }
+}
+
+/// Call `poll` from a background thread on the schedule of `policy`, until
+/// the returned [`Watcher`] is dropped. `poll` returns whether it succeeded.
+pub(crate) fn schedule(
+    policy: PollPolicy,
+    mut poll: impl FnMut() -> bool + Send + 'static,
+) -> Watcher {
+    let (stop, stopped) = mpsc::channel::<()>();
+    std::thread::spawn(move || {
+        let mut failures = 0;
+        loop {
+            // Every `RandomState` is seeded differently
+            let random = RandomState::new().build_hasher().finish();
+            let delay = policy.delay(failures, random);
+            if stopped.recv_timeout(delay) != Err(mpsc::RecvTimeoutError::Timeout) {
+                return;
+            }
+            failures = if poll() {
+                0
+            } else {
+                failures.saturating_add(1)
+            };
+        }
+    });
+    Watcher::new(move || drop(stop))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_backs_off() {
+        let policy =
+            PollPolicy::every(Duration::from_secs(10)).max_backoff(Duration::from_secs(60));
+        assert_eq!(policy.delay(0, 0), Duration::from_secs(10));
+        assert_eq!(policy.delay(1, 0), Duration::from_secs(20));
+        assert_eq!(policy.delay(2, 0), Duration::from_secs(40));
+        assert_eq!(policy.delay(3, 0), Duration::from_secs(60));
+        assert_eq!(policy.delay(u32::MAX, 0), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_delay_jitter() {
+        let policy = PollPolicy::every(Duration::from_secs(10)).jitter(Duration::from_secs(2));
+        for random in [0, 1, 7, u64::MAX] {
+            let delay = policy.delay(0, random);
+            assert!(delay >= Duration::from_secs(10) && delay <= Duration::from_secs(12));
+        }
+        assert_eq!(
+            PollPolicy::every(Duration::from_secs(10)).delay(0, 7),
+            Duration::from_secs(10)
+        );
+    }
+}
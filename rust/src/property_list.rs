@@ -0,0 +1,169 @@
+//! macOS property lists and `defaults` domains.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+use std::process::Command;
+
+use plist::Value;
+
+use crate::canonical_key;
+use crate::config::ConfigError;
+use crate::source::Source;
+
+/// What a [`PlistSource`] reads.
+#[derive(Debug, Clone)]
+enum Target {
+    /// A property list file.
+    File(String),
+    /// A `defaults` domain.
+    Domain(String),
+}
+
+/// A [`Source`] reading a property list, where macOS applications keep
+/// their preferences. macOS only.
+///
+/// Keys are converted like any other name (`LogLevel` sets `log-level`), and
+/// the entries of nested dictionaries are read too, joined with `_`
+/// (`Port` in `Server` sets `server-port`). Strings, numbers, booleans,
+/// and dates are read as text; arrays and data are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::{make_config, PlistSource};
+///
+/// // What `defaults write com.example.myapp LogLevel debug` set
+/// let config = make_config(|c| {
+///     c.source(PlistSource::domain("com.example.myapp"))
+///         .option("log-level", "Log level", "info")
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlistSource {
+    target: Target,
+}
+
+impl PlistSource {
+    /// The property list file at `path`, XML or binary. A missing file has
+    /// no values.
+    pub fn file(path: &str) -> Self {
+        Self {
+            target: Target::File(path.to_string()),
+        }
+    }
+
+    /// The preferences of the `defaults` domain `domain`
+    /// (`com.example.myapp`), read with `defaults export` so that values
+    /// not yet written to `~/Library/Preferences` are included.
+    pub fn domain(domain: &str) -> Self {
+        Self {
+            target: Target::Domain(domain.to_string()),
+        }
+    }
+}
+
+impl Source for PlistSource {
+    fn name(&self) -> &'static str {
+        "property list"
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        let document = match self.target {
+            Target::File(ref path) => {
+                if !Path::new(path).exists() {
+                    return Ok(HashMap::new());
+                }
+                Value::from_file(path).map_err(|err| {
+                    ConfigError::FileError(format!("Failed to parse {path}: {err}"))
+                })?
+            }
+            Target::Domain(ref domain) => {
+                let output = Command::new("defaults")
+                    .args(["export", domain, "-"])
+                    .output()
+                    .map_err(|err| {
+                        ConfigError::SourceError(format!("could not run defaults: {err}"))
+                    })?;
+                if !output.status.success() {
+                    return Err(ConfigError::SourceError(format!(
+                        "defaults export {domain} failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    )));
+                }
+                Value::from_reader(Cursor::new(output.stdout)).map_err(|err| {
+                    ConfigError::SourceError(format!("invalid defaults output: {err}"))
+                })?
+            }
+        };
+        let mut values = HashMap::new();
+        flatten_plist(&document, "", &mut values);
+        Ok(values)
+    }
+}
+
+/// Add the scalar values of a property list dictionary to `values`, by the
+/// canonical form of their keys, nested keys joined with `_`.
+fn flatten_plist(document: &Value, prefix: &str, values: &mut HashMap<String, String>) {
+    let Value::Dictionary(dictionary) = document else {
+        return;
+    };
+    for (key, value) in dictionary {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}_{key}")
+        };
+        let text = match value {
+            Value::Dictionary(_) => {
+                flatten_plist(value, &key, values);
+                continue;
+            }
+            Value::String(text) => text.clone(),
+            Value::Integer(number) => number.to_string(),
+            Value::Real(number) => number.to_string(),
+            Value::Boolean(flag) => flag.to_string(),
+            Value::Date(date) => date.to_xml_format(),
+            _ => continue,
+        };
+        values.insert(canonical_key(&key).into_owned(), text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_file() {
+        let dir = std::env::temp_dir().join("lino-arguments-test-plist");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("com.example.myapp.plist");
+        std::fs::write(
+            &path,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>LogLevel</key><string>debug</string>
+    <key>Verbose</key><true/>
+    <key>Server</key>
+    <dict><key>Port</key><integer>8080</integer></dict>
+    <key>RecentFiles</key><array><string>a.txt</string></array>
+</dict>
+</plist>"#,
+        )
+        .unwrap();
+        let values = PlistSource::file(path.to_str().unwrap()).load().unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values["log-level"], "debug");
+        assert_eq!(values["verbose"], "true");
+        assert_eq!(values["server-port"], "8080");
+
+        let missing = dir.join("missing.plist");
+        assert!(PlistSource::file(missing.to_str().unwrap())
+            .load()
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+//! A resolved [`Config`] as a [figment](https://docs.rs/figment) provider.
+//! Requires the `figment` feature.
+
+use figment::value::{Dict, Map, Value};
+use figment::{Error, Metadata, Profile, Provider};
+
+use crate::config::{parse_float, parse_int, Config};
+use crate::{to_snake_case, ValueType};
+
+/// Provides every resolved value, by its snake_case name, to the default
+/// profile, so that the command line, environment, `.lenv`, and `.env`
+/// layers merge into a figment pipeline like any other provider.
+///
+/// Values of keys declared in the [`Schema`](crate::Schema) have their
+/// [`ValueType`]; the others are parsed like those of figment's `Env`
+/// provider, where `8080` is an integer, `true` a boolean, and `[a, b]` an
+/// array. Declare a key as [`ValueType::String`] to keep a value like
+/// `1234` a string.
+///
+/// # Examples
+///
+/// ```
+/// use figment::Figment;
+/// use lino_arguments::make_config_from;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct App {
+///     port: u16,
+///     log_level: String,
+/// }
+///
+/// let config = make_config_from(["app", "--port", "8080"], |c| {
+///     c.option("port", "Server port", "3000")
+///         .option("log-level", "Log level", "info")
+/// });
+/// let app: App = Figment::from(config).extract().unwrap();
+/// assert_eq!(app.port, 8080);
+/// assert_eq!(app.log_level, "info");
+/// ```
+impl Provider for Config {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("lino-arguments")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let dict = self
+            .keys()
+            .filter_map(|key| {
+                let (value, _) = self.resolved(key)?;
+                Some((to_snake_case(key), self.typed(key, value)))
+            })
+            .collect();
+        Ok(Profile::Default.collect(dict))
+    }
+}
+
+impl Config {
+    /// `value` as the type `key` is declared with, or parsed like an
+    /// environment variable if it is not declared.
+    fn typed(&self, key: &str, value: &str) -> Value {
+        let strict = self.strict_numbers();
+        let typed = match self.value_type(key) {
+            Some(ValueType::String) => None,
+            Some(ValueType::Integer) => parse_int(value, strict).map(Value::from),
+            Some(ValueType::Float) => parse_float(value, strict).map(Value::from),
+            Some(ValueType::Bool) => Some(Value::from(matches!(
+                value.to_lowercase().as_str(),
+                "true" | "1" | "yes" | "on"
+            ))),
+            None => return value.parse().unwrap_or_else(|never| match never {}),
+        };
+        typed.unwrap_or_else(|| Value::from(value.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::providers::Serialized;
+    use figment::Figment;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{make_config_from, KeySpec, Schema, ValueType};
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct App {
+        port: u16,
+        verbose: bool,
+        api_key: String,
+        ratio: f64,
+    }
+
+    #[test]
+    fn test_extract_typed_values() {
+        let schema = Schema::new()
+            .key(KeySpec::new("api-key", ValueType::String).default("1234"))
+            .key(KeySpec::new("ratio", ValueType::Float).default("1"));
+        let config = make_config_from(["app", "--port", "8080", "--verbose"], |c| {
+            c.schema(schema)
+                .option("port", "Server port", "3000")
+                .flag("verbose", "Verbose output")
+        });
+        let app: App = Figment::from(config).extract().unwrap();
+        assert_eq!(
+            app,
+            App {
+                port: 8080,
+                verbose: true,
+                api_key: "1234".to_string(),
+                ratio: 1.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_merge_over_other_providers() {
+        let defaults = App {
+            port: 80,
+            verbose: false,
+            api_key: "none".to_string(),
+            ratio: 0.5,
+        };
+        let config = make_config_from(["app", "--port", "8080"], |c| {
+            c.option("port", "Server port", "3000")
+        });
+        let app: App = Figment::from(Serialized::defaults(defaults))
+            .merge(config)
+            .extract()
+            .unwrap();
+        assert_eq!(app.port, 8080);
+        assert_eq!(app.api_key, "none");
+    }
+}
@@ -0,0 +1,181 @@
+//! The Windows registry.
+
+use std::collections::HashMap;
+use std::io;
+
+use winreg::enums::{
+    RegType, HKEY_CLASSES_ROOT, HKEY_CURRENT_CONFIG, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE,
+    HKEY_USERS,
+};
+use winreg::types::FromRegValue;
+use winreg::{RegKey, RegValue, HKEY};
+
+use crate::canonical_key;
+use crate::config::ConfigError;
+use crate::source::Source;
+
+/// A [`Source`] reading the values of a registry key, where Windows
+/// applications keep their settings. Windows only.
+///
+/// Value names are converted to keys like any other name (`LogLevel` sets
+/// `log-level`), and the values of subkeys are read too, joined with `_`
+/// (`LogLevel` under `Server` sets `server-log-level`). Strings and numbers
+/// are read as text, multi-string values joined with `,`; binary values are
+/// skipped. A key that does not exist has no values.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::{make_config, RegistrySource};
+///
+/// let config = make_config(|c| {
+///     c.source(RegistrySource::new(r"HKCU\Software\MyApp"))
+///         .option("log-level", "Log level", "info")
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct RegistrySource {
+    path: String,
+}
+
+impl RegistrySource {
+    /// The key at `path`, starting with its root key, abbreviated or not
+    /// (`HKCU\Software\MyApp`, `HKEY_LOCAL_MACHINE\SOFTWARE\MyApp`).
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+}
+
+impl Source for RegistrySource {
+    fn name(&self) -> &'static str {
+        "Windows registry"
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        let (root, subkey) = split_root(&self.path).ok_or_else(|| {
+            ConfigError::SourceError(format!("{} does not start with a root key", self.path))
+        })?;
+        let key = match RegKey::predef(root).open_subkey(subkey) {
+            Ok(key) => key,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(err) => return Err(ConfigError::SourceError(format!("{}: {err}", self.path))),
+        };
+        let mut values = HashMap::new();
+        read_key(&key, "", &mut values)
+            .map_err(|err| ConfigError::SourceError(format!("{}: {err}", self.path)))?;
+        Ok(values)
+    }
+}
+
+/// The root key a registry path starts with, and the rest of the path.
+fn split_root(path: &str) -> Option<(HKEY, &str)> {
+    let (root, rest) = path.split_once('\\').unwrap_or((path, ""));
+    let root = match root.to_ascii_uppercase().as_str() {
+        "HKCU" | "HKEY_CURRENT_USER" => HKEY_CURRENT_USER,
+        "HKLM" | "HKEY_LOCAL_MACHINE" => HKEY_LOCAL_MACHINE,
+        "HKCR" | "HKEY_CLASSES_ROOT" => HKEY_CLASSES_ROOT,
+        "HKU" | "HKEY_USERS" => HKEY_USERS,
+        "HKCC" | "HKEY_CURRENT_CONFIG" => HKEY_CURRENT_CONFIG,
+        _ => return None,
+    };
+    Some((root, rest.trim_matches('\\')))
+}
+
+/// Add the values of `key` and its subkeys to `values`, their names
+/// prefixed with `prefix`.
+fn read_key(key: &RegKey, prefix: &str, values: &mut HashMap<String, String>) -> io::Result<()> {
+    let join = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}_{name}")
+        }
+    };
+    for value in key.enum_values() {
+        let (name, value) = value?;
+        // The default value of a key has an empty name
+        if name.is_empty() {
+            continue;
+        }
+        if let Some(text) = value_text(&value) {
+            values.insert(canonical_key(&join(&name)).into_owned(), text);
+        }
+    }
+    for name in key.enum_keys() {
+        let name = name?;
+        read_key(&key.open_subkey(&name)?, &join(&name), values)?;
+    }
+    Ok(())
+}
+
+/// A registry value as text, or `None` for binary values.
+fn value_text(value: &RegValue) -> Option<String> {
+    match value.vtype {
+        RegType::REG_SZ | RegType::REG_EXPAND_SZ => String::from_reg_value(value).ok(),
+        RegType::REG_MULTI_SZ => Vec::<String>::from_reg_value(value)
+            .ok()
+            .map(|items| items.join(",")),
+        RegType::REG_DWORD => u32::from_reg_value(value).ok().map(|n| n.to_string()),
+        RegType::REG_QWORD => u64::from_reg_value(value).ok().map(|n| n.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winreg::types::ToRegValue;
+
+    #[test]
+    fn test_split_root() {
+        let (root, rest) = split_root(r"HKCU\Software\MyApp").unwrap();
+        assert_eq!(root, HKEY_CURRENT_USER);
+        assert_eq!(rest, r"Software\MyApp");
+        let (root, rest) = split_root(r"hkey_local_machine\SOFTWARE\MyApp\").unwrap();
+        assert_eq!(root, HKEY_LOCAL_MACHINE);
+        assert_eq!(rest, r"SOFTWARE\MyApp");
+        assert!(split_root(r"Software\MyApp").is_none());
+    }
+
+    #[test]
+    fn test_value_text() {
+        assert_eq!(
+            value_text(&"debug".to_reg_value()).as_deref(),
+            Some("debug")
+        );
+        assert_eq!(value_text(&8080u32.to_reg_value()).as_deref(), Some("8080"));
+        let hosts = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(value_text(&hosts.to_reg_value()).as_deref(), Some("a,b"));
+        let binary = RegValue {
+            bytes: vec![1, 2].into(),
+            vtype: RegType::REG_BINARY,
+        };
+        assert_eq!(value_text(&binary), None);
+    }
+
+    #[test]
+    fn test_load() {
+        let path = r"Software\lino-arguments-test";
+        let (key, _) = RegKey::predef(HKEY_CURRENT_USER)
+            .create_subkey(path)
+            .unwrap();
+        key.set_value("LogLevel", &"debug").unwrap();
+        let (server, _) = key.create_subkey("Server").unwrap();
+        server.set_value("Port", &8080u32).unwrap();
+
+        let values = RegistrySource::new(&format!(r"HKCU\{path}"))
+            .load()
+            .unwrap();
+        RegKey::predef(HKEY_CURRENT_USER)
+            .delete_subkey_all(path)
+            .unwrap();
+        assert_eq!(values["log-level"], "debug");
+        assert_eq!(values["server-port"], "8080");
+        assert!(RegistrySource::new(&format!(r"HKCU\{path}"))
+            .load()
+            .unwrap()
+            .is_empty());
+    }
+}
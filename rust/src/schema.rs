@@ -0,0 +1,1929 @@
+//! Declarative description of the configuration surface. Requires the `std`
+//! feature.
+//!
+//! A [`Schema`] lists every key an application reads together with its
+//! type, default, description, and the layers it may be set from. Passing it
+//! to [`ConfigBuilder::schema()`](crate::ConfigBuilder::schema) defines the
+//! matching options and validates the resolved values.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::config::{
+    index_entries, parse_float, parse_int, read_env_entries, read_lenv_entries, Config,
+    ConfigError, FileEntry, ValueSource,
+};
+use crate::{canonical_key, convert_case, Case};
+
+/// The type a configuration value must parse as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum ValueType {
+    /// Any string.
+    #[default]
+    String,
+    /// A whole number, as accepted by [`Config::get_int()`].
+    Integer,
+    /// A floating point number, as accepted by [`Config::get_float()`].
+    Float,
+    /// `true`/`false`, `1`/`0`, `yes`/`no` or `on`/`off`. Defined as a flag.
+    Bool,
+}
+
+impl ValueType {
+    /// Whether `value` parses as this type.
+    fn accepts(self, value: &str, strict_numbers: bool) -> bool {
+        match self {
+            ValueType::String => true,
+            ValueType::Integer => parse_int(value, strict_numbers).is_some(),
+            ValueType::Float => parse_float(value, strict_numbers).is_some(),
+            ValueType::Bool => matches!(
+                value.to_lowercase().as_str(),
+                "true" | "false" | "1" | "0" | "yes" | "no" | "on" | "off"
+            ),
+        }
+    }
+}
+
+impl ValueType {
+    /// The JSON Schema `type` keyword for this type.
+    fn json_type(self) -> &'static str {
+        match self {
+            ValueType::String => "string",
+            ValueType::Integer => "integer",
+            ValueType::Float => "number",
+            ValueType::Bool => "boolean",
+        }
+    }
+
+    /// `value` as a JSON value of this type, or as a string if it does not
+    /// parse.
+    fn to_json(self, value: &str) -> Value {
+        let typed = match self {
+            ValueType::String => None,
+            ValueType::Integer => parse_int(value, false).map(Value::from),
+            ValueType::Float => parse_float(value, false).map(json_number),
+            ValueType::Bool => match value.to_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => Some(Value::Bool(true)),
+                "false" | "0" | "no" | "off" => Some(Value::Bool(false)),
+                _ => None,
+            },
+        };
+        typed.unwrap_or_else(|| Value::from(value))
+    }
+}
+
+/// A JSON value as it would be written in a config file.
+fn json_to_string(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// A JSON number, written without a fraction when it is a whole number.
+fn json_number(number: f64) -> Value {
+    const EXACT: f64 = (1u64 << f64::MANTISSA_DIGITS) as f64;
+    if number.fract() == 0.0 && number.abs() <= EXACT {
+        Value::from(number as i64)
+    } else {
+        Value::from(number)
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ValueType::String => "string",
+            ValueType::Integer => "integer",
+            ValueType::Float => "float",
+            ValueType::Bool => "boolean",
+        })
+    }
+}
+
+/// A reusable check an application registers on a [`Schema`] under a
+/// name, and attaches to keys with [`Validator::named()`].
+///
+/// Closures taking the value and returning the violation implement it, so
+/// most validators need no type of their own.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{KeySpec, Schema, Validate, Validator, ValueType};
+///
+/// struct ReadableDir;
+///
+/// impl Validate for ReadableDir {
+///     fn validate(&self, value: &str) -> Result<(), String> {
+///         match std::fs::read_dir(value) {
+///             Ok(_) => Ok(()),
+///             Err(err) => Err(format!("must be a readable directory ({err})")),
+///         }
+///     }
+///
+///     fn describe(&self) -> Option<String> {
+///         Some("must be a readable directory".to_string())
+///     }
+/// }
+///
+/// let schema = Schema::new()
+///     .register("readable-dir", ReadableDir)
+///     .register("even", |value: &str| match value.parse::<i64>() {
+///         Ok(n) if n % 2 == 0 => Ok(()),
+///         _ => Err("must be an even number".to_string()),
+///     })
+///     .key(KeySpec::new("data-dir", ValueType::String).validator(Validator::named("readable-dir")))
+///     .key(KeySpec::new("workers", ValueType::Integer).validator(Validator::named("even")));
+/// ```
+pub trait Validate: Send + Sync {
+    /// Check `value`, describing the violation if there is one. The message
+    /// follows the key name, as in `"must be a readable directory"`.
+    fn validate(&self, value: &str) -> Result<(), String>;
+
+    /// The constraint in words, for generated documentation. Validators
+    /// without a description are left out of it.
+    fn describe(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<F> Validate for F
+where
+    F: Fn(&str) -> Result<(), String> + Send + Sync,
+{
+    fn validate(&self, value: &str) -> Result<(), String> {
+        self(value)
+    }
+}
+
+impl fmt::Debug for dyn Validate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Validate")
+    }
+}
+
+/// Validators registered on a schema, by name.
+type Registry = BTreeMap<String, Arc<dyn Validate>>;
+
+/// A constraint on the value of a key, attached with
+/// [`KeySpec::validator()`].
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{KeySpec, Validator, ValueType};
+///
+/// let port = KeySpec::new("port", ValueType::Integer).validator(Validator::range(1..=65535));
+/// let name = KeySpec::new("name", ValueType::String).validator(Validator::matches(r"^[a-z-]+$"));
+/// let format = KeySpec::new("format", ValueType::String)
+///     .validator(Validator::one_of(["json", "text"]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Validator(Rule, Severity);
+
+#[derive(Debug, Clone)]
+enum Rule {
+    Range(Bound<f64>, Bound<f64>),
+    Matches(Regex),
+    OneOf(Vec<String>),
+    Named(String),
+}
+
+/// How a failed check affects building the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Severity {
+    /// The configuration does not build (the default).
+    #[default]
+    Error,
+    /// The configuration builds, and the issue is collected in
+    /// [`Config::warnings()`].
+    Warning,
+}
+
+impl Validator {
+    fn rule(rule: Rule) -> Self {
+        Self(rule, Severity::Error)
+    }
+
+    /// Report a failed check with `severity`, e.g. [`Severity::Warning`]
+    /// for a recommendation the configuration may ignore.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::{KeySpec, Severity, Validator, ValueType};
+    ///
+    /// let port = KeySpec::new("port", ValueType::Integer)
+    ///     .validator(Validator::range(1..=65535))
+    ///     .validator(Validator::range(1024..).severity(Severity::Warning));
+    /// ```
+    pub fn severity(mut self, severity: Severity) -> Self {
+        self.1 = severity;
+        self
+    }
+
+    /// Require a number within `bounds`, e.g. `1..=65535` or `0.0..1.0`.
+    pub fn range<T: Copy + Into<f64>>(bounds: impl RangeBounds<T>) -> Self {
+        let bound = |bound: Bound<&T>| match bound {
+            Bound::Included(&value) => Bound::Included(value.into()),
+            Bound::Excluded(&value) => Bound::Excluded(value.into()),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        Self::rule(Rule::Range(
+            bound(bounds.start_bound()),
+            bound(bounds.end_bound()),
+        ))
+    }
+
+    /// Require the value to match a regular expression. Anchor the pattern
+    /// with `^…$` to match the whole value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regular expression.
+    pub fn matches(pattern: &str) -> Self {
+        match Regex::new(pattern) {
+            Ok(regex) => Self::rule(Rule::Matches(regex)),
+            Err(err) => panic!("invalid pattern for Validator::matches: {err}"),
+        }
+    }
+
+    /// Require the value to be one of `values`, compared exactly.
+    pub fn one_of<S: AsRef<str>>(values: impl IntoIterator<Item = S>) -> Self {
+        Self::rule(Rule::OneOf(
+            values.into_iter().map(|v| v.as_ref().to_string()).collect(),
+        ))
+    }
+
+    /// Apply the validator registered as `name` with [`Schema::register()`].
+    /// A value checked against a schema without it is reported as invalid.
+    pub fn named(name: &str) -> Self {
+        Self::rule(Rule::Named(name.to_string()))
+    }
+
+    /// The constraints expressed by the JSON Schema keywords of `schema`.
+    /// Keywords without a matching validator, and patterns the `regex`
+    /// crate does not accept, are ignored.
+    fn from_json_keywords(schema: &Value) -> Vec<Self> {
+        let bound = |inclusive: &str, exclusive: &str| {
+            if let Some(limit) = schema.get(inclusive).and_then(Value::as_f64) {
+                Bound::Included(limit)
+            } else if let Some(limit) = schema.get(exclusive).and_then(Value::as_f64) {
+                Bound::Excluded(limit)
+            } else {
+                Bound::Unbounded
+            }
+        };
+        let mut validators = Vec::new();
+        let range = (
+            bound("minimum", "exclusiveMinimum"),
+            bound("maximum", "exclusiveMaximum"),
+        );
+        if range != (Bound::Unbounded, Bound::Unbounded) {
+            validators.push(Self::rule(Rule::Range(range.0, range.1)));
+        }
+        if let Some(Ok(regex)) = schema
+            .get("pattern")
+            .and_then(Value::as_str)
+            .map(Regex::new)
+        {
+            validators.push(Self::rule(Rule::Matches(regex)));
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            validators.push(Self::one_of(values.iter().map(json_to_string)));
+        }
+        if let Some(value) = schema.get("const") {
+            validators.push(Self::one_of([json_to_string(value)]));
+        }
+        validators
+    }
+
+    /// The constraint in words (`"must be at most 10"`), for generated
+    /// documentation.
+    fn describe(&self, custom: &Registry) -> Option<String> {
+        Some(match &self.0 {
+            Rule::Range(start, end) => {
+                let lower = match start {
+                    Bound::Included(min) => Some(format!("at least {min}")),
+                    Bound::Excluded(min) => Some(format!("greater than {min}")),
+                    Bound::Unbounded => None,
+                };
+                let upper = match end {
+                    Bound::Included(max) => Some(format!("at most {max}")),
+                    Bound::Excluded(max) => Some(format!("less than {max}")),
+                    Bound::Unbounded => None,
+                };
+                match (start, end) {
+                    (Bound::Included(min), Bound::Included(max)) => {
+                        format!("must be between {min} and {max}")
+                    }
+                    _ => {
+                        let bounds: Vec<String> = lower.into_iter().chain(upper).collect();
+                        format!("must be {}", bounds.join(" and "))
+                    }
+                }
+            }
+            Rule::Matches(regex) => format!("must match `{}`", regex.as_str()),
+            Rule::OneOf(values) => {
+                let values: Vec<String> = values.iter().map(|v| format!("`{v}`")).collect();
+                format!("must be one of {}", values.join(", "))
+            }
+            Rule::Named(name) => return custom.get(name)?.describe(),
+        })
+    }
+
+    /// The JSON Schema keywords expressing this constraint.
+    fn json_keywords(&self) -> Map<String, Value> {
+        let mut keywords = Map::new();
+        match &self.0 {
+            Rule::Range(start, end) => {
+                for (bound, inclusive, exclusive) in [
+                    (start, "minimum", "exclusiveMinimum"),
+                    (end, "maximum", "exclusiveMaximum"),
+                ] {
+                    match *bound {
+                        Bound::Included(limit) => {
+                            keywords.insert(inclusive.into(), json_number(limit));
+                        }
+                        Bound::Excluded(limit) => {
+                            keywords.insert(exclusive.into(), json_number(limit));
+                        }
+                        Bound::Unbounded => {}
+                    }
+                }
+            }
+            Rule::Matches(regex) => {
+                keywords.insert("pattern".into(), regex.as_str().into());
+            }
+            Rule::OneOf(values) => {
+                keywords.insert("enum".into(), values.clone().into());
+            }
+            Rule::Named(_) => {}
+        }
+        keywords
+    }
+
+    /// Check `value`, describing the violation if there is one.
+    pub(crate) fn check(&self, value: &str, strict_numbers: bool) -> Result<(), String> {
+        match &self.0 {
+            Rule::Range(start, end) => {
+                let Some(number) = parse_float(value, strict_numbers) else {
+                    return Err("is not a number".to_string());
+                };
+                if (*start, *end).contains(&number) {
+                    return Ok(());
+                }
+                Err(match (start, end) {
+                    (Bound::Included(min), Bound::Included(max)) => {
+                        format!("must be between {min} and {max}")
+                    }
+                    (Bound::Included(min), _) if number < *min => format!("must be at least {min}"),
+                    (Bound::Excluded(min), _) if number <= *min => {
+                        format!("must be greater than {min}")
+                    }
+                    (_, Bound::Included(max)) => format!("must be at most {max}"),
+                    (_, Bound::Excluded(max)) => format!("must be less than {max}"),
+                    _ => unreachable!("an unbounded range contains every number"),
+                })
+            }
+            Rule::Matches(regex) if regex.is_match(value) => Ok(()),
+            Rule::Matches(regex) => Err(format!("must match `{}`", regex.as_str())),
+            Rule::OneOf(values) if values.iter().any(|v| v == value) => Ok(()),
+            Rule::OneOf(values) => Err(format!("must be one of: {}", values.join(", "))),
+            Rule::Named(name) => Err(format!("uses unregistered validator `{name}`")),
+        }
+    }
+
+    /// Check `value`, running named validators from `custom`.
+    fn check_with(
+        &self,
+        value: &str,
+        strict_numbers: bool,
+        custom: &Registry,
+    ) -> Result<(), String> {
+        match &self.0 {
+            Rule::Named(name) if custom.contains_key(name) => custom[name].validate(value),
+            _ => self.check(value, strict_numbers),
+        }
+    }
+}
+
+/// The declaration of a single configuration key.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{KeySpec, ValueSource, ValueType};
+///
+/// let token = KeySpec::new("api-token", ValueType::String)
+///     .description("Token for the upstream API")
+///     .required(true)
+///     .sources([ValueSource::Environment, ValueSource::LenvFile]);
+/// assert!(token.required);
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct KeySpec {
+    /// The key name, in any case (e.g. `"api-token"`).
+    pub name: String,
+    /// The type the value must parse as.
+    pub value_type: ValueType,
+    /// The value used when no layer sets the key.
+    pub default: Option<String>,
+    /// Help text for the key.
+    pub description: String,
+    /// Whether the key must resolve to a non-empty value.
+    pub required: bool,
+    /// The layers the key may be set from; empty allows every layer.
+    /// The default value is always allowed.
+    pub sources: Vec<ValueSource>,
+    /// Constraints the value must satisfy, checked in order.
+    pub validators: Vec<Validator>,
+    /// Sample values, shown in generated documentation.
+    pub examples: Vec<String>,
+    /// The version the key was introduced in (e.g. `"1.2.0"`).
+    pub since: Option<String>,
+    /// Set when the key should no longer be used.
+    pub deprecated: Option<Deprecation>,
+    /// The environment variable to read, if not the key in UPPER_CASE.
+    pub env: Option<String>,
+    /// Whether the value is a credential, left out of issues and read with
+    /// [`Config::get_secret()`].
+    pub sensitive: bool,
+}
+
+/// Why a key is deprecated and what to use instead.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Deprecation {
+    /// Why the key is deprecated; may be empty.
+    pub message: String,
+    /// The key that replaces it, if any.
+    pub replacement: Option<String>,
+}
+
+impl Deprecation {
+    /// The suggested migration, naming the replacement's CLI option and
+    /// environment variable.
+    fn hint(&self) -> Option<String> {
+        let replacement = self.replacement.as_deref()?;
+        Some(format!(
+            "use `--{}` / `{}` instead",
+            convert_case(replacement, Case::Kebab),
+            convert_case(replacement, Case::UpperSnake)
+        ))
+    }
+}
+
+impl KeySpec {
+    /// Declare an optional key of the given type, allowed from every layer.
+    pub fn new(name: &str, value_type: ValueType) -> Self {
+        Self {
+            name: name.to_string(),
+            value_type,
+            default: None,
+            description: String::new(),
+            required: false,
+            sources: Vec::new(),
+            validators: Vec::new(),
+            examples: Vec::new(),
+            since: None,
+            deprecated: None,
+            env: None,
+            sensitive: false,
+        }
+    }
+
+    /// Read the key from the environment variable `name` instead of the
+    /// key in UPPER_CASE.
+    pub fn env(mut self, name: &str) -> Self {
+        self.env = Some(name.to_string());
+        self
+    }
+
+    /// Set the value used when no layer sets the key.
+    pub fn default(mut self, value: &str) -> Self {
+        self.default = Some(value.to_string());
+        self
+    }
+
+    /// Set the help text for the key.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Require the key to resolve to a non-empty value.
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Mark the value as a credential. Issues about the key leave the value
+    /// out, the JSON Schema marks it `writeOnly`, and it should be read with
+    /// [`Config::get_secret()`].
+    pub fn sensitive(mut self, sensitive: bool) -> Self {
+        self.sensitive = sensitive;
+        self
+    }
+
+    /// The value to show in an issue about this key, unless it is sensitive.
+    fn shown(&self, value: &str) -> Option<String> {
+        (!self.sensitive).then(|| value.to_string())
+    }
+
+    /// Restrict the layers the key may be set from.
+    pub fn sources(mut self, sources: impl IntoIterator<Item = ValueSource>) -> Self {
+        self.sources = sources.into_iter().collect();
+        self
+    }
+
+    /// Add a constraint the value must satisfy.
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Add a sample value, shown in generated documentation.
+    pub fn example(mut self, value: &str) -> Self {
+        self.examples.push(value.to_string());
+        self
+    }
+
+    /// Record the version the key was introduced in.
+    pub fn since(mut self, version: &str) -> Self {
+        self.since = Some(version.to_string());
+        self
+    }
+
+    /// Mark the key as deprecated, explaining why. Setting it still works,
+    /// but records a warning (see [`Config::warnings()`]).
+    pub fn deprecated(mut self, message: &str) -> Self {
+        self.deprecated
+            .get_or_insert_with(Deprecation::default)
+            .message = message.to_string();
+        self
+    }
+
+    /// Mark the key as deprecated in favor of `key`, which the warning
+    /// suggests migrating to.
+    pub fn replaced_by(mut self, key: &str) -> Self {
+        self.deprecated
+            .get_or_insert_with(Deprecation::default)
+            .replacement = Some(key.to_string());
+        self
+    }
+
+    /// Whether a value resolved from `source` is acceptable for this key.
+    pub fn allows(&self, source: ValueSource) -> bool {
+        source == ValueSource::Default || self.sources.is_empty() || self.sources.contains(&source)
+    }
+
+    /// Declare a key from the JSON Schema of its value. Constraints nested
+    /// in `allOf` are collected as well.
+    fn from_json_schema(name: &str, property: &Value) -> Self {
+        let value_type = match property.get("type").and_then(Value::as_str) {
+            Some("integer") => ValueType::Integer,
+            Some("number") => ValueType::Float,
+            Some("boolean") => ValueType::Bool,
+            _ => ValueType::String,
+        };
+        let mut spec = Self::new(name, value_type);
+        if let Some(description) = property.get("description").and_then(Value::as_str) {
+            spec.description = description.to_string();
+        }
+        spec.default = property.get("default").map(json_to_string);
+        if property.get("deprecated") == Some(&Value::Bool(true)) {
+            spec.deprecated = Some(Deprecation::default());
+        }
+        spec.sensitive = property.get("writeOnly") == Some(&Value::Bool(true));
+        if let Some(examples) = property.get("examples").and_then(Value::as_array) {
+            spec.examples = examples.iter().map(json_to_string).collect();
+        }
+        let nested = property.get("allOf").and_then(Value::as_array);
+        for schema in std::iter::once(property).chain(nested.into_iter().flatten()) {
+            spec.validators
+                .extend(Validator::from_json_keywords(schema));
+        }
+        spec
+    }
+
+    /// The JSON Schema describing this key's value.
+    ///
+    /// Constraints that would repeat a keyword already present (two
+    /// patterns, say) go into `allOf`, so every one of them still applies.
+    fn to_json_schema(&self) -> Value {
+        let mut property = Map::new();
+        property.insert("type".into(), self.value_type.json_type().into());
+        if !self.description.is_empty() {
+            property.insert("description".into(), self.description.clone().into());
+        }
+        if let Some(ref default) = self.default {
+            property.insert("default".into(), self.value_type.to_json(default));
+        }
+        if self.deprecated.is_some() {
+            property.insert("deprecated".into(), true.into());
+        }
+        if self.sensitive {
+            property.insert("writeOnly".into(), true.into());
+        }
+        if !self.examples.is_empty() {
+            let examples: Vec<Value> = self
+                .examples
+                .iter()
+                .map(|example| self.value_type.to_json(example))
+                .collect();
+            property.insert("examples".into(), examples.into());
+        }
+        let mut all_of = Vec::new();
+        for validator in self.validators.iter().filter(|v| v.1 == Severity::Error) {
+            let keywords = validator.json_keywords();
+            if keywords
+                .keys()
+                .any(|keyword| property.contains_key(keyword))
+            {
+                all_of.push(Value::Object(keywords));
+            } else {
+                property.extend(keywords);
+            }
+        }
+        if !all_of.is_empty() {
+            property.insert("allOf".into(), all_of.into());
+        }
+        Value::Object(property)
+    }
+
+    /// The environment variable the key is read from.
+    fn env_name(&self) -> Cow<'_, str> {
+        match self.env {
+            Some(ref env) => Cow::Borrowed(env),
+            None => convert_case(&self.name, Case::UpperSnake),
+        }
+    }
+
+    /// The Markdown table row documenting this key.
+    fn markdown_row(&self, custom: &Registry) -> String {
+        let code = |text: &str| format!("`{}`", text.replace('|', "\\|"));
+        let mut notes = Vec::new();
+        if let Some(ref deprecation) = self.deprecated {
+            let mut note = String::from("**Deprecated.**");
+            if !deprecation.message.is_empty() {
+                note = format!("{note} {}", deprecation.message.replace('|', "\\|"));
+            }
+            if let Some(hint) = deprecation.hint() {
+                note = format!("{note} {}{}.", hint[..1].to_uppercase(), &hint[1..]);
+            }
+            notes.push(note);
+        }
+        if !self.description.is_empty() {
+            notes.push(self.description.replace('|', "\\|"));
+        }
+        if self.required {
+            notes.push("**Required.**".to_string());
+        }
+        if self.sensitive {
+            notes.push("**Sensitive.**".to_string());
+        }
+        if let Some(constraints) = self.constraints(custom) {
+            notes.push(constraints.replace('|', "\\|"));
+        }
+        if !self.examples.is_empty() {
+            let examples: Vec<String> = self.examples.iter().map(|e| code(e)).collect();
+            notes.push(format!("Examples: {}.", examples.join(", ")));
+        }
+        if let Some(ref version) = self.since {
+            notes.push(format!("Since {version}."));
+        }
+        format!(
+            "| {} | {} | {} | {} | {} |",
+            code(&format!("--{}", convert_case(&self.name, Case::Kebab))),
+            code(&self.env_name()),
+            self.value_type,
+            self.default.as_deref().map(code).unwrap_or_default(),
+            notes.join(" "),
+        )
+    }
+
+    /// The constraints of the validators as a sentence, such as `"Must be
+    /// between 1 and 65535."`, or `None` without any.
+    fn constraints(&self, custom: &Registry) -> Option<String> {
+        let constraints: Vec<String> = self
+            .validators
+            .iter()
+            .filter_map(|validator| {
+                let constraint = validator.describe(custom)?;
+                Some(match constraint.strip_prefix("must ") {
+                    Some(rest) if validator.1 == Severity::Warning => format!("should {rest}"),
+                    _ => constraint,
+                })
+            })
+            .collect();
+        if constraints.is_empty() {
+            return None;
+        }
+        let constraints = constraints.join("; ");
+        let mut first = constraints.chars();
+        let capital = first.next().map(|c| c.to_uppercase()).into_iter().flatten();
+        Some(format!(
+            "{}{}.",
+            capital.collect::<String>(),
+            first.as_str()
+        ))
+    }
+
+    /// The commented `.env` lines documenting this key: the variable is set
+    /// empty when required, and commented out with its default otherwise.
+    fn env_example(&self, custom: &Registry) -> String {
+        let mut comments = Vec::new();
+        if let Some(ref deprecation) = self.deprecated {
+            let mut note = String::from("Deprecated.");
+            if !deprecation.message.is_empty() {
+                note = format!("{note} {}", deprecation.message);
+            }
+            if let Some(hint) = deprecation.hint() {
+                note = format!("{note} {}{}.", hint[..1].to_uppercase(), &hint[1..]);
+            }
+            comments.push(note);
+        }
+        if !self.description.is_empty() {
+            comments.push(self.description.clone());
+        }
+        let mut kind = format!("Type: {}.", self.value_type);
+        if let Some(constraints) = self.constraints(custom) {
+            kind = format!("{kind} {constraints}");
+        }
+        comments.push(kind);
+        comments.push(match (self.required, &self.default) {
+            (true, _) => "Required.".to_string(),
+            (false, Some(default)) => format!("Optional, defaults to {default}."),
+            (false, None) => "Optional.".to_string(),
+        });
+        if self.sensitive {
+            comments.push("Sensitive: do not commit a real value.".to_string());
+        }
+        if !self.examples.is_empty() {
+            comments.push(format!("Examples: {}.", self.examples.join(", ")));
+        }
+        if let Some(ref version) = self.since {
+            comments.push(format!("Since {version}."));
+        }
+        let mut lines: String = comments
+            .iter()
+            .flat_map(|comment| comment.lines())
+            .map(|line| match line {
+                "" => "#\n".to_string(),
+                line => format!("# {line}\n"),
+            })
+            .collect();
+        let value = self.default.as_deref().map(env_quoted).unwrap_or_default();
+        match self.required {
+            true => lines.push_str(&format!("{}={value}\n", self.env_name())),
+            false => lines.push_str(&format!("# {}={value}\n", self.env_name())),
+        }
+        lines
+    }
+
+    /// Check the resolved `value` of this key, if any, adding every problem
+    /// found to `issues`. Validators are skipped for a value of the wrong
+    /// type, since they would only restate the type mismatch.
+    fn check(
+        &self,
+        value: Option<(&str, ValueSource)>,
+        strict_numbers: bool,
+        custom: &Registry,
+        issues: &mut Vec<Issue>,
+    ) {
+        let Some((value, source)) = value.filter(|(value, _)| !value.is_empty()) else {
+            if self.required {
+                issues.push(Issue::new(&self.name, "is required but not set"));
+            }
+            return;
+        };
+        let issue = |message: String, severity| Issue {
+            key: self.name.clone(),
+            value: self.shown(value),
+            source: Some(source),
+            location: None,
+            message,
+            hint: None,
+            severity,
+        };
+        if !self.allows(source) {
+            let message = format!("may not be set from the {source}");
+            issues.push(issue(message, Severity::Error));
+        }
+        if !self.value_type.accepts(value, strict_numbers) {
+            let message = format!("is not a valid {}", self.value_type);
+            issues.push(issue(message, Severity::Error));
+            return;
+        }
+        issues.extend(self.validators.iter().filter_map(|validator| {
+            let message = validator.check_with(value, strict_numbers, custom).err()?;
+            Some(issue(message, validator.1))
+        }));
+    }
+}
+
+/// A problem found while validating a configuration against a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// The key the problem is about, as declared in the schema.
+    pub key: String,
+    /// The offending value, or `None` if the key was not set.
+    pub value: Option<String>,
+    /// The layer the offending value came from.
+    pub source: Option<ValueSource>,
+    /// Where the offending value was written, for values read from a file.
+    pub location: Option<Location>,
+    /// What is wrong, phrased to follow the key (e.g. `"is required but not set"`).
+    pub message: String,
+    /// How to fix it, such as the migration for a deprecated key.
+    pub hint: Option<String>,
+    /// Whether the problem fails the configuration or is only a warning.
+    pub severity: Severity,
+}
+
+impl Issue {
+    fn new(key: &str, message: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            value: None,
+            source: None,
+            location: None,
+            message: message.to_string(),
+            hint: None,
+            severity: Severity::Error,
+        }
+    }
+}
+
+/// Where a value was written in a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The file path, as given to the builder.
+    pub file: String,
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 1-based column, in characters, the value starts at.
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref location) = self.location {
+            write!(f, "{location}: ")?;
+        }
+        write!(f, "`{}` {}", self.key, self.message)?;
+        match (&self.value, self.source) {
+            (Some(value), Some(source)) => write!(f, " (got {value:?} from the {source})"),
+            (Some(value), None) => write!(f, " (got {value:?})"),
+            (None, Some(source)) => write!(f, " (from the {source})"),
+            _ => Ok(()),
+        }?;
+        match self.hint {
+            Some(ref hint) => write!(f, "; {hint}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The declared configuration surface of an application.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{make_config_from, KeySpec, Schema, ValueType};
+///
+/// let schema = Schema::new()
+///     .key(KeySpec::new("port", ValueType::Integer).default("3000"))
+///     .key(KeySpec::new("verbose", ValueType::Bool).description("Log more"));
+///
+/// let config = make_config_from(["app", "--port", "8080"], |c| c.schema(schema.clone()));
+/// assert_eq!(config.get_int("port", 0), 8080);
+/// assert!(schema.validate(&config).is_ok());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    keys: Vec<KeySpec>,
+    /// The position of every key in `keys`, by canonical key.
+    index: HashMap<String, usize>,
+    /// Validators keys can refer to with [`Validator::named()`].
+    custom: Registry,
+    /// Whether keys not declared in the schema are rejected in config files.
+    closed: bool,
+}
+
+impl Schema {
+    /// Create an empty schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare a key, replacing an earlier declaration of the same key in
+    /// any case.
+    pub fn key(mut self, spec: KeySpec) -> Self {
+        match self.index.get(canonical_key(&spec.name).as_ref()) {
+            Some(&position) => self.keys[position] = spec,
+            None => {
+                let key = canonical_key(&spec.name).into_owned();
+                self.index.insert(key, self.keys.len());
+                self.keys.push(spec);
+            }
+        }
+        self
+    }
+
+    /// Register `validator` under `name`, for keys to apply with
+    /// [`Validator::named()`]. Registering a name again replaces the
+    /// earlier validator.
+    pub fn register(mut self, name: &str, validator: impl Validate + 'static) -> Self {
+        self.custom.insert(name.to_string(), Arc::new(validator));
+        self
+    }
+
+    /// Every declared key, in declaration order.
+    pub fn keys(&self) -> &[KeySpec] {
+        &self.keys
+    }
+
+    /// The declaration of `key`, given in any case.
+    pub fn get(&self, key: &str) -> Option<&KeySpec> {
+        let &position = self.index.get(canonical_key(key).as_ref())?;
+        Some(&self.keys[position])
+    }
+
+    /// Export the schema as a [JSON Schema](https://json-schema.org) (draft
+    /// 2020-12) document describing a configuration object, so editors and
+    /// external validators can check config files.
+    ///
+    /// Properties are named as the keys were declared. Types, defaults,
+    /// descriptions, required keys, and validator constraints are exported;
+    /// allowed sources have no JSON Schema equivalent and are left out, as
+    /// are validators with [`Severity::Warning`], which values may break.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::{KeySpec, Schema, Validator, ValueType};
+    ///
+    /// let schema = Schema::new().key(
+    ///     KeySpec::new("port", ValueType::Integer)
+    ///         .default("3000")
+    ///         .validator(Validator::range(1..=65535)),
+    /// );
+    ///
+    /// let json = schema.to_json_schema();
+    /// assert_eq!(json["properties"]["port"]["type"], "integer");
+    /// assert_eq!(json["properties"]["port"]["default"], 3000);
+    /// assert_eq!(json["properties"]["port"]["maximum"], 65535);
+    /// ```
+    pub fn to_json_schema(&self) -> Value {
+        let properties: Map<String, Value> = self
+            .keys
+            .iter()
+            .map(|spec| (spec.name.clone(), spec.to_json_schema()))
+            .collect();
+        let required: Vec<&str> = self
+            .keys
+            .iter()
+            .filter(|spec| spec.required)
+            .map(|spec| spec.name.as_str())
+            .collect();
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+
+    /// Render a Markdown reference table of every key, with its CLI
+    /// option, environment variable, type, default, and description,
+    /// followed by whether it is required, its constraints, examples, and
+    /// the version it was introduced in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::{KeySpec, Schema, Validator, ValueType};
+    ///
+    /// let schema = Schema::new().key(
+    ///     KeySpec::new("port", ValueType::Integer)
+    ///         .default("3000")
+    ///         .description("Server port.")
+    ///         .validator(Validator::range(1..=65535))
+    ///         .example("8080")
+    ///         .since("0.4.0"),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     schema.render_markdown(),
+    ///     "| Option | Environment | Type | Default | Description |\n\
+    ///      |--------|-------------|------|---------|-------------|\n\
+    ///      | `--port` | `PORT` | integer | `3000` | Server port. Must be between 1 and 65535. \
+    ///      Examples: `8080`. Since 0.4.0. |\n"
+    /// );
+    /// ```
+    pub fn render_markdown(&self) -> String {
+        let mut table = String::from(
+            "| Option | Environment | Type | Default | Description |\n\
+             |--------|-------------|------|---------|-------------|\n",
+        );
+        for spec in &self.keys {
+            table.push_str(&spec.markdown_row(&self.custom));
+            table.push('\n');
+        }
+        table
+    }
+
+    /// A commented `.env.example` template with every key: its description,
+    /// type and constraints, default, and whether it is required.
+    ///
+    /// Required keys are set empty, to be filled in; optional keys are
+    /// commented out with their default, so that copying the template
+    /// changes nothing until a line is uncommented. Generating the template
+    /// from the schema keeps it in sync with the code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::{KeySpec, Schema, Validator, ValueType};
+    ///
+    /// let schema = Schema::new()
+    ///     .key(KeySpec::new("api-key", ValueType::String).required(true))
+    ///     .key(
+    ///         KeySpec::new("port", ValueType::Integer)
+    ///             .default("3000")
+    ///             .description("Server port.")
+    ///             .validator(Validator::range(1..=65535)),
+    ///     );
+    ///
+    /// let template = schema.render_env_example();
+    /// assert_eq!(
+    ///     template.lines().collect::<Vec<_>>(),
+    ///     [
+    ///         "# Type: string.",
+    ///         "# Required.",
+    ///         "API_KEY=",
+    ///         "",
+    ///         "# Server port.",
+    ///         "# Type: integer. Must be between 1 and 65535.",
+    ///         "# Optional, defaults to 3000.",
+    ///         "# PORT=3000",
+    ///     ]
+    /// );
+    /// ```
+    pub fn render_env_example(&self) -> String {
+        let entries: Vec<String> = self
+            .keys
+            .iter()
+            .map(|spec| spec.env_example(&self.custom))
+            .collect();
+        entries.join("\n")
+    }
+
+    /// Read a schema from a [JSON Schema](https://json-schema.org) document
+    /// describing a configuration object, such as one written by
+    /// [`to_json_schema()`](Self::to_json_schema).
+    ///
+    /// Every entry of `properties` becomes a key. `type`, `description`,
+    /// `default`, `minimum`/`maximum` (and their exclusive forms),
+    /// `pattern`, `enum`, and `const` are understood, also inside `allOf`;
+    /// the top-level `required` list and `additionalProperties: false` are
+    /// honored. Other keywords are ignored.
+    pub fn from_json_schema(document: &Value) -> Self {
+        let mut schema = Self::new();
+        if let Some(properties) = document.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                schema = schema.key(KeySpec::from_json_schema(name, property));
+            }
+        }
+        let required = document.get("required").and_then(Value::as_array);
+        for name in required.into_iter().flatten().filter_map(Value::as_str) {
+            if let Some(&position) = schema.index.get(canonical_key(name).as_ref()) {
+                schema.keys[position].required = true;
+            }
+        }
+        schema.closed = document.get("additionalProperties") == Some(&Value::Bool(false));
+        schema
+    }
+
+    /// Check the variables defined in a `.lenv` file against the schema,
+    /// reporting every problem with the line it was found on. A missing
+    /// file passes.
+    ///
+    /// Required keys are not checked here, since another layer may provide
+    /// them; [`validate()`](Self::validate) checks them once the
+    /// configuration is resolved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::{ConfigError, Schema};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::from_json_schema(&json!({
+    ///     "type": "object",
+    ///     "properties": { "port": { "type": "integer", "maximum": 65535 } }
+    /// }));
+    ///
+    /// let dir = std::env::temp_dir().join("lino-arguments-doc-validate-lenv");
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// let path = dir.join(".lenv");
+    /// std::fs::write(&path, "# server\nPORT: 70000\n").unwrap();
+    ///
+    /// let err = schema.validate_lenv_file(path.to_str().unwrap()).unwrap_err();
+    /// let ConfigError::Invalid(issues) = err else { unreachable!() };
+    /// assert_eq!(issues[0].location.as_ref().unwrap().line, 2);
+    /// ```
+    pub fn validate_lenv_file(&self, file_path: &str) -> Result<(), ConfigError> {
+        let entries = read_lenv_entries(file_path)?;
+        self.validate_entries(&entries, file_path, ValueSource::LenvFile)
+    }
+
+    /// Check the variables defined in a `.env` file against the schema, like
+    /// [`validate_lenv_file()`](Self::validate_lenv_file).
+    pub fn validate_env_file(&self, file_path: &str) -> Result<(), ConfigError> {
+        let entries = read_env_entries(file_path)?;
+        self.validate_entries(&entries, file_path, ValueSource::EnvFile)
+    }
+
+    fn validate_entries(
+        &self,
+        entries: &[FileEntry],
+        file: &str,
+        source: ValueSource,
+    ) -> Result<(), ConfigError> {
+        let mut issues = Vec::new();
+        self.check_entries(entries, file, source, false, &mut issues);
+        fail_on_errors(issues)
+    }
+
+    /// Check variables read from `file`, adding every problem found to
+    /// `issues`.
+    pub(crate) fn check_entries(
+        &self,
+        entries: &[FileEntry],
+        file: &str,
+        source: ValueSource,
+        strict_numbers: bool,
+        issues: &mut Vec<Issue>,
+    ) {
+        for entry in entries {
+            let found = issues.len();
+            match self.get(&entry.key) {
+                Some(spec) => spec.check(
+                    Some((&entry.value, source)),
+                    strict_numbers,
+                    &self.custom,
+                    issues,
+                ),
+                None if self.closed => issues.push(Issue {
+                    key: entry.key.clone(),
+                    value: Some(entry.value.clone()),
+                    source: Some(source),
+                    location: None,
+                    message: "is not defined in the schema".to_string(),
+                    hint: None,
+                    severity: Severity::Error,
+                }),
+                None => {}
+            }
+            for issue in &mut issues[found..] {
+                issue.key = entry.key.clone();
+                issue.location = Some(entry.location(file));
+            }
+        }
+    }
+
+    /// Point `issues` about values from `source` at the entries of `file`
+    /// that set them.
+    pub(crate) fn locate(
+        &self,
+        issues: &mut [Issue],
+        entries: &[FileEntry],
+        file: &str,
+        source: ValueSource,
+    ) {
+        let entries = index_entries(entries);
+        for issue in issues.iter_mut() {
+            if issue.source != Some(source) || issue.location.is_some() {
+                continue;
+            }
+            let Some(spec) = self.get(&issue.key) else {
+                continue;
+            };
+            if let Some(entry) = entries.get(canonical_key(&spec.env_name()).as_ref()) {
+                issue.location = Some(entry.location(file));
+            }
+        }
+    }
+
+    /// An issue of `severity` for every sensitive key set in `file`, whose
+    /// permission bits `mode` let every user read it.
+    pub(crate) fn exposed_secrets(
+        &self,
+        entries: &[FileEntry],
+        file: &str,
+        source: ValueSource,
+        mode: u32,
+        severity: Severity,
+    ) -> Vec<Issue> {
+        let entries = index_entries(entries);
+        self.keys
+            .iter()
+            .filter(|spec| spec.sensitive)
+            .filter_map(|spec| {
+                let entry = entries.get(canonical_key(&spec.env_name()).as_ref())?;
+                Some(Issue {
+                    key: spec.name.clone(),
+                    value: None,
+                    source: Some(source),
+                    location: Some(entry.location(file)),
+                    message: format!("is stored in a world-readable file (mode {mode:o})"),
+                    hint: Some(format!("restrict access with `chmod 600 {file}`")),
+                    severity,
+                })
+            })
+            .collect()
+    }
+
+    /// Check a resolved configuration against the schema.
+    ///
+    /// Validation does not count as a read and is not recorded in the audit
+    /// log. Every key is checked, and all errors found are returned together
+    /// in [`ConfigError::Invalid`](crate::ConfigError::Invalid), so they can
+    /// be fixed in one pass. Failed checks with [`Severity::Warning`] do not
+    /// fail validation; the builder collects them in [`Config::warnings()`].
+    pub fn validate(&self, config: &Config) -> Result<(), ConfigError> {
+        fail_on_errors(self.check_config(config))
+    }
+
+    /// Every problem with a resolved configuration, warnings included.
+    pub(crate) fn check_config(&self, config: &Config) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        for spec in &self.keys {
+            spec.check(
+                config.resolved(&spec.name),
+                config.strict_numbers(),
+                &self.custom,
+                &mut issues,
+            );
+        }
+        issues
+    }
+
+    /// A warning for every deprecated key that was set by a layer other
+    /// than its default, with the suggested migration as the hint.
+    pub(crate) fn deprecation_warnings(&self, config: &Config) -> Vec<Issue> {
+        self.keys
+            .iter()
+            .filter_map(|spec| {
+                let deprecation = spec.deprecated.as_ref()?;
+                let (value, source) = config.resolved(&spec.name)?;
+                if source == ValueSource::Default {
+                    return None;
+                }
+                let message = if deprecation.message.is_empty() {
+                    "is deprecated".to_string()
+                } else {
+                    format!("is deprecated: {}", deprecation.message)
+                };
+                Some(Issue {
+                    key: spec.name.clone(),
+                    value: spec.shown(value),
+                    source: Some(source),
+                    location: None,
+                    message,
+                    hint: deprecation.hint(),
+                    severity: Severity::Warning,
+                })
+            })
+            .collect()
+    }
+}
+
+/// [`ConfigError::Invalid`] with the errors among `issues`, if there are
+/// any.
+/// `value` as written in a `.env` file: bare when dotenv reads it back
+/// unchanged, double-quoted otherwise.
+fn env_quoted(value: &str) -> String {
+    let bare = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-./:@,+%".contains(c));
+    if bare {
+        return value.to_string();
+    }
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '\\' | '"' | '$' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn fail_on_errors(mut issues: Vec<Issue>) -> Result<(), ConfigError> {
+    issues.retain(|issue| issue.severity == Severity::Error);
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(issues))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issues(spec: &KeySpec, value: &str, source: ValueSource) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        spec.check(Some((value, source)), false, &Registry::new(), &mut issues);
+        issues
+    }
+
+    #[test]
+    fn test_value_types() {
+        assert!(ValueType::Integer.accepts("1_000", false));
+        assert!(!ValueType::Integer.accepts("1_000", true));
+        assert!(!ValueType::Integer.accepts("1.5", false));
+        assert!(ValueType::Float.accepts("1.5", false));
+        assert!(ValueType::Bool.accepts("OFF", false));
+        assert!(!ValueType::Bool.accepts("maybe", false));
+        assert!(ValueType::String.accepts("anything", false));
+    }
+
+    #[test]
+    fn test_required_key() {
+        let spec = KeySpec::new("token", ValueType::String).required(true);
+        let mut missing = Vec::new();
+        spec.check(None, false, &Registry::new(), &mut missing);
+        assert_eq!(missing[0].to_string(), "`token` is required but not set");
+        assert!(!issues(&spec, "", ValueSource::Environment).is_empty());
+        assert!(issues(&spec, "abc", ValueSource::Environment).is_empty());
+        assert!(issues(
+            &KeySpec::new("token", ValueType::String),
+            "",
+            ValueSource::Cli
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_type_mismatch_names_value_and_source() {
+        let spec = KeySpec::new("port", ValueType::Integer);
+        let issue = issues(&spec, "eighty", ValueSource::Cli).remove(0);
+        assert_eq!(issue.value.as_deref(), Some("eighty"));
+        assert_eq!(issue.source, Some(ValueSource::Cli));
+        assert_eq!(
+            issue.to_string(),
+            "`port` is not a valid integer (got \"eighty\" from the command line)"
+        );
+    }
+
+    #[test]
+    fn test_allowed_sources() {
+        let spec = KeySpec::new("token", ValueType::String).sources([ValueSource::Environment]);
+        assert!(issues(&spec, "abc", ValueSource::Environment).is_empty());
+        assert!(issues(&spec, "abc", ValueSource::Default).is_empty());
+        let issue = issues(&spec, "abc", ValueSource::Cli).remove(0);
+        assert_eq!(issue.message, "may not be set from the command line");
+    }
+
+    #[test]
+    fn test_range_validator() {
+        let port = KeySpec::new("port", ValueType::Integer).validator(Validator::range(1..=65535));
+        assert!(issues(&port, "1", ValueSource::Cli).is_empty());
+        assert!(issues(&port, "65_535", ValueSource::Cli).is_empty());
+        let issue = issues(&port, "70000", ValueSource::Environment).remove(0);
+        assert_eq!(
+            issue.to_string(),
+            "`port` must be between 1 and 65535 (got \"70000\" from the environment)"
+        );
+
+        let ratio = Validator::range(0.0..1.0);
+        assert!(ratio.check("0.5", false).is_ok());
+        assert_eq!(ratio.check("1", false).unwrap_err(), "must be less than 1");
+        assert_eq!(ratio.check("-1", false).unwrap_err(), "must be at least 0");
+        assert_eq!(ratio.check("half", false).unwrap_err(), "is not a number");
+        assert_eq!(
+            Validator::range(10..).check("5", false).unwrap_err(),
+            "must be at least 10"
+        );
+        assert_eq!(
+            Validator::range(..=10).check("11", false).unwrap_err(),
+            "must be at most 10"
+        );
+    }
+
+    #[test]
+    fn test_matches_validator() {
+        let name = Validator::matches(r"^[a-z-]+$");
+        assert!(name.check("my-app", false).is_ok());
+        assert_eq!(
+            name.check("My App", false).unwrap_err(),
+            "must match `^[a-z-]+$`"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid pattern")]
+    fn test_matches_validator_rejects_invalid_pattern() {
+        Validator::matches("[a-z");
+    }
+
+    #[test]
+    fn test_one_of_validator() {
+        let format = Validator::one_of(["json", "text"]);
+        assert!(format.check("json", false).is_ok());
+        assert_eq!(
+            format.check("JSON", false).unwrap_err(),
+            "must be one of: json, text"
+        );
+    }
+
+    #[test]
+    fn test_validators_run_in_order() {
+        let spec = KeySpec::new("mode", ValueType::String)
+            .validator(Validator::matches("^[a-z]+$"))
+            .validator(Validator::one_of(["fast", "slow"]));
+        let issue = issues(&spec, "Fast", ValueSource::Cli).remove(0);
+        assert_eq!(issue.message, "must match `^[a-z]+$`");
+        let issue = issues(&spec, "medium", ValueSource::Cli).remove(0);
+        assert_eq!(issue.message, "must be one of: fast, slow");
+    }
+
+    #[test]
+    fn test_all_issues_of_a_key_are_reported() {
+        let spec = KeySpec::new("mode", ValueType::String)
+            .sources([ValueSource::Environment])
+            .validator(Validator::matches("^[a-z]+$"))
+            .validator(Validator::one_of(["fast", "slow"]));
+        let messages: Vec<_> = issues(&spec, "Fast", ValueSource::Cli)
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "may not be set from the command line",
+                "must match `^[a-z]+$`",
+                "must be one of: fast, slow",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_named_validators() {
+        struct Even;
+
+        impl Validate for Even {
+            fn validate(&self, value: &str) -> Result<(), String> {
+                match value.parse::<i64>() {
+                    Ok(n) if n % 2 == 0 => Ok(()),
+                    _ => Err("must be even".to_string()),
+                }
+            }
+
+            fn describe(&self) -> Option<String> {
+                Some("must be even".to_string())
+            }
+        }
+
+        let schema = Schema::new()
+            .register("even", Even)
+            .register("short", |value: &str| {
+                (value.len() <= 2)
+                    .then_some(())
+                    .ok_or("is too long".to_string())
+            })
+            .key(
+                KeySpec::new("workers", ValueType::Integer)
+                    .validator(Validator::named("even"))
+                    .validator(Validator::named("short"))
+                    .validator(Validator::named("missing")),
+            );
+        let spec = &schema.keys()[0];
+        let messages = |value: &str| {
+            let mut issues = Vec::new();
+            spec.check(
+                Some((value, ValueSource::Cli)),
+                false,
+                &schema.custom,
+                &mut issues,
+            );
+            issues
+                .into_iter()
+                .map(|issue| issue.message)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(messages("12"), ["uses unregistered validator `missing`"]);
+        assert_eq!(
+            messages("123"),
+            [
+                "must be even",
+                "is too long",
+                "uses unregistered validator `missing`"
+            ]
+        );
+        assert!(schema
+            .render_markdown()
+            .contains("| integer |  | Must be even. |"));
+        assert_eq!(spec.to_json_schema(), json!({ "type": "integer" }));
+    }
+
+    #[test]
+    fn test_warning_severity() {
+        let port = KeySpec::new("port", ValueType::Integer)
+            .validator(Validator::range(1..=65535))
+            .validator(Validator::range(1024..).severity(Severity::Warning));
+
+        let found = issues(&port, "80", ValueSource::Cli);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].message, "must be at least 1024");
+        assert_eq!(found[0].severity, Severity::Warning);
+        assert!(fail_on_errors(found).is_ok());
+
+        let found = issues(&port, "0", ValueSource::Cli);
+        let severities: Vec<_> = found.iter().map(|issue| issue.severity).collect();
+        assert_eq!(severities, [Severity::Error, Severity::Warning]);
+        let Err(ConfigError::Invalid(errors)) = fail_on_errors(found) else {
+            panic!("expected the error to fail validation");
+        };
+        assert_eq!(errors.len(), 1);
+
+        assert_eq!(port.to_json_schema()["minimum"], 1);
+        assert!(port.to_json_schema().get("allOf").is_none());
+        assert!(port
+            .markdown_row(&Registry::new())
+            .ends_with("Must be between 1 and 65535; should be at least 1024. |"));
+    }
+
+    #[test]
+    fn test_sensitive_key() {
+        let token = KeySpec::new("api-token", ValueType::String)
+            .sensitive(true)
+            .validator(Validator::matches("^sk-"));
+
+        let [issue] = &issues(&token, "hunter2", ValueSource::Environment)[..] else {
+            panic!("expected one issue");
+        };
+        assert_eq!(issue.value, None);
+        assert_eq!(
+            issue.to_string(),
+            "`api-token` must match `^sk-` (from the environment)"
+        );
+
+        let json = token.to_json_schema();
+        assert_eq!(json["writeOnly"], true);
+        assert!(KeySpec::from_json_schema("api-token", &json).sensitive);
+        assert!(token
+            .markdown_row(&Registry::new())
+            .contains("**Sensitive.** Must match `^sk-`."));
+    }
+
+    #[test]
+    fn test_validators_skipped_for_wrong_type() {
+        let port = KeySpec::new("port", ValueType::Integer).validator(Validator::range(1..=65535));
+        let messages: Vec<_> = issues(&port, "http", ValueSource::Cli)
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect();
+        assert_eq!(messages, ["is not a valid integer"]);
+    }
+
+    #[test]
+    fn test_json_schema_document() {
+        let schema = Schema::new()
+            .key(
+                KeySpec::new("port", ValueType::Integer)
+                    .default("3_000")
+                    .description("Server port")
+                    .validator(Validator::range(1..=65535)),
+            )
+            .key(
+                KeySpec::new("ratio", ValueType::Float)
+                    .default("0.5")
+                    .validator(Validator::range(0.0..1.0)),
+            )
+            .key(KeySpec::new("verbose", ValueType::Bool).default("no"))
+            .key(
+                KeySpec::new("format", ValueType::String)
+                    .required(true)
+                    .validator(Validator::one_of(["json", "text"])),
+            );
+
+        assert_eq!(
+            schema.to_json_schema(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "port": {
+                        "type": "integer",
+                        "description": "Server port",
+                        "default": 3000,
+                        "minimum": 1,
+                        "maximum": 65535,
+                    },
+                    "ratio": {
+                        "type": "number",
+                        "default": 0.5,
+                        "minimum": 0,
+                        "exclusiveMaximum": 1,
+                    },
+                    "verbose": { "type": "boolean", "default": false },
+                    "format": { "type": "string", "enum": ["json", "text"] },
+                },
+                "required": ["format"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_json_schema_repeated_keywords_go_to_all_of() {
+        let spec = KeySpec::new("name", ValueType::String)
+            .default("oops")
+            .validator(Validator::matches("^[a-z]+$"))
+            .validator(Validator::matches("^.{3,}$"));
+        assert_eq!(
+            spec.to_json_schema(),
+            json!({
+                "type": "string",
+                "default": "oops",
+                "pattern": "^[a-z]+$",
+                "allOf": [{ "pattern": "^.{3,}$" }],
+            })
+        );
+        assert_eq!(ValueType::Integer.to_json("lots"), json!("lots"));
+    }
+
+    #[test]
+    fn test_from_json_schema_round_trip() {
+        let exported = Schema::new()
+            .key(
+                KeySpec::new("port", ValueType::Integer)
+                    .default("3000")
+                    .description("Server port")
+                    .validator(Validator::range(1..=65535)),
+            )
+            .key(
+                KeySpec::new("format", ValueType::String)
+                    .required(true)
+                    .validator(Validator::one_of(["json", "text"])),
+            )
+            .to_json_schema();
+
+        let schema = Schema::from_json_schema(&exported);
+        assert_eq!(schema.to_json_schema(), exported);
+        let port = schema.get("PORT").unwrap();
+        assert_eq!(port.value_type, ValueType::Integer);
+        assert_eq!(port.default.as_deref(), Some("3000"));
+        assert_eq!(issues(port, "0", ValueSource::LenvFile).len(), 1);
+        assert!(schema.get("format").unwrap().required);
+    }
+
+    #[test]
+    fn test_from_json_schema_keywords() {
+        let schema = Schema::from_json_schema(&json!({
+            "properties": {
+                "name": {
+                    "pattern": "^[a-z]+$",
+                    "allOf": [{ "exclusiveMinimum": 0 }, { "pattern": "[" }],
+                },
+                "mode": { "const": "fast", "minLength": 3 },
+            },
+            "required": ["missing"],
+        }));
+
+        let name = schema.get("name").unwrap();
+        assert_eq!(name.value_type, ValueType::String);
+        assert_eq!(name.validators.len(), 2);
+        assert_eq!(
+            issues(name, "Ab", ValueSource::EnvFile)
+                .into_iter()
+                .map(|issue| issue.message)
+                .collect::<Vec<_>>(),
+            ["must match `^[a-z]+$`", "is not a number"]
+        );
+        let mode = schema.get("mode").unwrap();
+        assert_eq!(
+            issues(mode, "slow", ValueSource::EnvFile)[0].message,
+            "must be one of: fast"
+        );
+        assert_eq!(schema.keys().len(), 2);
+    }
+
+    #[test]
+    fn test_check_entries_reports_locations() {
+        let entry = |key: &str, value: &str, line| FileEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+            line,
+            column: key.len() + 3,
+        };
+        let entries = [entry("PORT", "http", 3), entry("EXTRA", "1", 4)];
+        let open = Schema::new().key(KeySpec::new("port", ValueType::Integer));
+        let mut found = Vec::new();
+        open.check_entries(&entries, ".lenv", ValueSource::LenvFile, false, &mut found);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].to_string(),
+            ".lenv:3:7: `PORT` is not a valid integer (got \"http\" from the .lenv file)"
+        );
+
+        let closed = Schema::from_json_schema(&json!({
+            "properties": { "port": { "type": "integer" } },
+            "additionalProperties": false,
+        }));
+        let mut found = Vec::new();
+        closed.check_entries(&entries, ".env", ValueSource::EnvFile, false, &mut found);
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            found[1].to_string(),
+            ".env:4:8: `EXTRA` is not defined in the schema (got \"1\" from the .env file)"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let schema = Schema::new()
+            .key(
+                KeySpec::new("apiToken", ValueType::String)
+                    .description("Token for the | API")
+                    .required(true)
+                    .validator(Validator::matches("^(a|b)+$")),
+            )
+            .key(KeySpec::new("verbose", ValueType::Bool))
+            .key(
+                KeySpec::new("ratio", ValueType::Float)
+                    .validator(Validator::range(0.0..1.0))
+                    .validator(Validator::one_of(["0.5", "0.25"]))
+                    .example("0.5")
+                    .example("0.25"),
+            );
+
+        let table = schema.render_markdown();
+        let rows: Vec<&str> = table.lines().skip(2).collect();
+        assert_eq!(
+            rows,
+            [
+                "| `--api-token` | `API_TOKEN` | string |  | Token for the \\| API **Required.** \
+                 Must match `^(a\\|b)+$`. |",
+                "| `--verbose` | `VERBOSE` | boolean |  |  |",
+                "| `--ratio` | `RATIO` | float |  | Must be at least 0 and less than 1; \
+                 must be one of `0.5`, `0.25`. Examples: `0.5`, `0.25`. |",
+            ]
+        );
+        assert_eq!(Schema::new().render_markdown().lines().count(), 2);
+    }
+
+    #[test]
+    fn test_render_env_example() {
+        let schema = Schema::new()
+            .key(
+                KeySpec::new("db-password", ValueType::String)
+                    .env("PGPASSWORD")
+                    .description("Database password.\n\nAsk the team.")
+                    .sensitive(true)
+                    .required(true),
+            )
+            .key(
+                KeySpec::new("greeting", ValueType::String)
+                    .default("hello world")
+                    .deprecated("Unused since the redesign.")
+                    .example("hi")
+                    .since("0.3.0"),
+            )
+            .key(KeySpec::new("verbose", ValueType::Bool));
+
+        assert_eq!(
+            schema.render_env_example(),
+            "# Database password.\n\
+             #\n\
+             # Ask the team.\n\
+             # Type: string.\n\
+             # Required.\n\
+             # Sensitive: do not commit a real value.\n\
+             PGPASSWORD=\n\
+             \n\
+             # Deprecated. Unused since the redesign.\n\
+             # Type: string.\n\
+             # Optional, defaults to hello world.\n\
+             # Examples: hi.\n\
+             # Since 0.3.0.\n\
+             # GREETING=\"hello world\"\n\
+             \n\
+             # Type: boolean.\n\
+             # Optional.\n\
+             # VERBOSE=\n"
+        );
+        assert_eq!(Schema::new().render_env_example(), "");
+    }
+
+    #[test]
+    fn test_examples_in_json_schema() {
+        let spec = KeySpec::new("port", ValueType::Integer)
+            .example("8080")
+            .example("443");
+        assert_eq!(spec.to_json_schema()["examples"], json!([8080, 443]));
+        let schema = Schema::new().key(spec).to_json_schema();
+        let read = Schema::from_json_schema(&schema);
+        assert_eq!(read.get("port").unwrap().examples, ["8080", "443"]);
+    }
+
+    #[test]
+    fn test_deprecation_metadata() {
+        let spec = KeySpec::new("listen-port", ValueType::Integer)
+            .deprecated("Renamed for consistency.")
+            .replaced_by("port");
+        let deprecation = spec.deprecated.as_ref().unwrap();
+        assert_eq!(deprecation.message, "Renamed for consistency.");
+        assert_eq!(deprecation.replacement.as_deref(), Some("port"));
+        assert_eq!(
+            deprecation.hint().as_deref(),
+            Some("use `--port` / `PORT` instead")
+        );
+        assert_eq!(
+            KeySpec::new("old", ValueType::String)
+                .replaced_by("new")
+                .deprecated
+                .unwrap()
+                .message,
+            ""
+        );
+
+        let row = spec.markdown_row(&Registry::new());
+        assert!(row.ends_with(
+            "| **Deprecated.** Renamed for consistency. Use `--port` / `PORT` instead. |"
+        ));
+
+        let json = Schema::new().key(spec).to_json_schema();
+        assert_eq!(json["properties"]["listen-port"]["deprecated"], true);
+        let read = Schema::from_json_schema(&json);
+        assert!(read.get("listen-port").unwrap().deprecated.is_some());
+    }
+
+    #[test]
+    fn test_issue_display_with_hint() {
+        let issue = Issue {
+            hint: Some("use `--port` / `PORT` instead".to_string()),
+            value: Some("80".to_string()),
+            source: Some(ValueSource::Environment),
+            ..Issue::new("listen-port", "is deprecated")
+        };
+        assert_eq!(
+            issue.to_string(),
+            "`listen-port` is deprecated (got \"80\" from the environment); \
+             use `--port` / `PORT` instead"
+        );
+    }
+
+    #[test]
+    fn test_schema_replaces_key_in_any_case() {
+        let schema = Schema::new()
+            .key(KeySpec::new("api-key", ValueType::String))
+            .key(KeySpec::new("API_KEY", ValueType::Integer));
+        assert_eq!(schema.keys().len(), 1);
+        assert_eq!(schema.get("apiKey").unwrap().value_type, ValueType::Integer);
+        assert!(schema.get("port").is_none());
+    }
+
+    #[test]
+    fn test_schema_index_keeps_declaration_order() {
+        let schema = Schema::new()
+            .key(KeySpec::new("host", ValueType::String))
+            .key(KeySpec::new("port", ValueType::String))
+            .key(KeySpec::new("HOST", ValueType::Integer))
+            .key(KeySpec::new("max-retries", ValueType::Integer));
+        let names: Vec<&str> = schema
+            .keys()
+            .iter()
+            .map(|spec| spec.name.as_str())
+            .collect();
+        assert_eq!(names, ["HOST", "port", "max-retries"]);
+        assert_eq!(schema.get("maxRetries").unwrap().name, "max-retries");
+        assert_eq!(schema.get("Port").unwrap().name, "port");
+    }
+}
@@ -0,0 +1,712 @@
+//! Configuration shared across threads and reloaded while the program runs.
+
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock, Weak};
+
+use crate::canonical_key;
+use crate::config::{Config, ConfigBuilder, ConfigError, EnvLayer, ValueSource};
+use crate::poll::{schedule, PollPolicy};
+use crate::source::Watcher;
+
+/// A callback run after every reload.
+type ReloadCallback = Arc<dyn Fn(&Config) + Send + Sync>;
+
+/// A callback run with the changes of every reload that changed values.
+type ChangeCallback = Arc<dyn Fn(&ConfigDiff) + Send + Sync>;
+
+/// A check run on every reloaded configuration before it is used.
+type ReloadCheck = Arc<dyn Fn(&Config) -> Result<(), ConfigError> + Send + Sync>;
+
+/// A [`Config`] that can be reloaded while the program runs, shared between
+/// threads by cloning.
+///
+/// [`snapshot()`](Self::snapshot) returns the current configuration, which
+/// stays unchanged while it is in use; [`reload()`](Self::reload) resolves
+/// every layer again with the same builder and arguments and swaps in the
+/// result. With the `watch` feature, [`watch_files()`](Self::watch_files)
+/// reloads whenever the config files change; on Unix,
+/// [`reload_on_sighup()`](Self::reload_on_sighup) reloads when the process
+/// receives `SIGHUP`; and [`poll()`](Self::poll) reloads on a schedule, for
+/// sources that cannot be watched.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::try_make_shared_config_from;
+///
+/// let shared = try_make_shared_config_from(["app"], |c| {
+///     c.option("doc-shared-port", "Port", "3000")
+/// })?;
+/// shared.on_reload(|config| println!("port is now {}", config.get("doc-shared-port")));
+///
+/// let config = shared.snapshot();
+/// assert_eq!(config.get("doc-shared-port"), "3000");
+/// shared.reload()?;
+/// # Ok::<(), lino_arguments::ConfigError>(())
+/// ```
+#[derive(Clone)]
+pub struct SharedConfig {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    builder: ConfigBuilder,
+    args: Vec<OsString>,
+    /// The variables the first build set in the environment from the
+    /// config files, which reloads do not take for the environment.
+    file_vars: Vec<(String, String)>,
+    current: RwLock<Arc<Config>>,
+    /// Held while reloading, so that reloads do not interleave.
+    reloading: Mutex<()>,
+    callbacks: Mutex<Vec<ReloadCallback>>,
+    change_callbacks: Mutex<Vec<ChangeCallback>>,
+    /// The keys of the [`KeyWatch`]es still in use.
+    watches: Mutex<Vec<Weak<dyn WatchedKey>>>,
+    checks: Mutex<Vec<ReloadCheck>>,
+    /// Why the last reload failed, if it did.
+    last_error: Mutex<Option<ConfigError>>,
+}
+
+/// A copy of the callbacks or checks in `list`, to run without holding its
+/// lock.
+fn listed<T: Clone>(list: &Mutex<Vec<T>>) -> Vec<T> {
+    list.lock().unwrap_or_else(|err| err.into_inner()).clone()
+}
+
+impl SharedConfig {
+    fn new(builder: ConfigBuilder, args: Vec<OsString>) -> Result<Self, ConfigError> {
+        let config = builder.try_build_from(args.clone())?;
+        let file_vars = builder.file_env_vars(&config);
+        Ok(Self {
+            inner: Arc::new(Inner {
+                builder,
+                args,
+                file_vars,
+                current: RwLock::new(Arc::new(config)),
+                reloading: Mutex::new(()),
+                callbacks: Mutex::new(Vec::new()),
+                change_callbacks: Mutex::new(Vec::new()),
+                watches: Mutex::new(Vec::new()),
+                checks: Mutex::new(Vec::new()),
+                last_error: Mutex::new(None),
+            }),
+        })
+    }
+
+    /// The current configuration.
+    pub fn snapshot(&self) -> Arc<Config> {
+        self.inner
+            .current
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Resolve the configuration again, reading the config files, secrets,
+    /// and sources afresh, and make it the current one. On error, including
+    /// a [veto](Self::veto_reload), the current configuration is kept.
+    ///
+    /// Unlike the first build, a reload keeps the values of the config
+    /// files to itself rather than setting them in the process environment,
+    /// so it is safe from any thread.
+    ///
+    /// The checks and callbacks run without any lock held, so they may
+    /// register other callbacks or reload again themselves.
+    pub fn reload(&self) -> Result<(), ConfigError> {
+        let reloading = self
+            .inner
+            .reloading
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let old = self.snapshot();
+        let config = match self.build(&old) {
+            Ok(config) => Arc::new(config),
+            Err(err) => {
+                *self.last_error() = Some(err.clone());
+                return Err(err);
+            }
+        };
+        *self
+            .inner
+            .current
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = config.clone();
+        *self.last_error() = None;
+        drop(reloading);
+        for callback in listed(&self.inner.callbacks) {
+            callback(&config);
+        }
+        let diff = ConfigDiff::between(&old, &config);
+        let watches: Vec<_> = self.watches().iter().filter_map(Weak::upgrade).collect();
+        for watch in watches {
+            watch.update(&diff);
+        }
+        if !diff.is_empty() {
+            for callback in listed(&self.inner.change_callbacks) {
+                callback(&diff);
+            }
+        }
+        Ok(())
+    }
+
+    /// Why the last reload failed, or `None` if it succeeded or there was
+    /// none. The current configuration is still the one built before it.
+    ///
+    /// Reloads run in the background, such as by
+    /// [`reload_on_sighup()`](Self::reload_on_sighup) or
+    /// [`poll()`](Self::poll), only report their failures here. Schema
+    /// violations come as [`ConfigError::Invalid`] with every issue, which
+    /// the `diagnostics` feature renders with `ConfigError::to_report()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::{try_make_shared_config_from, ConfigError};
+    ///
+    /// let shared = try_make_shared_config_from(["app"], |c| {
+    ///     c.option("doc-last-error-port", "Port", "3000")
+    /// })?;
+    /// shared.veto_reload(|_| Err(ConfigError::ParseError("not now".to_string())));
+    /// assert!(shared.reload().is_err());
+    /// assert!(shared.last_reload_error().is_some());
+    /// assert_eq!(shared.snapshot().get("doc-last-error-port"), "3000");
+    /// # Ok::<(), lino_arguments::ConfigError>(())
+    /// ```
+    pub fn last_reload_error(&self) -> Option<ConfigError> {
+        self.last_error().clone()
+    }
+
+    fn last_error(&self) -> std::sync::MutexGuard<'_, Option<ConfigError>> {
+        self.inner
+            .last_error
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Build the configuration following `previous` and run the reload
+    /// checks on it.
+    fn build(&self, previous: &Config) -> Result<Config, ConfigError> {
+        let layer = EnvLayer::in_memory(&self.inner.file_vars);
+        let mut config = self
+            .inner
+            .builder
+            .try_build_with(self.inner.args.clone(), layer)?;
+        config.follow(previous);
+        for check in listed(&self.inner.checks) {
+            check(&config)?;
+        }
+        Ok(config)
+    }
+
+    /// Call `callback` with the new configuration after every reload.
+    pub fn on_reload(&self, callback: impl Fn(&Config) + Send + Sync + 'static) {
+        self.inner
+            .callbacks
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(Arc::new(callback));
+    }
+
+    /// Call `callback` with the changed keys after every reload that
+    /// changed values, so that a subsystem can react to the settings it
+    /// uses only.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::try_make_shared_config_from;
+    ///
+    /// let shared = try_make_shared_config_from(["app"], |c| {
+    ///     c.option("doc-change-level", "Log level", "info")
+    /// })?;
+    /// shared.on_change(|diff| {
+    ///     if let Some(change) = diff.get("doc-change-level") {
+    ///         println!("log level {:?} -> {:?}", change.old, change.new);
+    ///     }
+    /// });
+    /// # Ok::<(), lino_arguments::ConfigError>(())
+    /// ```
+    pub fn on_change(&self, callback: impl Fn(&ConfigDiff) + Send + Sync + 'static) {
+        self.inner
+            .change_callbacks
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(Arc::new(callback));
+    }
+
+    /// A handle on the value of `key`, parsed as `T`, which follows the
+    /// reloads, so that a component can read its own setting without
+    /// handling whole snapshots. Fails if the current value cannot be
+    /// parsed; values of later reloads that cannot be parsed are skipped.
+    /// Once the handle and its clones are dropped, reloads forget the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lino_arguments::try_make_shared_config_from;
+    ///
+    /// let shared = try_make_shared_config_from(["app"], |c| {
+    ///     c.option("doc-watch-port", "Port", "3000")
+    /// })?;
+    /// let port = shared.watch::<u16>("doc-watch-port")?;
+    /// assert_eq!(port.get(), 3000);
+    /// # Ok::<(), lino_arguments::ConfigError>(())
+    /// ```
+    pub fn watch<T>(&self, key: &str) -> Result<KeyWatch<T>, ConfigError>
+    where
+        T: FromStr + Clone + Send + Sync + 'static,
+        T::Err: Display,
+    {
+        let key = canonical_key(key).into_owned();
+        let value: T = parse_value(&key, &self.snapshot().get(&key))?;
+        let state = Arc::new(KeyState {
+            #[cfg(feature = "tokio")]
+            sender: tokio::sync::watch::Sender::new(value.clone()),
+            value: RwLock::new(value),
+            key,
+        });
+        let watched: Weak<KeyState<T>> = Arc::downgrade(&state);
+        self.watches().push(watched);
+        Ok(KeyWatch { state })
+    }
+
+    /// The watches still in use, with those whose [`KeyWatch`]es were all
+    /// dropped removed.
+    fn watches(&self) -> std::sync::MutexGuard<'_, Vec<Weak<dyn WatchedKey>>> {
+        let mut watches = self
+            .inner
+            .watches
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        watches.retain(|watch| watch.strong_count() > 0);
+        watches
+    }
+
+    /// Run `check` on every reloaded configuration before it replaces the
+    /// current one; an error vetoes the reload, and is returned by
+    /// [`reload()`](Self::reload).
+    pub fn veto_reload(
+        &self,
+        check: impl Fn(&Config) -> Result<(), ConfigError> + Send + Sync + 'static,
+    ) {
+        self.inner
+            .checks
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(Arc::new(check));
+    }
+
+    /// Reload on the schedule of `policy` from a background thread, until the
+    /// returned [`Watcher`] is dropped, so that the values of sources that
+    /// cannot be watched are picked up. Subscribe with
+    /// [`on_change()`](Self::on_change) to hear about the polls that changed
+    /// values.
+    ///
+    /// Failed reloads, such as when a source cannot be reached, keep the
+    /// current configuration, and the polls back off as `policy` says.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use lino_arguments::{try_make_shared_config_from, PollPolicy};
+    ///
+    /// let shared = try_make_shared_config_from(["app"], |c| {
+    ///     c.option("doc-poll-level", "Log level", "info")
+    /// })?;
+    /// let _polling = shared.poll(
+    ///     PollPolicy::every(Duration::from_secs(30)).jitter(Duration::from_secs(5)),
+    /// );
+    /// # Ok::<(), lino_arguments::ConfigError>(())
+    /// ```
+    pub fn poll(&self, policy: PollPolicy) -> Watcher {
+        let shared = self.clone();
+        schedule(policy, move || shared.reload().is_ok())
+    }
+
+    /// Reload whenever the process receives `SIGHUP`, the signal daemons
+    /// reload their configuration on, until the returned [`Watcher`] is
+    /// dropped. Unix only.
+    ///
+    /// Reloads that fail or are [vetoed](Self::veto_reload) keep the current
+    /// configuration.
+    #[cfg(unix)]
+    pub fn reload_on_sighup(&self) -> Result<Watcher, ConfigError> {
+        use signal_hook::consts::SIGHUP;
+        use signal_hook::iterator::Signals;
+
+        let mut signals = Signals::new([SIGHUP])?;
+        let handle = signals.handle();
+        let shared = self.clone();
+        std::thread::spawn(move || {
+            for _ in signals.forever() {
+                let _ = shared.reload();
+            }
+        });
+        Ok(Watcher::new(move || handle.close()))
+    }
+
+    /// Reload whenever the `.lenv`, `.env`, or sops files, or the secrets
+    /// directories change, until the returned [`Watcher`] is dropped.
+    /// Requires the `watch` feature.
+    ///
+    /// Changes less than 100 milliseconds apart, such as an editor writing a
+    /// file several times over, are coalesced into a single reload; see
+    /// [`watch_files_with()`](Self::watch_files_with) to set the window.
+    /// Reloads that fail keep the current configuration. Files that do not
+    /// exist yet, even in directories that do not exist yet, are picked up
+    /// once they are created, as a build skips missing files.
+    #[cfg(feature = "watch")]
+    pub fn watch_files(&self) -> Result<Watcher, ConfigError> {
+        self.watch_files_with(std::time::Duration::from_millis(100))
+    }
+
+    /// Like [`watch_files()`](Self::watch_files), but reload once the files
+    /// have not changed for `debounce`, so that a burst of changes causes a
+    /// single reload. Requires the `watch` feature.
+    #[cfg(feature = "watch")]
+    pub fn watch_files_with(&self, debounce: std::time::Duration) -> Result<Watcher, ConfigError> {
+        use notify::EventKind;
+        use std::sync::mpsc::{self, RecvTimeoutError};
+
+        let targets = self
+            .inner
+            .builder
+            .watched_paths()
+            .iter()
+            .map(|path| resolve(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (changed, changes) = mpsc::channel::<()>();
+        let relevant_to = targets.clone();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            // A change to a file or directory watched, or the creation
+            // of a directory leading to one that does not exist yet
+            let relevant = event.paths.iter().any(|path| {
+                relevant_to
+                    .iter()
+                    .any(|target| path.starts_with(target) || target.starts_with(path))
+            });
+            if relevant {
+                let _ = changed.send(());
+            }
+        })
+        .map_err(|err| ConfigError::FileError(format!("Failed to watch files: {err}")))?;
+        let watcher = Arc::new(Mutex::new(FileWatcher {
+            watcher,
+            dirs: Vec::new(),
+        }));
+        {
+            let mut watcher = watcher.lock().unwrap_or_else(|err| err.into_inner());
+            for target in &targets {
+                let dir = watch_dir(target);
+                watcher.watch(&dir).map_err(|err| {
+                    ConfigError::FileError(format!("Failed to watch {}: {err}", dir.display()))
+                })?;
+            }
+        }
+
+        let shared = self.clone();
+        let weak = Arc::downgrade(&watcher);
+        // Stops when the watcher is dropped, and with it the sender
+        std::thread::spawn(move || {
+            while changes.recv().is_ok() {
+                loop {
+                    match changes.recv_timeout(debounce) {
+                        Ok(()) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                // Watch the directories created since, closer to the files
+                if let Some(watcher) = weak.upgrade() {
+                    let mut watcher = watcher.lock().unwrap_or_else(|err| err.into_inner());
+                    for target in &targets {
+                        let _ = watcher.watch(&watch_dir(target));
+                    }
+                }
+                let _ = shared.reload();
+            }
+        });
+        Ok(Watcher::new(move || drop(watcher)))
+    }
+}
+
+impl std::fmt::Debug for SharedConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SharedConfig")
+            .field(&self.snapshot())
+            .finish()
+    }
+}
+
+/// The value of a key of a [`SharedConfig`], kept current across reloads,
+/// from [`SharedConfig::watch()`]. Clones follow the same key.
+pub struct KeyWatch<T> {
+    state: Arc<KeyState<T>>,
+}
+
+struct KeyState<T> {
+    key: String,
+    value: RwLock<T>,
+    #[cfg(feature = "tokio")]
+    sender: tokio::sync::watch::Sender<T>,
+}
+
+impl<T: Clone> KeyState<T> {
+    fn set(&self, value: T) {
+        #[cfg(feature = "tokio")]
+        self.sender.send_replace(value.clone());
+        *self.value.write().unwrap_or_else(|err| err.into_inner()) = value;
+    }
+}
+
+/// The state of a [`KeyWatch`], whatever the type of its value.
+trait WatchedKey: Send + Sync {
+    /// Take the new value of the key from `diff`, if it changed and parses.
+    fn update(&self, diff: &ConfigDiff);
+}
+
+impl<T> WatchedKey for KeyState<T>
+where
+    T: FromStr + Clone + Send + Sync,
+    T::Err: Display,
+{
+    fn update(&self, diff: &ConfigDiff) {
+        let Some(change) = diff.get(&self.key) else {
+            return;
+        };
+        let new = change.new.as_deref().unwrap_or_default();
+        if let Ok(value) = parse_value::<T>(&self.key, new) {
+            self.set(value);
+        }
+    }
+}
+
+impl<T: Clone> KeyWatch<T> {
+    /// The current value.
+    pub fn get(&self) -> T {
+        self.state
+            .value
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// The key, in kebab-case.
+    pub fn key(&self) -> &str {
+        &self.state.key
+    }
+
+    /// A tokio watch channel receiving the value after every reload that
+    /// changes it. Requires the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub fn subscribe(&self) -> tokio::sync::watch::Receiver<T> {
+        self.state.sender.subscribe()
+    }
+}
+
+impl<T> Clone for KeyWatch<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: Clone + std::fmt::Debug> std::fmt::Debug for KeyWatch<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyWatch")
+            .field("key", &self.state.key)
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+/// `value` of `key` parsed as `T`.
+fn parse_value<T>(key: &str, value: &str) -> Result<T, ConfigError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|err| ConfigError::ParseError(format!("invalid value {value:?} for {key}: {err}")))
+}
+
+/// The keys whose values a reload changed, for
+/// [`SharedConfig::on_change()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDiff {
+    changes: Vec<KeyChange>,
+}
+
+/// A key whose value a reload changed, set, or unset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChange {
+    /// The key, in kebab-case.
+    pub key: String,
+    /// The value before the reload, or `None` if the key was not set.
+    pub old: Option<String>,
+    /// The value after the reload, or `None` if the key is no longer set.
+    pub new: Option<String>,
+    /// The layer the old value came from.
+    pub old_source: Option<ValueSource>,
+    /// The layer the new value came from.
+    pub new_source: Option<ValueSource>,
+}
+
+impl ConfigDiff {
+    /// The keys whose values differ between `old` and `new`, in key order.
+    /// Keys whose value stayed the same but moved to another layer are not
+    /// changes.
+    fn between(old: &Config, new: &Config) -> Self {
+        let mut keys: Vec<&str> = old.keys().chain(new.keys()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        let changes = keys
+            .into_iter()
+            .filter_map(|key| {
+                let (before, after) = (old.resolved(key), new.resolved(key));
+                if before.map(|(value, _)| value) == after.map(|(value, _)| value) {
+                    return None;
+                }
+                Some(KeyChange {
+                    key: key.to_string(),
+                    old: before.map(|(value, _)| value.to_string()),
+                    new: after.map(|(value, _)| value.to_string()),
+                    old_source: before.map(|(_, source)| source),
+                    new_source: after.map(|(_, source)| source),
+                })
+            })
+            .collect();
+        Self { changes }
+    }
+
+    /// Every changed key, in key order.
+    pub fn changes(&self) -> &[KeyChange] {
+        &self.changes
+    }
+
+    /// The change of `key`, in any case, if it changed.
+    pub fn get(&self, key: &str) -> Option<&KeyChange> {
+        let key = canonical_key(key);
+        self.changes.iter().find(|change| change.key == key)
+    }
+
+    /// Whether `key`, in any case, changed.
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Whether no value changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// The absolute path of `path` with the symbolic links of its directory
+/// resolved, as file system events report it. Only the part of the path
+/// that exists is resolved, as the rest may be created later.
+#[cfg(feature = "watch")]
+fn resolve(path: &std::path::Path) -> Result<std::path::PathBuf, ConfigError> {
+    let path = std::path::absolute(path)?;
+    let Some(existing) = path.ancestors().find(|dir| dir.is_dir()) else {
+        return Ok(path);
+    };
+    let rest = path.strip_prefix(existing).unwrap_or(&path);
+    Ok(existing.canonicalize()?.join(rest))
+}
+
+/// The directory to watch for changes to `target`: the target itself if it
+/// is a directory, or else the nearest existing directory above it. Editors
+/// replace files rather than write to them, so files are watched through
+/// their directory.
+#[cfg(feature = "watch")]
+fn watch_dir(target: &std::path::Path) -> std::path::PathBuf {
+    target
+        .ancestors()
+        .skip(usize::from(!target.is_dir()))
+        .find(|dir| dir.is_dir())
+        .unwrap_or(target)
+        .to_path_buf()
+}
+
+/// The watcher behind [`SharedConfig::watch_files_with()`] and the
+/// directories it watches.
+#[cfg(feature = "watch")]
+struct FileWatcher {
+    watcher: notify::RecommendedWatcher,
+    dirs: Vec<std::path::PathBuf>,
+}
+
+#[cfg(feature = "watch")]
+impl FileWatcher {
+    /// Watch `dir` unless it already is.
+    fn watch(&mut self, dir: &std::path::Path) -> notify::Result<()> {
+        use notify::{RecursiveMode, Watcher as _};
+
+        if self.dirs.iter().any(|watched| watched == dir) {
+            return Ok(());
+        }
+        self.watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        self.dirs.push(dir.to_path_buf());
+        Ok(())
+    }
+}
+
+/// Like [`try_make_config()`](crate::try_make_config), but returns a
+/// [`SharedConfig`] that can be reloaded.
+pub fn try_make_shared_config<F>(configure: F) -> Result<SharedConfig, ConfigError>
+where
+    F: FnOnce(&mut ConfigBuilder) -> &mut ConfigBuilder,
+{
+    try_make_shared_config_from(std::env::args_os(), configure)
+}
+
+/// Like [`try_make_config_from()`](crate::try_make_config_from), but
+/// returns a [`SharedConfig`] that can be reloaded.
+pub fn try_make_shared_config_from<I, T, F>(
+    args: I,
+    configure: F,
+) -> Result<SharedConfig, ConfigError>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+    F: FnOnce(&mut ConfigBuilder) -> &mut ConfigBuilder,
+{
+    let mut builder = ConfigBuilder::new();
+    configure(&mut builder);
+    SharedConfig::new(builder, args.into_iter().map(Into::into).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_dropped_watches_are_removed() {
+        let shared = crate::try_make_shared_config_from(["app"], |c| {
+            c.option("shared-drop-port", "Port", "3000")
+        })
+        .unwrap();
+        let port = shared.watch::<u16>("shared-drop-port").unwrap();
+        let copy = port.clone();
+        for _ in 0..3 {
+            drop(shared.watch::<u16>("shared-drop-port").unwrap());
+        }
+        assert_eq!(shared.watches().len(), 1);
+        drop(port);
+        shared.reload().unwrap();
+        assert_eq!(shared.watches().len(), 1);
+        assert_eq!(copy.get(), 3000);
+        drop(copy);
+        shared.reload().unwrap();
+        assert!(shared.inner.watches.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,67 @@
+//! [sops](https://github.com/getsops/sops)-encrypted config files.
+//!
+//! Files are decrypted by the `sops` binary, so every backend it supports
+//! (age, PGP, AWS/GCP KMS, Azure Key Vault, Vault) works with the usual
+//! environment configuration, such as `SOPS_AGE_KEY_FILE`.
+
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+use serde_json::Value;
+
+use crate::config::ConfigError;
+use crate::source::flatten_json_text;
+
+/// Whether `text`, a JSON or YAML document, carries sops metadata.
+pub(crate) fn is_encrypted(text: &str) -> bool {
+    match serde_json::from_str::<Value>(text) {
+        Ok(document) => document.get("sops").is_some_and(Value::is_object),
+        Err(_) => text.lines().any(|line| line.trim_end() == "sops:"),
+    }
+}
+
+/// Decrypt the sops file at `path` into its values, by the canonical form
+/// of their flattened keys, only for `keys` if given. A missing file has no
+/// values.
+pub(crate) fn decrypt(
+    path: &str,
+    keys: Option<&HashSet<String>>,
+) -> Result<HashMap<String, String>, ConfigError> {
+    let failed =
+        |reason: String| ConfigError::FileError(format!("Failed to decrypt {path}: {reason}"));
+    if !std::path::Path::new(path).exists() {
+        return Ok(HashMap::new());
+    }
+    let text = std::fs::read_to_string(path)?;
+    if !is_encrypted(&text) {
+        return Err(failed("not a sops-encrypted file".to_string()));
+    }
+    let output = Command::new("sops")
+        .args(["--decrypt", "--output-type", "json", path])
+        .output()
+        .map_err(|err| failed(format!("could not run sops: {err}")))?;
+    if !output.status.success() {
+        return Err(failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    let text = String::from_utf8(output.stdout).map_err(|err| failed(err.to_string()))?;
+    let mut values = HashMap::new();
+    flatten_json_text(&text, keys, &mut values).map_err(|err| failed(err.to_string()))?;
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_encrypted() {
+        assert!(is_encrypted(
+            r#"{"port": "ENC[...]", "sops": {"mac": "ENC[...]"}}"#
+        ));
+        assert!(!is_encrypted(r#"{"port": 8080}"#));
+        assert!(is_encrypted("port: ENC[...]\nsops:\n    mac: ENC[...]\n"));
+        assert!(!is_encrypted("port: 8080\n"));
+    }
+}
@@ -0,0 +1,452 @@
+//! Configuration layers fetched from outside the process, such as parameter
+//! stores and configuration services.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde_json::value::RawValue;
+
+use crate::canonical_key;
+use crate::config::ConfigError;
+
+/// A layer of configuration values fetched from outside the process.
+///
+/// Register one with [`ConfigBuilder::source()`](crate::ConfigBuilder::source).
+/// Its values rank with the secrets directories, below environment
+/// variables and above the `.lenv` and `.env` files, and are reported as
+/// [`ValueSource::Remote`](crate::ValueSource::Remote) with the source's
+/// [`name()`](Self::name).
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use lino_arguments::{make_config_from, ConfigError, Source};
+///
+/// struct Flags;
+///
+/// impl Source for Flags {
+///     fn name(&self) -> &'static str {
+///         "flag service"
+///     }
+///
+///     fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+///         Ok(HashMap::from([("doc-flag-port".to_string(), "8080".to_string())]))
+///     }
+/// }
+///
+/// let config = make_config_from(["app"], |c| {
+///     c.source(Flags).option("doc-flag-port", "Port", "3000")
+/// });
+/// assert_eq!(config.get("doc-flag-port"), "8080");
+/// ```
+pub trait Source: Send + Sync {
+    /// What the source is, for messages (`got "x" from the {name}`).
+    fn name(&self) -> &'static str;
+
+    /// Fetch every value, by key in any case (`db-password`, `DB_PASSWORD`).
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError>;
+
+    /// Fetch the values of `keys`, canonical keys such as `db-password`,
+    /// which are all a build looks up. Sources that fetch large documents
+    /// override it to skip the values that were not asked for; it may
+    /// return other values too.
+    ///
+    /// The default fetches every value with [`load()`](Self::load), as
+    /// [`Config::preflight()`](crate::Config::preflight) does to check the
+    /// whole source.
+    fn load_keys(&self, keys: &HashSet<String>) -> Result<HashMap<String, String>, ConfigError> {
+        let _ = keys;
+        self.load()
+    }
+
+    /// The revision of the values last [loaded](Self::load), such as a
+    /// commit hash, reported by [`Config::revision()`](crate::Config::revision)
+    /// for the values resolved from the source.
+    ///
+    /// Returns `None`, the default, for sources without revisions.
+    fn revision(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether the values last [loaded](Self::load) are a cached copy,
+    /// because the source could not be reached, reported by
+    /// [`Config::is_stale()`](crate::Config::is_stale) for the values
+    /// resolved from the source.
+    ///
+    /// Returns `false`, the default, for sources without a cache.
+    fn is_stale(&self) -> bool {
+        false
+    }
+
+    /// Start calling `changed` from a background thread whenever the values
+    /// may have changed, until the returned [`Watcher`] is dropped.
+    ///
+    /// Returns `Ok(None)`, the default, for sources that cannot be watched.
+    fn watch(
+        &self,
+        changed: Box<dyn Fn() + Send + 'static>,
+    ) -> Result<Option<Watcher>, ConfigError> {
+        let _ = changed;
+        Ok(None)
+    }
+
+    /// Save the values of every successful load to the file at `path`, and
+    /// load them from it when the source cannot be reached.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use lino_arguments::{make_config, EtcdSource, Source};
+    ///
+    /// let config = make_config(|c| {
+    ///     c.source(EtcdSource::new("/myapp/").cached(".etcd-cache.json"))
+    ///         .option("log-level", "Log level", "info")
+    /// });
+    /// if config.is_stale("log-level") {
+    ///     eprintln!("etcd is unreachable; using the cached log level");
+    /// }
+    /// ```
+    fn cached(self, path: &str) -> Cached<Self>
+    where
+        Self: Sized,
+    {
+        Cached {
+            source: self,
+            path: path.to_string(),
+            stale: Arc::default(),
+        }
+    }
+}
+
+/// A [`Source`] with an offline cache, made by [`Source::cached()`].
+///
+/// The values of every successful load are saved to the cache file
+/// (readable only by the current user on Unix), so every value is fetched,
+/// even for [`Source::load_keys()`]. When the source cannot be
+/// reached and the cache file exists, its values are loaded instead and
+/// reported as [stale](crate::Config::is_stale).
+#[derive(Debug, Clone)]
+pub struct Cached<S> {
+    source: S,
+    path: String,
+    stale: Arc<AtomicBool>,
+}
+
+impl<S: Source> Source for Cached<S> {
+    fn name(&self) -> &'static str {
+        self.source.name()
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        load_cached(&self.path, &self.stale, || self.source.load())
+    }
+
+    fn revision(&self) -> Option<String> {
+        if self.is_stale() {
+            None
+        } else {
+            self.source.revision()
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        self.stale.load(Ordering::SeqCst)
+    }
+
+    fn watch(
+        &self,
+        changed: Box<dyn Fn() + Send + 'static>,
+    ) -> Result<Option<Watcher>, ConfigError> {
+        self.source.watch(changed)
+    }
+}
+
+/// A [`Source`] of values handed over by the host rather than read from
+/// the process environment or files, which WebAssembly hosts such as
+/// browsers and Cloudflare Workers do not have: the bindings of a Worker,
+/// or the text of a `.lenv` file fetched over the network.
+///
+/// # Examples
+///
+/// ```
+/// use lino_arguments::{make_config_from, StaticSource};
+///
+/// let bindings = StaticSource::new("Worker bindings", [("DOC_STATIC_REGION", "eu")]);
+/// let settings = StaticSource::lenv("settings.lenv", "DOC_STATIC_PORT: 8080\n");
+/// let config = make_config_from(["worker"], |c| {
+///     c.source(bindings)
+///         .source(settings)
+///         .option("doc-static-region", "Region", "us")
+///         .option("doc-static-port", "Port", "3000")
+/// });
+/// assert_eq!(config.get("doc-static-region"), "eu");
+/// assert_eq!(config.get("doc-static-port"), "8080");
+/// ```
+#[derive(Debug, Clone)]
+pub struct StaticSource {
+    name: &'static str,
+    values: HashMap<String, String>,
+}
+
+impl StaticSource {
+    /// The `values` by key in any case, reported as coming from `name`.
+    pub fn new<K, V>(name: &'static str, values: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        Self {
+            name,
+            values: values
+                .into_iter()
+                .map(|(key, value)| (canonical_key(key.as_ref()).into_owned(), value.into()))
+                .collect(),
+        }
+    }
+
+    /// The values of the `.lenv` file `text`, reported as coming from
+    /// `name`.
+    pub fn lenv(name: &'static str, text: &str) -> Self {
+        Self::new(name, crate::config::parse_lenv(text))
+    }
+}
+
+impl Source for StaticSource {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        Ok(self.values.clone())
+    }
+}
+
+/// Keeps a [`Source::watch()`] running until it is dropped.
+pub struct Watcher {
+    stop: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl Watcher {
+    /// A watcher running `stop` when it is dropped.
+    pub fn new(stop: impl FnOnce() + Send + 'static) -> Self {
+        Self {
+            stop: Some(Box::new(stop)),
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            stop();
+        }
+    }
+}
+
+impl std::fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Watcher")
+    }
+}
+
+/// The canonical key of `name` below `prefix`, whose remaining `/` separate
+/// words like any other separator (`/myapp/prod/db/password` below
+/// `/myapp/prod/` is `db-password`).
+pub(crate) fn key_below(name: &str, prefix: &str) -> String {
+    let relative = name.strip_prefix(prefix).unwrap_or(name);
+    canonical_key(relative.trim_matches('/')).into_owned()
+}
+
+/// Add the scalar values of the JSON document `text` to `values`, by the
+/// canonical form of their keys, only for `keys` if given. Nested keys are
+/// joined with `_` (`{"db": {"password": ..}}` sets `db-password`); arrays
+/// and nulls are skipped. The document is parsed one object at a time, so
+/// members that lead to none of `keys` are skipped without being turned
+/// into values. Fails if `text` is not JSON.
+pub(crate) fn flatten_json_text(
+    text: &str,
+    keys: Option<&HashSet<String>>,
+    values: &mut HashMap<String, String>,
+) -> serde_json::Result<()> {
+    let document: &RawValue = serde_json::from_str(text)?;
+    flatten_raw(document, "", keys, values)
+}
+
+fn flatten_raw(
+    document: &RawValue,
+    prefix: &str,
+    keys: Option<&HashSet<String>>,
+    values: &mut HashMap<String, String>,
+) -> serde_json::Result<()> {
+    if !document.get().starts_with('{') {
+        return Ok(());
+    }
+    let map: BTreeMap<String, &RawValue> = serde_json::from_str(document.get())?;
+    for (key, value) in map {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}_{key}")
+        };
+        let canonical = canonical_key(&key);
+        let text = match value.get().as_bytes().first() {
+            Some(b'{') => {
+                // Nested keys are joined with `_`, which always separates
+                // words, so they extend the canonical key of the object
+                let leads_to_key = |key: &String| {
+                    key.strip_prefix(canonical.as_ref())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+                };
+                if canonical.is_empty() || keys.is_none_or(|keys| keys.iter().any(leads_to_key)) {
+                    flatten_raw(value, &key, keys, values)?;
+                }
+                continue;
+            }
+            _ if keys.is_some_and(|keys| !keys.contains(canonical.as_ref())) => continue,
+            Some(b'"') => serde_json::from_str::<String>(value.get())?,
+            Some(b't' | b'f') => serde_json::from_str::<bool>(value.get())?.to_string(),
+            Some(b'-' | b'0'..=b'9') => {
+                serde_json::from_str::<serde_json::Number>(value.get())?.to_string()
+            }
+            _ => continue,
+        };
+        values.insert(canonical.into_owned(), text);
+    }
+    Ok(())
+}
+
+/// Save the values fetched from a source to the cache file at `path`,
+/// readable only by the current user on Unix.
+pub(crate) fn write_cache(path: &str, values: &HashMap<String, String>) -> Result<(), ConfigError> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    let json =
+        serde_json::to_string(values).map_err(|err| ConfigError::FileError(err.to_string()))?;
+    file.write_all(json.as_bytes())?;
+    Ok(())
+}
+
+/// The values `fetch` returns, saved to the cache file at `path`, or the
+/// values saved there when `fetch` fails, setting `stale`.
+pub(crate) fn load_cached(
+    path: &str,
+    stale: &AtomicBool,
+    fetch: impl FnOnce() -> Result<HashMap<String, String>, ConfigError>,
+) -> Result<HashMap<String, String>, ConfigError> {
+    match fetch() {
+        Ok(values) => {
+            stale.store(false, Ordering::SeqCst);
+            write_cache(path, &values)?;
+            Ok(values)
+        }
+        Err(_) if Path::new(path).exists() => {
+            let values = read_cache(path)?;
+            stale.store(true, Ordering::SeqCst);
+            Ok(values)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// The values saved to the cache file at `path`.
+pub(crate) fn read_cache(path: &str) -> Result<HashMap<String, String>, ConfigError> {
+    let text = std::fs::read_to_string(path)?;
+    serde_json::from_str(&text)
+        .map_err(|err| ConfigError::FileError(format!("Invalid cache file {path}: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flatten_json_text() {
+        let text = r#"{
+            "port": 8080,
+            "debug": true,
+            "apiToken": "sk-1234",
+            "db": {"password": "hunter2", "hosts": ["a", "b"]},
+            "unset": null
+        }"#;
+        let mut values = HashMap::new();
+        flatten_json_text(text, None, &mut values).unwrap();
+        assert_eq!(values.len(), 4);
+        assert_eq!(values["port"], "8080");
+        assert_eq!(values["debug"], "true");
+        assert_eq!(values["api-token"], "sk-1234");
+        assert_eq!(values["db-password"], "hunter2");
+        assert!(flatten_json_text("{\"port\": ", None, &mut values).is_err());
+    }
+
+    #[test]
+    fn test_flatten_json_text_keys() {
+        // Only the objects leading to a wanted key are descended into
+        let text = r#"{
+            "port": 8080,
+            "db": {"password": "hunter2", "user": "admin"},
+            "logging": {"level": "info", "sinks": {"file": "/var/log/app"}},
+            "big": [1, 2, 3]
+        }"#;
+        let keys = HashSet::from(["db-password".to_string(), "logging-sinks-file".to_string()]);
+        let mut values = HashMap::new();
+        flatten_json_text(text, Some(&keys), &mut values).unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["db-password"], "hunter2");
+        assert_eq!(values["logging-sinks-file"], "/var/log/app");
+
+        let keys = HashSet::from(["db-user".to_string()]);
+        let mut values = HashMap::new();
+        flatten_json_text(
+            r#"{"db": {"user": "admin"}, "dbx": {"user": 1}}"#,
+            Some(&keys),
+            &mut values,
+        )
+        .unwrap();
+        assert_eq!(
+            values,
+            HashMap::from([("db-user".to_string(), "admin".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_key_below() {
+        assert_eq!(
+            key_below("/myapp/prod/db/password", "/myapp/prod/"),
+            "db-password"
+        );
+        assert_eq!(key_below("/myapp/prod/PORT", "/myapp/prod"), "port");
+        assert_eq!(key_below("/other/API_TOKEN", "/myapp/"), "other-api-token");
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+        let path = path.to_str().unwrap();
+        let values = HashMap::from([("port".to_string(), "8080".to_string())]);
+        write_cache(path, &values).unwrap();
+        assert_eq!(read_cache(path).unwrap(), values);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::write(path, "not json").unwrap();
+        assert!(read_cache(path).is_err());
+    }
+}
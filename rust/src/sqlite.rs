@@ -0,0 +1,196 @@
+//! Settings persisted in an SQLite database. Requires the `sqlite` feature.
+//!
+//! Values live in a `config` table, one row per key:
+//!
+//! ```sql
+//! CREATE TABLE config (
+//!     key TEXT PRIMARY KEY,
+//!     value TEXT NOT NULL,
+//!     updated_at TEXT NOT NULL
+//! );
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+
+use crate::canonical_key;
+use crate::config::ConfigError;
+use crate::source::Source;
+
+/// A [`Source`] reading, and a store writing, the `config` table of an
+/// SQLite database, for settings that users edit at runtime and that should
+/// resolve like any other value.
+///
+/// Keys are stored in their canonical form, and every write records its
+/// time in `updated_at` (UTC, ISO 8601). A database that does not exist yet
+/// has no values; the first write creates it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lino_arguments::{make_config, SqliteSource};
+///
+/// let settings = SqliteSource::new("settings.db");
+/// settings.set("theme", "dark")?;
+///
+/// let config = make_config(|c| {
+///     c.source(settings).option("theme", "Color theme", "light")
+/// });
+/// assert_eq!(config.get("theme"), "dark");
+/// # Ok::<(), lino_arguments::ConfigError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct SqliteSource {
+    path: String,
+}
+
+impl SqliteSource {
+    /// The database at `path`.
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+
+    /// The value of `key`, in any case, or `None` if it is not set.
+    pub fn get(&self, key: &str) -> Result<Option<String>, ConfigError> {
+        let Some(connection) = self.open_existing()? else {
+            return Ok(None);
+        };
+        if !has_table(&connection).map_err(|err| self.error(err))? {
+            return Ok(None);
+        }
+        connection
+            .query_row(
+                "SELECT value FROM config WHERE key = ?1",
+                [canonical_key(key).as_ref()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| self.error(err))
+    }
+
+    /// Set `key`, in any case, to `value`, creating the database and the
+    /// table if needed.
+    pub fn set(&self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let connection = self.open()?;
+        connection
+            .execute(
+                "INSERT INTO config (key, value, updated_at)
+                 VALUES (?1, ?2, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+                 ON CONFLICT (key) DO UPDATE
+                 SET value = excluded.value, updated_at = excluded.updated_at",
+                params![canonical_key(key).as_ref(), value],
+            )
+            .map_err(|err| self.error(err))?;
+        Ok(())
+    }
+
+    /// Remove `key`, in any case. Removing a key that is not set is not an
+    /// error.
+    pub fn delete(&self, key: &str) -> Result<(), ConfigError> {
+        let Some(connection) = self.open_existing()? else {
+            return Ok(());
+        };
+        if has_table(&connection).map_err(|err| self.error(err))? {
+            connection
+                .execute(
+                    "DELETE FROM config WHERE key = ?1",
+                    [canonical_key(key).as_ref()],
+                )
+                .map_err(|err| self.error(err))?;
+        }
+        Ok(())
+    }
+
+    /// The database, created with the table if needed.
+    fn open(&self) -> Result<Connection, ConfigError> {
+        let connection = Connection::open(&self.path).map_err(|err| self.error(err))?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS config (
+                     key TEXT PRIMARY KEY,
+                     value TEXT NOT NULL,
+                     updated_at TEXT NOT NULL
+                 )",
+            )
+            .map_err(|err| self.error(err))?;
+        Ok(connection)
+    }
+
+    /// The database, or `None` if it does not exist.
+    fn open_existing(&self) -> Result<Option<Connection>, ConfigError> {
+        if !Path::new(&self.path).exists() {
+            return Ok(None);
+        }
+        Connection::open_with_flags(&self.path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+            .map(Some)
+            .map_err(|err| self.error(err))
+    }
+
+    fn error(&self, err: rusqlite::Error) -> ConfigError {
+        ConfigError::SourceError(format!("{}: {err}", self.path))
+    }
+}
+
+impl Source for SqliteSource {
+    fn name(&self) -> &'static str {
+        "SQLite database"
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+        let Some(connection) = self.open_existing()? else {
+            return Ok(HashMap::new());
+        };
+        if !has_table(&connection).map_err(|err| self.error(err))? {
+            return Ok(HashMap::new());
+        }
+        let mut statement = connection
+            .prepare("SELECT key, value FROM config")
+            .map_err(|err| self.error(err))?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))
+            .map_err(|err| self.error(err))?;
+        rows.map(|row| {
+            row.map(|(key, value)| (canonical_key(&key).into_owned(), value))
+                .map_err(|err| self.error(err))
+        })
+        .collect()
+    }
+}
+
+/// Whether the database has the `config` table.
+fn has_table(connection: &Connection) -> rusqlite::Result<bool> {
+    connection.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'config')",
+        [],
+        |row| row.get(0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_records_update_time() {
+        let dir = std::env::temp_dir().join("lino-arguments-test-sqlite");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.db");
+        let _ = std::fs::remove_file(&path);
+        let settings = SqliteSource::new(path.to_str().unwrap());
+        settings.set("Theme", "dark").unwrap();
+
+        let connection = Connection::open(&path).unwrap();
+        let (key, updated_at): (String, String) = connection
+            .query_row("SELECT key, updated_at FROM config", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(key, "theme");
+        assert_eq!(updated_at.len(), "2026-01-01T00:00:00.000Z".len());
+        assert!(updated_at.ends_with('Z'));
+    }
+}
@@ -0,0 +1,344 @@
+//! Spans and events describing how a configuration is resolved, emitted
+//! with [tracing](https://docs.rs/tracing) under the `tracing` feature and
+//! as [log](https://docs.rs/log) records under the `log` feature. Without
+//! either every function here does nothing.
+//!
+//! Events use the `lino_arguments` target: one for each layer loaded, with
+//! the number of keys and the time it took, one for each key resolved, with
+//! the name it was matched by and its layer, all inside a `resolve` span,
+//! and one for each default used instead of a missing or unparsable value
+//! and each warning about the resolved values.
+#![cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+
+use std::fmt::Debug;
+
+use crate::config::{ConfigError, ValueSource};
+use crate::Issue;
+
+/// When an operation started, to report its duration. There is no clock on
+/// `wasm32-unknown-unknown`, where durations are reported as zero.
+pub(crate) struct Start {
+    #[cfg(any(feature = "tracing", feature = "log"))]
+    instant: Option<std::time::Instant>,
+}
+
+impl Start {
+    #[cfg(any(feature = "tracing", feature = "log"))]
+    fn elapsed(&self) -> std::time::Duration {
+        self.instant
+            .map_or(std::time::Duration::ZERO, |instant| instant.elapsed())
+    }
+}
+
+/// The span of one resolution, entered until it is dropped.
+pub(crate) struct Span {
+    #[cfg(feature = "tracing")]
+    _entered: tracing::span::EnteredSpan,
+}
+
+pub(crate) fn start() -> Start {
+    Start {
+        #[cfg(any(feature = "tracing", feature = "log"))]
+        instant: if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+            None
+        } else {
+            Some(std::time::Instant::now())
+        },
+    }
+}
+
+/// Enter the span of resolving `options` options.
+pub(crate) fn resolving(options: usize) -> Span {
+    Span {
+        #[cfg(feature = "tracing")]
+        _entered: tracing::debug_span!(target: "lino_arguments", "resolve", options).entered(),
+    }
+}
+
+/// `keys` values were loaded from `name`, a file, directory, or source of
+/// the `layer`.
+pub(crate) fn loaded(layer: ValueSource, name: &str, keys: usize, start: Start) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        target: "lino_arguments",
+        layer = %layer,
+        name,
+        keys,
+        duration_us = start.elapsed().as_micros() as u64,
+        "loaded values",
+    );
+    #[cfg(feature = "log")]
+    log::debug!(
+        target: "lino_arguments",
+        "loaded {keys} values from the {layer} {name} in {:?}",
+        start.elapsed(),
+    );
+}
+
+/// Loading `name` failed with `error`.
+fn load_failed(layer: ValueSource, name: &str, error: &ConfigError, start: Start) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        target: "lino_arguments",
+        layer = %layer,
+        name,
+        error = %error,
+        duration_us = start.elapsed().as_micros() as u64,
+        "could not load values",
+    );
+    #[cfg(feature = "log")]
+    log::warn!(target: "lino_arguments", "could not load the {layer} {name}: {error}");
+}
+
+/// Report `result`, the values of `name` or why they could not be loaded,
+/// and pass it on.
+pub(crate) fn traced<T>(
+    layer: ValueSource,
+    name: &str,
+    start: Start,
+    result: Result<T, ConfigError>,
+) -> Result<T, ConfigError>
+where
+    for<'a> &'a T: IntoIterator,
+{
+    match result {
+        Ok(ref values) if cfg!(any(feature = "tracing", feature = "log")) => {
+            loaded(layer, name, values.into_iter().count(), start)
+        }
+        Ok(_) => {}
+        Err(ref error) => load_failed(layer, name, error, start),
+    }
+    result
+}
+
+/// `key` was resolved from `layer`, where it is named `variant`.
+pub(crate) fn resolved(key: &str, variant: Option<&str>, layer: ValueSource, start: Start) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        target: "lino_arguments",
+        key,
+        variant,
+        layer = %layer,
+        duration_us = start.elapsed().as_micros() as u64,
+        "resolved key",
+    );
+    #[cfg(feature = "log")]
+    match variant {
+        Some(variant) => log::debug!(
+            target: "lino_arguments",
+            "resolved {key} from the {layer}, as {variant}",
+        ),
+        None => log::debug!(target: "lino_arguments", "resolved {key} from the {layer}"),
+    }
+}
+
+/// `key` was looked up in the environment and found as `variant`, if at
+/// all.
+pub(crate) fn env_lookup(key: &str, variant: Option<&str>) {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(target: "lino_arguments", key, variant, "looked up environment variable");
+    #[cfg(feature = "log")]
+    log::trace!(target: "lino_arguments", "looked up {key}, found {variant:?}");
+}
+
+/// `key` is not set, so `default` is used.
+pub(crate) fn fallback(key: &str, default: &dyn Debug) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(target: "lino_arguments", key, ?default, "using the default");
+    #[cfg(feature = "log")]
+    log::debug!(target: "lino_arguments", "{key} is not set, using the default {default:?}");
+}
+
+/// The value of `key` is not a valid `kind`, so `default` is used. The
+/// value itself is left out, since it may be a secret.
+pub(crate) fn parse_failed(key: &str, kind: &str, default: &dyn Debug) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        target: "lino_arguments",
+        key,
+        kind,
+        ?default,
+        "invalid value, using the default",
+    );
+    #[cfg(feature = "log")]
+    log::warn!(
+        target: "lino_arguments",
+        "{key} is not a valid {kind}, using the default {default:?}",
+    );
+}
+
+/// The resolved values raised `issue`, a warning such as a deprecation.
+pub(crate) fn warning(issue: &Issue) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(target: "lino_arguments", key = issue.key, "{issue}");
+    #[cfg(feature = "log")]
+    log::warn!(target: "lino_arguments", "{issue}");
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::fmt::{Debug, Write};
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::{span, Event, Metadata, Subscriber};
+
+    use crate::make_config_from;
+
+    /// Records the fields of every event, as `name=value` pairs.
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            write!(self.0, "{}={:?} ", field.name(), value).unwrap();
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(String::new());
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    #[test]
+    fn test_resolution_events() {
+        let dir = std::env::temp_dir().join("lino-arguments-test-trace");
+        std::fs::create_dir_all(&dir).unwrap();
+        let lenv = dir.join(".lenv");
+        std::fs::write(&lenv, "TRACE_HOST: db.internal\n").unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        tracing::subscriber::with_default(Recorder(events.clone()), || {
+            make_config_from(["app", "--trace-port", "8080"], |c| {
+                c.lenv(lenv.to_str().unwrap())
+                    .option("trace-port", "Port", "3000")
+                    .option("trace-host", "Host", "localhost")
+            });
+        });
+        std::env::remove_var("TRACE_HOST");
+
+        let events = events.lock().unwrap();
+        let find = |needle: &str| {
+            events
+                .iter()
+                .find(|event| event.contains(needle))
+                .unwrap_or_else(|| panic!("no event with {needle} in {events:#?}"))
+        };
+        let loaded = find("layer=.lenv file");
+        assert!(loaded.contains("message=loaded values"));
+        assert!(loaded.contains("keys=1"));
+        let port = find("key=\"trace-port\"");
+        assert!(
+            port.contains("variant=\"trace-port\" layer=command line"),
+            "{port}"
+        );
+        let host = find("key=\"trace-host\"");
+        assert!(
+            host.contains("variant=\"TRACE_HOST\" layer=.lenv file"),
+            "{host}"
+        );
+    }
+}
+
+#[cfg(all(test, feature = "log"))]
+mod log_tests {
+    use std::sync::Mutex;
+
+    use log::{Level, Log, Metadata, Record};
+
+    use crate::{getenv_bool, getenv_int, try_make_config_from, KeySpec, Schema, ValueType};
+
+    /// Every record logged by any test, as `LEVEL message`.
+    static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct Recorder;
+
+    impl Log for Recorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            if record.target() == "lino_arguments" {
+                let line = format!("{} {}", record.level(), record.args());
+                RECORDS.lock().unwrap().push(line);
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn records_with(needle: &str) -> Vec<String> {
+        RECORDS
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|record| record.contains(needle))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_log_records() {
+        let _ = log::set_logger(&Recorder);
+        log::set_max_level(log::LevelFilter::Trace);
+
+        std::env::set_var("LOG_TEST_PORT", "eighty");
+        assert_eq!(getenv_int("LOG_TEST_PORT", 3000), 3000);
+        std::env::remove_var("LOG_TEST_PORT");
+        assert!(!getenv_bool("LOG_TEST_MISSING", false));
+
+        let schema =
+            Schema::new().key(KeySpec::new("log-test-old", ValueType::String).deprecated("Gone."));
+        std::env::set_var("LOG_TEST_OLD", "1");
+        try_make_config_from(["app"], |c| c.schema(schema)).unwrap();
+        std::env::remove_var("LOG_TEST_OLD");
+
+        assert_eq!(
+            records_with("LOG_TEST_PORT is not"),
+            [format!(
+                "{} LOG_TEST_PORT is not a valid integer, using the default 3000",
+                Level::Warn
+            )]
+        );
+        assert_eq!(
+            records_with("LOG_TEST_MISSING is not"),
+            [format!(
+                "{} LOG_TEST_MISSING is not set, using the default false",
+                Level::Debug
+            )]
+        );
+        let resolved = records_with("resolved log-test-old");
+        assert_eq!(
+            resolved,
+            [format!(
+                "{} resolved log-test-old from the environment, as LOG_TEST_OLD",
+                Level::Debug
+            )]
+        );
+        let [deprecation] = &records_with("`log-test-old` is deprecated")[..] else {
+            panic!("expected one deprecation warning");
+        };
+        assert!(deprecation.starts_with("WARN "), "{deprecation}");
+    }
+}
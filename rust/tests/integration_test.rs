@@ -1,14 +1,27 @@
 //! Integration tests for lino-arguments
 
+#![cfg(feature = "std")]
+
 use lino_arguments::{
-    getenv, getenv_bool, getenv_int, init_with, load_env_file, load_env_file_override,
-    load_lenv_file, load_lenv_file_override, make_config_from, read_lino_env, to_camel_case,
-    to_kebab_case, to_pascal_case, to_snake_case, to_upper_case, write_lino_env, LinoEnv,
-    LinoParser, Parser,
+    canonical_key, convert_all, convert_case, convert_case_with, convert_with_separator,
+    detect_case, from_sources_from, getenv, getenv_bool, getenv_byte_size, getenv_duration,
+    getenv_float, getenv_float_with, getenv_int, getenv_int_with, getenv_list, getenv_map,
+    getenv_secret, getenv_with, init_with, load_env_file, load_env_file_override, load_lenv_file,
+    load_lenv_file_override, make_config_from, normalize_value, parse_byte_size, parse_duration,
+    parse_list, parse_map, read_lino_env, split_words, split_words_with, to_camel_case,
+    to_dot_case, to_flat_case, to_kebab_case, to_pascal_case, to_path_case,
+    to_screaming_kebab_case, to_snake_case, to_title_case, to_train_case, to_upper_case,
+    try_make_config_from, write_case, write_kebab_case, write_lino_env, write_upper_case,
+    AcronymStyle, AwsSource, Case, CaseOptions, ConfigError, EmptyValues, EtcdSource,
+    FilePermissions, GetenvOptions, GitSource, GrpcSource, KeyMap, KeySpec, KubernetesSource,
+    LinoEnv, LinoParser, Parser, RedisSource, Schema, Severity, Validator, ValueSource, ValueType,
+    WordCase,
 };
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::env;
 use std::fs;
+use std::time::Duration;
 use tempfile::tempdir;
 
 // ============================================================================
@@ -89,6 +102,221 @@ mod case_conversion {
         assert_eq!(to_pascal_case("api_key"), "ApiKey");
         assert_eq!(to_pascal_case("my_variable_name"), "MyVariableName");
     }
+
+    #[test]
+    fn test_already_upper_case_keys_stay_single_words() {
+        assert_eq!(to_kebab_case("PORT"), "port");
+        assert_eq!(to_snake_case("PORT"), "port");
+        assert_eq!(to_camel_case("PORT"), "port");
+        assert_eq!(to_pascal_case("PORT"), "Port");
+    }
+
+    #[test]
+    fn test_digits_attach_to_preceding_word() {
+        assert_eq!(to_upper_case("v2ApiKey"), "V2_API_KEY");
+        assert_eq!(to_upper_case("ipv6Address"), "IPV6_ADDRESS");
+        assert_eq!(to_kebab_case("v2ApiKey"), "v2-api-key");
+        assert_eq!(to_snake_case("ipv6Address"), "ipv6_address");
+        assert_eq!(to_pascal_case("v2-api-key"), "V2ApiKey");
+        assert_eq!(to_camel_case("IPV6_ADDRESS"), "ipv6Address");
+    }
+
+    #[test]
+    fn test_convert_case() {
+        assert_eq!(convert_case("api-key", Case::UpperSnake), "API_KEY");
+        assert_eq!(convert_case("API_KEY", Case::Camel), "apiKey");
+        assert_eq!(convert_case("apiKey", Case::Kebab), "api-key");
+        assert_eq!(convert_case("ApiKey", Case::Snake), "api_key");
+        assert_eq!(convert_case("api_key", Case::Pascal), "ApiKey");
+    }
+
+    #[test]
+    fn test_detect_case() {
+        assert_eq!(detect_case("API_KEY"), Some(Case::UpperSnake));
+        assert_eq!(detect_case("apiKey"), Some(Case::Camel));
+        assert_eq!(detect_case("api-key"), Some(Case::Kebab));
+        assert_eq!(detect_case("Api_key"), None);
+    }
+
+    #[test]
+    fn test_to_train_case() {
+        assert_eq!(to_train_case("api-key"), "Api-Key");
+        assert_eq!(to_train_case("CONTENT_TYPE"), "Content-Type");
+        assert_eq!(convert_case("x-request-id", Case::Train), "X-Request-Id");
+    }
+
+    #[test]
+    fn test_to_dot_case() {
+        assert_eq!(to_dot_case("apiKey"), "api.key");
+        assert_eq!(to_dot_case("SERVER_PORT"), "server.port");
+        assert_eq!(to_upper_case("server.port"), "SERVER_PORT");
+    }
+
+    #[test]
+    fn test_to_screaming_kebab_case() {
+        assert_eq!(to_screaming_kebab_case("apiKey"), "API-KEY");
+        assert_eq!(convert_case("build-dir", Case::ScreamingKebab), "BUILD-DIR");
+    }
+
+    #[test]
+    fn test_to_path_case() {
+        assert_eq!(to_path_case("apiKey"), "api/key");
+        assert_eq!(to_path_case("DATABASE_URL"), "database/url");
+        assert_eq!(to_upper_case("database/url"), "DATABASE_URL");
+    }
+
+    #[test]
+    fn test_to_title_case() {
+        assert_eq!(to_title_case("max-retries"), "Max Retries");
+        assert_eq!(to_title_case("apiKey"), "Api Key");
+        assert_eq!(to_kebab_case("Max Retries"), "max-retries");
+    }
+
+    #[test]
+    fn test_to_flat_case() {
+        assert_eq!(to_flat_case("apiKey"), "apikey");
+        assert_eq!(to_flat_case("max-retries"), "maxretries");
+    }
+
+    #[test]
+    fn test_convert_with_separator() {
+        assert_eq!(
+            convert_with_separator("apiKey", ":", WordCase::Lower),
+            "api:key"
+        );
+        assert_eq!(
+            convert_with_separator("API_KEY", "::", WordCase::Upper),
+            "API::KEY"
+        );
+    }
+
+    #[test]
+    fn test_convert_case_with_acronyms() {
+        let options = CaseOptions::new().acronyms(["HTTP", "URL", "ID", "OAuth"]);
+        assert_eq!(
+            convert_case_with("OAuthToken", Case::Kebab, &options),
+            "oauth-token"
+        );
+        assert_eq!(
+            convert_case_with("userID", Case::UpperSnake, &options),
+            "USER_ID"
+        );
+        assert_eq!(
+            convert_case_with("user_id", Case::Pascal, &options),
+            "UserId"
+        );
+        let options = options.acronym_style(AcronymStyle::Preserve);
+        assert_eq!(
+            convert_case_with("user_id", Case::Pascal, &options),
+            "UserID"
+        );
+        assert_eq!(
+            convert_case_with("http_url_parser", Case::Pascal, &options),
+            "HTTPURLParser"
+        );
+    }
+
+    #[test]
+    fn test_split_words() {
+        let words: Vec<&str> = split_words("myHTTPServer_v2").collect();
+        assert_eq!(words, ["my", "HTTP", "Server", "v2"]);
+        let options = CaseOptions::new().acronyms(["OAuth"]);
+        let words: Vec<&str> = split_words_with("OAuth2Token", &options).collect();
+        assert_eq!(words, ["OAuth2", "Token"]);
+        let custom: Vec<String> = split_words("api-key").map(str::to_uppercase).collect();
+        assert_eq!(custom.join("::"), "API::KEY");
+    }
+
+    #[test]
+    fn test_convert_case_borrows_when_unchanged() {
+        assert!(matches!(
+            convert_case("API_KEY", Case::UpperSnake),
+            Cow::Borrowed("API_KEY")
+        ));
+        assert!(matches!(
+            convert_case("apiKey", Case::UpperSnake),
+            Cow::Owned(_)
+        ));
+        assert_eq!(convert_case("apiKey", Case::UpperSnake), "API_KEY");
+    }
+
+    #[test]
+    fn test_write_case_into_reused_buffer() {
+        let mut buffer = String::new();
+        write_upper_case(&mut buffer, "api-key").unwrap();
+        assert_eq!(buffer, "API_KEY");
+        buffer.clear();
+        write_kebab_case(&mut buffer, "maxRetries").unwrap();
+        assert_eq!(buffer, "max-retries");
+        buffer.clear();
+        write_case(&mut buffer, "apiKey", Case::Dot).unwrap();
+        assert_eq!(buffer, "api.key");
+    }
+
+    #[test]
+    fn test_convert_case_with_exceptions() {
+        let options = CaseOptions::new().exceptions(["iOS", "macOS", "OAuth2"]);
+        assert_eq!(
+            convert_case_with("iOSVersion", Case::Kebab, &options),
+            "iOS-version"
+        );
+        assert_eq!(
+            convert_case_with("min_macos", Case::UpperSnake, &options),
+            "MIN_macOS"
+        );
+        assert_eq!(
+            convert_case_with("oauth2-client", Case::Camel, &options),
+            "OAuth2Client"
+        );
+    }
+
+    #[test]
+    fn test_config_get_by_any_key_case() {
+        let config = make_config_from(["app"], |c| c.option("api-key", "API key", "secret"));
+        assert_eq!(config.get("api-key"), "secret");
+        assert_eq!(config.get("API_KEY"), "secret");
+        assert_eq!(config.get("api.key"), "secret");
+        assert!(config.has("ApiKey"));
+        assert_eq!(canonical_key("API_KEY"), "api-key");
+    }
+
+    #[test]
+    fn test_key_map_renames_config_keys() {
+        let keys = ["apiKey", "max-retries", "HTTP_PORT"];
+        assert_eq!(
+            convert_all(keys, Case::Kebab),
+            ["api-key", "max-retries", "http-port"]
+        );
+        let map = KeyMap::new(keys, Case::UpperSnake);
+        let renamed: Vec<_> = map.iter().map(|(_, name)| name).collect();
+        assert_eq!(renamed, ["API_KEY", "MAX_RETRIES", "HTTP_PORT"]);
+        assert_eq!(map.convert("api.key"), Some("API_KEY"));
+        assert_eq!(map.original("MAX_RETRIES"), Some("max-retries"));
+    }
+
+    #[test]
+    fn test_digit_keys_round_trip_through_all_converters() {
+        let converters: [fn(&str) -> String; 5] = [
+            to_upper_case,
+            to_camel_case,
+            to_kebab_case,
+            to_snake_case,
+            to_pascal_case,
+        ];
+
+        for key in ["v2ApiKey", "ipv6Address", "s3-bucket", "oauth2_client_id"] {
+            for from in converters {
+                let intermediate = from(key);
+                for to in converters {
+                    assert_eq!(
+                        to(&intermediate),
+                        to(key),
+                        "converting {key:?} via {intermediate:?}"
+                    );
+                }
+            }
+        }
+    }
 }
 
 // ============================================================================
@@ -112,6 +340,23 @@ mod getenv_tests {
         env::remove_var("LINO_TEST_API_KEY");
     }
 
+    #[test]
+    fn test_getenv_with_extra_lookup_case() {
+        env::set_var("LINO-TEST-BUILD-DIR", "out");
+        assert_eq!(getenv("lino-test-build-dir", "build"), "build");
+        let options = GetenvOptions::new().lookup_case(Case::ScreamingKebab);
+        assert_eq!(getenv_with("lino-test-build-dir", "build", &options), "out");
+        env::remove_var("LINO-TEST-BUILD-DIR");
+    }
+
+    #[test]
+    fn test_getenv_with_flat_case_lookup() {
+        env::set_var("linotestmaxretries", "5");
+        let options = GetenvOptions::new().lookup_case(Case::Flat);
+        assert_eq!(getenv_int_with("LINO_TEST_MAX_RETRIES", 3, &options), 5);
+        env::remove_var("linotestmaxretries");
+    }
+
     #[test]
     fn test_getenv_int_parses_correctly() {
         env::set_var("LINO_TEST_PORT", "8080");
@@ -155,6 +400,31 @@ mod getenv_tests {
         assert!(result);
         env::remove_var("LINO_TEST_BOOL_INVALID");
     }
+
+    #[test]
+    fn test_getenv_matches_value_parsers() {
+        env::set_var("CR_PARSED_TIMEOUT", "250ms");
+        env::set_var("crParsedMaxBody", "2 GiB");
+        env::set_var("cr-parsed-hosts", " a , b ");
+        env::set_var("CR_PARSED_LABELS", "tier=web");
+        assert_eq!(
+            getenv_duration("crParsedTimeout", Duration::ZERO),
+            parse_duration("250ms").unwrap()
+        );
+        assert_eq!(getenv_byte_size("CR_PARSED_MAX_BODY", 0), 2 << 30);
+        assert_eq!(getenv_list("crParsedHosts"), ["a", "b"]);
+        assert_eq!(getenv_map("cr-parsed-labels")["tier"], "web");
+        assert_eq!(getenv_byte_size("CR_PARSED_MISSING", 7), 7);
+        assert!(getenv_list("CR_PARSED_MISSING").is_empty());
+        for key in [
+            "CR_PARSED_TIMEOUT",
+            "crParsedMaxBody",
+            "cr-parsed-hosts",
+            "CR_PARSED_LABELS",
+        ] {
+            env::remove_var(key);
+        }
+    }
 }
 
 // ============================================================================
@@ -451,6 +721,50 @@ mod clap_reexport {
         assert_eq!(args.port, "3000");
         assert!(!args.verbose);
     }
+
+    #[derive(Parser, Debug)]
+    #[command(name = "test-app")]
+    struct ParsedArgs {
+        #[arg(long, value_parser = parse_duration, default_value = "30s")]
+        timeout: Duration,
+
+        #[arg(long, value_parser = parse_byte_size, default_value = "1MiB")]
+        max_body: u64,
+
+        #[arg(long, value_parser = parse_list, default_value = "")]
+        hosts: std::vec::Vec<String>,
+
+        #[arg(long, value_parser = parse_map, default_value = "")]
+        labels: HashMap<String, String>,
+    }
+
+    #[test]
+    fn test_lino_value_parsers() {
+        let args = ParsedArgs::parse_from([
+            "test-app",
+            "--timeout",
+            "1m30s",
+            "--max-body",
+            "10KB",
+            "--hosts",
+            "a,b",
+            "--labels",
+            "team=core",
+        ]);
+        assert_eq!(args.timeout, Duration::from_secs(90));
+        assert_eq!(args.max_body, 10_000);
+        assert_eq!(args.hosts, ["a", "b"]);
+        assert_eq!(args.labels["team"], "core");
+
+        let args = ParsedArgs::parse_from(["test-app"]);
+        assert_eq!(args.timeout, Duration::from_secs(30));
+        assert_eq!(args.max_body, 1 << 20);
+        assert!(args.hosts.is_empty());
+        assert!(args.labels.is_empty());
+
+        let err = ParsedArgs::try_parse_from(["test-app", "--timeout", "soon"]).unwrap_err();
+        assert!(err.to_string().contains("invalid duration `soon`"));
+    }
 }
 
 // ============================================================================
@@ -667,6 +981,49 @@ mod lino_parser_tests {
 mod make_config_tests {
     use super::*;
 
+    /// Serializes the tests that put stand-in binaries on `PATH`.
+    #[cfg(unix)]
+    static PATH_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Run `f` with a shell `script` named `name` first on `PATH`.
+    #[cfg(unix)]
+    fn with_command<T>(name: &str, script: &str, f: impl FnOnce() -> T) -> T {
+        use std::os::unix::fs::PermissionsExt;
+
+        let _lock = PATH_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let dir = tempdir().unwrap();
+        let command = dir.path().join(name);
+        fs::write(&command, format!("#!/bin/sh\n{script}")).unwrap();
+        fs::set_permissions(&command, fs::Permissions::from_mode(0o755)).unwrap();
+        let path = env::var("PATH").unwrap_or_default();
+        env::set_var("PATH", format!("{}:{path}", dir.path().display()));
+        let result = f();
+        env::set_var("PATH", path);
+        result
+    }
+
+    #[test]
+    fn test_try_make_config_returns_argument_errors() {
+        fn configure(c: &mut lino_arguments::ConfigBuilder) -> &mut lino_arguments::ConfigBuilder {
+            c.option("mc-arg-port", "Port", "3000")
+        }
+        let err = try_make_config_from(["app", "--bogus"], configure).unwrap_err();
+        let ConfigError::ArgumentError(ref clap_err) = err else {
+            panic!("{err}");
+        };
+        assert_eq!(clap_err.kind(), clap::error::ErrorKind::UnknownArgument);
+        assert!(err
+            .to_string()
+            .starts_with("Argument error: unexpected argument '--bogus'"));
+
+        let err = try_make_config_from(["app", "--help"], configure).unwrap_err();
+        let ConfigError::ArgumentError(ref clap_err) = err else {
+            panic!("{err}");
+        };
+        assert_eq!(clap_err.kind(), clap::error::ErrorKind::DisplayHelp);
+        assert!(err.to_string().contains("--mc-arg-port"));
+    }
+
     #[test]
     fn test_make_config_basic_options() {
         let config = make_config_from(["app", "--port", "9090"], |c| {
@@ -824,157 +1181,2621 @@ mod make_config_tests {
     }
 
     #[test]
-    fn test_make_config_cli_overrides_lenv() {
+    fn test_make_config_configuration_overrides_lenv() {
         let dir = tempdir().unwrap();
-        let file_path = dir.path().join("test_override.lenv");
-        let file_path_str = file_path.to_str().unwrap();
+        let lenv_path = dir.path().join("test.lenv");
+        let lenv_path_str = lenv_path.to_str().unwrap();
+        let config_path = dir.path().join("production.lenv");
+        let config_path_str = config_path.to_str().unwrap();
 
-        fs::write(&file_path, "MC_CLIOV_PORT: 7070\n").unwrap();
+        fs::write(&lenv_path, "MC_CONF_PORT: 5000\nMC_CONF_HOST: localhost\n").unwrap();
+        fs::write(&config_path, "MC_CONF_PORT: 9000\n").unwrap();
 
-        env::remove_var("MC_CLIOV_PORT");
+        env::remove_var("MC_CONF_PORT");
+        env::remove_var("MC_CONF_HOST");
 
-        let config = make_config_from(["app", "--mc-cliov-port", "9999"], |c| {
-            c.lenv(file_path_str)
-                .option("mc-cliov-port", "Server port", "3000")
+        let config = make_config_from(["app", "--configuration", config_path_str], |c| {
+            c.lenv(lenv_path_str)
+                .option("mc-conf-port", "Port", "3000")
+                .option("mc-conf-host", "Host", "")
         });
 
-        // CLI should override .lenv value
-        assert_eq!(config.get("mcCliovPort"), "9999");
-
-        env::remove_var("MC_CLIOV_PORT");
-    }
+        // The --configuration file wins over the .lenv file
+        assert_eq!(config.get("mc-conf-port"), "9000");
+        assert_eq!(config.source("mc-conf-port"), Some(ValueSource::LenvFile));
+        assert_eq!(config.get("mc-conf-host"), "localhost");
 
-    #[test]
-    fn test_make_config_multiple_options_and_flags() {
+        env::remove_var("MC_CONF_PORT");
         let config = make_config_from(
-            ["app", "--port", "8080", "--api-key", "secret", "--verbose"],
-            |c| {
-                c.option("port", "Server port", "3000")
-                    .option("api-key", "API key", "")
-                    .flag("verbose", "Enable verbose logging")
-            },
+            ["app", "-c", config_path_str, "--mc-conf-port", "7000"],
+            |c| c.option("mc-conf-port", "Port", "3000"),
         );
+        assert_eq!(config.get("mc-conf-port"), "7000");
 
-        assert_eq!(config.get("port"), "8080");
-        assert_eq!(config.get("apiKey"), "secret");
-        assert!(config.get_bool("verbose"));
+        env::remove_var("MC_CONF_PORT");
+        env::remove_var("MC_CONF_HOST");
     }
 
     #[test]
-    fn test_make_config_app_metadata() {
-        let config = make_config_from(["my-app", "--port", "8080"], |c| {
-            c.name("my-app")
-                .about("A test application")
-                .version("1.0.0")
-                .option("port", "Server port", "3000")
+    fn test_make_config_secrets_dir() {
+        let dir = tempdir().unwrap();
+        let secrets = dir.path().join("secrets");
+        fs::create_dir(&secrets).unwrap();
+        fs::write(secrets.join("mc_secret_password"), "hunter2\n").unwrap();
+        fs::write(secrets.join("MC_SECRET_TOKEN"), "sk-1234").unwrap();
+        fs::write(secrets.join(".mc_secret_hidden"), "hidden").unwrap();
+        let secrets = secrets.to_str().unwrap();
+        let lenv_path = dir.path().join("test.lenv");
+        fs::write(&lenv_path, "MC_SECRET_PASSWORD: from-file\n").unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_SECRET_PASSWORD");
+        env::set_var("MC_SECRET_TOKEN", "from-env");
+
+        let config = make_config_from(["app"], |c| {
+            c.lenv(lenv_path)
+                .secrets_dir(secrets)
+                .option("mc-secret-password", "Password", "")
+                .option("mc-secret-token", "Token", "")
+                .option("mc-secret-hidden", "Hidden", "default")
+                .option("mc-secret-user", "User", "admin")
         });
 
-        assert_eq!(config.get("port"), "8080");
-    }
-}
+        // Secrets outrank files and defaults, but not the environment
+        assert_eq!(config.get("mc-secret-password"), "hunter2");
+        assert_eq!(
+            config.source("mc-secret-password"),
+            Some(ValueSource::SecretsDir)
+        );
+        assert_eq!(config.get("mc-secret-token"), "from-env");
+        assert_eq!(config.get("mc-secret-hidden"), "default");
+        assert_eq!(config.get("mc-secret-user"), "admin");
+        assert!(env::var("MC_SECRET_PASSWORD").is_ok_and(|value| value == "from-file"));
 
-// ============================================================================
-// init() and init_with() Tests
-// ============================================================================
+        env::remove_var("MC_SECRET_PASSWORD");
+        env::remove_var("MC_SECRET_TOKEN");
 
-mod init_tests {
-    use super::*;
+        let config = make_config_from(["app"], |c| {
+            c.secrets_dir(dir.path().join("missing").to_str().unwrap())
+                .option("mc-secret-user", "User", "admin")
+        });
+        assert_eq!(config.get("mc-secret-user"), "admin");
+    }
 
     #[test]
-    fn test_init_with_loads_lenv_file() {
+    fn test_make_config_file_vars() {
         let dir = tempdir().unwrap();
-        let lenv_path = dir.path().join("test.lenv");
-        let lenv_path_str = lenv_path.to_str().unwrap();
+        let secrets = dir.path().join("secrets");
+        fs::create_dir(&secrets).unwrap();
+        fs::write(secrets.join("mc_file_var_password"), "from-secrets").unwrap();
+        let password_path = dir.path().join("password");
+        fs::write(&password_path, "hunter2\n").unwrap();
+        let token_path = dir.path().join("token");
+        fs::write(&token_path, "from-file").unwrap();
+
+        env::remove_var("MC_FILE_VAR_PASSWORD");
+        env::set_var("MC_FILE_VAR_PASSWORD_FILE", &password_path);
+        env::set_var("MC_FILE_VAR_TOKEN", "from-env");
+        env::set_var("MC_FILE_VAR_TOKEN_FILE", &token_path);
 
-        fs::write(&lenv_path, "INIT_LENV_PORT_T1: 7777\n").unwrap();
-        env::remove_var("INIT_LENV_PORT_T1");
+        let config = make_config_from(["app"], |c| {
+            c.file_vars()
+                .secrets_dir(secrets.to_str().unwrap())
+                .option("mc-file-var-password", "Password", "")
+                .option("mc-file-var-token", "Token", "")
+        });
 
-        init_with(Some(lenv_path_str), None);
+        // The file stands in for the unset variable, above the secrets
+        assert_eq!(config.get("mc-file-var-password"), "hunter2");
+        assert_eq!(
+            config.source("mc-file-var-password"),
+            Some(ValueSource::FileVar)
+        );
+        assert_eq!(
+            config.provenance_json()["keys"]["mc-file-var-password"]["variant"],
+            "MC_FILE_VAR_PASSWORD_FILE"
+        );
+        // The variable itself wins
+        assert_eq!(config.get("mc-file-var-token"), "from-env");
 
-        assert_eq!(env::var("INIT_LENV_PORT_T1").unwrap(), "7777");
+        // Only when enabled
+        let config = make_config_from(["app"], |c| {
+            c.option("mc-file-var-password", "Password", "unset")
+        });
+        assert_eq!(config.get("mc-file-var-password"), "unset");
 
-        env::remove_var("INIT_LENV_PORT_T1");
+        env::set_var("MC_FILE_VAR_PASSWORD_FILE", dir.path().join("missing"));
+        let result = try_make_config_from(["app"], |c| {
+            c.file_vars().option("mc-file-var-password", "Password", "")
+        });
+        assert!(matches!(result, Err(ConfigError::FileError(message))
+            if message.contains("MC_FILE_VAR_PASSWORD_FILE")));
+
+        env::remove_var("MC_FILE_VAR_PASSWORD_FILE");
+        env::remove_var("MC_FILE_VAR_TOKEN");
+        env::remove_var("MC_FILE_VAR_TOKEN_FILE");
     }
 
     #[test]
-    fn test_init_with_loads_env_file() {
+    fn test_make_config_systemd_credentials() {
         let dir = tempdir().unwrap();
-        let env_path = dir.path().join(".env");
-        let env_path_str = env_path.to_str().unwrap();
-
-        fs::write(&env_path, "INIT_ENV_HOST_T2=example.com\n").unwrap();
-        env::remove_var("INIT_ENV_HOST_T2");
+        fs::write(dir.path().join("mc_credential_password"), "from-systemd").unwrap();
+        let docker = dir.path().join("docker");
+        fs::create_dir(&docker).unwrap();
+        fs::write(docker.join("mc_credential_password"), "from-docker").unwrap();
+        fs::write(docker.join("mc_credential_user"), "admin").unwrap();
 
-        init_with(None, Some(env_path_str));
+        env::set_var("CREDENTIALS_DIRECTORY", dir.path());
+        let config = make_config_from(["app"], |c| {
+            c.systemd_credentials()
+                .secrets_dir(docker.to_str().unwrap())
+                .option("mc-credential-password", "Password", "")
+                .option("mc-credential-user", "User", "")
+        });
+        env::remove_var("CREDENTIALS_DIRECTORY");
 
-        assert_eq!(env::var("INIT_ENV_HOST_T2").unwrap(), "example.com");
+        // The first directory with a key wins
+        assert_eq!(config.get("mc-credential-password"), "from-systemd");
+        assert_eq!(config.get("mc-credential-user"), "admin");
 
-        env::remove_var("INIT_ENV_HOST_T2");
+        let config = make_config_from(["app"], |c| {
+            c.systemd_credentials()
+                .option("mc-credential-password", "Password", "unset")
+        });
+        assert_eq!(config.get("mc-credential-password"), "unset");
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_init_with_loads_both_files() {
+    fn test_make_config_sops_file() {
         let dir = tempdir().unwrap();
-        let lenv_path = dir.path().join("test.lenv");
-        let lenv_path_str = lenv_path.to_str().unwrap();
-        let env_path = dir.path().join(".env");
-        let env_path_str = env_path.to_str().unwrap();
-
-        fs::write(&lenv_path, "INIT_BOTH_A_T3: from_lenv\n").unwrap();
-        fs::write(&env_path, "INIT_BOTH_B_T3=from_env\n").unwrap();
+        let encrypted = "{\"mc_sops\": \"ENC[...]\", \"sops\": {\"mac\": \"ENC[...]\"}}";
+        let secrets = dir.path().join("secrets.json");
+        fs::write(&secrets, encrypted).unwrap();
+        let secrets = secrets.to_str().unwrap();
+        let broken = dir.path().join("broken.json");
+        fs::write(&broken, encrypted).unwrap();
+        let plain = dir.path().join("plain.json");
+        fs::write(&plain, "{\"mc_sops_password\": \"hunter2\"}").unwrap();
+
+        // A stand-in for the sops binary printing the decrypted document
+        let sops = "case \"$4\" in *broken*) echo 'no key' >&2; exit 1;; esac\n\
+                    echo '{\"mc_sops\": {\"password\": \"hunter2\", \"port\": 7000}}'\n";
+        let (config, broken, plain) = with_command("sops", sops, || {
+            let config = try_make_config_from(["app"], |c| {
+                c.sops(secrets)
+                    .sops(dir.path().join("missing.json").to_str().unwrap())
+                    .option("mc-sops-password", "Password", "")
+                    .option("mc-sops-port", "Port", "3000")
+            })
+            .unwrap();
+            config.preflight().unwrap();
+            let broken = try_make_config_from(["app"], |c| c.sops(broken.to_str().unwrap()));
+            let plain = try_make_config_from(["app"], |c| c.sops(plain.to_str().unwrap()));
+            (config, broken, plain)
+        });
 
-        env::remove_var("INIT_BOTH_A_T3");
-        env::remove_var("INIT_BOTH_B_T3");
+        assert_eq!(config.get("mc-sops-password"), "hunter2");
+        assert_eq!(config.get_int("mc-sops-port", 0), 7000);
+        assert_eq!(config.source("mc-sops-port"), Some(ValueSource::SopsFile));
+        assert!(env::var("MC_SOPS_PASSWORD").is_err());
+        assert!(broken.unwrap_err().to_string().ends_with(": no key"));
+        assert!(plain
+            .unwrap_err()
+            .to_string()
+            .ends_with("not a sops-encrypted file"));
+
+        // Preflight decrypts the files again
+        fs::write(secrets, "{\"mc_sops_password\": \"hunter2\"}").unwrap();
+        assert!(config
+            .preflight()
+            .unwrap_err()
+            .to_string()
+            .ends_with("not a sops-encrypted file"));
+    }
 
-        init_with(Some(lenv_path_str), Some(env_path_str));
+    #[cfg(unix)]
+    #[test]
+    fn test_make_config_aws_source() {
+        let dir = tempdir().unwrap();
+        let cache = dir.path().join("aws-cache.json");
+        let cache = cache.to_str().unwrap();
+        let options = |c: &mut lino_arguments::ConfigBuilder| {
+            c.source(AwsSource::ssm("/mc-aws/prod/").cache(cache))
+                .source(AwsSource::secrets_manager("mc-aws/api"))
+                .option("mc-aws-password", "Password", "")
+                .option("mc-aws-port", "Port", "3000")
+                .option("mc-aws-token", "Token", "");
+        };
+
+        // A stand-in for the aws CLI answering both services
+        let aws = r#"case "$1 $2" in
+"ssm get-parameters-by-path") echo '{"Parameters": [
+    {"Name": "/mc-aws/prod/mc/aws/password", "Value": "hunter2", "Type": "SecureString"},
+    {"Name": "/mc-aws/prod/MC_AWS_PORT", "Value": "7000", "Type": "String"}]}';;
+"secretsmanager get-secret-value") echo '{"SecretString": "{\"mc_aws_token\": \"sk-1234\", \"mc_aws_port\": 1}"}';;
+esac
+"#;
+        let config = with_command("aws", aws, || {
+            try_make_config_from(["app"], |c| {
+                options(c);
+                c
+            })
+        })
+        .unwrap();
+        assert_eq!(config.get("mc-aws-password"), "hunter2");
+        assert!(!config.is_stale("mc-aws-password"));
+        assert_eq!(config.get("mc-aws-port"), "7000");
+        assert_eq!(config.get("mc-aws-token"), "sk-1234");
+        assert_eq!(
+            config.source("mc-aws-port"),
+            Some(ValueSource::Remote("AWS Parameter Store"))
+        );
 
-        assert_eq!(env::var("INIT_BOTH_A_T3").unwrap(), "from_lenv");
-        assert_eq!(env::var("INIT_BOTH_B_T3").unwrap(), "from_env");
+        // AWS is unreachable: the parameters come from the cache, and the
+        // uncached secret fails the build
+        let unreachable = "echo 'Could not connect' >&2; exit 255\n";
+        let err = with_command("aws", unreachable, || {
+            try_make_config_from(["app"], |c| {
+                options(c);
+                c
+            })
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("Could not connect"));
+        let config = with_command("aws", unreachable, || {
+            try_make_config_from(["app"], |c| {
+                c.source(AwsSource::ssm("/mc-aws/prod/").cache(cache))
+                    .option("mc-aws-password", "Password", "")
+            })
+        })
+        .unwrap();
+        assert_eq!(config.get("mc-aws-password"), "hunter2");
+        assert!(config.is_stale("mc-aws-password"));
+    }
 
-        env::remove_var("INIT_BOTH_A_T3");
-        env::remove_var("INIT_BOTH_B_T3");
+    #[cfg(unix)]
+    #[test]
+    fn test_make_config_etcd_source() {
+        use lino_arguments::Source;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // A stand-in for etcdctl: `/mc-etcd/mc/etcd/password` = `hunter2`,
+        // and two watch responses
+        let etcdctl = r#"case "$2" in
+get) echo '{"kvs": [{"key": "L21jLWV0Y2QvbWMvZXRjZC9wYXNzd29yZA==", "value": "aHVudGVyMg=="}]}';;
+watch) echo '{"Events": [1]}'; echo '{"Events": [2]}';;
+esac
+"#;
+        let source = EtcdSource::new("/mc-etcd/").endpoints(["http://etcd:2379"]);
+        let changes = Arc::new(AtomicUsize::new(0));
+        let config = with_command("etcdctl", etcdctl, || {
+            let counter = changes.clone();
+            let watcher = source
+                .watch(Box::new(move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }))
+                .unwrap()
+                .unwrap();
+            for _ in 0..100 {
+                if changes.load(Ordering::SeqCst) == 2 {
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            drop(watcher);
+            try_make_config_from(["app"], |c| {
+                c.source(source.clone())
+                    .option("mc-etcd-password", "Password", "")
+            })
+        })
+        .unwrap();
+        assert_eq!(changes.load(Ordering::SeqCst), 2);
+        assert_eq!(config.get("mc-etcd-password"), "hunter2");
+        assert_eq!(
+            config.source("mc-etcd-password"),
+            Some(ValueSource::Remote("etcd"))
+        );
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_init_with_lenv_takes_priority_over_env() {
-        let dir = tempdir().unwrap();
-        let lenv_path = dir.path().join("test.lenv");
-        let lenv_path_str = lenv_path.to_str().unwrap();
-        let env_path = dir.path().join(".env");
-        let env_path_str = env_path.to_str().unwrap();
+    fn test_make_config_redis_source() {
+        use lino_arguments::Source;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // A stand-in for redis-cli answering HGETALL and publishing two
+        // keyspace notifications
+        let redis_cli = r#"case "$*" in
+*"--json HGETALL mc:config") echo '["mc_redis_level", "debug", "MC_REDIS_FLAG", "true"]';;
+*"SUBSCRIBE __keyspace@2__:mc:config") printf 'subscribe\nmc:config\n1\n'
+    printf 'message\nmc:config\nhset\n'; printf 'message\nmc:config\nhdel\n';;
+*) echo "ERR unknown arguments $*";;
+esac
+"#;
+        let source = RedisSource::new("mc:config")
+            .url("redis://cache:6379")
+            .database(2);
+        let changes = Arc::new(AtomicUsize::new(0));
+        let config = with_command("redis-cli", redis_cli, || {
+            let counter = changes.clone();
+            let watcher = source
+                .watch(Box::new(move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }))
+                .unwrap()
+                .unwrap();
+            for _ in 0..100 {
+                if changes.load(Ordering::SeqCst) == 2 {
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            drop(watcher);
+            try_make_config_from(["app"], |c| {
+                c.source(source.clone())
+                    .option("mc-redis-level", "Log level", "info")
+                    .option("mc-redis-flag", "Flag", "false")
+            })
+        })
+        .unwrap();
+        assert_eq!(changes.load(Ordering::SeqCst), 2);
+        assert_eq!(config.get("mc-redis-level"), "debug");
+        assert_eq!(config.get("mc-redis-flag"), "true");
+        assert_eq!(
+            config.source("mc-redis-level"),
+            Some(ValueSource::Remote("Redis"))
+        );
 
-        // Same key in both files — .lenv should win
-        fs::write(&lenv_path, "INIT_PRIO_T4: from_lenv\n").unwrap();
-        fs::write(&env_path, "INIT_PRIO_T4=from_env\n").unwrap();
+        let err = with_command(
+            "redis-cli",
+            "echo 'NOAUTH Authentication required.'",
+            || {
+                try_make_config_from(["app"], |c| {
+                    c.source(RedisSource::new("mc:config")).option(
+                        "mc-redis-level",
+                        "Log level",
+                        "info",
+                    )
+                })
+            },
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("NOAUTH"));
+    }
 
-        env::remove_var("INIT_PRIO_T4");
+    #[test]
+    fn test_make_config_git_source() {
+        use std::process::Command;
 
-        init_with(Some(lenv_path_str), Some(env_path_str));
+        let dir = tempdir().unwrap();
+        let repo = dir.path().join("config");
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{output:?}");
+            String::from_utf8(output.stdout).unwrap().trim().to_string()
+        };
+        fs::create_dir(&repo).unwrap();
+        git(&["init", "--quiet", "--initial-branch", "main"]);
+        fs::write(repo.join("app.lenv"), "MC_GIT_LEVEL: info\n").unwrap();
+        git(&["add", "app.lenv"]);
+        git(&["commit", "--quiet", "-m", "Add config"]);
+        git(&["tag", "v1"]);
+        let v1 = git(&["rev-parse", "HEAD"]);
+        fs::write(repo.join("app.lenv"), "MC_GIT_LEVEL: debug\n").unwrap();
+        git(&["commit", "--quiet", "-am", "Raise log level"]);
+        let head = git(&["rev-parse", "HEAD"]);
+        // Uncommitted changes are not read
+        fs::write(repo.join("app.lenv"), "MC_GIT_LEVEL: trace\n").unwrap();
+
+        let build = |source: GitSource| {
+            try_make_config_from(["app"], |c| {
+                c.source(source).option("mc-git-level", "Log level", "warn")
+            })
+            .unwrap()
+        };
+
+        // A local checkout, at HEAD and at a tag
+        let local = repo.to_str().unwrap();
+        let config = build(GitSource::new(local, "app.lenv"));
+        assert_eq!(config.get("mc-git-level"), "debug");
+        assert_eq!(config.revision("mc-git-level"), Some(head.as_str()));
+        let config = build(GitSource::new(local, "app.lenv").reference("v1"));
+        assert_eq!(config.get("mc-git-level"), "info");
+        assert_eq!(config.revision("mc-git-level"), Some(v1.as_str()));
+        assert_eq!(
+            config.source("mc-git-level"),
+            Some(ValueSource::Remote("git repository"))
+        );
 
-        // .lenv is loaded first, so it sets the var; .env won't overwrite
-        assert_eq!(env::var("INIT_PRIO_T4").unwrap(), "from_lenv");
+        // A remote repository, cloned and then fetched
+        let url = format!("file://{}", repo.display());
+        let checkout = dir.path().join("checkout");
+        let remote = GitSource::new(&url, "app.lenv").checkout(checkout.to_str().unwrap());
+        assert_eq!(build(remote.clone()).get("mc-git-level"), "debug");
+        git(&["commit", "--quiet", "-am", "Trace everything"]);
+        let config = build(remote);
+        assert_eq!(config.get("mc-git-level"), "trace");
+        assert_eq!(
+            config.revision("mc-git-level"),
+            Some(git(&["rev-parse", "HEAD"]).as_str())
+        );
 
-        env::remove_var("INIT_PRIO_T4");
+        let err = try_make_config_from(["app"], |c| {
+            c.source(GitSource::new(local, "app.lenv").reference("missing"))
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("has no ref missing"));
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_init_with_existing_env_var_takes_priority() {
-        let dir = tempdir().unwrap();
-        let lenv_path = dir.path().join("test.lenv");
-        let lenv_path_str = lenv_path.to_str().unwrap();
-
-        fs::write(&lenv_path, "INIT_EXISTING_T5: from_lenv\n").unwrap();
+    fn test_make_config_grpc_source() {
+        use lino_arguments::Source;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // A stand-in for grpcurl: the current snapshot, then one change
+        let grpcurl = r#"case "$*" in
+*'-d {"application":"mc-app","profile":"prod"} config:443 lino.config.v1.ConfigService/GetConfig')
+    echo '{"values": {"MC_GRPC_LEVEL": "debug"}, "revision": "1"}';;
+*/WatchConfig) printf '{\n  "revision": "1"\n}\n{\n  "revision": "2"\n}\n';;
+*) echo "unexpected arguments: $*" >&2; exit 1;;
+esac
+"#;
+        let source = GrpcSource::new("config:443", "mc-app").profile("prod");
+        let changes = Arc::new(AtomicUsize::new(0));
+        let config = with_command("grpcurl", grpcurl, || {
+            let counter = changes.clone();
+            let watcher = source
+                .watch(Box::new(move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }))
+                .unwrap()
+                .unwrap();
+            for _ in 0..100 {
+                if changes.load(Ordering::SeqCst) == 1 {
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            drop(watcher);
+            try_make_config_from(["app"], |c| {
+                c.source(source.clone())
+                    .option("mc-grpc-level", "Log level", "info")
+            })
+        })
+        .unwrap();
+        assert_eq!(changes.load(Ordering::SeqCst), 1);
+        assert_eq!(config.get("mc-grpc-level"), "debug");
+        assert_eq!(
+            config.source("mc-grpc-level"),
+            Some(ValueSource::Remote("config service"))
+        );
+    }
 
-        // Set env var BEFORE init — it should take priority
-        env::set_var("INIT_EXISTING_T5", "from_process");
+    #[cfg(all(unix, feature = "azure"))]
+    #[test]
+    fn test_make_config_azure_source() {
+        use lino_arguments::AzureSource;
+
+        // A stand-in for the az CLI with a production profile and a Key
+        // Vault reference
+        let az = r#"case "$1 $2" in
+"appconfig kv") case "$*" in
+    *"--label production"*) echo '[{"key": "App:McAzure:Port", "label": "production", "value": "443"}]';;
+    *) echo '[{"key": "App:McAzure:Port", "label": null, "value": "8080"},
+        {"key": "App:McAzure:Host", "label": null, "value": "localhost"},
+        {"key": "App:McAzure:Db:Password", "label": null,
+         "value": "{\"uri\":\"https://vault.vault.azure.net/secrets/db\"}",
+         "contentType": "application/vnd.microsoft.appconfig.keyvaultref+json;charset=utf-8"}]';;
+    esac;;
+"keyvault secret") echo '{"value": "hunter2"}';;
+esac
+"#;
+        let config = with_command("az", az, || {
+            try_make_config_from(["app"], |c| {
+                c.source(
+                    AzureSource::new("mc-store")
+                        .prefix("App:")
+                        .label("production"),
+                )
+                .option("mc-azure-port", "Port", "3000")
+                .option("mc-azure-host", "Host", "")
+                .option("mc-azure-db-password", "Password", "")
+            })
+        })
+        .unwrap();
+        assert_eq!(config.get("mc-azure-port"), "443");
+        assert_eq!(config.get("mc-azure-host"), "localhost");
+        assert_eq!(config.get("mc-azure-db-password"), "hunter2");
+        assert_eq!(
+            config.source("mc-azure-db-password"),
+            Some(ValueSource::Remote("Azure App Configuration"))
+        );
+    }
 
-        init_with(Some(lenv_path_str), None);
+    #[test]
+    fn test_make_config_cached_source() {
+        use lino_arguments::Source;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        /// A source that is up until `down` is set, at revision `r1`
+        struct Flaky(Arc<AtomicBool>);
+
+        impl Source for Flaky {
+            fn name(&self) -> &'static str {
+                "flaky service"
+            }
+
+            fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+                if self.0.load(Ordering::SeqCst) {
+                    return Err(ConfigError::SourceError("connection refused".into()));
+                }
+                Ok(HashMap::from([(
+                    "MC_CACHED_LEVEL".to_string(),
+                    "debug".to_string(),
+                )]))
+            }
+
+            fn revision(&self) -> Option<String> {
+                Some("r1".to_string())
+            }
+        }
 
-        assert_eq!(env::var("INIT_EXISTING_T5").unwrap(), "from_process");
+        let dir = tempdir().unwrap();
+        let cache = dir.path().join("flaky-cache.json");
+        let down = Arc::new(AtomicBool::new(false));
+        let build = || {
+            try_make_config_from(["app"], |c| {
+                c.source(Flaky(down.clone()).cached(cache.to_str().unwrap()))
+                    .option("mc-cached-level", "Log level", "info")
+            })
+        };
+
+        // Unreachable before anything was cached
+        down.store(true, Ordering::SeqCst);
+        assert!(build()
+            .unwrap_err()
+            .to_string()
+            .contains("connection refused"));
+
+        down.store(false, Ordering::SeqCst);
+        let config = build().unwrap();
+        assert_eq!(config.get("mc-cached-level"), "debug");
+        assert!(!config.is_stale("mc-cached-level"));
+        assert_eq!(config.revision("mc-cached-level"), Some("r1"));
+
+        down.store(true, Ordering::SeqCst);
+        let config = build().unwrap();
+        assert_eq!(config.get("mc-cached-level"), "debug");
+        assert!(config.is_stale("mc-cached-level"));
+        assert_eq!(config.revision("mc-cached-level"), None);
+        assert_eq!(
+            config.source("mc-cached-level"),
+            Some(ValueSource::Remote("flaky service"))
+        );
+    }
 
-        env::remove_var("INIT_EXISTING_T5");
+    #[test]
+    fn test_make_config_static_source() {
+        use lino_arguments::StaticSource;
+
+        env::remove_var("MC_STATIC_REGION");
+        let config = try_make_config_from(["worker"], |c| {
+            c.source(StaticSource::new(
+                "Worker bindings",
+                HashMap::from([("MC_STATIC_REGION", "eu".to_string())]),
+            ))
+            .source(StaticSource::lenv(
+                "settings.lenv",
+                "# fetched\nMC_STATIC_PORT: 3000\nMC_STATIC_PORT: 8080\n",
+            ))
+            .option("mc-static-region", "Region", "us")
+            .option("mc-static-port", "Port", "80")
+        })
+        .unwrap();
+        assert_eq!(config.get("mc-static-region"), "eu");
+        assert_eq!(
+            config.source("mc-static-region"),
+            Some(ValueSource::Remote("Worker bindings"))
+        );
+        assert_eq!(config.get("mc-static-port"), "8080");
+        assert_eq!(
+            config.source("mc-static-port"),
+            Some(ValueSource::Remote("settings.lenv"))
+        );
     }
-}
+
+    #[test]
+    fn test_shared_config_reload() {
+        use lino_arguments::try_make_shared_config_from;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("shared.lenv");
+        fs::write(&lenv_path, "MC_SHARED_LEVEL: debug\n").unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_SHARED_LEVEL");
+        let shared = try_make_shared_config_from(["app"], |c| {
+            c.lenv(lenv_path).schema(
+                Schema::new()
+                    .key(KeySpec::new("mc-shared-level", ValueType::String).default("info"))
+                    .key(KeySpec::new("mc-shared-workers", ValueType::Integer).default("1")),
+            )
+        })
+        .unwrap();
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let counter = reloads.clone();
+        shared.on_reload(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let before = shared.snapshot();
+        assert_eq!(before.get("mc-shared-level"), "debug");
+        assert!(shared.last_reload_error().is_none());
+
+        fs::write(lenv_path, "MC_SHARED_LEVEL: trace\n").unwrap();
+        shared.reload().unwrap();
+        assert_eq!(shared.snapshot().get("mc-shared-level"), "trace");
+        assert_eq!(
+            shared.snapshot().source("mc-shared-level"),
+            Some(ValueSource::LenvFile)
+        );
+        // Reloads keep the file values out of the environment
+        assert_eq!(env::var("MC_SHARED_LEVEL").unwrap(), "debug");
+        // Snapshots taken before the reload are unchanged
+        assert_eq!(before.get("mc-shared-level"), "debug");
+        assert_eq!(before.generation(), 1);
+        assert_eq!(shared.snapshot().generation(), 2);
+        assert!(shared.snapshot().resolved_at() >= before.resolved_at());
+        assert_eq!(reloads.load(Ordering::SeqCst), 1);
+
+        // Failed reloads keep the current configuration
+        fs::write(
+            lenv_path,
+            "MC_SHARED_LEVEL: warn\nMC_SHARED_WORKERS: many\n",
+        )
+        .unwrap();
+        assert!(matches!(shared.reload(), Err(ConfigError::Invalid(_))));
+        assert_eq!(shared.snapshot().get("mc-shared-level"), "trace");
+        let Some(ConfigError::Invalid(issues)) = shared.last_reload_error() else {
+            panic!("expected the reload error to be kept");
+        };
+        assert_eq!(issues[0].key, "mc-shared-workers");
+        assert_eq!(issues[0].value.as_deref(), Some("many"));
+        assert_eq!(shared.snapshot().generation(), 2);
+        assert_eq!(reloads.load(Ordering::SeqCst), 1);
+
+        fs::write(lenv_path, "MC_SHARED_WORKERS: 4\n").unwrap();
+        shared.reload().unwrap();
+        assert_eq!(shared.snapshot().get("mc-shared-level"), "info");
+        assert_eq!(shared.snapshot().get("mc-shared-workers"), "4");
+        assert!(env::var_os("MC_SHARED_WORKERS").is_none());
+        assert_eq!(shared.snapshot().generation(), 3);
+        assert!(shared.last_reload_error().is_none());
+        assert_eq!(reloads.load(Ordering::SeqCst), 2);
+
+        // The environment still outranks the files
+        env::set_var("MC_SHARED_LEVEL", "error");
+        shared.reload().unwrap();
+        assert_eq!(shared.snapshot().get("mc-shared-level"), "error");
+        assert_eq!(
+            shared.snapshot().source("mc-shared-level"),
+            Some(ValueSource::Environment)
+        );
+
+        env::remove_var("MC_SHARED_LEVEL");
+        env::remove_var("MC_SHARED_WORKERS");
+    }
+
+    #[test]
+    fn test_shared_config_callbacks_can_call_back() {
+        use lino_arguments::try_make_shared_config_from;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("reentrant.lenv");
+        fs::write(&lenv_path, "MC_REENTRANT_LEVEL: debug\n").unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_REENTRANT_LEVEL");
+        let shared = try_make_shared_config_from(["app"], |c| {
+            c.lenv(lenv_path)
+                .option("mc-reentrant-level", "Log level", "info")
+        })
+        .unwrap();
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let changes = Arc::new(AtomicUsize::new(0));
+
+        // A reload callback that registers more callbacks and reloads again
+        let (handle, reload_count, change_count) =
+            (shared.clone(), reloads.clone(), changes.clone());
+        shared.on_reload(move |_| {
+            if reload_count.fetch_add(1, Ordering::SeqCst) > 0 {
+                return;
+            }
+            let counter = reload_count.clone();
+            handle.on_reload(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+            handle.veto_reload(|_| Ok(()));
+            let (inner, counter) = (handle.clone(), change_count.clone());
+            handle.on_change(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                inner.on_change(|_| {});
+            });
+            handle.reload().unwrap();
+        });
+
+        fs::write(lenv_path, "MC_REENTRANT_LEVEL: trace\n").unwrap();
+        shared.reload().unwrap();
+        // The outer reload and the nested one each ran the first callback,
+        // and the nested one also ran the callback registered during it
+        assert_eq!(reloads.load(Ordering::SeqCst), 3);
+        assert_eq!(changes.load(Ordering::SeqCst), 1);
+        assert_eq!(shared.snapshot().get("mc-reentrant-level"), "trace");
+
+        fs::write(lenv_path, "MC_REENTRANT_LEVEL: warn\n").unwrap();
+        shared.reload().unwrap();
+        assert_eq!(reloads.load(Ordering::SeqCst), 5);
+        assert_eq!(changes.load(Ordering::SeqCst), 2);
+
+        env::remove_var("MC_REENTRANT_LEVEL");
+    }
+
+    #[test]
+    fn test_shared_config_on_change() {
+        use lino_arguments::{try_make_shared_config_from, ConfigDiff, KeyChange};
+        use std::sync::{Arc, Mutex};
+
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("change.lenv");
+        fs::write(&lenv_path, "MC_CHANGE_LEVEL: debug\nMC_CHANGE_HOST: db\n").unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_CHANGE_LEVEL");
+        env::remove_var("MC_CHANGE_HOST");
+        let shared = try_make_shared_config_from(["app", "--mc-change-port", "8080"], |c| {
+            c.lenv(lenv_path)
+                .option("mc-change-level", "Log level", "info")
+                .option("mc-change-host", "Database host", "localhost")
+                .option("mc-change-port", "Port", "3000")
+        })
+        .unwrap();
+        let diffs: Arc<Mutex<Vec<ConfigDiff>>> = Arc::default();
+        let received = diffs.clone();
+        shared.on_change(move |diff| received.lock().unwrap().push(diff.clone()));
+
+        // Nothing changed
+        shared.reload().unwrap();
+        assert!(diffs.lock().unwrap().is_empty());
+
+        fs::write(lenv_path, "MC_CHANGE_LEVEL: trace\nMC_CHANGE_HOST: db\n").unwrap();
+        shared.reload().unwrap();
+        fs::write(lenv_path, "MC_CHANGE_HOST: db\n").unwrap();
+        shared.reload().unwrap();
+
+        let diffs = diffs.lock().unwrap();
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(
+            diffs[0].changes(),
+            [KeyChange {
+                key: "mc-change-level".to_string(),
+                old: Some("debug".to_string()),
+                new: Some("trace".to_string()),
+                old_source: Some(ValueSource::LenvFile),
+                new_source: Some(ValueSource::LenvFile),
+            }]
+        );
+        assert!(diffs[0].contains("MC_CHANGE_LEVEL"));
+        assert!(!diffs[0].contains("mc-change-host"));
+
+        // Back to the default
+        let change = diffs[1].get("mc-change-level").unwrap();
+        assert_eq!(change.new.as_deref(), Some("info"));
+        assert_eq!(change.new_source, Some(ValueSource::Default));
+
+        env::remove_var("MC_CHANGE_LEVEL");
+        env::remove_var("MC_CHANGE_HOST");
+    }
+
+    #[test]
+    fn test_shared_config_poll() {
+        use lino_arguments::{try_make_shared_config_from, PollPolicy, Source};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{mpsc, Arc};
+        use std::time::Duration;
+
+        /// A source whose value counts its loads, failing the second one
+        struct Counter(Arc<AtomicUsize>);
+
+        impl Source for Counter {
+            fn name(&self) -> &'static str {
+                "counter"
+            }
+
+            fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+                let loads = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+                if loads == 2 {
+                    return Err(ConfigError::SourceError("timed out".into()));
+                }
+                Ok(HashMap::from([(
+                    "MC_POLL_LOADS".to_string(),
+                    loads.to_string(),
+                )]))
+            }
+        }
+
+        let loads = Arc::new(AtomicUsize::new(0));
+        let source = Counter(loads.clone());
+        let shared = try_make_shared_config_from(["app"], |c| {
+            c.source(source).option("mc-poll-loads", "Loads", "0")
+        })
+        .unwrap();
+        assert_eq!(shared.snapshot().get("mc-poll-loads"), "1");
+
+        let (sender, receiver) = mpsc::channel();
+        shared.on_change(move |diff| {
+            let change = diff.get("mc-poll-loads").unwrap();
+            let _ = sender.send((change.old.clone(), change.new.clone()));
+        });
+        let polling = shared.poll(PollPolicy::every(Duration::from_millis(10)));
+        // The failed load keeps the first value
+        let (old, new) = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(old.as_deref(), Some("1"));
+        assert_eq!(new.as_deref(), Some("3"));
+
+        drop(polling);
+        std::thread::sleep(Duration::from_millis(50));
+        let stopped_at = loads.load(Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(loads.load(Ordering::SeqCst), stopped_at);
+    }
+
+    #[test]
+    fn test_shared_config_watch_key() {
+        use lino_arguments::try_make_shared_config_from;
+
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("key.lenv");
+        fs::write(&lenv_path, "MC_KEY_PORT: 8080\n").unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_KEY_PORT");
+        let shared = try_make_shared_config_from(["app"], |c| {
+            c.lenv(lenv_path)
+                .option("mc-key-port", "Port", "3000")
+                .option("mc-key-host", "Host", "localhost")
+        })
+        .unwrap();
+        let port = shared.watch::<u16>("MC_KEY_PORT").unwrap();
+        assert_eq!(port.key(), "mc-key-port");
+        assert_eq!(port.get(), 8080);
+        assert!(matches!(
+            shared.watch::<u16>("mc-key-host"),
+            Err(ConfigError::ParseError(_))
+        ));
+
+        fs::write(lenv_path, "MC_KEY_PORT: 9090\n").unwrap();
+        shared.reload().unwrap();
+        assert_eq!(port.get(), 9090);
+
+        // Values that cannot be parsed are skipped
+        fs::write(lenv_path, "MC_KEY_PORT: http\n").unwrap();
+        shared.reload().unwrap();
+        assert_eq!(port.get(), 9090);
+
+        fs::remove_file(lenv_path).unwrap();
+        shared.reload().unwrap();
+        assert_eq!(port.clone().get(), 3000);
+
+        env::remove_var("MC_KEY_PORT");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_shared_config_watch_key_channel() {
+        use lino_arguments::try_make_shared_config_from;
+
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("channel.lenv");
+        fs::write(&lenv_path, "MC_CHANNEL_LEVEL: debug\n").unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_CHANNEL_LEVEL");
+        let shared = try_make_shared_config_from(["app"], |c| {
+            c.lenv(lenv_path)
+                .option("mc-channel-level", "Log level", "info")
+        })
+        .unwrap();
+        let level = shared.watch::<String>("mc-channel-level").unwrap();
+        let mut receiver = level.subscribe();
+        assert_eq!(*receiver.borrow(), "debug");
+        assert!(!receiver.has_changed().unwrap());
+
+        fs::write(lenv_path, "MC_CHANNEL_LEVEL: trace\n").unwrap();
+        shared.reload().unwrap();
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(*receiver.borrow_and_update(), "trace");
+
+        env::remove_var("MC_CHANNEL_LEVEL");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shared_config_reload_on_sighup() {
+        use lino_arguments::try_make_shared_config_from;
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("sighup.lenv");
+        fs::write(&lenv_path, "MC_HUP_LEVEL: debug\n").unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_HUP_LEVEL");
+        let shared = try_make_shared_config_from(["app"], |c| {
+            c.lenv(lenv_path)
+                .option("mc-hup-level", "Log level", "info")
+        })
+        .unwrap();
+        shared.veto_reload(|config| match config.get("mc-hup-level").as_str() {
+            "off" => Err(ConfigError::ParseError(
+                "mc-hup-level off would silence the service".to_string(),
+            )),
+            _ => Ok(()),
+        });
+        let (sender, receiver) = mpsc::channel();
+        shared.on_reload(move |config| {
+            let _ = sender.send(config.get("mc-hup-level"));
+        });
+
+        fs::write(lenv_path, "MC_HUP_LEVEL: off\n").unwrap();
+        assert!(matches!(shared.reload(), Err(ConfigError::ParseError(_))));
+        assert_eq!(shared.snapshot().get("mc-hup-level"), "debug");
+
+        let watcher = shared.reload_on_sighup().unwrap();
+        fs::write(lenv_path, "MC_HUP_LEVEL: trace\n").unwrap();
+        let status = std::process::Command::new("kill")
+            .args(["-HUP", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let level = receiver
+            .recv_timeout(Duration::from_secs(10))
+            .expect("the config was not reloaded");
+        assert_eq!(level, "trace");
+        assert_eq!(shared.snapshot().get("mc-hup-level"), "trace");
+
+        drop(watcher);
+        env::remove_var("MC_HUP_LEVEL");
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_shared_config_watch_files() {
+        use lino_arguments::try_make_shared_config_from;
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("watched.lenv");
+        fs::write(&lenv_path, "MC_WATCHED_LEVEL: debug\n").unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_WATCHED_LEVEL");
+        let shared = try_make_shared_config_from(["app"], |c| {
+            c.lenv(lenv_path)
+                .option("mc-watched-level", "Log level", "info")
+        })
+        .unwrap();
+        let (sender, receiver) = mpsc::channel();
+        shared.on_reload(move |config| {
+            let _ = sender.send(config.get("mc-watched-level"));
+        });
+        let watcher = shared.watch_files().unwrap();
+
+        fs::write(lenv_path, "MC_WATCHED_LEVEL: trace\n").unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            let level = receiver
+                .recv_timeout(remaining)
+                .expect("the config was not reloaded");
+            if level == "trace" {
+                break;
+            }
+        }
+        assert_eq!(shared.snapshot().get("mc-watched-level"), "trace");
+
+        drop(watcher);
+        env::remove_var("MC_WATCHED_LEVEL");
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_shared_config_watch_files_in_missing_directory() {
+        use lino_arguments::try_make_shared_config_from;
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let dir = tempdir().unwrap();
+        let conf_dir = dir.path().join("conf").join("app");
+        let lenv_path = conf_dir.join("watched.lenv");
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_MISSING_DIR_LEVEL");
+        let shared = try_make_shared_config_from(["app"], |c| {
+            c.lenv(lenv_path)
+                .option("mc-missing-dir-level", "Log level", "info")
+        })
+        .unwrap();
+        let (sender, receiver) = mpsc::channel();
+        shared.on_reload(move |config| {
+            let _ = sender.send(config.get("mc-missing-dir-level"));
+        });
+        let watcher = shared.watch_files().unwrap();
+
+        fs::create_dir_all(&conf_dir).unwrap();
+        fs::write(lenv_path, "MC_MISSING_DIR_LEVEL: trace\n").unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            let level = receiver
+                .recv_timeout(remaining)
+                .expect("the config was not reloaded");
+            if level == "trace" {
+                break;
+            }
+        }
+
+        // Changes in the created directory are watched from now on
+        fs::write(lenv_path, "MC_MISSING_DIR_LEVEL: warn\n").unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            let level = receiver
+                .recv_timeout(remaining)
+                .expect("the config was not reloaded");
+            if level == "warn" {
+                break;
+            }
+        }
+
+        drop(watcher);
+        env::remove_var("MC_MISSING_DIR_LEVEL");
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_shared_config_watch_files_debounced() {
+        use lino_arguments::try_make_shared_config_from;
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("debounced.lenv");
+        fs::write(&lenv_path, "MC_DEBOUNCED_LEVEL: debug\n").unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_DEBOUNCED_LEVEL");
+        let shared = try_make_shared_config_from(["app"], |c| {
+            c.lenv(lenv_path)
+                .option("mc-debounced-level", "Log level", "info")
+        })
+        .unwrap();
+        let (sender, receiver) = mpsc::channel();
+        shared.on_reload(move |config| {
+            let _ = sender.send(config.get("mc-debounced-level"));
+        });
+        let watcher = shared.watch_files_with(Duration::from_millis(300)).unwrap();
+
+        for level in ["warn", "error", "trace"] {
+            fs::write(lenv_path, format!("MC_DEBOUNCED_LEVEL: {level}\n")).unwrap();
+        }
+        let level = receiver
+            .recv_timeout(Duration::from_secs(10))
+            .expect("the config was not reloaded");
+        assert_eq!(level, "trace");
+        // The burst was coalesced into that one reload
+        assert!(receiver.recv_timeout(Duration::from_millis(600)).is_err());
+        assert_eq!(shared.snapshot().generation(), 2);
+
+        drop(watcher);
+        env::remove_var("MC_DEBOUNCED_LEVEL");
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_make_config_sqlite_source() {
+        use lino_arguments::SqliteSource;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.db");
+        let settings = SqliteSource::new(path.to_str().unwrap());
+        // Nothing is created until the first write
+        assert_eq!(settings.get("mc-sqlite-theme").unwrap(), None);
+        settings.delete("mc-sqlite-theme").unwrap();
+        assert!(!path.exists());
+
+        settings.set("MC_SQLITE_THEME", "dark").unwrap();
+        settings.set("mc-sqlite-font", "mono").unwrap();
+        settings.set("mc-sqlite-font", "serif").unwrap();
+        settings.delete("mcSqliteFont").unwrap();
+        assert_eq!(
+            settings.get("mcSqliteTheme").unwrap().as_deref(),
+            Some("dark")
+        );
+
+        let config = make_config_from(["app"], |c| {
+            c.source(settings.clone())
+                .option("mc-sqlite-theme", "Color theme", "light")
+                .option("mc-sqlite-font", "Font", "sans")
+        });
+        assert_eq!(config.get("mc-sqlite-theme"), "dark");
+        assert_eq!(config.get("mc-sqlite-font"), "sans");
+        assert_eq!(
+            config.source("mc-sqlite-theme"),
+            Some(ValueSource::Remote("SQLite database"))
+        );
+    }
+
+    #[test]
+    fn test_make_config_from_sources() {
+        #[derive(serde::Deserialize)]
+        struct Settings {
+            mc_struct_port: u16,
+            mc_struct_host: String,
+            mc_struct_workers: usize,
+            mc_struct_debug: bool,
+        }
+
+        let dir = tempdir().unwrap();
+        let lenv = dir.path().join(".lenv");
+        fs::write(&lenv, "MC_STRUCT_HOST: db.internal\n").unwrap();
+        env::set_var("mcStructWorkers", "4");
+        let settings: Settings = from_sources_from(
+            ["app", "--mc-struct-port", "8080", "--mc-struct-debug"],
+            |c| {
+                c.lenv(lenv.to_str().unwrap())
+                    .option("mc-struct-port", "Port", "3000")
+                    .option("mc-struct-host", "Host", "localhost")
+                    .flag("mc-struct-debug", "Debug")
+            },
+        )
+        .unwrap();
+        assert_eq!(settings.mc_struct_port, 8080);
+        assert_eq!(settings.mc_struct_host, "db.internal");
+        assert_eq!(settings.mc_struct_workers, 4);
+        assert!(settings.mc_struct_debug);
+
+        let result: Result<Settings, _> =
+            from_sources_from(["app"], |c| c.option("mc-struct-port", "Port", "eighty"));
+        assert!(matches!(result, Err(ConfigError::ParseError(_))));
+        env::remove_var("mcStructWorkers");
+    }
+
+    #[cfg(feature = "figment")]
+    #[test]
+    fn test_make_config_figment_provider() {
+        use figment::Figment;
+        use serde::Deserialize;
+
+        #[derive(Deserialize)]
+        struct App {
+            mc_figment_port: u16,
+            mc_figment_host: String,
+        }
+
+        let dir = tempdir().unwrap();
+        let lenv = dir.path().join(".lenv");
+        fs::write(&lenv, "MC_FIGMENT_HOST: db.internal\n").unwrap();
+        let config = make_config_from(["app", "--mc-figment-port", "8080"], |c| {
+            c.lenv(lenv.to_str().unwrap())
+                .option("mc-figment-port", "Port", "3000")
+                .option("mc-figment-host", "Host", "localhost")
+        });
+        let app: App = Figment::from(config).extract().unwrap();
+        assert_eq!(app.mc_figment_port, 8080);
+        assert_eq!(app.mc_figment_host, "db.internal");
+    }
+
+    #[cfg(feature = "config-rs")]
+    #[test]
+    fn test_make_config_config_rs_source() {
+        let dir = tempdir().unwrap();
+        let lenv = dir.path().join(".lenv");
+        fs::write(&lenv, "MC_CONFIG_RS_HOST: db.internal\n").unwrap();
+        let lino = make_config_from(["app", "--mc-config-rs-port", "8080"], |c| {
+            c.lenv(lenv.to_str().unwrap())
+                .option("mc-config-rs-port", "Port", "3000")
+                .option("mc-config-rs-host", "Host", "localhost")
+        });
+        let settings = config_rs::Config::builder()
+            .set_default("mc_config_rs_port", 80)
+            .unwrap()
+            .set_default("mc_config_rs_workers", 4)
+            .unwrap()
+            .add_source(lino)
+            .build()
+            .unwrap();
+        assert_eq!(settings.get::<u16>("mc_config_rs_port").unwrap(), 8080);
+        assert_eq!(
+            settings.get_string("mc_config_rs_host").unwrap(),
+            "db.internal"
+        );
+        assert_eq!(settings.get_int("mc_config_rs_workers").unwrap(), 4);
+    }
+
+    #[test]
+    fn test_make_config_kubernetes_source() {
+        let dir = tempdir().unwrap();
+        let config_map = dir.path().join("config");
+        let data = config_map.join("..2026_10_15_12_00_00.000000000");
+        fs::create_dir_all(&data).unwrap();
+        fs::write(data.join("mc_kube_level"), "debug\n").unwrap();
+        fs::write(config_map.join("mc_kube_level"), "debug\n").unwrap();
+        let podinfo = dir.path().join("podinfo");
+        fs::create_dir(&podinfo).unwrap();
+        fs::write(podinfo.join("mc_kube_pod"), "web-7d4b9").unwrap();
+        fs::write(podinfo.join("labels"), "mc_kube_app=\"web\"\n").unwrap();
+
+        let source = KubernetesSource::empty()
+            .config_map(config_map.to_str().unwrap())
+            .downward_api(podinfo.to_str().unwrap())
+            .downward_api(dir.path().join("missing").to_str().unwrap());
+        let config = make_config_from(["app"], |c| {
+            c.source(source)
+                .option("mc-kube-level", "Log level", "info")
+                .option("mc-kube-pod", "Pod name", "")
+                .option("labels-mc-kube-app", "App label", "")
+        });
+        assert_eq!(config.get("mc-kube-level"), "debug");
+        assert_eq!(config.get("mc-kube-pod"), "web-7d4b9");
+        assert_eq!(config.get("labels-mc-kube-app"), "web");
+        assert_eq!(
+            config.source("mc-kube-level"),
+            Some(ValueSource::Remote("Kubernetes volume"))
+        );
+    }
+
+    #[cfg(feature = "age")]
+    #[test]
+    fn test_make_config_encrypted_values() {
+        use lino_arguments::encrypt_value;
+
+        let dir = tempdir().unwrap();
+        let identity = dir.path().join("key.txt");
+        fs::write(
+            &identity,
+            "# public key: age1s29n956ysyxpkytgcyl2zyeuqqmx5d3nht42p4evggwkvudzdgpsncysf5\n\
+             AGE-SECRET-KEY-10XW9KLDPVF3P0KRK9WK9FEYM93L85FCGE5ZZKVMENFHKQW20DRASU75R46\n",
+        )
+        .unwrap();
+        let identity = identity.to_str().unwrap();
+        let token = encrypt_value(
+            "age1s29n956ysyxpkytgcyl2zyeuqqmx5d3nht42p4evggwkvudzdgpsncysf5",
+            "sk-live-1234",
+        )
+        .unwrap();
+        let lenv_path = dir.path().join("test.lenv");
+        fs::write(
+            &lenv_path,
+            format!("MC_ENC_TOKEN: {token}\nMC_ENC_USER: admin\n"),
+        )
+        .unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+
+        env::remove_var("MC_ENC_TOKEN");
+        env::remove_var("MC_ENC_USER");
+        let config = try_make_config_from(["app"], |c| {
+            c.lenv(lenv_path)
+                .age_identity(identity)
+                .option("mc-enc-token", "Token", "")
+                .option("mc-enc-user", "User", "")
+        })
+        .unwrap();
+        assert_eq!(config.get("mc-enc-token"), "sk-live-1234");
+        assert_eq!(config.get("mc-enc-user"), "admin");
+
+        // Encrypted to someone else
+        let other = encrypt_value(
+            "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p",
+            "sk-live-1234",
+        )
+        .unwrap();
+        let err = try_make_config_from(["app", "--mc-enc-token", &other], |c| {
+            c.age_identity(identity).option("mc-enc-token", "Token", "")
+        })
+        .unwrap_err();
+        let ConfigError::Invalid(issues) = err else {
+            panic!("expected an invalid configuration, got {err}");
+        };
+        assert_eq!(issues[0].key, "mc-enc-token");
+        assert!(issues[0].message.starts_with("could not be decrypted"));
+
+        env::remove_var("MC_ENC_TOKEN");
+        env::remove_var("MC_ENC_USER");
+    }
+
+    #[test]
+    fn test_make_config_cli_overrides_lenv() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test_override.lenv");
+        let file_path_str = file_path.to_str().unwrap();
+
+        fs::write(&file_path, "MC_CLIOV_PORT: 7070\n").unwrap();
+
+        env::remove_var("MC_CLIOV_PORT");
+
+        let config = make_config_from(["app", "--mc-cliov-port", "9999"], |c| {
+            c.lenv(file_path_str)
+                .option("mc-cliov-port", "Server port", "3000")
+        });
+
+        // CLI should override .lenv value
+        assert_eq!(config.get("mcCliovPort"), "9999");
+
+        env::remove_var("MC_CLIOV_PORT");
+    }
+
+    #[test]
+    fn test_make_config_multiple_options_and_flags() {
+        let config = make_config_from(
+            ["app", "--port", "8080", "--api-key", "secret", "--verbose"],
+            |c| {
+                c.option("port", "Server port", "3000")
+                    .option("api-key", "API key", "")
+                    .flag("verbose", "Enable verbose logging")
+            },
+        );
+
+        assert_eq!(config.get("port"), "8080");
+        assert_eq!(config.get("apiKey"), "secret");
+        assert!(config.get_bool("verbose"));
+    }
+
+    #[test]
+    fn test_make_config_app_metadata() {
+        let config = make_config_from(["my-app", "--port", "8080"], |c| {
+            c.name("my-app")
+                .about("A test application")
+                .version("1.0.0")
+                .option("port", "Server port", "3000")
+        });
+
+        assert_eq!(config.get("port"), "8080");
+    }
+}
+
+// ============================================================================
+// Value Normalization Tests
+// ============================================================================
+
+mod normalize_tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_value() {
+        assert_eq!(normalize_value(" 8080 "), "8080");
+        assert_eq!(normalize_value("\" 8080 \""), "8080");
+        assert_eq!(normalize_value("'value'"), "value");
+        assert_eq!(normalize_value("\"it's\""), "it's");
+        assert_eq!(normalize_value("\"half"), "\"half");
+        assert_eq!(normalize_value("\""), "\"");
+        assert_eq!(normalize_value("\"\""), "");
+    }
+
+    #[test]
+    fn test_getenv_does_not_normalize_by_default() {
+        env::set_var("NORM_DEFAULT_PORT", "\" 8080 \"");
+        assert_eq!(getenv("NORM_DEFAULT_PORT", ""), "\" 8080 \"");
+        env::remove_var("NORM_DEFAULT_PORT");
+    }
+
+    #[test]
+    fn test_getenv_with_normalize() {
+        env::set_var("NORM_WITH_PORT", "\" 8080 \"");
+        let options = GetenvOptions::new().normalize(true);
+        assert_eq!(getenv_with("NORM_WITH_PORT", "", &options), "8080");
+        assert_eq!(getenv_int_with("NORM_WITH_PORT", 0, &options), 8080);
+        env::remove_var("NORM_WITH_PORT");
+    }
+
+    #[test]
+    fn test_normalized_empty_counts_as_unset() {
+        env::set_var("NORM_EMPTY_PORT", "\"  \"");
+        let options = GetenvOptions::new()
+            .normalize(true)
+            .empty_values(EmptyValues::Unset);
+        assert_eq!(getenv_with("NORM_EMPTY_PORT", "3000", &options), "3000");
+        env::remove_var("NORM_EMPTY_PORT");
+    }
+
+    #[test]
+    fn test_make_config_normalize_values() {
+        env::set_var("MC_NORM_HOST", "  'example.com' ");
+        let config = make_config_from(["app"], |c| {
+            c.normalize_values(true).option("mc-norm-host", "Host", "")
+        });
+        assert_eq!(config.get("mcNormHost"), "example.com");
+        env::remove_var("MC_NORM_HOST");
+    }
+
+    #[test]
+    fn test_make_config_normalized_empty_uses_default() {
+        env::set_var("MC_NORM_EMPTY_PORT", " \"\" ");
+        let config = make_config_from(["app"], |c| {
+            c.normalize_values(true)
+                .empty_values(EmptyValues::Unset)
+                .option("mc-norm-empty-port", "Port", "3000")
+        });
+        assert_eq!(config.get("mcNormEmptyPort"), "3000");
+        env::remove_var("MC_NORM_EMPTY_PORT");
+    }
+}
+
+// ============================================================================
+// Numeric Parsing Tests
+// ============================================================================
+
+mod numeric_parsing_tests {
+    use super::*;
+
+    #[test]
+    fn test_getenv_int_accepts_separators_and_plus() {
+        env::set_var("NUM_SEP_LIMIT", "1,000");
+        assert_eq!(getenv_int("NUM_SEP_LIMIT", 0), 1000);
+        env::set_var("NUM_SEP_LIMIT", "1_000");
+        assert_eq!(getenv_int("NUM_SEP_LIMIT", 0), 1000);
+        env::set_var("NUM_SEP_LIMIT", "+7");
+        assert_eq!(getenv_int("NUM_SEP_LIMIT", 0), 7);
+        env::remove_var("NUM_SEP_LIMIT");
+    }
+
+    #[test]
+    fn test_getenv_int_strict_rejects_separators() {
+        env::set_var("NUM_STRICT_LIMIT", "1,000");
+        let strict = GetenvOptions::new().strict_numbers(true);
+        assert_eq!(getenv_int_with("NUM_STRICT_LIMIT", 5, &strict), 5);
+        env::remove_var("NUM_STRICT_LIMIT");
+    }
+
+    #[test]
+    fn test_getenv_float() {
+        env::set_var("NUM_FLOAT_RATIO", "1_000.25");
+        assert_eq!(getenv_float("NUM_FLOAT_RATIO", 0.0), 1000.25);
+        let strict = GetenvOptions::new().strict_numbers(true);
+        assert_eq!(getenv_float_with("NUM_FLOAT_RATIO", 0.5, &strict), 0.5);
+        env::set_var("NUM_FLOAT_RATIO", "1.234,567");
+        assert_eq!(getenv_float("NUM_FLOAT_RATIO", 0.5), 0.5);
+        env::remove_var("NUM_FLOAT_RATIO");
+    }
+
+    #[test]
+    fn test_getenv_float_default_when_missing() {
+        assert_eq!(getenv_float("NUM_FLOAT_MISSING_12345", 1.5), 1.5);
+    }
+
+    #[test]
+    fn test_config_typed_getters_accept_separators() {
+        let config = make_config_from(["app", "--limit", "10,000", "--ratio", "+0.75"], |c| {
+            c.option("limit", "Limit", "").option("ratio", "Ratio", "")
+        });
+        assert_eq!(config.get_int("limit", 0), 10_000);
+        assert_eq!(config.get_float("ratio", 0.0), 0.75);
+    }
+
+    #[test]
+    fn test_config_strict_numbers() {
+        let config = make_config_from(["app", "--limit", "10,000", "--ratio", "+0.75"], |c| {
+            c.strict_numbers(true)
+                .option("limit", "Limit", "")
+                .option("ratio", "Ratio", "")
+        });
+        assert_eq!(config.get_int("limit", 1), 1);
+        assert_eq!(config.get_float("ratio", 0.5), 0.5);
+    }
+}
+
+// ============================================================================
+// Empty-String Policy Tests
+// ============================================================================
+
+mod empty_values_tests {
+    use super::*;
+
+    #[test]
+    fn test_getenv_keeps_empty_by_default() {
+        env::set_var("EMPTY_KEEP_VAR", "");
+        assert_eq!(getenv("EMPTY_KEEP_VAR", "default"), "");
+        env::remove_var("EMPTY_KEEP_VAR");
+    }
+
+    #[test]
+    fn test_getenv_with_unset_falls_back_to_default() {
+        env::set_var("EMPTY_UNSET_VAR", "");
+        let options = GetenvOptions::new().empty_values(EmptyValues::Unset);
+        assert_eq!(
+            getenv_with("EMPTY_UNSET_VAR", "default", &options),
+            "default"
+        );
+        env::remove_var("EMPTY_UNSET_VAR");
+    }
+
+    #[test]
+    fn test_getenv_with_unset_falls_through_to_next_variant() {
+        env::set_var("emptyVariantVar", "");
+        env::set_var("EMPTY_VARIANT_VAR", "found");
+        let options = GetenvOptions::new().empty_values(EmptyValues::Unset);
+        assert_eq!(getenv_with("emptyVariantVar", "default", &options), "found");
+        env::remove_var("emptyVariantVar");
+        env::remove_var("EMPTY_VARIANT_VAR");
+    }
+
+    #[test]
+    fn test_make_config_keeps_empty_by_default() {
+        env::set_var("MC_EMPTY_KEEP_PORT", "");
+        let config = make_config_from(["app"], |c| {
+            c.option("mc-empty-keep-port", "Server port", "3000")
+        });
+        assert_eq!(config.get("mcEmptyKeepPort"), "");
+        env::remove_var("MC_EMPTY_KEEP_PORT");
+    }
+
+    #[test]
+    fn test_make_config_unset_uses_default() {
+        env::set_var("MC_EMPTY_DEFAULT_PORT", "");
+        let config = make_config_from(["app"], |c| {
+            c.empty_values(EmptyValues::Unset).option(
+                "mc-empty-default-port",
+                "Server port",
+                "3000",
+            )
+        });
+        assert_eq!(config.get("mcEmptyDefaultPort"), "3000");
+        assert_eq!(
+            config.source("mcEmptyDefaultPort"),
+            Some(ValueSource::Default)
+        );
+        env::remove_var("MC_EMPTY_DEFAULT_PORT");
+    }
+
+    #[test]
+    fn test_make_config_unset_falls_through_to_lenv() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("empty.lenv");
+        fs::write(&file_path, "MC_EMPTY_LENV_PORT: 7070\n").unwrap();
+
+        env::set_var("MC_EMPTY_LENV_PORT", "");
+        let config = make_config_from(["app"], |c| {
+            c.empty_values(EmptyValues::Unset)
+                .lenv(file_path.to_str().unwrap())
+                .option("mc-empty-lenv-port", "Server port", "3000")
+        });
+        assert_eq!(config.get("mcEmptyLenvPort"), "7070");
+        assert_eq!(
+            config.source("mcEmptyLenvPort"),
+            Some(ValueSource::LenvFile)
+        );
+        env::remove_var("MC_EMPTY_LENV_PORT");
+    }
+}
+
+// ============================================================================
+// Config Audit Log Tests
+// ============================================================================
+
+mod audit_tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_is_empty_before_any_read() {
+        let config = make_config_from(["app"], |c| c.option("port", "Server port", "3000"));
+
+        assert!(config.audit().is_empty());
+    }
+
+    #[test]
+    fn test_audit_records_reads_in_order() {
+        let config = make_config_from(["app", "--audit-order-port", "8080"], |c| {
+            c.option("audit-order-port", "Server port", "3000")
+                .flag("audit-order-verbose", "Verbose")
+        });
+
+        config.get("audit-order-port");
+        config.get_bool("audit-order-verbose");
+        config.get_int("auditOrderPort", 0);
+
+        let keys: Vec<String> = config.audit().into_iter().map(|e| e.key).collect();
+        assert_eq!(
+            keys,
+            vec!["audit-order-port", "audit-order-verbose", "auditOrderPort"]
+        );
+    }
+
+    #[test]
+    fn test_audit_records_resolved_sources() {
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("audit.lenv");
+        let env_path = dir.path().join(".env");
+        fs::write(&lenv_path, "AUDIT_SRC_LENV: 1\n").unwrap();
+        fs::write(&env_path, "AUDIT_SRC_DOTENV=2\n").unwrap();
+
+        env::remove_var("AUDIT_SRC_LENV");
+        env::remove_var("AUDIT_SRC_DOTENV");
+        env::set_var("AUDIT_SRC_ENV", "3");
+
+        let config = make_config_from(["app", "--audit-src-cli", "4"], |c| {
+            c.lenv(lenv_path.to_str().unwrap())
+                .env(env_path.to_str().unwrap())
+                .option("audit-src-lenv", "", "")
+                .option("audit-src-dotenv", "", "")
+                .option("audit-src-env", "", "")
+                .option("audit-src-cli", "", "")
+                .option("audit-src-default", "", "5")
+        });
+
+        config.get("audit-src-lenv");
+        config.get("audit-src-dotenv");
+        config.get("audit-src-env");
+        config.get("audit-src-cli");
+        config.get("audit-src-default");
+        config.get("audit-src-missing");
+
+        let sources: Vec<Option<ValueSource>> =
+            config.audit().into_iter().map(|e| e.source).collect();
+        assert_eq!(
+            sources,
+            vec![
+                Some(ValueSource::LenvFile),
+                Some(ValueSource::EnvFile),
+                Some(ValueSource::Environment),
+                Some(ValueSource::Cli),
+                Some(ValueSource::Default),
+                None,
+            ]
+        );
+
+        env::remove_var("AUDIT_SRC_LENV");
+        env::remove_var("AUDIT_SRC_DOTENV");
+        env::remove_var("AUDIT_SRC_ENV");
+    }
+
+    #[test]
+    fn test_audit_counts_repeated_reads() {
+        let config = make_config_from(["app"], |c| c.option("port", "Server port", "3000"));
+
+        config.get("port");
+        config.get("port");
+
+        let audit = config.audit();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].reads, 2);
+        assert!(audit[0].first_read <= audit[0].last_read);
+    }
+
+    #[test]
+    fn test_audit_keeps_one_entry_per_key() {
+        let config = make_config_from(["app"], |c| c.option("port", "Server port", "3000"));
+
+        for _ in 0..10_000 {
+            config.get_int("port", 0);
+        }
+
+        let audit = config.audit();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].key, "port");
+        assert_eq!(audit[0].source, Some(ValueSource::Default));
+        assert_eq!(audit[0].reads, 10_000);
+    }
+
+    #[test]
+    fn test_audit_has_does_not_record() {
+        let config = make_config_from(["app"], |c| c.option("port", "Server port", "3000"));
+
+        assert!(config.has("port"));
+        assert!(config.audit().is_empty());
+    }
+
+    #[test]
+    fn test_audit_shared_between_clones() {
+        let config = make_config_from(["app"], |c| c.option("port", "Server port", "3000"));
+        let clone = config.clone();
+
+        clone.get("port");
+
+        assert_eq!(config.audit().len(), 1);
+    }
+}
+
+// ============================================================================
+// Schema Tests
+// ============================================================================
+
+mod schema_tests {
+    use super::*;
+
+    fn server_schema() -> Schema {
+        Schema::new()
+            .key(
+                KeySpec::new("schema-port", ValueType::Integer)
+                    .default("3000")
+                    .description("Server port"),
+            )
+            .key(KeySpec::new("schema-verbose", ValueType::Bool).description("Verbose"))
+            .key(
+                KeySpec::new("schema-token", ValueType::String).sources([ValueSource::Environment]),
+            )
+    }
+
+    #[test]
+    fn test_schema_defines_options() {
+        let config =
+            try_make_config_from(["app", "--schema-verbose"], |c| c.schema(server_schema()))
+                .unwrap();
+
+        assert_eq!(config.get_int("schema-port", 0), 3000);
+        assert_eq!(config.source("schema-port"), Some(ValueSource::Default));
+        assert!(config.get_bool("schema-verbose"));
+        assert!(!config.has("schema-token"));
+    }
+
+    #[test]
+    fn test_schema_rejects_wrong_type() {
+        let err = try_make_config_from(["app", "--schema-port", "http"], |c| {
+            c.schema(server_schema())
+        })
+        .unwrap_err();
+
+        let ConfigError::Invalid(issues) = err else {
+            panic!("expected an invalid configuration, got {err:?}");
+        };
+        let [issue] = &issues[..] else {
+            panic!("expected one issue, got {issues:?}");
+        };
+        assert_eq!(issue.key, "schema-port");
+        assert_eq!(issue.value.as_deref(), Some("http"));
+        assert_eq!(issue.source, Some(ValueSource::Cli));
+    }
+
+    #[test]
+    fn test_schema_rejects_disallowed_source() {
+        let err = try_make_config_from(["app", "--schema-token", "abc"], |c| {
+            c.schema(server_schema())
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: `schema-token` may not be set from the command line \
+             (got \"abc\" from the command line)"
+        );
+    }
+
+    #[test]
+    fn test_schema_required_key() {
+        let schema = Schema::new()
+            .key(KeySpec::new("schema-required-url", ValueType::String).required(true));
+
+        let err = try_make_config_from(["app"], |c| c.schema(schema.clone())).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: `schema-required-url` is required but not set"
+        );
+
+        let config = try_make_config_from(["app", "--schema-required-url", "http://x"], |c| {
+            c.schema(schema)
+        })
+        .unwrap();
+        assert_eq!(config.get("schema-required-url"), "http://x");
+    }
+
+    #[test]
+    fn test_schema_keeps_existing_option_definition() {
+        let config = try_make_config_from(["app", "-p", "8080"], |c| {
+            c.option_short("schema-port", 'p', "Port", "1")
+                .schema(server_schema())
+        })
+        .unwrap();
+
+        assert_eq!(config.get("schema-port"), "8080");
+    }
+
+    #[test]
+    fn test_schema_validators_run_during_build() {
+        let schema = Schema::new()
+            .key(
+                KeySpec::new("schema-range-port", ValueType::Integer)
+                    .default("3000")
+                    .validator(Validator::range(1..=65535)),
+            )
+            .key(
+                KeySpec::new("schema-format", ValueType::String)
+                    .default("text")
+                    .validator(Validator::one_of(["json", "text"])),
+            );
+
+        let config = try_make_config_from(["app", "--schema-format", "json"], |c| {
+            c.schema(schema.clone())
+        })
+        .unwrap();
+        assert_eq!(config.get("schema-format"), "json");
+
+        env::set_var("SCHEMA_RANGE_PORT", "0");
+        let err = try_make_config_from(["app"], |c| c.schema(schema)).unwrap_err();
+        env::remove_var("SCHEMA_RANGE_PORT");
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: `schema-range-port` must be between 1 and 65535 \
+             (got \"0\" from the environment)"
+        );
+    }
+
+    #[test]
+    fn test_schema_reports_every_issue_at_once() {
+        let schema = server_schema()
+            .key(KeySpec::new("schema-all-url", ValueType::String).required(true))
+            .key(
+                KeySpec::new("schema-all-level", ValueType::String)
+                    .default("debug")
+                    .validator(Validator::one_of(["info", "warn"])),
+            );
+
+        let err = try_make_config_from(["app", "--schema-port", "http"], |c| c.schema(schema))
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration:\n  \
+             - `schema-port` is not a valid integer (got \"http\" from the command line)\n  \
+             - `schema-all-url` is required but not set\n  \
+             - `schema-all-level` must be one of: info, warn (got \"debug\" from the default value)"
+        );
+    }
+
+    #[test]
+    fn test_schema_json_schema_export() {
+        let json = server_schema().to_json_schema();
+
+        assert_eq!(json["type"], "object");
+        assert_eq!(json["properties"]["schema-port"]["type"], "integer");
+        assert_eq!(json["properties"]["schema-port"]["default"], 3000);
+        assert_eq!(json["properties"]["schema-verbose"]["type"], "boolean");
+        assert_eq!(json["properties"]["schema-token"]["type"], "string");
+        assert_eq!(json["required"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_json_schema_checks_config_files_before_resolution() {
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("app.lenv");
+        let env_path = dir.path().join("app.env");
+        fs::write(
+            &lenv_path,
+            "# ports\nSCHEMA_FILE_PORT: 70000\nSCHEMA_FILE_MODE: fast\n",
+        )
+        .unwrap();
+        fs::write(
+            &env_path,
+            "SCHEMA_FILE_MODE=fast\n\nexport SCHEMA_FILE_TYPO=1\n",
+        )
+        .unwrap();
+
+        let document = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "schema-file-port": { "type": "integer", "maximum": 65535 },
+                "schema-file-mode": { "enum": ["fast", "slow"] },
+            },
+            "additionalProperties": false,
+        });
+
+        let err = try_make_config_from(["app"], |c| {
+            c.lenv(lenv_path.to_str().unwrap())
+                .env(env_path.to_str().unwrap())
+                .json_schema(&document)
+                .option("schema-file-port", "Port", "3000")
+        })
+        .unwrap_err();
+
+        let ConfigError::Invalid(issues) = err else {
+            panic!("expected an invalid configuration, got {err:?}");
+        };
+        let found: Vec<_> = issues
+            .iter()
+            .map(|issue| {
+                let location = issue.location.as_ref().unwrap();
+                let position = (location.line, location.column);
+                (issue.key.as_str(), position, issue.message.as_str())
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("SCHEMA_FILE_PORT", (2, 19), "must be at most 65535"),
+                ("SCHEMA_FILE_TYPO", (3, 25), "is not defined in the schema"),
+            ]
+        );
+        assert!(env::var("SCHEMA_FILE_PORT").is_err());
+    }
+
+    #[test]
+    fn test_validate_env_file_against_json_schema() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".env");
+        fs::write(&path, "PORT=8080\nDEBUG=maybe\n").unwrap();
+        let schema = Schema::from_json_schema(&serde_json::json!({
+            "properties": {
+                "port": { "type": "integer" },
+                "debug": { "type": "boolean" },
+            },
+        }));
+
+        let err = schema
+            .validate_env_file(path.to_str().unwrap())
+            .unwrap_err();
+
+        let path = path.to_str().unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Invalid configuration: {path}:2:7: `DEBUG` is not a valid boolean \
+                 (got \"maybe\" from the .env file)"
+            )
+        );
+        assert!(schema.validate_env_file("/nonexistent/.env").is_ok());
+    }
+
+    #[test]
+    fn test_schema_render_markdown_reference() {
+        let schema = server_schema().key(
+            KeySpec::new("schema-docs-level", ValueType::String)
+                .default("info")
+                .description("Log level.")
+                .validator(Validator::one_of(["info", "debug"]))
+                .example("debug")
+                .since("0.4.0"),
+        );
+
+        let table = schema.render_markdown();
+
+        assert!(table.starts_with("| Option | Environment | Type | Default | Description |\n"));
+        assert!(
+            table.contains("| `--schema-port` | `SCHEMA_PORT` | integer | `3000` | Server port |")
+        );
+        assert!(table.contains(
+            "| `--schema-docs-level` | `SCHEMA_DOCS_LEVEL` | string | `info` | Log level. \
+             Must be one of `info`, `debug`. Examples: `debug`. Since 0.4.0. |"
+        ));
+    }
+
+    #[test]
+    fn test_deprecated_key_resolves_with_warning() {
+        let schema = server_schema().key(
+            KeySpec::new("schema-old-port", ValueType::Integer)
+                .deprecated("Use the unified port option.")
+                .replaced_by("schema-port"),
+        );
+
+        let config = try_make_config_from(["app"], |c| c.schema(schema.clone())).unwrap();
+        assert!(config.warnings().is_empty());
+
+        env::set_var("SCHEMA_OLD_PORT", "8080");
+        let config = try_make_config_from(["app"], |c| c.schema(schema)).unwrap();
+        env::remove_var("SCHEMA_OLD_PORT");
+
+        assert_eq!(config.get("schema-old-port"), "8080");
+        let [warning] = config.warnings() else {
+            panic!("expected one warning, got {:?}", config.warnings());
+        };
+        assert_eq!(warning.source, Some(ValueSource::Environment));
+        assert_eq!(
+            warning.to_string(),
+            "`schema-old-port` is deprecated: Use the unified port option. \
+             (got \"8080\" from the environment); \
+             use `--schema-port` / `SCHEMA_PORT` instead"
+        );
+    }
+
+    #[test]
+    fn test_key_from_attributes_resolves_custom_env() {
+        let port = KeySpec::from_attributes(
+            "schema-attr-port",
+            ValueType::Integer,
+            r#"env = "SCHEMA_ATTR_HTTP_PORT", default = 3000, range = "1..=65535""#,
+        )
+        .unwrap();
+        let schema = Schema::new().key(port);
+
+        let config = try_make_config_from(["app"], |c| c.schema(schema.clone())).unwrap();
+        assert_eq!(config.get_int("schema-attr-port", 0), 3000);
+
+        env::set_var("SCHEMA_ATTR_HTTP_PORT", "8080");
+        let config = try_make_config_from(["app"], |c| c.schema(schema.clone())).unwrap();
+        assert_eq!(config.get_int("schema-attr-port", 0), 8080);
+        assert_eq!(
+            config.source("schema-attr-port"),
+            Some(ValueSource::Environment)
+        );
+
+        env::set_var("SCHEMA_ATTR_HTTP_PORT", "0");
+        let err = try_make_config_from(["app"], |c| c.schema(schema)).unwrap_err();
+        env::remove_var("SCHEMA_ATTR_HTTP_PORT");
+
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: `schema-attr-port` must be between 1 and 65535 \
+             (got \"0\" from the environment)"
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_lino_config() {
+        use lino_arguments::LinoConfig;
+
+        #[derive(LinoConfig, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Settings {
+            /// Port to listen on
+            #[lino(env = "SCHEMA_DERIVE_HTTP_PORT", default = 3000, range = "1..=65535")]
+            schema_derive_port: u16,
+            #[lino(sensitive, one_of = "json, text")]
+            schema_derive_format: Option<String>,
+            #[lino(default = -1.5, description = r#"Offset, in \"units\""#)]
+            schema_derive_offset: f64,
+            schema_derive_verbose: bool,
+        }
+
+        let schema = Settings::schema();
+        let port = schema.get("schema-derive-port").unwrap();
+        assert_eq!(port.value_type, ValueType::Integer);
+        assert_eq!(port.env.as_deref(), Some("SCHEMA_DERIVE_HTTP_PORT"));
+        assert_eq!(port.description, "Port to listen on");
+        let format = schema.get("schema-derive-format").unwrap();
+        assert_eq!(format.value_type, ValueType::String);
+        assert!(format.sensitive);
+        let offset = schema.get("schema-derive-offset").unwrap();
+        assert_eq!(offset.value_type, ValueType::Float);
+        assert_eq!(offset.default.as_deref(), Some("-1.5"));
+        assert_eq!(offset.description, r#"Offset, in \"units\""#);
+        let verbose = schema.get("schema-derive-verbose").unwrap();
+        assert_eq!(verbose.value_type, ValueType::Bool);
+
+        let settings: Settings = lino_arguments::from_sources_from(
+            [
+                "app",
+                "--schema-derive-format",
+                "json",
+                "--schema-derive-verbose",
+            ],
+            |c| c.schema(Settings::schema()),
+        )
+        .unwrap();
+        assert_eq!(settings.schema_derive_port, 3000);
+        assert_eq!(settings.schema_derive_format.as_deref(), Some("json"));
+        assert!(settings.schema_derive_verbose);
+
+        let err = try_make_config_from(["app", "--schema-derive-format", "xml"], |c| {
+            c.schema(Settings::schema())
+        })
+        .unwrap_err();
+        assert!(matches!(err, ConfigError::Invalid(_)));
+    }
+
+    #[test]
+    fn test_registered_validator_applies_by_name() {
+        let dir = tempdir().unwrap();
+        let readable_dir = |value: &str| {
+            if std::path::Path::new(value).is_dir() {
+                Ok(())
+            } else {
+                Err("must be an existing directory".to_string())
+            }
+        };
+        let schema = server_schema().register("readable-dir", readable_dir).key(
+            KeySpec::new("schema-data-dir", ValueType::String)
+                .validator(Validator::named("readable-dir")),
+        );
+        let data_dir = dir.path().to_str().unwrap();
+
+        let config = try_make_config_from(["app", "--schema-data-dir", data_dir], |c| {
+            c.schema(schema.clone())
+        })
+        .unwrap();
+        assert_eq!(config.get("schema-data-dir"), data_dir);
+
+        let missing = dir.path().join("missing");
+        let missing = missing.to_str().unwrap();
+        let err = try_make_config_from(["app", "--schema-data-dir", missing], |c| c.schema(schema))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Invalid configuration: `schema-data-dir` must be an existing directory \
+                 (got {missing:?} from the command line)"
+            )
+        );
+    }
+
+    #[test]
+    fn test_schema_issues_point_at_file_values() {
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("app.lenv");
+        fs::write(&lenv_path, "# server\nSCHEMA_PORT: http\n").unwrap();
+        let lenv_path = lenv_path.to_str().unwrap();
+        env::remove_var("SCHEMA_PORT");
+
+        let err = try_make_config_from(["app"], |c| c.lenv(lenv_path).schema(server_schema()))
+            .unwrap_err();
+        env::remove_var("SCHEMA_PORT");
+
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Invalid configuration: {lenv_path}:2:14: `schema-port` is not a valid integer \
+                 (got \"http\" from the .lenv file)"
+            )
+        );
+    }
+
+    #[test]
+    fn test_warning_rules_build_with_warnings() {
+        let schema = server_schema().key(
+            KeySpec::new("schema-workers", ValueType::Integer)
+                .validator(Validator::range(1..=64))
+                .validator(Validator::range(..=16).severity(Severity::Warning)),
+        );
+
+        let config = try_make_config_from(["app", "--schema-workers", "32"], |c| {
+            c.schema(schema.clone())
+        })
+        .unwrap();
+        assert_eq!(config.get_int("schema-workers", 0), 32);
+        let [warning] = config.warnings() else {
+            panic!("expected one warning, got {:?}", config.warnings());
+        };
+        assert_eq!(warning.severity, Severity::Warning);
+        assert_eq!(
+            warning.to_string(),
+            "`schema-workers` must be at most 16 (got \"32\" from the command line)"
+        );
+        assert!(schema.validate(&config).is_ok());
+
+        let err = try_make_config_from(["app", "--schema-workers", "128"], |c| c.schema(schema))
+            .unwrap_err();
+        let ConfigError::Invalid(issues) = err else {
+            panic!("expected an invalid configuration, got {err:?}");
+        };
+        let messages: Vec<_> = issues.iter().map(|issue| issue.message.as_str()).collect();
+        assert_eq!(messages, ["must be between 1 and 64"]);
+    }
+
+    #[test]
+    fn test_preflight_revalidates_schema() {
+        let dir = tempdir().unwrap();
+        let data_dir = dir.path().join("data");
+        fs::create_dir(&data_dir).unwrap();
+        let schema = server_schema()
+            .register("dir", |value: &str| {
+                if std::path::Path::new(value).is_dir() {
+                    Ok(())
+                } else {
+                    Err("must be an existing directory".to_string())
+                }
+            })
+            .key(
+                KeySpec::new("schema-preflight-dir", ValueType::String)
+                    .validator(Validator::named("dir")),
+            );
+        let args = ["app", "--schema-preflight-dir", data_dir.to_str().unwrap()];
+
+        let config = try_make_config_from(args, |c| c.schema(schema)).unwrap();
+        assert!(config.preflight().is_ok());
+
+        fs::remove_dir(&data_dir).unwrap();
+        let err = config.preflight().unwrap_err();
+        assert!(matches!(err, ConfigError::Invalid(ref issues) if issues.len() == 1));
+        assert!(config.audit().is_empty());
+    }
+
+    #[test]
+    fn test_preflight_rejects_malformed_env_file() {
+        let dir = tempdir().unwrap();
+        let env_path = dir.path().join(".env");
+        fs::write(
+            &env_path,
+            "SCHEMA_PREFLIGHT_OK=1\nSCHEMA_PREFLIGHT_BAD='unterminated\n",
+        )
+        .unwrap();
+        let missing = dir.path().join("missing.lenv");
+
+        let config = try_make_config_from(["app"], |c| {
+            c.env(env_path.to_str().unwrap())
+                .lenv(missing.to_str().unwrap())
+                .schema(server_schema())
+        })
+        .unwrap();
+        env::remove_var("SCHEMA_PREFLIGHT_OK");
+
+        let err = config.preflight().unwrap_err();
+        let ConfigError::FileError(message) = err else {
+            panic!("expected a file error, got {err:?}");
+        };
+        assert!(message.starts_with(&format!("Failed to read {}: ", env_path.display())));
+    }
+
+    #[test]
+    fn test_preflight_reads_every_input_again() {
+        use lino_arguments::Source;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        /// A source that is up until `down` is set
+        struct Remote(Arc<AtomicBool>);
+
+        impl Source for Remote {
+            fn name(&self) -> &'static str {
+                "preflight service"
+            }
+
+            fn load(&self) -> Result<HashMap<String, String>, ConfigError> {
+                if self.0.load(Ordering::SeqCst) {
+                    return Err(ConfigError::SourceError("connection refused".into()));
+                }
+                Ok(HashMap::from([(
+                    "SCHEMA_PREFLIGHT_LEVEL".to_string(),
+                    "debug".to_string(),
+                )]))
+            }
+        }
+
+        let dir = tempdir().unwrap();
+        let secrets = dir.path().join("secrets");
+        fs::create_dir(&secrets).unwrap();
+        fs::write(secrets.join("schema_preflight_user"), "admin").unwrap();
+        let token_path = dir.path().join("token");
+        fs::write(&token_path, "sk-1234").unwrap();
+
+        env::remove_var("SCHEMA_PREFLIGHT_TOKEN");
+        env::set_var("SCHEMA_PREFLIGHT_TOKEN_FILE", &token_path);
+        let down = Arc::new(AtomicBool::new(false));
+        let config = try_make_config_from(["app"], |c| {
+            c.file_vars()
+                .secrets_dir(secrets.to_str().unwrap())
+                .source(Remote(down.clone()))
+                .option("schema-preflight-token", "Token", "")
+                .option("schema-preflight-user", "User", "")
+                .option("schema-preflight-level", "Log level", "info")
+        })
+        .unwrap();
+        env::remove_var("SCHEMA_PREFLIGHT_TOKEN_FILE");
+        assert_eq!(config.get("schema-preflight-token"), "sk-1234");
+        assert!(config.preflight().is_ok());
+
+        // The source has gone away since the build
+        down.store(true, Ordering::SeqCst);
+        let err = config.preflight().unwrap_err();
+        assert!(
+            matches!(err, ConfigError::SourceError(ref message) if message == "connection refused")
+        );
+        down.store(false, Ordering::SeqCst);
+
+        // A secret the build would skip
+        fs::write(secrets.join("schema_preflight_key"), [0xff, 0xfe]).unwrap();
+        let err = config.preflight().unwrap_err();
+        let ConfigError::FileError(message) = err else {
+            panic!("expected a file error, got {err:?}");
+        };
+        assert!(message.contains("schema_preflight_key"));
+        fs::remove_file(secrets.join("schema_preflight_key")).unwrap();
+
+        // The file named by the _FILE variable is gone
+        fs::remove_file(&token_path).unwrap();
+        let err = config.preflight().unwrap_err();
+        let ConfigError::FileError(message) = err else {
+            panic!("expected a file error, got {err:?}");
+        };
+        assert!(message.contains("SCHEMA_PREFLIGHT_TOKEN_FILE"));
+        assert_eq!(config.audit().len(), 1);
+    }
+
+    #[test]
+    fn test_sensitive_key_is_redacted() {
+        let schema = server_schema().key(
+            KeySpec::new("schema-secret-token", ValueType::String)
+                .sensitive(true)
+                .validator(Validator::matches("^sk-")),
+        );
+
+        env::set_var("SCHEMA_SECRET_TOKEN", "sk-live-1234");
+        let config = try_make_config_from(["app"], |c| c.schema(schema.clone())).unwrap();
+        let token = config.get_secret("schema-secret-token");
+        assert_eq!(format!("{token:?}"), "[REDACTED]");
+        assert_eq!(token.expose(), "sk-live-1234");
+        assert_eq!(
+            getenv_secret("schemaSecretToken").unwrap().expose(),
+            "sk-live-1234"
+        );
+
+        env::set_var("SCHEMA_SECRET_TOKEN", "hunter2");
+        let err = try_make_config_from(["app"], |c| c.schema(schema)).unwrap_err();
+        env::remove_var("SCHEMA_SECRET_TOKEN");
+
+        assert!(!err.to_string().contains("hunter2"));
+        assert!(getenv_secret("schemaSecretToken").is_none());
+    }
+
+    #[test]
+    fn test_masked_dump() {
+        let schema = server_schema().key(
+            KeySpec::new("schema-masked-key", ValueType::String)
+                .default("sk-live-51HqLyjWDarjtT1zdp7dcabcd")
+                .sensitive(true),
+        );
+        let config =
+            try_make_config_from(["app", "--schema-port", "8080"], |c| c.schema(schema)).unwrap();
+
+        let dump = config.to_masked_string();
+        assert!(dump.contains("schema-masked-key: sk-****abcd\n"));
+        assert!(dump.contains("schema-port: 8080\n"));
+        assert!(!dump.contains("51HqLyjW"));
+
+        let json = serde_json::to_value(config.masked().reveal(0)).unwrap();
+        assert_eq!(json["schema-masked-key"], "****");
+        assert_eq!(json["schema-port"], "8080");
+        assert!(config.audit().is_empty());
+    }
+
+    #[test]
+    fn test_provenance_json() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".lenv");
+        fs::write(
+            &path,
+            "# Provenance\nPROV_HOST: db.internal\nPROV_KEY: sk-live-1234\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+        let schema = Schema::new()
+            .key(KeySpec::new("prov-host", ValueType::String))
+            .key(KeySpec::new("prov-key", ValueType::String).sensitive(true))
+            .key(KeySpec::new("prov-port", ValueType::Integer).default("3000"));
+
+        env::set_var("PROV_KEY", "sk-live-5678");
+        let config = try_make_config_from(["app", "--prov-port", "8080"], |c| {
+            c.lenv_override(path).schema(schema)
+        })
+        .unwrap();
+        env::remove_var("PROV_HOST");
+        env::remove_var("PROV_KEY");
+
+        let json = config.provenance_json();
+        assert_eq!(json["generation"], 1);
+        let host = &json["keys"]["prov-host"];
+        assert_eq!(host["value"], "db.internal");
+        assert_eq!(host["source"], ".lenv file");
+        assert_eq!(host["variant"], "PROV_HOST");
+        assert_eq!(host["file"], path);
+        assert_eq!(host["line"], 2);
+        assert_eq!(host["column"], 12);
+        let key = &json["keys"]["prov-key"];
+        assert_eq!(key["value"], "****1234");
+        assert_eq!(key["line"], 3);
+        let port = &json["keys"]["prov-port"];
+        assert_eq!(port["source"], "command line");
+        assert_eq!(port["variant"], "prov-port");
+        assert!(port["file"].is_null());
+        assert!(port["revision"].is_null());
+        assert!(config.audit().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_world_readable_secret_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".lenv");
+        fs::write(
+            &path,
+            "SCHEMA_FILE_PORT: 8080\nSCHEMA_FILE_KEY: sk-live-1234\n",
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        let path = path.to_str().unwrap();
+        let schema = Schema::new()
+            .key(KeySpec::new("schema-file-port", ValueType::Integer))
+            .key(KeySpec::new("schema-file-key", ValueType::String).sensitive(true));
+
+        let config =
+            try_make_config_from(["app"], |c| c.lenv(path).schema(schema.clone())).unwrap();
+        let [warning] = config.warnings() else {
+            panic!("expected one warning, got {:?}", config.warnings());
+        };
+        assert_eq!(warning.key, "schema-file-key");
+        assert_eq!(warning.severity, Severity::Warning);
+        assert_eq!(warning.location.as_ref().unwrap().line, 2);
+        assert_eq!(
+            warning.message,
+            "is stored in a world-readable file (mode 644)"
+        );
+        assert!(!warning.to_string().contains("sk-live-1234"));
+
+        let err = try_make_config_from(["app"], |c| {
+            c.lenv(path)
+                .schema(schema.clone())
+                .file_permissions(FilePermissions::Deny)
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("chmod 600"));
+
+        let config = try_make_config_from(["app"], |c| {
+            c.lenv(path)
+                .schema(schema.clone())
+                .file_permissions(FilePermissions::Ignore)
+        })
+        .unwrap();
+        assert!(config.warnings().is_empty());
+
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600)).unwrap();
+        let config = try_make_config_from(["app"], |c| {
+            c.lenv(path)
+                .schema(schema)
+                .file_permissions(FilePermissions::Deny)
+        })
+        .unwrap();
+        assert!(config.warnings().is_empty());
+        assert_eq!(config.get("schema-file-key"), "sk-live-1234");
+        env::remove_var("SCHEMA_FILE_PORT");
+        env::remove_var("SCHEMA_FILE_KEY");
+    }
+
+    #[cfg(feature = "keyring")]
+    #[test]
+    fn test_keyring_falls_back_without_a_secret() {
+        use lino_arguments::delete_keyring_secret;
+
+        let service = "lino-arguments-test";
+        delete_keyring_secret(service, "schema-keyring-token").unwrap();
+        let schema = Schema::new().key(
+            KeySpec::new("schema-keyring-token", ValueType::String)
+                .sensitive(true)
+                .default("none"),
+        );
+        let config = try_make_config_from(["app"], |c| c.schema(schema).keyring(service)).unwrap();
+        assert_eq!(config.get("schema-keyring-token"), "none");
+        assert_eq!(
+            config.source("schema-keyring-token"),
+            Some(ValueSource::Default)
+        );
+    }
+
+    #[test]
+    fn test_schema_validate_does_not_record_reads() {
+        let schema = server_schema();
+        let config = make_config_from(["app"], |c| c.schema(schema.clone()));
+
+        assert!(schema.validate(&config).is_ok());
+        assert!(config.audit().is_empty());
+    }
+}
+
+// ============================================================================
+// init() and init_with() Tests
+// ============================================================================
+
+mod init_tests {
+    use super::*;
+
+    #[test]
+    fn test_init_with_loads_lenv_file() {
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("test.lenv");
+        let lenv_path_str = lenv_path.to_str().unwrap();
+
+        fs::write(&lenv_path, "INIT_LENV_PORT_T1: 7777\n").unwrap();
+        env::remove_var("INIT_LENV_PORT_T1");
+
+        init_with(Some(lenv_path_str), None);
+
+        assert_eq!(env::var("INIT_LENV_PORT_T1").unwrap(), "7777");
+
+        env::remove_var("INIT_LENV_PORT_T1");
+    }
+
+    #[test]
+    fn test_init_with_loads_env_file() {
+        let dir = tempdir().unwrap();
+        let env_path = dir.path().join(".env");
+        let env_path_str = env_path.to_str().unwrap();
+
+        fs::write(&env_path, "INIT_ENV_HOST_T2=example.com\n").unwrap();
+        env::remove_var("INIT_ENV_HOST_T2");
+
+        init_with(None, Some(env_path_str));
+
+        assert_eq!(env::var("INIT_ENV_HOST_T2").unwrap(), "example.com");
+
+        env::remove_var("INIT_ENV_HOST_T2");
+    }
+
+    #[test]
+    fn test_init_with_loads_both_files() {
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("test.lenv");
+        let lenv_path_str = lenv_path.to_str().unwrap();
+        let env_path = dir.path().join(".env");
+        let env_path_str = env_path.to_str().unwrap();
+
+        fs::write(&lenv_path, "INIT_BOTH_A_T3: from_lenv\n").unwrap();
+        fs::write(&env_path, "INIT_BOTH_B_T3=from_env\n").unwrap();
+
+        env::remove_var("INIT_BOTH_A_T3");
+        env::remove_var("INIT_BOTH_B_T3");
+
+        init_with(Some(lenv_path_str), Some(env_path_str));
+
+        assert_eq!(env::var("INIT_BOTH_A_T3").unwrap(), "from_lenv");
+        assert_eq!(env::var("INIT_BOTH_B_T3").unwrap(), "from_env");
+
+        env::remove_var("INIT_BOTH_A_T3");
+        env::remove_var("INIT_BOTH_B_T3");
+    }
+
+    #[test]
+    fn test_init_with_lenv_takes_priority_over_env() {
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("test.lenv");
+        let lenv_path_str = lenv_path.to_str().unwrap();
+        let env_path = dir.path().join(".env");
+        let env_path_str = env_path.to_str().unwrap();
+
+        // Same key in both files — .lenv should win
+        fs::write(&lenv_path, "INIT_PRIO_T4: from_lenv\n").unwrap();
+        fs::write(&env_path, "INIT_PRIO_T4=from_env\n").unwrap();
+
+        env::remove_var("INIT_PRIO_T4");
+
+        init_with(Some(lenv_path_str), Some(env_path_str));
+
+        // .lenv is loaded first, so it sets the var; .env won't overwrite
+        assert_eq!(env::var("INIT_PRIO_T4").unwrap(), "from_lenv");
+
+        env::remove_var("INIT_PRIO_T4");
+    }
+
+    #[test]
+    fn test_init_with_existing_env_var_takes_priority() {
+        let dir = tempdir().unwrap();
+        let lenv_path = dir.path().join("test.lenv");
+        let lenv_path_str = lenv_path.to_str().unwrap();
+
+        fs::write(&lenv_path, "INIT_EXISTING_T5: from_lenv\n").unwrap();
+
+        // Set env var BEFORE init — it should take priority
+        env::set_var("INIT_EXISTING_T5", "from_process");
+
+        init_with(Some(lenv_path_str), None);
+
+        assert_eq!(env::var("INIT_EXISTING_T5").unwrap(), "from_process");
+
+        env::remove_var("INIT_EXISTING_T5");
+    }
+}
 
 // ============================================================================
 // Drop-in parse() Tests (init + standard clap parse)
@@ -1115,3 +3936,781 @@ mod dropin_parse_tests {
         assert_eq!(args.port, 3000);
     }
 }
+
+// ============================================================================
+// Command-Line Tool Tests
+// ============================================================================
+
+mod cli_tests {
+    use super::*;
+    use std::path::Path;
+    use std::process::{Command, Output};
+
+    /// Run the binary in `dir` with `args` and extra environment variables.
+    fn lino(dir: &Path, args: &[&str], vars: &[(&str, &str)]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_lino-arguments"))
+            .current_dir(dir)
+            .args(args)
+            .env_remove("NO_COLOR")
+            .env_remove("FORCE_COLOR")
+            .envs(vars.iter().copied())
+            .output()
+            .unwrap()
+    }
+
+    fn stdout(output: &Output) -> String {
+        String::from_utf8(output.stdout.clone()).unwrap()
+    }
+
+    /// The lines of a table printed to stdout, with the padding between
+    /// columns collapsed to single spaces.
+    fn table(output: &Output) -> Vec<String> {
+        stdout(output)
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect()
+    }
+
+    #[test]
+    fn test_inspect_reports_sources() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(".lenv"),
+            "CLI_INSPECT_PORT: 8080\nCLI_INSPECT_TOKEN: sk-live-51HqLyjWDarjtT1zdp7dcabcd\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(".env"),
+            "CLI_INSPECT_HOST=example.com\nCLI_INSPECT_PORT=1\n",
+        )
+        .unwrap();
+
+        let output = lino(dir.path(), &["inspect"], &[("CLI_INSPECT_HOST", "other")]);
+        assert!(output.status.success());
+        assert_eq!(
+            table(&output),
+            [
+                "KEY VALUE SOURCE",
+                "cli-inspect-host other environment (CLI_INSPECT_HOST)",
+                "cli-inspect-port 8080 .lenv file (.lenv:1)",
+                "cli-inspect-token sk-****abcd .lenv file (.lenv:2)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inspect_with_schema() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("app.lenv"), "CLI_SCHEMA_PORT: eighty\n").unwrap();
+        let schema = Schema::new()
+            .key(KeySpec::new("cli-schema-port", ValueType::Integer).required(true))
+            .key(KeySpec::new("cli-schema-host", ValueType::String).default("localhost"))
+            .key(KeySpec::new("cli-schema-pass", ValueType::String).sensitive(true));
+        fs::write(
+            dir.path().join("schema.json"),
+            schema.to_json_schema().to_string(),
+        )
+        .unwrap();
+
+        let output = lino(
+            dir.path(),
+            &["inspect", "-c", "app.lenv", "--schema", "schema.json"],
+            &[("CLI_SCHEMA_PASS", "hunter2-hunter2")],
+        );
+        assert!(output.status.success());
+        let stdout = stdout(&output);
+        assert!(stdout.contains("localhost"), "{stdout}");
+        assert!(stdout.contains("eighty"), "{stdout}");
+        assert!(stdout.contains("****ter2"), "{stdout}");
+        assert!(!stdout.contains("hunter2-"), "{stdout}");
+    }
+
+    #[test]
+    fn test_explain_walks_the_chain() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".lenv"), "CLI_EXPLAIN_PORT: 8080\n").unwrap();
+        fs::write(dir.path().join(".env"), "# Ports\nCLI_EXPLAIN_PORT=1\n").unwrap();
+
+        let output = lino(
+            dir.path(),
+            &["explain", "cliExplainPort"],
+            &[("cli_explain_port", "5")],
+        );
+        assert!(output.status.success());
+        assert_eq!(
+            table(&output),
+            [
+                "LAYER NAME VALUE STATUS",
+                "command line --cli-explain-port (not set)",
+                "environment CLI_EXPLAIN_PORT (not set)",
+                "environment cli_explain_port 5 ignored, only CLI_EXPLAIN_PORT is read",
+                ".lenv file CLI_EXPLAIN_PORT (.lenv:1) 8080 used",
+                ".env file CLI_EXPLAIN_PORT (.env:2) 1 overridden",
+                "default value default (not set)",
+            ]
+        );
+
+        let output = lino(
+            dir.path(),
+            &[
+                "explain",
+                "cli-explain-port",
+                "--",
+                "--cli-explain-port",
+                "7",
+            ],
+            &[],
+        );
+        let stdout = stdout(&output)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert!(stdout.contains("--cli-explain-port 7 used"), "{stdout}");
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(".lenv"),
+            "CLI_EXPORT_PORT: 8080\nCLI_EXPORT_NOTE: say \"hi\" for $5\n",
+        )
+        .unwrap();
+
+        let output = lino(dir.path(), &["export"], &[]);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["cli_export_port"], "8080");
+        assert_eq!(json["cli_export_note"], "say \"hi\" for $5");
+
+        let output = lino(dir.path(), &["export", "--format", "toml"], &[]);
+        assert_eq!(
+            stdout(&output),
+            "cli_export_note = \"say \\\"hi\\\" for $5\"\ncli_export_port = \"8080\"\n"
+        );
+
+        // A flattened .env file reads back to the same values
+        let output = lino(dir.path(), &["export", "--format", "env"], &[]);
+        let exported = dir.path().join("exported.env");
+        fs::write(&exported, &output.stdout).unwrap();
+        env::remove_var("CLI_EXPORT_NOTE");
+        let config = make_config_from(["app"], |c| {
+            c.env(exported.to_str().unwrap())
+                .option("cli-export-note", "", "")
+                .option("cli-export-port", "", "")
+        });
+        assert_eq!(config.get("cli-export-note"), "say \"hi\" for $5");
+        assert_eq!(config.get("cli-export-port"), "8080");
+        env::remove_var("CLI_EXPORT_NOTE");
+        env::remove_var("CLI_EXPORT_PORT");
+    }
+
+    #[test]
+    fn test_validate_reports_lines() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("schema.json"),
+            r#"{
+                "properties": {
+                    "cli-validate-port": {"type": "integer", "maximum": 65535},
+                    "cli-validate-host": {"type": "string"}
+                },
+                "required": ["cli-validate-host"]
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("app.lenv"),
+            "CLI_VALIDATE_PORT: 99999
+",
+        )
+        .unwrap();
+
+        let args = ["validate", "--schema", "schema.json", "-c", "app.lenv"];
+        let output = lino(dir.path(), &args, &[]);
+        assert!(!output.status.success());
+        let report = String::from_utf8_lossy(&output.stderr);
+        assert!(report.contains("error: app.lenv:1:"), "{report}");
+        assert!(report.contains("must be at most 65535"), "{report}");
+
+        // Once the file is fixed, the environment is checked too
+        fs::write(
+            dir.path().join("app.lenv"),
+            "CLI_VALIDATE_PORT: 8080
+",
+        )
+        .unwrap();
+        let output = lino(dir.path(), &args, &[]);
+        assert!(!output.status.success());
+        let report = String::from_utf8_lossy(&output.stderr);
+        assert!(report.contains("cli-validate-host"), "{report}");
+
+        let output = lino(dir.path(), &args, &[("CLI_VALIDATE_HOST", "db")]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(stdout(&output), "The configuration matches schema.json.\n");
+
+        let output = lino(dir.path(), &["validate"], &[]);
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_diff_files_and_environment() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("a.lenv"),
+            "CLI_DIFF_PORT: 8080\nCLI_DIFF_OLD: x\nCLI_DIFF_TOKEN: abcdef123456\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("b.env"),
+            "CLI_DIFF_PORT=9090\nCLI_DIFF_NEW=y\nCLI_DIFF_TOKEN=abcdef123456\n",
+        )
+        .unwrap();
+
+        let output = lino(dir.path(), &["diff", "a.lenv", "b.env"], &[]);
+        assert!(output.status.success());
+        assert_eq!(
+            stdout(&output),
+            "+ CLI_DIFF_NEW=y\n- CLI_DIFF_OLD=x\n~ CLI_DIFF_PORT: 8080 -> 9090\n"
+        );
+
+        let vars = [
+            ("CLI_DIFF_PORT", "8080"),
+            ("CLI_DIFF_TOKEN", "zzzzzz987654"),
+        ];
+        let output = lino(dir.path(), &["diff", "a.lenv", "--against-env"], &vars);
+        assert_eq!(
+            stdout(&output),
+            "- CLI_DIFF_OLD=x\n~ CLI_DIFF_TOKEN: ****3456 -> ****7654\n"
+        );
+
+        let output = lino(dir.path(), &["diff", "a.lenv", "a.lenv"], &[]);
+        assert_eq!(stdout(&output), "No differences.\n");
+    }
+
+    #[test]
+    fn test_generate_env_template() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("schema.json"),
+            r#"{
+                "properties": {
+                    "database-url": {"type": "string", "description": "Where the data lives."},
+                    "port": {"type": "integer", "default": 3000}
+                },
+                "required": ["database-url"]
+            }"#,
+        )
+        .unwrap();
+
+        let output = lino(
+            dir.path(),
+            &["generate-env", "--schema", "schema.json"],
+            &[],
+        );
+        assert!(output.status.success());
+        assert_eq!(
+            stdout(&output),
+            "# Where the data lives.\n\
+             # Type: string.\n\
+             # Required.\n\
+             DATABASE_URL=\n\
+             \n\
+             # Type: integer.\n\
+             # Optional, defaults to 3000.\n\
+             # PORT=3000\n"
+        );
+
+        let output = lino(dir.path(), &["generate-env"], &[]);
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_schema_formats() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("schema.json"),
+            r#"{"properties": {"port": {"type": "integer", "default": 3000, "maximum": 65535}}}"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join(".lenv"), "API_TOKEN: secret\n").unwrap();
+
+        let output = lino(dir.path(), &["schema", "--schema", "schema.json"], &[]);
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(
+            json["properties"],
+            serde_json::json!({"port": {"type": "integer", "default": 3000, "maximum": 65535}})
+        );
+
+        let args = ["schema", "--schema", "schema.json", "--format", "markdown"];
+        let output = lino(dir.path(), &args, &[]);
+        assert_eq!(
+            stdout(&output).lines().nth(2),
+            Some("| `--port` | `PORT` | integer | `3000` | Must be at most 65535. |")
+        );
+
+        let output = lino(dir.path(), &["schema"], &[]);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(
+            json["properties"],
+            serde_json::json!({"api-token": {"type": "string", "writeOnly": true}})
+        );
+    }
+
+    #[test]
+    fn test_init_scaffolds_files() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("help.txt"),
+            "Usage: app [OPTIONS]\n\n\
+             Options:\n  \
+             -p, --port <PORT>  Server port [default: 3000]\n      \
+             --api-key <KEY>    The API key\n  \
+             -h, --help         Print help\n",
+        )
+        .unwrap();
+
+        let args = [
+            "init",
+            "--from-help",
+            "help.txt",
+            "--write-schema",
+            "schema.json",
+        ];
+        let output = lino(dir.path(), &args, &[]);
+        assert!(output.status.success());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".lenv")).unwrap(),
+            "# Server port\nPORT: 3000\n\n# The API key\n# API_KEY:\n"
+        );
+        let example = fs::read_to_string(dir.path().join(".env.example")).unwrap();
+        assert!(example.contains("# PORT=3000\n"));
+        let schema = fs::read_to_string(dir.path().join("schema.json")).unwrap();
+        let schema: serde_json::Value = serde_json::from_str(&schema).unwrap();
+        assert_eq!(schema["properties"]["api-key"]["writeOnly"], true);
+
+        let output = lino(dir.path(), &["init", "--key", "host"], &[]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_lino-arguments"))
+            .current_dir(dir.path())
+            .args(["init", "--interactive", "--force"])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        std::io::Write::write_all(&mut stdin, b"host\nlocalhost\n\nexample.com\n\n").unwrap();
+        drop(stdin);
+        assert!(child.wait_with_output().unwrap().status.success());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".lenv")).unwrap(),
+            "HOST: example.com\n"
+        );
+    }
+
+    #[test]
+    fn test_completions() {
+        let dir = tempdir().unwrap();
+        let output = lino(dir.path(), &["completions", "bash"], &[]);
+        assert!(output.status.success());
+        assert!(stdout(&output).contains("generate-env"));
+
+        fs::write(
+            dir.path().join("schema.json"),
+            r#"{"properties": {"port": {"type": "integer", "description": "Server port"}}}"#,
+        )
+        .unwrap();
+        let args = [
+            "completions",
+            "fish",
+            "--schema",
+            "schema.json",
+            "--name",
+            "myapp",
+        ];
+        let output = lino(dir.path(), &args, &[]);
+        assert!(output.status.success());
+        let script = stdout(&output);
+        assert!(
+            script.contains("complete -c myapp -l port -d 'Server port' -r"),
+            "{script}"
+        );
+        assert!(script.contains("-l configuration"), "{script}");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_doctor_finds_problems() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("schema.json"),
+            r#"{"properties": {
+                "cli-doc-port": {"type": "integer"},
+                "cli-doc-timeout": {"type": "integer", "default": 30}
+            }}"#,
+        )
+        .unwrap();
+        let env_file = dir.path().join(".env");
+        fs::write(&env_file, "CLI_DOC_API_KEY=abc\n").unwrap();
+        fs::set_permissions(&env_file, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let args = ["doctor", "--schema", "schema.json", "--prefix", "CLI_DOC_"];
+        let vars = [
+            ("CLI_DOC_PORT", "1"),
+            ("cli_doc_port", "2"),
+            ("CLI_DOC_TIMEOUT", ""),
+        ];
+        let output = lino(dir.path(), &args, &vars);
+        // Warnings alone only fail with --strict
+        assert!(output.status.success());
+        let strict = lino(dir.path(), &[&args[..], &["--strict"]].concat(), &vars);
+        assert_eq!(strict.status.code(), Some(1));
+        assert_eq!(stdout(&strict), stdout(&output));
+        assert_eq!(
+            stdout(&output),
+            "warning: .env sets the secret CLI_DOC_API_KEY but every user can read it; \
+             restrict it with `chmod 600 .env`\n\
+             warning: CLI_DOC_PORT and cli_doc_port are both set in the environment, \
+             but only CLI_DOC_PORT is read\n\
+             warning: CLI_DOC_TIMEOUT is set to an empty string in the environment, \
+             which hides the default \"30\" of `cli-doc-timeout`; remove it to use the default\n\
+             warning: CLI_DOC_API_KEY is set in .env, but no key of the schema reads it\n\
+             4 problems found\n"
+        );
+
+        fs::set_permissions(&env_file, fs::Permissions::from_mode(0o600)).unwrap();
+        let output = lino(dir.path(), &["doctor"], &[]);
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), "No problems found.\n");
+
+        fs::create_dir(dir.path().join("broken.lenv")).unwrap();
+        let output = lino(dir.path(), &["doctor", "-c", "broken.lenv"], &[]);
+        assert_eq!(output.status.code(), Some(1));
+        assert!(stdout(&output).starts_with("error: broken.lenv cannot be read:"));
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".lenv"), "# Server\nPORT: 80\n").unwrap();
+
+        let output = lino(dir.path(), &["set", "port", "8080"], &[]);
+        assert!(output.status.success());
+        let output = lino(dir.path(), &["set", "API_KEY", "abc"], &[]);
+        assert!(output.status.success());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".lenv")).unwrap(),
+            "# Server\nPORT: 8080\nAPI_KEY: abc\n"
+        );
+        let output = lino(dir.path(), &["get", "API_KEY"], &[]);
+        assert_eq!(stdout(&output), "abc\n");
+        let output = lino(dir.path(), &["get", "HOST"], &[]);
+        assert_eq!(output.status.code(), Some(1));
+
+        let args = ["set", "NOTE", "two words", "--file", "app.env"];
+        assert!(lino(dir.path(), &args, &[]).status.success());
+        let output = lino(dir.path(), &["get", "NOTE", "--file", "app.env"], &[]);
+        assert_eq!(stdout(&output), "two words\n");
+    }
+
+    #[cfg(feature = "age")]
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        let recipient = "age1s29n956ysyxpkytgcyl2zyeuqqmx5d3nht42p4evggwkvudzdgpsncysf5";
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("key.txt"),
+            "AGE-SECRET-KEY-10XW9KLDPVF3P0KRK9WK9FEYM93L85FCGE5ZZKVMENFHKQW20DRASU75R46\n",
+        )
+        .unwrap();
+        let plaintext = "# Secrets\nAPI_TOKEN: sk-live-1234\nUSER: admin\n";
+        fs::write(dir.path().join(".lenv"), plaintext).unwrap();
+
+        let output = lino(dir.path(), &["encrypt", "-r", recipient, "API_TOKEN"], &[]);
+        assert!(output.status.success());
+        let encrypted = fs::read_to_string(dir.path().join(".lenv")).unwrap();
+        assert!(
+            encrypted.starts_with("# Secrets\nAPI_TOKEN: enc:"),
+            "{encrypted}"
+        );
+        assert!(encrypted.ends_with("\nUSER: admin\n"), "{encrypted}");
+
+        let output = lino(dir.path(), &["decrypt", "-i", "key.txt"], &[]);
+        assert_eq!(stdout(&output), plaintext);
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".lenv")).unwrap(),
+            encrypted
+        );
+
+        let output = lino(dir.path(), &["decrypt", "-i", "key.txt", "--in-place"], &[]);
+        assert!(output.status.success());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".lenv")).unwrap(),
+            plaintext
+        );
+
+        let output = lino(
+            dir.path(),
+            &["encrypt", "-r", recipient, "--value", "x"],
+            &[],
+        );
+        let value = stdout(&output);
+        let output = lino(
+            dir.path(),
+            &["decrypt", "-i", "key.txt", "--value", value.trim()],
+            &[],
+        );
+        assert_eq!(stdout(&output), "x\n");
+    }
+
+    #[test]
+    fn test_convert_between_formats() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("config.toml"),
+            "debug = true\n\n[database]\nhost = \"localhost\"\nmax-connections = 10\n",
+        )
+        .unwrap();
+
+        let output = lino(dir.path(), &["convert", "config.toml", "--to", "env"], &[]);
+        assert!(output.status.success());
+        assert_eq!(
+            stdout(&output),
+            "DATABASE_HOST=localhost\nDATABASE_MAX_CONNECTIONS=10\nDEBUG=true\n"
+        );
+
+        let args = [
+            "convert",
+            "config.toml",
+            "--to",
+            "lenv",
+            "--separator",
+            "__",
+        ];
+        let output = lino(dir.path(), &args, &[]);
+        assert_eq!(
+            stdout(&output),
+            "DATABASE__HOST: localhost\nDATABASE__MAX_CONNECTIONS: 10\nDEBUG: true\n"
+        );
+        fs::write(dir.path().join("app.lenv"), &output.stdout).unwrap();
+
+        let args = ["convert", "app.lenv", "--to", "json", "--separator", "__"];
+        let output = lino(dir.path(), &args, &[]);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["database"]["max_connections"], "10");
+        assert_eq!(json["debug"], "true");
+
+        let args = ["convert", "config.toml", "--to", "yaml", "--case", "camel"];
+        let output = lino(dir.path(), &args, &[]);
+        assert_eq!(
+            stdout(&output),
+            "database:\n  host: localhost\n  maxConnections: 10\ndebug: true\n"
+        );
+
+        fs::write(dir.path().join("config.ini"), "").unwrap();
+        let output = lino(dir.path(), &["convert", "config.ini", "--to", "env"], &[]);
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_exports_configuration() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".lenv"), "CLI_RUN_PORT: 8080\n").unwrap();
+        fs::write(
+            dir.path().join("schema.json"),
+            r#"{"properties": {
+                "cli-run-host": {"type": "string", "default": "localhost"},
+                "cli-run-port": {"type": "integer", "maximum": 9000}
+            }}"#,
+        )
+        .unwrap();
+
+        let args = [
+            "run",
+            "--schema",
+            "schema.json",
+            "--",
+            "sh",
+            "-c",
+            "echo $CLI_RUN_HOST:$CLI_RUN_PORT; exit 3",
+        ];
+        let output = lino(dir.path(), &args, &[]);
+        assert_eq!(stdout(&output), "localhost:8080\n");
+        assert_eq!(output.status.code(), Some(3));
+
+        let output = lino(dir.path(), &args, &[("CLI_RUN_PORT", "9999")]);
+        assert_eq!(stdout(&output), "");
+        assert!(String::from_utf8_lossy(&output.stderr).contains("at most 9000"));
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_watch_emits_json_lines() {
+        use std::io::{BufRead, BufReader};
+        use std::process::Stdio;
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let dir = tempdir().unwrap();
+        let lenv = dir.path().join("app.lenv");
+        fs::write(&lenv, "CLI_WATCH_PORT: 80\n").unwrap();
+        let mut child = Command::new(env!("CARGO_BIN_EXE_lino-arguments"))
+            .current_dir(dir.path())
+            .args(["watch", "-c", "app.lenv", "--json"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let (sender, lines) = mpsc::channel();
+        let stdout = child.stdout.take().unwrap();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let _ = sender.send(line.unwrap());
+            }
+        });
+        let next = || -> serde_json::Value {
+            let line = lines.recv_timeout(Duration::from_secs(10)).unwrap();
+            serde_json::from_str(&line).unwrap()
+        };
+
+        let first = next();
+        assert_eq!(first["keys"]["cli-watch-port"]["value"], "80");
+        fs::write(&lenv, "CLI_WATCH_PORT: 8080\n").unwrap();
+        let second = next();
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert_eq!(second["keys"]["cli-watch-port"]["value"], "8080");
+        assert_eq!(second["keys"]["cli-watch-port"]["line"], 1);
+        assert!(second["generation"].as_u64() > first["generation"].as_u64());
+    }
+
+    #[test]
+    fn test_case_conversion() {
+        let dir = tempdir().unwrap();
+        let output = lino(
+            dir.path(),
+            &["case", "kebab", "API_KEY", "maxConnections"],
+            &[],
+        );
+        assert_eq!(stdout(&output), "api-key\nmax-connections\n");
+
+        let args = ["case", "UPPER_SNAKE", "iOSVersion", "--exception", "iOS"];
+        let output = lino(dir.path(), &args, &[]);
+        assert_eq!(stdout(&output), "iOS_VERSION\n");
+
+        let output = lino(dir.path(), &["case", "shouting", "x"], &[]);
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_output_json() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".lenv"), "CLI_JSON_PORT: 80\n").unwrap();
+        fs::write(dir.path().join("b.lenv"), "CLI_JSON_PORT: 81\n").unwrap();
+        fs::write(
+            dir.path().join("schema.json"),
+            r#"{"properties": {"cli-json-port": {"type": "integer", "maximum": 70}}}"#,
+        )
+        .unwrap();
+        let json = |args: &[&str]| -> serde_json::Value {
+            let output = lino(dir.path(), args, &[("CLI_JSON_PORT", "8080")]);
+            serde_json::from_slice(&output.stdout).unwrap()
+        };
+
+        let report = json(&["inspect", "--output", "json"]);
+        assert_eq!(report["keys"]["cli-json-port"]["value"], "8080");
+        assert_eq!(report["warnings"], serde_json::json!([]));
+
+        let report = json(&["--output", "json", "explain", "cli-json-port"]);
+        assert_eq!(report["value"], "8080");
+        assert_eq!(report["source"], "environment");
+        let statuses: Vec<&str> = report["candidates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|candidate| candidate["status"].as_str().unwrap())
+            .collect();
+        assert_eq!(statuses, ["unset", "used", "overridden", "unset", "unset"]);
+        assert_eq!(report["candidates"][2]["file"], ".lenv");
+        assert_eq!(report["candidates"][2]["line"], 1);
+
+        let report = json(&["diff", ".lenv", "b.lenv", "--output", "json"]);
+        assert_eq!(
+            report,
+            serde_json::json!({"changes": [
+                {"name": "CLI_JSON_PORT", "change": "changed", "old": "80", "new": "81"}
+            ]})
+        );
+
+        let report = json(&["doctor", "--output", "json"]);
+        assert_eq!(report, serde_json::json!({"problems": []}));
+
+        let report = json(&["validate", "--schema", "schema.json", "--output", "json"]);
+        assert_eq!(report["valid"], false);
+        assert_eq!(report["problems"][0]["file"], ".lenv");
+        assert_eq!(report["problems"][0]["line"], 1);
+        assert_eq!(report["problems"][0]["severity"], "error");
+    }
+
+    #[test]
+    fn test_color_and_secrets() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(".lenv"),
+            "CLI_COLOR_TOKEN: sk-live-51HqLyjWDarjtT1zdp7dcabcd\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("b.lenv"),
+            "CLI_COLOR_TOKEN: sk-other-abcd\n",
+        )
+        .unwrap();
+
+        let output = lino(dir.path(), &["inspect"], &[]);
+        assert!(!stdout(&output).contains('\x1b'));
+        assert!(stdout(&output).contains("sk-****abcd"));
+
+        let output = lino(dir.path(), &["inspect"], &[("FORCE_COLOR", "1")]);
+        let lines: Vec<String> = stdout(&output).lines().map(String::from).collect();
+        assert_eq!(
+            lines[0],
+            "\x1b[1mKEY\x1b[0m              \x1b[1mVALUE\x1b[0m        \x1b[1mSOURCE\x1b[0m"
+        );
+        assert_eq!(
+            lines[1],
+            "cli-color-token  sk-****abcd  \x1b[2m.lenv file (.lenv:1)\x1b[0m"
+        );
+        let colored = [("FORCE_COLOR", "1"), ("NO_COLOR", "1")];
+        let output = lino(dir.path(), &["inspect"], &colored);
+        assert!(!stdout(&output).contains('\x1b'));
+
+        let output = lino(dir.path(), &["inspect", "--show-secrets"], &[]);
+        assert!(stdout(&output).contains("sk-live-51HqLyjWDarjtT1zdp7dcabcd"));
+        let output = lino(
+            dir.path(),
+            &["--show-secrets", "diff", ".lenv", "b.lenv"],
+            &[],
+        );
+        assert_eq!(
+            stdout(&output),
+            "~ CLI_COLOR_TOKEN: sk-live-51HqLyjWDarjtT1zdp7dcabcd -> sk-other-abcd\n"
+        );
+        let output = lino(
+            dir.path(),
+            &["diff", ".lenv", "b.lenv"],
+            &[("FORCE_COLOR", "1")],
+        );
+        assert_eq!(
+            stdout(&output),
+            "\x1b[33m~ CLI_COLOR_TOKEN: sk-****abcd -> ****abcd\x1b[0m\n"
+        );
+    }
+}
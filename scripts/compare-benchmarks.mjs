@@ -0,0 +1,158 @@
+#!/usr/bin/env node
+
+/**
+ * Compare criterion benchmark results with the in-repo baseline
+ *
+ * This script reads the estimates criterion wrote for the last run of
+ * `cargo bench --bench performance` (target/criterion/<id>/new/estimates.json)
+ * and compares the median of every benchmark with the one recorded in
+ * benches/baseline.json. It fails when a benchmark got slower than the
+ * threshold allows, so performance-motivated changes can be validated.
+ *
+ * Supports both single-language and multi-language repository structures:
+ * - Single-language: Cargo.toml in repository root
+ * - Multi-language: Cargo.toml in rust/ subfolder
+ *
+ * Usage: node scripts/compare-benchmarks.mjs [--threshold <percent>] [--update] [--rust-root <path>]
+ *
+ * Options:
+ *   --threshold: Allowed slowdown in percent before failing (default: 25)
+ *   --update: Write the current results as the new baseline instead
+ *
+ * Environment variables:
+ *   - BENCH_THRESHOLD: Default for --threshold
+ *   - RUST_ROOT: Optional path to Rust package root
+ */
+
+import { readFileSync, readdirSync, existsSync, writeFileSync } from 'fs';
+import { join, relative } from 'path';
+import { getRustRoot, parseRustRootConfig } from './rust-paths.mjs';
+
+// Parse CLI arguments
+const args = process.argv.slice(2);
+const getArg = (name, defaultValue) => {
+  const index = args.indexOf(`--${name}`);
+  return index >= 0 && args[index + 1] ? args[index + 1] : defaultValue;
+};
+
+const threshold = Number(
+  getArg('threshold', process.env.BENCH_THRESHOLD || '25')
+);
+const update = args.includes('--update');
+
+// Get Rust package root (auto-detect or use explicit config)
+const rustRootConfig = parseRustRootConfig();
+const rustRoot = getRustRoot({ rustRoot: rustRootConfig || undefined });
+
+const CRITERION_DIR = join(rustRoot, 'target', 'criterion');
+const BASELINE_PATH = join(rustRoot, 'benches', 'baseline.json');
+
+/**
+ * Find the median of every benchmark criterion measured
+ * @param {string} dir - Directory to search
+ * @returns {Record<string, number>} Median time in nanoseconds, by benchmark id
+ */
+function readResults(dir) {
+  const results = {};
+  for (const entry of readdirSync(dir, { withFileTypes: true })) {
+    if (!entry.isDirectory() || entry.name === 'report') {
+      continue;
+    }
+    const path = join(dir, entry.name);
+    const estimates = join(path, 'new', 'estimates.json');
+    if (existsSync(estimates)) {
+      const benchmark = JSON.parse(
+        readFileSync(join(path, 'new', 'benchmark.json'), 'utf-8')
+      );
+      const { median } = JSON.parse(readFileSync(estimates, 'utf-8'));
+      results[benchmark.full_id] = Math.round(median.point_estimate * 100) / 100;
+    } else {
+      Object.assign(results, readResults(path));
+    }
+  }
+  return results;
+}
+
+/**
+ * Format a duration in nanoseconds for people
+ * @param {number} ns - Duration in nanoseconds
+ * @returns {string} The duration with a unit
+ */
+function formatTime(ns) {
+  if (ns >= 1e6) {
+    return `${(ns / 1e6).toFixed(2)} ms`;
+  }
+  if (ns >= 1e3) {
+    return `${(ns / 1e3).toFixed(2)} µs`;
+  }
+  return `${ns.toFixed(2)} ns`;
+}
+
+/**
+ * Main function
+ */
+function main() {
+  if (!existsSync(CRITERION_DIR)) {
+    console.error(
+      `No results in ${CRITERION_DIR}; run \`cargo bench --bench performance\` first`
+    );
+    process.exit(1);
+  }
+  const results = readResults(CRITERION_DIR);
+
+  if (update) {
+    const sorted = Object.fromEntries(
+      Object.entries(results).sort(([a], [b]) => a.localeCompare(b))
+    );
+    writeFileSync(BASELINE_PATH, `${JSON.stringify(sorted, null, 2)}\n`);
+    console.log(
+      `✓ Wrote ${Object.keys(sorted).length} benchmarks to ${relative(process.cwd(), BASELINE_PATH)}\n`
+    );
+    return;
+  }
+
+  const baseline = JSON.parse(readFileSync(BASELINE_PATH, 'utf-8'));
+  console.log(
+    `\nComparing benchmarks with the baseline (threshold: ${threshold}%)...\n`
+  );
+
+  const regressions = [];
+  for (const [id, before] of Object.entries(baseline)) {
+    const after = results[id];
+    if (after === undefined) {
+      console.log(`  ${id}: not measured`);
+      continue;
+    }
+    const change = ((after - before) / before) * 100;
+    const sign = change >= 0 ? '+' : '';
+    console.log(
+      `  ${id}: ${formatTime(before)} -> ${formatTime(after)} (${sign}${change.toFixed(1)}%)`
+    );
+    if (change > threshold) {
+      regressions.push({ id, change });
+    }
+  }
+  for (const id of Object.keys(results).filter((id) => !(id in baseline))) {
+    console.log(`  ${id}: ${formatTime(results[id])} (no baseline)`);
+  }
+
+  if (regressions.length === 0) {
+    console.log('\n✓ No benchmark is slower than the threshold\n');
+  } else {
+    console.error('\n✗ Found benchmarks slower than the baseline:\n');
+    for (const { id, change } of regressions) {
+      console.error(`  ${id}: ${change.toFixed(1)}% slower`);
+    }
+    console.error(
+      '\nInvestigate the slowdown, or run with --update if it is expected\n'
+    );
+    process.exit(1);
+  }
+}
+
+try {
+  main();
+} catch (error) {
+  console.error('Error:', error.message);
+  process.exit(1);
+}
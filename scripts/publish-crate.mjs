@@ -21,7 +21,8 @@
  *   - publish_result: 'success', 'already_exists', or 'failed'
  */
 
-import { readFileSync, appendFileSync } from 'fs';
+import { readFileSync, appendFileSync, existsSync } from 'fs';
+import { dirname, join } from 'path';
 import { execSync } from 'child_process';
 import {
   getRustRoot,
@@ -75,16 +76,17 @@ function exec(command, options = {}) {
 
 /**
  * Get package info from Cargo.toml
+ * @param {string} cargoTomlPath
  * @returns {{name: string, version: string}}
  */
-function getPackageInfo() {
-  const cargoToml = readFileSync(CARGO_TOML, 'utf-8');
+function getPackageInfo(cargoTomlPath = CARGO_TOML) {
+  const cargoToml = readFileSync(cargoTomlPath, 'utf-8');
 
   const nameMatch = cargoToml.match(/^name\s*=\s*"([^"]+)"/m);
   const versionMatch = cargoToml.match(/^version\s*=\s*"([^"]+)"/m);
 
   if (!nameMatch || !versionMatch) {
-    console.error(`Error: Could not parse package info from ${CARGO_TOML}`);
+    console.error(`Error: Could not parse package info from ${cargoTomlPath}`);
     process.exit(1);
   }
 
@@ -94,8 +96,46 @@ function getPackageInfo() {
   };
 }
 
+/**
+ * Check whether a version of a crate is already on crates.io
+ * @param {string} name
+ * @param {string} version
+ * @returns {boolean}
+ */
+function isPublished(name, version) {
+  try {
+    const response = exec(`curl -s https://crates.io/api/v1/crates/${name}/${version}`);
+    return JSON.parse(response).version?.num === version;
+  } catch {
+    return false;
+  }
+}
+
+/**
+ * Publish the derive macro crate first if its version is not on crates.io,
+ * since the main crate depends on it by version
+ */
+function publishDerive() {
+  const deriveToml = join(dirname(CARGO_TOML), 'derive', 'Cargo.toml');
+  if (!existsSync(deriveToml)) {
+    return;
+  }
+  const { name, version } = getPackageInfo(deriveToml);
+  if (isPublished(name, version)) {
+    console.log(`${name}@${version} already exists on crates.io - skipping publish`);
+    return;
+  }
+  let command = `cargo publish --allow-dirty --manifest-path ${deriveToml}`;
+  if (token) {
+    command += ` --token ${token}`;
+  }
+  console.log(exec(command));
+  console.log(`Successfully published ${name}@${version} to crates.io`);
+}
+
 function main() {
   try {
+    publishDerive();
     const { name, version } = getPackageInfo();
     console.log(`Package: ${name}@${version}`);
     console.log('');